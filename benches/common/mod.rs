@@ -0,0 +1,58 @@
+//! Timing the scans of the benchmarks. Each scan is run once to warm up the caches of the
+//! filesystem before it is timed, and the median of the timed runs is reported along with the
+//! fastest and slowest of them, so that a single outlier doesn't decide the result.
+
+/// The number of times each scan is timed, after the run that warms up.
+pub const RUNS: usize = 10;
+
+/// The times of the runs of a scan, from the fastest to the slowest.
+pub struct Samples(Vec<std::time::Duration>);
+
+impl Samples {
+    /// Scans a directory once to warm up, then `RUNS` times, timing each scan.
+    ///
+    /// # Parameters
+    /// - `root` - The directory to scan
+    /// - `options` - The options of the scans
+    /// - `files` - The number of files that each scan must count
+    pub fn scan(root: &std::path::Path, options: &splik::ScanOptions, files: usize) -> std::io::Result<Self> {
+        Self::measure(|| {
+            let languages = splik::scan(root, options)?;
+            assert_eq!(languages.totals().files, files);
+            Ok(())
+        })
+    }
+
+    /// Runs something once to warm up, then `RUNS` times, timing each run.
+    ///
+    /// # Parameters
+    /// - `run` - What to time, such as a scan
+    pub fn measure(mut run: impl FnMut() -> std::io::Result<()>) -> std::io::Result<Self> {
+        let mut time = || -> std::io::Result<std::time::Duration> {
+            let start = std::time::Instant::now();
+            run()?;
+            Ok(start.elapsed())
+        };
+        time()?;
+        let mut samples = (0..RUNS).map(|_| time()).collect::<std::io::Result<Vec<_>>>()?;
+        samples.sort_unstable();
+        Ok(Self(samples))
+    }
+
+    /// Returns the median time of the runs.
+    pub fn median(&self) -> std::time::Duration {
+        let middle = self.0.len() / 2;
+        if self.0.len().is_multiple_of(2) {
+            (self.0[middle - 1] + self.0[middle]) / 2
+        } else {
+            self.0[middle]
+        }
+    }
+}
+
+impl std::fmt::Display for Samples {
+    fn fmt(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let (fastest, slowest) = (self.0[0], self.0[self.0.len() - 1]);
+        write!(formatter, "{:?} ({fastest:?} to {slowest:?})", self.median())
+    }
+}
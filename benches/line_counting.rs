@@ -0,0 +1,98 @@
+//! Measures the throughput of counting the lines of large files, over a generated corpus of short,
+//! long, and CRLF-terminated lines, both read through a buffer and memory-mapped, against the
+//! naive count of `BufRead::lines`. Run with `cargo bench --bench line_counting`.
+
+/// The number of files of each kind in the generated corpus.
+const FILES_PER_KIND: usize = 8;
+
+/// The approximate size of each file of the generated corpus.
+const FILE_SIZE: usize = 4 * 1024 * 1024;
+
+mod common;
+
+/// Counts and classifies the lines of the files of a directory with `BufRead::lines`, which
+/// allocates a string for each line and validates it as UTF-8, as the baseline that the scans are
+/// compared against. A line is blank if it is only whitespace, a comment if it starts with `//` or
+/// `/*`, and code otherwise, which is simpler than what the scans check.
+///
+/// # Returns
+/// - The number of lines, and how many of them are blank.
+fn count_naively(root: &std::path::Path) -> std::io::Result<(u64, u64)> {
+    let (mut lines, mut blanks, mut comments) = (0, 0, 0);
+    for entry in std::fs::read_dir(root)? {
+        let file = std::io::BufReader::new(std::fs::File::open(entry?.path())?);
+        for line in std::io::BufRead::lines(file) {
+            let line = line?;
+            let line = line.trim();
+            lines += 1;
+            if line.is_empty() {
+                blanks += 1;
+            } else if line.starts_with("//") || line.starts_with("/*") {
+                comments += 1;
+            }
+        }
+    }
+    std::hint::black_box(comments);
+    Ok((lines, blanks))
+}
+
+fn main() -> std::io::Result<()> {
+    let root = std::env::temp_dir().join(format!("splik-bench-lines-{}", std::process::id()));
+    std::fs::create_dir_all(&root)?;
+
+    // Short lines of code and comments, lines long enough to be read in several chunks, and
+    // Windows line endings, with the last line of each file left without a newline
+    let kinds = [
+        (
+            "short",
+            "    let value = compute(input); // A comment after some code\n\n/* Block */\n".to_owned(),
+        ),
+        ("long", format!("{}\n", "call(argument); ".repeat(8 * 1024))),
+        ("crlf", "fn main() {\r\n    println!(\"Hello\");\r\n}\r\n".to_owned()),
+    ];
+    let mut bytes = 0;
+    for (kind, lines) in &kinds {
+        let mut contents = lines.repeat(FILE_SIZE / lines.len());
+        contents.pop();
+        for index in 0..FILES_PER_KIND {
+            std::fs::write(root.join(format!("{kind}_{index}.rs")), &contents)?;
+            bytes += contents.len();
+        }
+    }
+
+    // Minified and generated files would otherwise be left out because of their long lines
+    let buffered = splik::ScanOptions {
+        include_generated: true,
+        ..splik::ScanOptions::default()
+    };
+    let mapped = splik::ScanOptions {
+        mmap_threshold: Some(0),
+        ..buffered.clone()
+    };
+    let files = FILES_PER_KIND * kinds.len();
+    let megabytes = bytes as f64 / (1024.0 * 1024.0);
+    println!(
+        "{files} files, {megabytes:.0} MiB, median of {} runs (fastest to slowest):",
+        common::RUNS
+    );
+
+    // Both count the final line without a newline, and the same blank lines
+    let totals = splik::scan(&root, &buffered)?.totals();
+    let naive = common::Samples::measure(|| {
+        assert_eq!(count_naively(&root)?, (totals.lines, totals.blanks));
+        Ok(())
+    })?;
+    let throughput = megabytes / naive.median().as_secs_f64();
+    println!("  {:<11}{naive}, {throughput:.0} MiB/s", "lines():");
+    for (name, options) in [("buffered", &buffered), ("mmap", &mapped)] {
+        let samples = common::Samples::scan(&root, options, files)?;
+        let throughput = megabytes / samples.median().as_secs_f64();
+        let speedup = naive.median().as_secs_f64() / samples.median().as_secs_f64();
+        println!(
+            "  {:<11}{samples}, {throughput:.0} MiB/s, {speedup:.1}x lines()",
+            format!("{name}:")
+        );
+    }
+
+    std::fs::remove_dir_all(&root)
+}
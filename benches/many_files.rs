@@ -0,0 +1,38 @@
+//! Measures the time of scanning a tree of many small files, where the work done for each file
+//! outweighs counting its lines. Run with `cargo bench --bench many_files`.
+
+/// The number of files of the generated tree.
+const FILES: usize = 100_000;
+
+/// The number of files in each directory of the generated tree.
+const FILES_PER_DIRECTORY: usize = 500;
+
+mod common;
+
+fn main() -> std::io::Result<()> {
+    // Generate small files of a few languages, so that their extensions are looked up, along with
+    // some test files
+    let root = std::env::temp_dir().join(format!("splik-bench-files-{}", std::process::id()));
+    let contents = "// A small file\nfn main() {\n    println!(\"Hello\");\n}\n\n".repeat(4);
+    let extensions = ["rs", "py", "js", "c", "go"];
+    for index in 0..FILES {
+        let directory = root.join(format!("module_{}", index / FILES_PER_DIRECTORY));
+        if index % FILES_PER_DIRECTORY == 0 {
+            std::fs::create_dir_all(&directory)?;
+        }
+        let extension = extensions[index % extensions.len()];
+        let name = if index % 10 == 0 {
+            format!("test_{index}.{extension}")
+        } else {
+            format!("file_{index}.{extension}")
+        };
+        std::fs::write(directory.join(name), &contents)?;
+    }
+
+    let samples = common::Samples::scan(&root, &splik::ScanOptions::default(), FILES)?;
+    println!("{FILES} files, median of {} runs (fastest to slowest):", common::RUNS);
+    println!("  time:      {samples}");
+    println!("  per file:  {:?}", samples.median() / FILES as u32);
+
+    std::fs::remove_dir_all(&root)
+}
@@ -0,0 +1,44 @@
+//! Compares the time of a scan that counts lines with one that only counts files and bytes, see
+//! `ScanOptions::skip_lines`. Run with `cargo bench --bench no_lines`.
+
+/// The number of files of the generated tree.
+const FILES: usize = 2_000;
+
+/// The number of lines of each file of the generated tree.
+const LINES_PER_FILE: usize = 500;
+
+mod common;
+
+fn main() -> std::io::Result<()> {
+    // Generate a tree of Rust files, spread over directories of 100 files each
+    let root = std::env::temp_dir().join(format!("splik-bench-{}", std::process::id()));
+    let line = "    let value = compute(input); // A comment after some code\n";
+    let contents = line.repeat(LINES_PER_FILE);
+    for index in 0..FILES {
+        let directory = root.join(format!("module_{}", index / 100));
+        std::fs::create_dir_all(&directory)?;
+        std::fs::write(directory.join(format!("file_{index}.rs")), &contents)?;
+    }
+
+    let counting = common::Samples::scan(&root, &splik::ScanOptions::default(), FILES)?;
+    let skipping = common::Samples::scan(
+        &root,
+        &splik::ScanOptions {
+            skip_lines: true,
+            ..splik::ScanOptions::default()
+        },
+        FILES,
+    )?;
+    println!(
+        "{FILES} files of {LINES_PER_FILE} lines, median of {} runs (fastest to slowest):",
+        common::RUNS
+    );
+    println!("  counting lines:  {counting}");
+    println!("  --no-lines:      {skipping}");
+    println!(
+        "  speedup:         {:.1}x",
+        counting.median().as_secs_f64() / skipping.median().as_secs_f64()
+    );
+
+    std::fs::remove_dir_all(&root)
+}
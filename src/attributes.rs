@@ -0,0 +1,151 @@
+//! Reading the GitHub linguist overrides of `.gitattributes` files.
+
+/// The name of the files that attributes are read from.
+const GITATTRIBUTES_FILE_NAME: &str = ".gitattributes";
+
+/// The linguist attributes of the `.gitattributes` files that apply to some directory, from the
+/// scan root down to that directory.
+#[derive(Default, Clone)]
+pub(crate) struct GitAttributes {
+    /// The rules of each `.gitattributes` file, in order. Files closer to the directory come last,
+    /// so that they take priority.
+    rules: Vec<Rule>,
+}
+
+/// A single line of a `.gitattributes` file.
+#[derive(Clone)]
+struct Rule {
+    /// The directory containing the `.gitattributes` file, which the pattern is relative to.
+    base: std::path::PathBuf,
+    /// Whether the pattern only matches file names, which is the case when it has no `/`.
+    matches_name: bool,
+    /// The pattern of the paths the rule applies to.
+    pattern: globset::GlobMatcher,
+    /// The value of `linguist-vendored`, if the rule sets or unsets it.
+    vendored: Option<bool>,
+    /// The value of `linguist-generated`, if the rule sets or unsets it.
+    generated: Option<bool>,
+    /// The language of `linguist-language`, if the rule sets it.
+    language: Option<&'static str>,
+}
+
+/// The linguist attributes of a single file.
+#[derive(Default)]
+pub(crate) struct FileAttributes {
+    /// Whether the file is vendored or generated, and shouldn't be counted.
+    pub(crate) ignored: bool,
+    /// The language the file is overridden to, if any.
+    pub(crate) language: Option<&'static str>,
+}
+
+impl GitAttributes {
+    /// Returns these attributes extended with the `.gitattributes` file of a directory, if it has
+    /// one. Lines that can't be parsed are ignored, as git does.
+    ///
+    /// # Parameters
+    /// - `directory` - The path of the directory
+    ///
+    /// # Returns
+    /// - The extended attributes, or `None` if the directory has no `.gitattributes` file.
+    pub(crate) fn with_directory(&self, directory: &std::path::Path) -> Option<Self> {
+        let contents = std::fs::read_to_string(directory.join(GITATTRIBUTES_FILE_NAME)).ok()?;
+        let mut attributes = self.clone();
+        attributes.rules.extend(contents.lines().filter_map(|line| Rule::parse(line, directory)));
+        Some(attributes)
+    }
+
+    /// Returns the linguist attributes of a file. When several rules set the same attribute, the
+    /// last one wins.
+    pub(crate) fn file(&self, path: &std::path::Path) -> FileAttributes {
+        let (mut vendored, mut generated, mut language) = (false, false, None);
+        for rule in self.rules.iter().filter(|rule| rule.matches(path)) {
+            vendored = rule.vendored.unwrap_or(vendored);
+            generated = rule.generated.unwrap_or(generated);
+            language = rule.language.or(language);
+        }
+        FileAttributes {
+            ignored: vendored || generated,
+            language,
+        }
+    }
+}
+
+impl Rule {
+    /// Parses a line of a `.gitattributes` file, such as `docs/** linguist-documentation`.
+    ///
+    /// # Parameters
+    /// - `line` - The line to parse
+    /// - `base` - The directory containing the `.gitattributes` file
+    ///
+    /// # Returns
+    /// - The rule, or `None` if the line is blank, a comment, a macro definition, a pattern that
+    ///   only matches directories, or has no linguist attributes.
+    fn parse(line: &str, base: &std::path::Path) -> Option<Self> {
+        let mut words = line.split_whitespace();
+        let pattern = words
+            .next()
+            .filter(|pattern| !pattern.starts_with('#') && !pattern.starts_with("[attr]"))?;
+
+        // Patterns ending with a slash only match directories, which attributes don't apply to
+        if pattern.ends_with('/') {
+            return None;
+        }
+
+        let (mut vendored, mut generated, mut language) = (None, None, None);
+        for attribute in words {
+            let (name, value) = match attribute.split_once('=') {
+                Some((name, value)) => (name, Some(value)),
+                None => (attribute, None),
+            };
+            // Unset (`-attribute`) and unspecified (`!attribute`) attributes are both false
+            let (name, set) = match name.strip_prefix(['-', '!']) {
+                Some(name) => (name, false),
+                None => (name, value.is_none_or(|value| value != "false")),
+            };
+            match name {
+                "linguist-vendored" => vendored = Some(set),
+                "linguist-generated" => generated = Some(set),
+                "linguist-language" => language = value.map(language_name),
+                _ => {}
+            }
+        }
+        if vendored.is_none() && generated.is_none() && language.is_none() {
+            return None;
+        }
+
+        let matches_name = !pattern.contains('/');
+        let pattern = globset::GlobBuilder::new(pattern.trim_start_matches('/'))
+            .literal_separator(true)
+            .build()
+            .ok()?
+            .compile_matcher();
+        Some(Self {
+            base: base.to_owned(),
+            matches_name,
+            pattern,
+            vendored,
+            generated,
+            language,
+        })
+    }
+
+    /// Returns whether this rule applies to a file.
+    fn matches(&self, path: &std::path::Path) -> bool {
+        let Ok(relative_path) = path.strip_prefix(&self.base) else { return false };
+        if self.matches_name {
+            relative_path.file_name().is_some_and(|name| self.pattern.is_match(name))
+        } else {
+            self.pattern.is_match(relative_path)
+        }
+    }
+}
+
+/// Returns the language with the given name from `linguist-language`, matched case-insensitively
+/// against the languages splik knows. Linguist names can't contain spaces, so dashes also match
+/// spaces, as in `TypeScript-React`. Languages that splik doesn't know are given a name of their
+/// own.
+fn language_name(name: &str) -> &'static str {
+    crate::known_language_names()
+        .find(|language| language.eq_ignore_ascii_case(name) || language.replace(' ', "-").eq_ignore_ascii_case(name))
+        .unwrap_or_else(|| Box::leak(name.to_owned().into_boxed_str()))
+}
@@ -0,0 +1,537 @@
+//! Comparing a scan against a previous scan, see `LanguageList::compare`.
+
+/// A previous scan, read from the JSON output of splik.
+#[derive(Default, serde::Deserialize)]
+pub struct Baseline {
+    /// The version of the structure of the report the baseline was read from, see
+    /// `SCHEMA_VERSION`. Reports from before it was added don't have one, and are read as version
+    /// 0.
+    #[serde(default)]
+    pub schema_version: u32,
+    /// The languages of the previous scan.
+    pub languages: Vec<BaselineLanguage>,
+}
+
+/// A language of a previous scan.
+#[derive(serde::Deserialize)]
+#[serde(from = "BaselineFields")]
+pub struct BaselineLanguage {
+    /// The name of the language.
+    pub name: String,
+    /// The number of files of this language.
+    pub files: usize,
+    /// The number of lines of this language.
+    pub lines: u64,
+    /// The number of bytes of this language.
+    pub bytes: u64,
+    /// The files of this language with their counts, which a baseline only has when it was read
+    /// from the output of `--files --output json`, so that the lines added and removed can be told
+    /// apart file by file. This is empty otherwise.
+    pub file_counts: Vec<BaselineFile>,
+}
+
+/// A file of a previous scan, see `BaselineLanguage::file_counts`.
+pub struct BaselineFile {
+    /// The path of the file.
+    pub path: String,
+    /// The number of lines of the file.
+    pub lines: u64,
+    /// The number of bytes of the file.
+    pub bytes: u64,
+}
+
+/// A file of a previous scan as it is written by `--files --output json`, see `FileReport`.
+#[derive(serde::Deserialize)]
+struct FileFields {
+    /// The path of the file.
+    path: String,
+    /// The name of the language the file was counted as.
+    language: String,
+    /// The number of lines of the file, which is left out when lines weren't counted.
+    #[serde(default)]
+    lines: u64,
+    /// The number of bytes of the file.
+    bytes: u64,
+}
+
+/// A language of a previous scan as it is written in a report, which `BaselineLanguage` is read
+/// from.
+#[derive(serde::Deserialize)]
+struct BaselineFields {
+    /// The name of the language.
+    name: String,
+    /// The number of files of the language, which reports from before it was added don't have.
+    file_count: Option<usize>,
+    /// The number of paths of files listed for the language, which is the number of its files in
+    /// reports without a `file_count`. Reports written with `--no-files` don't list them, and
+    /// reports edited by hand may give the number of files instead.
+    #[serde(default, rename = "files", deserialize_with = "deserialize_length")]
+    paths: usize,
+    /// The number of lines of the language.
+    lines: u64,
+    /// The number of bytes of the language.
+    bytes: u64,
+}
+
+impl From<BaselineFields> for BaselineLanguage {
+    fn from(fields: BaselineFields) -> Self {
+        Self {
+            name: fields.name,
+            files: fields.file_count.unwrap_or(fields.paths),
+            lines: fields.lines,
+            bytes: fields.bytes,
+            file_counts: Vec::new(),
+        }
+    }
+}
+
+impl Baseline {
+    /// Reads a baseline from the JSON output of a previous scan, as written by `--output json`,
+    /// or from the list of files written by `--files --output json`.
+    ///
+    /// # Parameters
+    /// - `path` - The path of the JSON file
+    ///
+    /// # Errors
+    /// Returns an error if the file couldn't be read, if it isn't the JSON output of a scan, or
+    /// if it was written by a newer version of splik with an incompatible `SCHEMA_VERSION`.
+    pub fn read(path: &std::path::Path) -> std::io::Result<Self> {
+        let contents = std::fs::read(path)?;
+        let invalid = |error| std::io::Error::new(std::io::ErrorKind::InvalidData, error);
+        // The list of files of `--files` is an array, and the report of the languages an object
+        if contents.trim_ascii_start().starts_with(b"[") {
+            let files = serde_json::from_slice::<Vec<FileFields>>(&contents).map_err(invalid)?;
+            return Ok(Self::from_files(files));
+        }
+        let baseline = serde_json::from_slice::<Self>(&contents).map_err(invalid)?;
+        if baseline.schema_version > crate::SCHEMA_VERSION {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                format!(
+                    "unsupported schema version {}, from a newer version of splik (this one reads up to version {})",
+                    baseline.schema_version,
+                    crate::SCHEMA_VERSION
+                ),
+            ));
+        }
+        Ok(baseline)
+    }
+
+    /// Returns the languages of a list of files, in the order each was first listed, with the
+    /// counts of each file kept.
+    fn from_files(files: Vec<FileFields>) -> Self {
+        let mut languages = Vec::<BaselineLanguage>::new();
+        for file in files {
+            let index = match languages.iter().position(|language| language.name == file.language) {
+                Some(index) => index,
+                None => {
+                    languages.push(BaselineLanguage {
+                        name: file.language,
+                        files: 0,
+                        lines: 0,
+                        bytes: 0,
+                        file_counts: Vec::new(),
+                    });
+                    languages.len() - 1
+                }
+            };
+            let language = &mut languages[index];
+            language.files += 1;
+            language.lines = language.lines.saturating_add(file.lines);
+            language.bytes = language.bytes.saturating_add(file.bytes);
+            language.file_counts.push(BaselineFile {
+                path: file.path,
+                lines: file.lines,
+                bytes: file.bytes,
+            });
+        }
+        Self {
+            schema_version: crate::SCHEMA_VERSION,
+            languages,
+        }
+    }
+
+    /// Compares this scan against an earlier one. Languages are matched by name,
+    /// case-insensitively. The lines added to and removed from a language are told apart file by
+    /// file when both scans have the counts of their files, see `BaselineLanguage::file_counts`,
+    /// and are otherwise its growth or its shrinkage.
+    ///
+    /// # Parameters
+    /// - `baseline` - The earlier scan
+    ///
+    /// # Returns
+    /// - The differences of each language, and of the totals.
+    pub fn compare(&self, baseline: &Baseline) -> Comparison {
+        let find_baseline =
+            |name: &str| baseline.languages.iter().find(|language| language.name.eq_ignore_ascii_case(name));
+
+        // Languages found by this scan, which may or may not be in the baseline
+        let mut languages = self
+            .languages
+            .iter()
+            .map(|language| {
+                let after = language.counts();
+                let (status, delta) = match find_baseline(&language.name) {
+                    Some(before) => {
+                        let mut delta = Delta::between(before.counts(), after);
+                        if !before.file_counts.is_empty() && !language.file_counts.is_empty() {
+                            delta.split_by_file(&before.file_counts, &language.file_counts);
+                        }
+                        let status = if delta.is_zero() {
+                            DeltaStatus::Unchanged
+                        } else {
+                            DeltaStatus::Changed
+                        };
+                        (status, delta)
+                    }
+                    None => (DeltaStatus::Added, Delta::between((0, 0, 0), after)),
+                };
+                LanguageDelta {
+                    name: language.name.clone(),
+                    status,
+                    delta,
+                }
+            })
+            .collect::<Vec<_>>();
+
+        // Languages that are only in the baseline
+        for before in &baseline.languages {
+            let is_removed = !self.languages.iter().any(|language| language.name.eq_ignore_ascii_case(&before.name));
+            if is_removed {
+                languages.push(LanguageDelta {
+                    name: before.name.clone(),
+                    status: DeltaStatus::Removed,
+                    delta: Delta::between(before.counts(), (0, 0, 0)),
+                });
+            }
+        }
+
+        // The parts of the totals are those of the languages, so that lines moved from one
+        // language to another count as both added and removed
+        let mut totals = Delta::between(baseline.totals(), self.totals());
+        totals.files_added = languages.iter().map(|language| language.delta.files_added).sum();
+        totals.files_removed = languages.iter().map(|language| language.delta.files_removed).sum();
+        totals.lines_added = languages.iter().map(|language| language.delta.lines_added).sum();
+        totals.lines_removed = languages.iter().map(|language| language.delta.lines_removed).sum();
+        totals.bytes_added = languages.iter().map(|language| language.delta.bytes_added).sum();
+        totals.bytes_removed = languages.iter().map(|language| language.delta.bytes_removed).sum();
+
+        Comparison {
+            schema_version: crate::SCHEMA_VERSION,
+            languages,
+            totals,
+        }
+    }
+
+    /// Returns the files, lines, and bytes across all languages.
+    fn totals(&self) -> (usize, u64, u64) {
+        self.languages.iter().map(BaselineLanguage::counts).fold((0, 0, 0), |totals, counts| {
+            (
+                totals.0 + counts.0,
+                totals.1.saturating_add(counts.1),
+                totals.2.saturating_add(counts.2),
+            )
+        })
+    }
+}
+
+impl BaselineLanguage {
+    /// Returns the files, lines, and bytes of the language.
+    fn counts(&self) -> (usize, u64, u64) {
+        (self.files, self.lines, self.bytes)
+    }
+}
+
+impl From<&crate::LanguageList> for Baseline {
+    /// Returns the counts of a scan, as they would be read back from its JSON output.
+    fn from(languages: &crate::LanguageList) -> Self {
+        Self {
+            schema_version: crate::SCHEMA_VERSION,
+            languages: languages
+                .languages
+                .iter()
+                .map(|language| BaselineLanguage {
+                    name: language.name.to_owned(),
+                    files: language.file_count,
+                    lines: language.lines,
+                    bytes: language.bytes,
+                    file_counts: language
+                        .files
+                        .iter()
+                        .map(|file| BaselineFile {
+                            path: file.path.clone(),
+                            lines: file.lines,
+                            bytes: file.bytes,
+                        })
+                        .collect(),
+                })
+                .collect(),
+        }
+    }
+}
+
+/// The differences between a scan and a baseline.
+#[derive(serde::Serialize)]
+pub struct Comparison {
+    /// The version of this structure, see `SCHEMA_VERSION`.
+    pub schema_version: u32,
+    /// The differences of each language found by either scan. Languages of the current scan come
+    /// first, in its order, followed by the languages that were removed.
+    pub languages: Vec<LanguageDelta>,
+    /// The differences of the totals across all languages.
+    pub totals: Delta,
+}
+
+/// The differences of a single language between a scan and a baseline.
+#[derive(serde::Serialize)]
+pub struct LanguageDelta {
+    /// The name of the language.
+    pub name: String,
+    /// Whether the language was added, removed, or changed.
+    pub status: DeltaStatus,
+    /// The differences of the counts of the language.
+    #[serde(flatten)]
+    pub delta: Delta,
+}
+
+/// How a language differs between a scan and a baseline.
+#[derive(serde::Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum DeltaStatus {
+    /// The language is only in the current scan.
+    Added,
+    /// The language is only in the baseline.
+    Removed,
+    /// The language is in both, with different counts.
+    Changed,
+    /// The language is in both, with the same counts.
+    Unchanged,
+}
+
+/// The differences of some files, lines, and bytes, from the baseline to the current scan.
+/// Positive values are increases. Each difference is the part that was added less the part that
+/// was removed.
+#[derive(Default, serde::Serialize)]
+pub struct Delta {
+    /// The difference in the number of files.
+    pub files: i64,
+    /// The difference in the number of lines.
+    pub lines: i64,
+    /// The difference in the number of bytes.
+    pub bytes: i64,
+    /// The number of files that were added.
+    pub files_added: u64,
+    /// The number of files that were removed.
+    pub files_removed: u64,
+    /// The number of lines that were added.
+    pub lines_added: u64,
+    /// The number of lines that were removed.
+    pub lines_removed: u64,
+    /// The number of bytes that were added.
+    pub bytes_added: u64,
+    /// The number of bytes that were removed.
+    pub bytes_removed: u64,
+    /// The difference in the number of files, as a percent of the files of the baseline. This is
+    /// left out when the baseline had none.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub files_percent: Option<f64>,
+    /// The difference in the number of lines, as a percent of the lines of the baseline. This is
+    /// left out when the baseline had none.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lines_percent: Option<f64>,
+    /// The difference in the number of bytes, as a percent of the bytes of the baseline. This is
+    /// left out when the baseline had none.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bytes_percent: Option<f64>,
+}
+
+impl Delta {
+    /// Returns the differences between some counts before and after, where what was added is the
+    /// growth of each count and what was removed its shrinkage.
+    fn between(before: (usize, u64, u64), after: (usize, u64, u64)) -> Self {
+        let difference = |before: u64, after: u64| after as i64 - before as i64;
+        let percent =
+            |before: u64, after: u64| (before != 0).then(|| 100.0 * difference(before, after) as f64 / before as f64);
+        Self {
+            files: difference(before.0 as u64, after.0 as u64),
+            lines: difference(before.1, after.1),
+            bytes: difference(before.2, after.2),
+            files_added: (after.0 as u64).saturating_sub(before.0 as u64),
+            files_removed: (before.0 as u64).saturating_sub(after.0 as u64),
+            lines_added: after.1.saturating_sub(before.1),
+            lines_removed: before.1.saturating_sub(after.1),
+            bytes_added: after.2.saturating_sub(before.2),
+            bytes_removed: before.2.saturating_sub(after.2),
+            files_percent: percent(before.0 as u64, after.0 as u64),
+            lines_percent: percent(before.1, after.1),
+            bytes_percent: percent(before.2, after.2),
+        }
+    }
+
+    /// Tells apart what was added and removed file by file, matching the files by path: the files
+    /// that are only in `after` were added with all of their lines and bytes, those only in
+    /// `before` were removed with all of theirs, and the lines and bytes of the files in both were
+    /// added or removed by as much as they grew or shrank. The differences stay the same.
+    fn split_by_file(&mut self, before: &[BaselineFile], after: &[BaselineFile]) {
+        let earlier = before
+            .iter()
+            .map(|file| (file.path.as_str(), file))
+            .collect::<std::collections::HashMap<_, _>>();
+        let later = after.iter().map(|file| file.path.as_str()).collect::<std::collections::HashSet<_>>();
+        let mut parts = Self::default();
+        for file in after {
+            let (lines, bytes) =
+                earlier.get(file.path.as_str()).map_or((0, 0), |earlier| (earlier.lines, earlier.bytes));
+            parts.files_added += u64::from(!earlier.contains_key(file.path.as_str()));
+            parts.lines_added += file.lines.saturating_sub(lines);
+            parts.lines_removed += lines.saturating_sub(file.lines);
+            parts.bytes_added += file.bytes.saturating_sub(bytes);
+            parts.bytes_removed += bytes.saturating_sub(file.bytes);
+        }
+        for file in before.iter().filter(|file| !later.contains(file.path.as_str())) {
+            parts.files_removed += 1;
+            parts.lines_removed += file.lines;
+            parts.bytes_removed += file.bytes;
+        }
+        self.files_added = parts.files_added;
+        self.files_removed = parts.files_removed;
+        self.lines_added = parts.lines_added;
+        self.lines_removed = parts.lines_removed;
+        self.bytes_added = parts.bytes_added;
+        self.bytes_removed = parts.bytes_removed;
+    }
+
+    /// Returns whether none of the counts changed.
+    fn is_zero(&self) -> bool {
+        self.files == 0 && self.lines == 0 && self.bytes == 0
+    }
+}
+
+impl DeltaStatus {
+    /// Returns how this status is shown in the human-readable and Markdown outputs.
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Added => "new",
+            Self::Removed => "removed",
+            Self::Changed => "changed",
+            Self::Unchanged => "unchanged",
+        }
+    }
+}
+
+impl crate::LanguageList {
+    /// Compares this scan against a baseline from a previous scan, see `Baseline::compare`.
+    ///
+    /// # Parameters
+    /// - `baseline` - The previous scan
+    ///
+    /// # Returns
+    /// - The differences of each language, and of the totals.
+    pub fn compare(&self, baseline: &Baseline) -> Comparison {
+        Baseline::from(self).compare(baseline)
+    }
+}
+
+/// A list whose length is read, or that length given as a number.
+#[derive(serde::Deserialize)]
+#[serde(untagged)]
+enum Length {
+    List(Vec<serde::de::IgnoredAny>),
+    Number(usize),
+}
+
+/// Deserializes the length of a list, without keeping its elements, or a number given instead of
+/// the list.
+fn deserialize_length<'de, D: serde::Deserializer<'de>>(deserializer: D) -> Result<usize, D::Error> {
+    Ok(match <Length as serde::Deserialize>::deserialize(deserializer)? {
+        Length::List(elements) => elements.len(),
+        Length::Number(length) => length,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    /// Returns a baseline language with the given counts, and without the counts of its files.
+    fn language(name: &str, files: usize, lines: u64, bytes: u64) -> super::BaselineLanguage {
+        super::BaselineLanguage {
+            name: name.to_owned(),
+            files,
+            lines,
+            bytes,
+            file_counts: Vec::new(),
+        }
+    }
+
+    /// Returns a file as it is listed by `--files --output json`.
+    fn file(path: &str, language: &str, lines: u64) -> super::FileFields {
+        super::FileFields {
+            path: path.to_owned(),
+            language: language.to_owned(),
+            lines,
+            bytes: 10 * lines,
+        }
+    }
+
+    #[test]
+    fn languages_without_files_grow_or_shrink() {
+        let baseline = super::Baseline {
+            languages: vec![
+                language("TypeScript", 10, 2000, 20_000),
+                language("JavaScript", 5, 1000, 10_000),
+            ],
+            ..super::Baseline::default()
+        };
+        let current = super::Baseline {
+            languages: vec![
+                language("TypeScript", 12, 3200, 32_000),
+                language("JavaScript", 4, 700, 7000),
+            ],
+            ..super::Baseline::default()
+        };
+        let comparison = current.compare(&baseline);
+        let typescript = &comparison.languages[0].delta;
+        assert_eq!(
+            (typescript.lines, typescript.lines_added, typescript.lines_removed),
+            (1200, 1200, 0)
+        );
+        let javascript = &comparison.languages[1].delta;
+        assert_eq!(
+            (javascript.lines, javascript.lines_added, javascript.lines_removed),
+            (-300, 0, 300)
+        );
+        let totals = &comparison.totals;
+        assert_eq!(
+            (totals.lines, totals.lines_added, totals.lines_removed),
+            (900, 1200, 300)
+        );
+        assert_eq!((totals.files, totals.files_added, totals.files_removed), (1, 2, 1));
+    }
+
+    #[test]
+    fn files_tell_added_and_removed_lines_apart() {
+        let baseline = super::Baseline::from_files(vec![
+            file("grown.rs", "Rust", 10),
+            file("shrunk.rs", "Rust", 10),
+            file("deleted.rs", "Rust", 5),
+            file("moved.py", "Python", 3),
+        ]);
+        let current = super::Baseline::from_files(vec![
+            file("grown.rs", "Rust", 25),
+            file("shrunk.rs", "Rust", 4),
+            file("created.rs", "Rust", 2),
+            file("moved.rs", "Rust", 3),
+        ]);
+        let comparison = current.compare(&baseline);
+        let rust = &comparison.languages[0];
+        assert!(rust.status == super::DeltaStatus::Changed);
+        assert_eq!(rust.delta.lines, 9);
+        assert_eq!((rust.delta.lines_added, rust.delta.lines_removed), (15 + 2 + 3, 6 + 5));
+        assert_eq!((rust.delta.files_added, rust.delta.files_removed), (2, 1));
+        assert_eq!((rust.delta.bytes_added, rust.delta.bytes_removed), (200, 110));
+        let python = &comparison.languages[1];
+        assert!(python.status == super::DeltaStatus::Removed);
+        assert_eq!((python.delta.lines_added, python.delta.lines_removed), (0, 3));
+        let totals = &comparison.totals;
+        assert_eq!((totals.lines, totals.lines_added, totals.lines_removed), (6, 20, 14));
+    }
+}
@@ -0,0 +1,179 @@
+//! Caching the counts of files between scans, see `FileCache`.
+
+/// The name of the directory that `FileCache::save` writes the cache to, in the scan root. It
+/// contains a `.gitignore` that ignores the whole directory, and is never counted by a scan.
+pub const CACHE_DIRECTORY: &str = ".splik-cache";
+
+/// The name of the file in `CACHE_DIRECTORY` that the cached files are written to.
+const CACHE_FILE: &str = "files.json";
+
+/// The version of the format of `CACHE_FILE`. Caches of other versions are ignored, which should
+/// be bumped whenever a change to the format would make old caches be read wrongly.
+const CACHE_VERSION: u32 = 2;
+
+/// The counts of files from previous scans, keyed by their paths, so that files that haven't
+/// changed since can be counted without reading them again. A file is unchanged if its
+/// modification time and size are the same as when it was cached.
+#[derive(Default)]
+pub struct FileCache {
+    /// The cached files.
+    files: std::sync::Mutex<std::collections::HashMap<std::path::PathBuf, CachedFile>>,
+}
+
+/// What is known about a file from a previous scan.
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
+pub(crate) struct CachedFile {
+    /// The modification time of the file when it was cached.
+    modified: Option<std::time::SystemTime>,
+    /// The size of the file in bytes when it was cached.
+    size: u64,
+    /// The language the file was counted as, since the counts depend on its comment syntax.
+    language: String,
+    /// Whether the file looked like binary data, see `is_binary`.
+    pub(crate) binary: bool,
+    /// Whether the file looked generated or minified, see `is_generated`.
+    pub(crate) generated: bool,
+    /// The line counts of the file, if it was counted.
+    pub(crate) counts: Option<crate::lines::LineCounts>,
+    /// Whether the file was found by a scan since the cache was loaded, which only these files
+    /// are saved for, so that deleted files don't stay in the cache.
+    #[serde(skip)]
+    used: bool,
+}
+
+/// The contents of `CACHE_FILE`.
+#[derive(serde::Serialize, serde::Deserialize)]
+struct CacheContents {
+    /// The version of the format, see `CACHE_VERSION`.
+    version: u32,
+    /// The version of splik that wrote the cache, since other versions may count lines
+    /// differently.
+    splik_version: String,
+    /// The cached files, keyed by their paths relative to the scan root.
+    files: std::collections::BTreeMap<String, CachedFile>,
+}
+
+impl CachedFile {
+    /// Creates an entry for a file that hasn't been counted yet.
+    ///
+    /// # Parameters
+    /// - `metadata` - The metadata of the file
+    /// - `language` - The name of the language of the file
+    /// - `binary` - Whether the file looks like binary data
+    /// - `generated` - Whether the file looks generated or minified
+    pub(crate) fn new(metadata: &std::fs::Metadata, language: &str, binary: bool, generated: bool) -> Self {
+        Self {
+            modified: metadata.modified().ok(),
+            size: metadata.len(),
+            language: language.to_owned(),
+            binary,
+            generated,
+            counts: None,
+            used: true,
+        }
+    }
+}
+
+impl FileCache {
+    /// Loads the cache saved by `save` in the given scan root, adding its files to this cache. A
+    /// cache that doesn't exist, or was written in another format or by another version of
+    /// splik, is ignored, so that every file is read again.
+    ///
+    /// # Parameters
+    /// - `root` - The scan root the cache was saved in, which the paths of its files are relative
+    ///   to
+    pub fn load(&self, root: &std::path::Path) -> std::io::Result<()> {
+        let contents = match std::fs::read(root.join(CACHE_DIRECTORY).join(CACHE_FILE)) {
+            Ok(contents) => contents,
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+            Err(error) => return Err(error),
+        };
+        let Ok(contents) = serde_json::from_slice::<CacheContents>(&contents) else {
+            return Ok(());
+        };
+        if contents.version != CACHE_VERSION || contents.splik_version != env!("CARGO_PKG_VERSION") {
+            return Ok(());
+        }
+        let mut files = self.files.lock().unwrap();
+        for (path, file) in contents.files {
+            files.insert(root.join(path), file);
+        }
+        Ok(())
+    }
+
+    /// Saves the files below the given scan root that were found since the cache was loaded to
+    /// `CACHE_DIRECTORY` in the root, replacing the cache saved there before.
+    ///
+    /// # Parameters
+    /// - `root` - The scan root to save the cache in
+    ///
+    /// # Errors
+    /// Returns an error if the cache couldn't be written.
+    pub fn save(&self, root: &std::path::Path) -> std::io::Result<()> {
+        let files = self
+            .files
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|(_, file)| file.used)
+            .filter_map(|(path, file)| {
+                let path = path.strip_prefix(root).ok()?.to_str()?;
+                Some((path.to_owned(), file.clone()))
+            })
+            .collect();
+        let contents = CacheContents {
+            version: CACHE_VERSION,
+            splik_version: env!("CARGO_PKG_VERSION").to_owned(),
+            files,
+        };
+
+        // Ignore the cache directory, so that it isn't committed
+        let directory = root.join(CACHE_DIRECTORY);
+        std::fs::create_dir_all(&directory)?;
+        let gitignore = directory.join(".gitignore");
+        if !gitignore.exists() {
+            std::fs::write(gitignore, "*\n")?;
+        }
+
+        // Write to a temporary file first, so that an interrupted write doesn't leave a broken cache
+        let temporary = directory.join(format!("{CACHE_FILE}.tmp"));
+        std::fs::write(&temporary, serde_json::to_vec(&contents)?)?;
+        std::fs::rename(temporary, directory.join(CACHE_FILE))
+    }
+
+    /// Returns what is cached about a file, if it hasn't changed since it was cached and was
+    /// counted as the same language.
+    ///
+    /// # Parameters
+    /// - `path` - The path of the file
+    /// - `metadata` - The current metadata of the file
+    /// - `language` - The name of the language the file is counted as
+    pub(crate) fn get(
+        &self,
+        path: &std::path::Path,
+        metadata: &std::fs::Metadata,
+        language: &str,
+    ) -> Option<CachedFile> {
+        let mut files = self.files.lock().unwrap();
+        let cached = files.get_mut(path)?;
+        let modified = metadata.modified().ok();
+        let unchanged = modified.is_some() && cached.modified == modified && cached.size == metadata.len();
+        if !unchanged || cached.language != language {
+            return None;
+        }
+        cached.used = true;
+        Some(cached.clone())
+    }
+
+    /// Returns the paths of the files found by a scan since the cache was loaded, such as to watch
+    /// them for changes.
+    pub fn paths(&self) -> Vec<std::path::PathBuf> {
+        let files = self.files.lock().unwrap();
+        files.iter().filter(|(_, file)| file.used).map(|(path, _)| path.clone()).collect()
+    }
+
+    /// Caches what is known about a file, replacing what was cached before.
+    pub(crate) fn insert(&self, path: &std::path::Path, file: CachedFile) {
+        self.files.lock().unwrap().insert(path.to_owned(), file);
+    }
+}
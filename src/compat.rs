@@ -0,0 +1,234 @@
+//! Output in the JSON formats of other line counters, for tools that already read their output.
+
+/// Writes languages in the shape of `tokei --output json`: an object with an entry for each
+/// language, holding its line counts and a report for each of its files, followed by a `Total`
+/// entry whose `children` hold the reports of every language.
+///
+/// # Parameters
+/// - `writer` - Where to write the output
+/// - `languages` - The languages to write. The same language may appear more than once, such as
+///   once per directory of `--by-dir`, in which case their counts are combined.
+pub(crate) fn write_tokei_json<'a>(
+    writer: &mut impl std::io::Write,
+    languages: impl Iterator<Item = &'a crate::LanguageInfo>,
+) -> std::io::Result<()> {
+    let mut output = TokeiOutput::default();
+    for language_info in languages {
+        output.languages.entry(language_info.name).or_default().add(language_info);
+    }
+    for (name, language) in &output.languages {
+        output.total.blanks += language.blanks;
+        output.total.code += language.code;
+        output.total.comments += language.comments;
+        output.total.children.insert(name, language.reports.clone());
+    }
+    serde_json::to_writer(&mut *writer, &output)?;
+    writeln!(writer)
+}
+
+/// Writes languages in the shape of `cloc --json`: a `header` object, an entry with the line
+/// counts of each language, and a `SUM` entry with the totals. The timings of the header, which
+/// splik doesn't measure, are written as zeros.
+///
+/// # Parameters
+/// - `writer` - Where to write the output
+/// - `languages` - The languages to write, which are combined by name as with `write_tokei_json`
+pub(crate) fn write_cloc_json<'a>(
+    writer: &mut impl std::io::Write,
+    languages: impl Iterator<Item = &'a crate::LanguageInfo>,
+) -> std::io::Result<()> {
+    let mut output = ClocOutput::default();
+    for language_info in languages {
+        output.languages.entry(language_info.name).or_default().add(language_info);
+        output.sum.add(language_info);
+    }
+    output.header.n_files = output.sum.files;
+    output.header.n_lines = output.sum.blank + output.sum.comment + output.sum.code;
+    serde_json::to_writer(&mut *writer, &output)?;
+    writeln!(writer)
+}
+
+/// The output of `write_tokei_json`.
+#[derive(Default, serde::Serialize)]
+struct TokeiOutput<'a> {
+    /// Each language, keyed by its name.
+    #[serde(flatten)]
+    languages: std::collections::BTreeMap<&'static str, TokeiLanguage<'a>>,
+    /// The totals across all languages.
+    #[serde(rename = "Total")]
+    total: TokeiLanguage<'a>,
+}
+
+/// A language of `tokei --output json`.
+#[derive(Default, serde::Serialize)]
+struct TokeiLanguage<'a> {
+    blanks: u64,
+    code: u64,
+    comments: u64,
+    /// A report for each file of the language.
+    reports: Vec<TokeiReport<'a>>,
+    /// The reports of the languages embedded in this one, keyed by language. Only the `Total`
+    /// entry has these, with the reports of every language.
+    children: std::collections::BTreeMap<&'static str, Vec<TokeiReport<'a>>>,
+    /// Whether tokei had trouble counting the language, which splik never reports.
+    inaccurate: bool,
+}
+
+impl<'a> TokeiLanguage<'a> {
+    /// Adds the counts of a language to this language, along with the reports of its files if
+    /// their paths were kept.
+    fn add(&mut self, language_info: &'a crate::LanguageInfo) {
+        self.blanks += language_info.blanks;
+        self.code += language_info.code;
+        self.comments += language_info.comments;
+        for file in &language_info.files {
+            self.reports.push(TokeiReport {
+                stats: TokeiStats {
+                    blanks: file.blanks,
+                    code: file.code,
+                    comments: file.comments,
+                    blobs: std::collections::BTreeMap::new(),
+                },
+                name: &file.path,
+            });
+        }
+    }
+}
+
+/// The report of a single file of `tokei --output json`.
+#[derive(Clone, serde::Serialize)]
+struct TokeiReport<'a> {
+    stats: TokeiStats,
+    name: &'a str,
+}
+
+/// The line counts of a file of `tokei --output json`.
+#[derive(Clone, serde::Serialize)]
+struct TokeiStats {
+    blanks: u64,
+    code: u64,
+    comments: u64,
+    /// The counts of the languages embedded in the file, which splik leaves empty.
+    blobs: std::collections::BTreeMap<&'static str, TokeiStats>,
+}
+
+/// The output of `write_cloc_json`.
+#[derive(Default, serde::Serialize)]
+struct ClocOutput {
+    header: ClocHeader,
+    /// Each language, keyed by its name.
+    #[serde(flatten)]
+    languages: std::collections::BTreeMap<&'static str, ClocLanguage>,
+    /// The totals across all languages.
+    #[serde(rename = "SUM")]
+    sum: ClocLanguage,
+}
+
+/// The `header` object of `cloc --json`.
+#[derive(Default, serde::Serialize)]
+struct ClocHeader {
+    elapsed_seconds: f64,
+    n_files: usize,
+    n_lines: u64,
+    files_per_second: f64,
+    lines_per_second: f64,
+}
+
+/// A language of `cloc --json`.
+#[derive(Default, serde::Serialize)]
+struct ClocLanguage {
+    #[serde(rename = "nFiles")]
+    files: usize,
+    blank: u64,
+    comment: u64,
+    code: u64,
+}
+
+impl ClocLanguage {
+    /// Adds the counts of a language to these.
+    fn add(&mut self, language_info: &crate::LanguageInfo) {
+        self.files += language_info.file_count;
+        self.blank += language_info.blanks;
+        self.comment += language_info.comments;
+        self.code += language_info.code;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    /// Returns the languages of a small tree, with the Rust files split across two lists, as for
+    /// the directories of `--by-dir`.
+    fn languages() -> [crate::LanguageList; 2] {
+        let options = crate::ScanOptions::default();
+        let fixture: [&[(&str, &str, &str)]; 2] = [
+            &[
+                ("src/main.rs", "Rust", "fn main() {}\n\n// Done\n"),
+                ("build.py", "Python", "# Build\nrun()\n"),
+            ],
+            &[("src/lib.rs", "Rust", "/* Library */\npub fn f() {}\n")],
+        ];
+        fixture.map(|files| {
+            let mut languages = crate::LanguageList::default();
+            for (name, language, contents) in files {
+                languages
+                    .add_contents_as((*name).to_owned(), false, language, contents.as_bytes(), &options)
+                    .unwrap();
+            }
+            languages.sort(crate::SortKey::Name, false);
+            languages
+        })
+    }
+
+    /// Returns the output written by `write` for the languages of the fixture, see `languages`.
+    fn written(write: fn(&mut Vec<u8>, std::vec::IntoIter<&crate::LanguageInfo>) -> std::io::Result<()>) -> String {
+        let lists = languages();
+        let languages = lists.iter().flat_map(|languages| languages.languages.iter()).collect::<Vec<_>>();
+        let mut output = Vec::new();
+        write(&mut output, languages.into_iter()).unwrap();
+        String::from_utf8(output).unwrap()
+    }
+
+    #[test]
+    fn tokei_shape() {
+        let report = |name: &str, blanks: u64, code: u64, comments: u64| {
+            serde_json::json!({
+                "stats": { "blanks": blanks, "code": code, "comments": comments, "blobs": {} },
+                "name": name,
+            })
+        };
+        let python = [report("build.py", 0, 1, 1)];
+        let rust = [report("src/main.rs", 1, 1, 1), report("src/lib.rs", 0, 1, 1)];
+        let expected = serde_json::json!({
+            "Python": { "blanks": 0, "code": 1, "comments": 1, "reports": python, "children": {}, "inaccurate": false },
+            "Rust": { "blanks": 1, "code": 2, "comments": 2, "reports": rust, "children": {}, "inaccurate": false },
+            "Total": {
+                "blanks": 1,
+                "code": 3,
+                "comments": 3,
+                "reports": [],
+                "children": { "Python": python, "Rust": rust },
+                "inaccurate": false,
+            },
+        });
+        let output = written(|writer, languages| super::write_tokei_json(writer, languages));
+        assert_eq!(output, format!("{expected}\n"));
+    }
+
+    #[test]
+    fn cloc_shape() {
+        let expected = serde_json::json!({
+            "header": {
+                "elapsed_seconds": 0.0,
+                "n_files": 3,
+                "n_lines": 7,
+                "files_per_second": 0.0,
+                "lines_per_second": 0.0,
+            },
+            "Python": { "nFiles": 1, "blank": 0, "comment": 1, "code": 1 },
+            "Rust": { "nFiles": 2, "blank": 1, "comment": 2, "code": 2 },
+            "SUM": { "nFiles": 3, "blank": 1, "comment": 3, "code": 3 },
+        });
+        let output = written(|writer, languages| super::write_cloc_json(writer, languages));
+        assert_eq!(output, format!("{expected}\n"));
+    }
+}
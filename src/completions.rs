@@ -0,0 +1,854 @@
+//! Generating shell completion scripts from the definition of the command line, for
+//! `splik completions`. The scripts are static, but the values of options that take a language,
+//! such as `--find`, are drawn from the language table when the script is generated.
+
+/// The arguments whose values are language names, completed from `splik::known_language_names`.
+const LANGUAGE_ARGUMENTS: &[&str] = &["find", "exclude", "only", "fail_if_present", "badge_language"];
+
+/// The elvish command that completes the directories starting with the current word.
+const ELVISH_DIRECTORIES: &str =
+    "edit:complete-filename $current | each {|candidate| if (path:is-dir $candidate[stem]) { put $candidate } }";
+
+/// A shell that a completion script can be generated for.
+#[derive(Clone, Copy, Debug, clap::ValueEnum)]
+pub enum Shell {
+    Bash,
+    Zsh,
+    Fish,
+    Powershell,
+    Elvish,
+}
+
+/// How the value of an option is completed.
+enum Values {
+    /// The option doesn't take a value, or its value is optional.
+    None,
+    /// The value is free text, such as a number, so nothing is offered.
+    Text,
+    /// The value is one of the given names.
+    Choices(Vec<String>),
+    /// The value is the name of a language.
+    Languages,
+    /// The value is the path of a file.
+    Files,
+    /// The value is the path of a directory.
+    Directories,
+}
+
+/// An option of the command line, such as `--find`.
+struct Flag {
+    /// The long name of the option, without the leading `--`.
+    long: Option<String>,
+    /// The short name of the option, without the leading `-`.
+    short: Option<char>,
+    /// The first sentence of the help of the option.
+    help: String,
+    /// The name of the value of the option, such as `language`.
+    value_name: String,
+    /// How the value of the option is completed.
+    values: Values,
+    /// Whether the option can be given more than once.
+    repeats: bool,
+}
+
+impl Flag {
+    /// Returns the names of the option as they are typed, such as `-f` and `--find`.
+    fn names(&self) -> Vec<String> {
+        self.short
+            .map(|short| format!("-{short}"))
+            .into_iter()
+            .chain(self.long.as_ref().map(|long| format!("--{long}")))
+            .collect()
+    }
+}
+
+/// A subcommand, such as `completions`, and its arguments.
+struct Subcommand {
+    name: String,
+    /// The first sentence of the help of the subcommand.
+    help: String,
+    /// The arguments of the subcommand, in order.
+    arguments: Vec<Argument>,
+    flags: Vec<Flag>,
+}
+
+/// An argument of a subcommand, such as the `shell` of `completions`.
+struct Argument {
+    /// The name of the argument, such as `shell`.
+    value_name: String,
+    /// How the argument is completed.
+    values: Values,
+    /// Whether the argument must be given.
+    required: bool,
+}
+
+/// The command line that a completion script is generated for.
+struct Completions {
+    /// The name of the command, such as `splik`.
+    name: String,
+    flags: Vec<Flag>,
+    subcommands: Vec<Subcommand>,
+    /// The names of the known languages, sorted and without duplicates.
+    languages: Vec<&'static str>,
+}
+
+/// Writes a completion script for the given shell.
+///
+/// # Parameters
+/// - `writer` - The writer to write the script to
+/// - `shell` - The shell to write the script for
+/// - `command` - The command line to complete, such as `Arguments::command()`
+///
+/// # Errors
+/// If the script couldn't be written.
+pub fn write_completions(
+    writer: &mut impl std::io::Write,
+    shell: Shell,
+    command: &mut clap::Command,
+) -> std::io::Result<()> {
+    command.build();
+    let mut languages = splik::known_language_names().collect::<Vec<_>>();
+    languages.sort_unstable();
+    languages.dedup();
+    let completions = Completions {
+        name: command.get_name().to_owned(),
+        flags: flags(command),
+        subcommands: command
+            .get_subcommands()
+            .filter(|subcommand| !subcommand.is_hide_set())
+            .map(|subcommand| Subcommand {
+                name: subcommand.get_name().to_owned(),
+                help: summary(subcommand.get_about()),
+                arguments: subcommand
+                    .get_positionals()
+                    .map(|argument| Argument {
+                        value_name: argument.get_id().to_string(),
+                        values: values(argument),
+                        required: argument.is_required_set(),
+                    })
+                    .collect(),
+                flags: flags(subcommand),
+            })
+            .collect(),
+        languages,
+    };
+    match shell {
+        Shell::Bash => completions.write_bash(writer),
+        Shell::Zsh => completions.write_zsh(writer),
+        Shell::Fish => completions.write_fish(writer),
+        Shell::Powershell => completions.write_powershell(writer),
+        Shell::Elvish => completions.write_elvish(writer),
+    }
+}
+
+/// Returns the options of a command that are shown in its help.
+fn flags(command: &clap::Command) -> Vec<Flag> {
+    command
+        .get_arguments()
+        .filter(|argument| !argument.is_positional() && !argument.is_hide_set())
+        .map(|argument| {
+            let takes_value = argument.get_action().takes_values()
+                && argument.get_num_args().is_some_and(|range| range.min_values() > 0);
+            let values = if takes_value { values(argument) } else { Values::None };
+            let value_name = match values {
+                Values::Languages => "language".to_owned(),
+                _ => argument
+                    .get_value_names()
+                    .and_then(|names| names.first())
+                    .map_or_else(|| argument.get_id().to_string(), ToString::to_string)
+                    .to_lowercase(),
+            };
+            Flag {
+                long: argument.get_long().map(str::to_owned),
+                short: argument.get_short(),
+                help: summary(argument.get_help()),
+                value_name,
+                values,
+                repeats: matches!(argument.get_action(), clap::ArgAction::Append | clap::ArgAction::Count),
+            }
+        })
+        .collect()
+}
+
+/// Returns how the value of an argument that takes one is completed.
+fn values(argument: &clap::Arg) -> Values {
+    if LANGUAGE_ARGUMENTS.contains(&argument.get_id().as_str()) {
+        return Values::Languages;
+    }
+    match argument.get_value_hint() {
+        clap::ValueHint::AnyPath | clap::ValueHint::FilePath => Values::Files,
+        clap::ValueHint::DirPath => Values::Directories,
+        _ => {
+            let choices = possible_values(argument);
+            if choices.is_empty() {
+                Values::Text
+            } else {
+                Values::Choices(choices)
+            }
+        }
+    }
+}
+
+/// Returns the names of the values that an argument accepts, if it only accepts some, such as
+/// the formats of `--output`.
+fn possible_values(argument: &clap::Arg) -> Vec<String> {
+    argument
+        .get_possible_values()
+        .iter()
+        .filter(|value| !value.is_hide_set())
+        .map(|value| value.get_name().to_owned())
+        .collect()
+}
+
+/// Returns the first sentence of a help message, without its period or the details after a
+/// colon, as the description of a completion.
+fn summary(help: Option<&clap::builder::StyledStr>) -> String {
+    let help = help.map(ToString::to_string).unwrap_or_default();
+    let paragraph = help
+        .split("\n\n")
+        .next()
+        .unwrap_or_default()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ");
+    let end = [". ", ": "].iter().filter_map(|separator| paragraph.find(separator)).min();
+    let sentence = &paragraph[..end.unwrap_or(paragraph.len())];
+    sentence.strip_suffix('.').unwrap_or(sentence).to_owned()
+}
+
+/// Quotes a string for POSIX shells and zsh, such as `'C/C++ Header'`.
+fn quote(string: &str) -> String {
+    format!("'{}'", string.replace('\'', r"'\''"))
+}
+
+/// Quotes a list of words as a bash string with a word on each line, for `compgen -W` with
+/// `IFS` set to a newline, so that words can have spaces.
+fn bash_words<'a>(words: impl IntoIterator<Item = &'a str>) -> String {
+    let words = words
+        .into_iter()
+        .map(|word| word.replace('\\', r"\\").replace('\'', r"\'"))
+        .collect::<Vec<_>>();
+    format!("$'{}'", words.join(r"\n"))
+}
+
+/// Returns the zsh `_arguments` action that completes a value, such as `_files`.
+///
+/// # Parameters
+/// - `values` - How the value is completed
+/// - `function` - The name of the completion function, whose helpers complete languages
+fn zsh_action(values: &Values, function: &str) -> String {
+    match values {
+        Values::None => String::new(),
+        Values::Text => " ".to_owned(),
+        Values::Choices(choices) => format!("({})", choices.join(" ")),
+        Values::Languages => format!("{function}_languages"),
+        Values::Files => "_files".to_owned(),
+        Values::Directories => "_files -/".to_owned(),
+    }
+}
+
+/// Returns the bash command substitution that completes a value, such as
+/// `$(compgen -f -- "$current")`, or `None` if nothing is completed.
+///
+/// # Parameters
+/// - `values` - How the value is completed
+/// - `function` - The name of the completion function, whose helpers complete languages
+fn bash_completion(values: &Values, function: &str) -> Option<String> {
+    match values {
+        Values::None | Values::Text => None,
+        Values::Choices(choices) => Some(format!(
+            "$(compgen -W {} -- \"$current\")",
+            bash_words(choices.iter().map(String::as_str))
+        )),
+        Values::Languages => Some(format!("$({function}_languages)")),
+        Values::Files => Some("$(compgen -f -- \"$current\")".to_owned()),
+        Values::Directories => Some("$(compgen -d -- \"$current\")".to_owned()),
+    }
+}
+
+/// Writes the branches of a bash `case` on the previous word that complete the values of the
+/// options that take one.
+///
+/// # Parameters
+/// - `writer` - The writer to write the branches to
+/// - `flags` - The options to complete the values of
+/// - `indent` - The indentation of the branches
+/// - `function` - The name of the completion function, whose helpers complete languages
+fn write_bash_values(
+    writer: &mut impl std::io::Write,
+    flags: &[Flag],
+    indent: &str,
+    function: &str,
+) -> std::io::Result<()> {
+    for flag in flags.iter().filter(|flag| !matches!(flag.values, Values::None)) {
+        let reply = bash_completion(&flag.values, function).unwrap_or_default();
+        writeln!(writer, "{indent}{})", flag.names().join("|"))?;
+        if matches!(flag.values, Values::Languages) {
+            // The names are quoted by the helper, so that `-o filenames` mustn't quote them again
+            writeln!(writer, "{indent}    compopt +o filenames 2>/dev/null")?;
+        }
+        writeln!(writer, "{indent}    COMPREPLY=({reply})")?;
+        writeln!(writer, "{indent}    return")?;
+        writeln!(writer, "{indent}    ;;")?;
+    }
+    Ok(())
+}
+
+/// Quotes a string for fish, such as `'C/C++ Header'`.
+fn fish_quote(string: &str) -> String {
+    format!("'{}'", string.replace('\\', r"\\").replace('\'', r"\'"))
+}
+
+/// Quotes a string for PowerShell, such as `'C/C++ Header'`.
+fn powershell_quote(string: &str) -> String {
+    format!("'{}'", string.replace('\'', "''"))
+}
+
+/// Quotes a string for elvish, such as `'C/C++ Header'`.
+fn elvish_quote(string: &str) -> String {
+    format!("'{}'", string.replace('\'', "''"))
+}
+
+impl Completions {
+    /// Returns the names of the given options as they are typed, such as `-f` and `--find`.
+    fn flag_names(flags: &[Flag]) -> impl Iterator<Item = String> + '_ {
+        flags.iter().flat_map(Flag::names)
+    }
+
+    /// Writes a bash completion script, to be sourced, such as from `~/.bashrc`.
+    fn write_bash(&self, writer: &mut impl std::io::Write) -> std::io::Result<()> {
+        let function = format!("_{}", self.name.replace('-', "_"));
+
+        // Languages with spaces, such as `JavaScript React`, are completed as one escaped word,
+        // matching what has been typed of them with or without the escapes
+        writeln!(writer, "{function}_languages() {{")?;
+        writeln!(writer, "    local language")?;
+        writeln!(
+            writer,
+            "    compgen -W \"$languages\" -- \"${{current//[\\\\\\\"\\']/}}\" | while IFS= read -r language; do"
+        )?;
+        writeln!(writer, "        printf '%q\\n' \"$language\"")?;
+        writeln!(writer, "    done")?;
+        writeln!(writer, "}}")?;
+        writeln!(writer)?;
+        writeln!(writer, "{function}() {{")?;
+        writeln!(writer, "    local IFS=$'\\n'")?;
+        writeln!(writer, "    local current=\"${{COMP_WORDS[COMP_CWORD]}}\"")?;
+        writeln!(writer, "    local previous=\"${{COMP_WORDS[COMP_CWORD - 1]}}\"")?;
+        writeln!(
+            writer,
+            "    local languages={}",
+            bash_words(self.languages.iter().copied())
+        )?;
+        writeln!(writer)?;
+        if !self.subcommands.is_empty() {
+            writeln!(writer, "    if [[ ${{COMP_CWORD}} -gt 1 ]]; then")?;
+            writeln!(writer, "        case \"${{COMP_WORDS[1]}}\" in")?;
+            for subcommand in &self.subcommands {
+                let flags = Self::flag_names(&subcommand.flags).collect::<Vec<_>>();
+                let mut arguments = subcommand
+                    .arguments
+                    .iter()
+                    .filter_map(|argument| bash_completion(&argument.values, &function))
+                    .collect::<Vec<_>>();
+                arguments.dedup();
+                writeln!(writer, "            {})", subcommand.name)?;
+                if subcommand.flags.iter().any(|flag| !matches!(flag.values, Values::None)) {
+                    writeln!(writer, "                case \"$previous\" in")?;
+                    write_bash_values(writer, &subcommand.flags, "                    ", &function)?;
+                    writeln!(writer, "                esac")?;
+                }
+                writeln!(writer, "                if [[ \"$current\" == -* ]]; then")?;
+                writeln!(
+                    writer,
+                    "                    COMPREPLY=($(compgen -W {} -- \"$current\"))",
+                    bash_words(flags.iter().map(String::as_str))
+                )?;
+                writeln!(writer, "                else")?;
+                writeln!(writer, "                    COMPREPLY=({})", arguments.join(" "))?;
+                writeln!(writer, "                fi")?;
+                writeln!(writer, "                return")?;
+                writeln!(writer, "                ;;")?;
+            }
+            writeln!(writer, "        esac")?;
+            writeln!(writer, "    fi")?;
+            writeln!(writer)?;
+        }
+        writeln!(writer, "    case \"$previous\" in")?;
+        write_bash_values(writer, &self.flags, "        ", &function)?;
+        writeln!(writer, "    esac")?;
+        writeln!(writer)?;
+        let flags = Self::flag_names(&self.flags).collect::<Vec<_>>();
+        writeln!(writer, "    if [[ \"$current\" == -* ]]; then")?;
+        writeln!(
+            writer,
+            "        COMPREPLY=($(compgen -W {} -- \"$current\"))",
+            bash_words(flags.iter().map(String::as_str))
+        )?;
+        writeln!(writer, "    else")?;
+        writeln!(writer, "        COMPREPLY=($(compgen -d -- \"$current\"))")?;
+        if !self.subcommands.is_empty() {
+            let subcommands = self.subcommands.iter().map(|subcommand| subcommand.name.as_str());
+            writeln!(
+                writer,
+                "        [[ ${{COMP_CWORD}} -eq 1 ]] && COMPREPLY+=($(compgen -W {} -- \"$current\"))",
+                bash_words(subcommands)
+            )?;
+        }
+        writeln!(writer, "    fi")?;
+        writeln!(writer, "}}")?;
+        writeln!(writer)?;
+        writeln!(writer, "complete -o filenames -F {function} {}", self.name)
+    }
+
+    /// Writes a zsh completion script, to be saved as `_splik` in a directory of `$fpath`, or
+    /// to be sourced.
+    fn write_zsh(&self, writer: &mut impl std::io::Write) -> std::io::Result<()> {
+        let function = format!("_{}", self.name.replace('-', "_"));
+        writeln!(writer, "#compdef {}", self.name)?;
+        writeln!(writer)?;
+        writeln!(writer, "{function}() {{")?;
+        if !self.subcommands.is_empty() {
+            writeln!(writer, "    if (( CURRENT > 2 )); then")?;
+            writeln!(writer, "        case ${{words[2]}} in")?;
+            for subcommand in &self.subcommands {
+                writeln!(writer, "            {})", subcommand.name)?;
+                writeln!(writer, "                shift words")?;
+                writeln!(writer, "                (( CURRENT-- ))")?;
+                writeln!(writer, "                _arguments -s -S \\")?;
+                let specs = subcommand.flags.iter().map(|flag| self.zsh_spec(flag, &function));
+                let arguments = subcommand.arguments.iter().map(|argument| {
+                    let optional = if argument.required { "" } else { ":" };
+                    let action = zsh_action(&argument.values, &function);
+                    quote(&format!("{optional}:{}:{action}", argument.value_name))
+                });
+                writeln!(
+                    writer,
+                    "                    {}",
+                    specs.chain(arguments).collect::<Vec<_>>().join(" \\\n                    ")
+                )?;
+                writeln!(writer, "                return")?;
+                writeln!(writer, "                ;;")?;
+            }
+            writeln!(writer, "        esac")?;
+            writeln!(writer, "    fi")?;
+            writeln!(writer)?;
+        }
+        writeln!(writer, "    _arguments -s -S \\")?;
+        for flag in &self.flags {
+            writeln!(writer, "        {} \\", self.zsh_spec(flag, &function))?;
+        }
+        writeln!(
+            writer,
+            "        {}",
+            quote(&format!("*:directory:{function}_directories"))
+        )?;
+        writeln!(writer, "}}")?;
+        writeln!(writer)?;
+        writeln!(writer, "{function}_languages() {{")?;
+        writeln!(writer, "    local -a languages")?;
+        let languages = self.languages.iter().map(|language| quote(language)).collect::<Vec<_>>();
+        writeln!(writer, "    languages=({})", languages.join(" "))?;
+        writeln!(writer, "    _wanted languages expl language compadd -a languages")?;
+        writeln!(writer, "}}")?;
+        writeln!(writer)?;
+        writeln!(writer, "{function}_directories() {{")?;
+        if !self.subcommands.is_empty() {
+            let subcommands = self
+                .subcommands
+                .iter()
+                .map(|subcommand| quote(&format!("{}:{}", subcommand.name, subcommand.help.replace(':', r"\:"))))
+                .collect::<Vec<_>>();
+            writeln!(writer, "    local -a commands")?;
+            writeln!(writer, "    commands=({})", subcommands.join(" "))?;
+            writeln!(
+                writer,
+                "    (( CURRENT == 2 )) && _describe -t commands command commands"
+            )?;
+        }
+        writeln!(writer, "    _files -/")?;
+        writeln!(writer, "}}")?;
+        writeln!(writer)?;
+        writeln!(writer, "if [ \"$funcstack[1]\" = \"{function}\" ]; then")?;
+        writeln!(writer, "    {function} \"$@\"")?;
+        writeln!(writer, "else")?;
+        writeln!(writer, "    compdef {function} {}", self.name)?;
+        writeln!(writer, "fi")
+    }
+
+    /// Returns the `_arguments` specification of an option, such as
+    /// `'*'{-f+,--find=}'[List all files of the specified language]:language:_splik_languages'`.
+    fn zsh_spec(&self, flag: &Flag, function: &str) -> String {
+        let help = flag.help.replace('\\', r"\\").replace('[', r"\[").replace(']', r"\]").replace(':', r"\:");
+        let action = zsh_action(&flag.values, function);
+        let (short_suffix, long_suffix) = match flag.values {
+            Values::None => ("", ""),
+            _ => ("+", "="),
+        };
+        let names = flag
+            .short
+            .map(|short| format!("-{short}{short_suffix}"))
+            .into_iter()
+            .chain(flag.long.as_ref().map(|long| format!("--{long}{long_suffix}")))
+            .collect::<Vec<_>>();
+        let value = match flag.values {
+            Values::None => String::new(),
+            _ => format!(":{}:{action}", flag.value_name.replace(':', r"\:")),
+        };
+        let description = format!("[{help}]{value}");
+        match names.as_slice() {
+            [name] if flag.repeats => quote(&format!("*{name}{description}")),
+            [name] => quote(&format!("{name}{description}")),
+            _ if flag.repeats => format!("{}{{{}}}{}", quote("*"), names.join(","), quote(&description)),
+            _ => {
+                let exclusions = format!("({})", flag.names().join(" "));
+                format!("{}{{{}}}{}", quote(&exclusions), names.join(","), quote(&description))
+            }
+        }
+    }
+
+    /// Writes a fish completion script, to be saved as `splik.fish` in
+    /// `~/.config/fish/completions`, or to be sourced.
+    fn write_fish(&self, writer: &mut impl std::io::Write) -> std::io::Result<()> {
+        let subcommands = self.subcommands.iter().map(|subcommand| subcommand.name.as_str()).collect::<Vec<_>>();
+        let main = format!("not __fish_seen_subcommand_from {}", subcommands.join(" "));
+        writeln!(writer, "complete -c {} -f", self.name)?;
+        writeln!(
+            writer,
+            "complete -c {} -n {} -a '(__fish_complete_directories (commandline -ct))'",
+            self.name,
+            fish_quote(&main)
+        )?;
+        for subcommand in &self.subcommands {
+            writeln!(
+                writer,
+                "complete -c {} -n __fish_use_subcommand -a {} -d {}",
+                self.name,
+                subcommand.name,
+                fish_quote(&subcommand.help)
+            )?;
+            let condition = fish_quote(&format!("__fish_seen_subcommand_from {}", subcommand.name));
+            let mut completions = subcommand
+                .arguments
+                .iter()
+                .filter_map(|argument| match &argument.values {
+                    Values::None | Values::Text | Values::Languages => None,
+                    Values::Choices(choices) => Some(format!("-a {}", fish_quote(&choices.join(" ")))),
+                    Values::Files => Some("-F".to_owned()),
+                    Values::Directories => Some("-a '(__fish_complete_directories (commandline -ct))'".to_owned()),
+                })
+                .collect::<Vec<_>>();
+            completions.dedup();
+            for completion in completions {
+                writeln!(writer, "complete -c {} -n {condition} {completion}", self.name)?;
+            }
+            for flag in &subcommand.flags {
+                self.write_fish_flag(writer, flag, &condition)?;
+            }
+        }
+        for flag in &self.flags {
+            self.write_fish_flag(writer, flag, &fish_quote(&main))?;
+        }
+        Ok(())
+    }
+
+    /// Writes the `complete` command of an option for fish.
+    ///
+    /// # Parameters
+    /// - `writer` - The writer to write the command to
+    /// - `flag` - The option to complete
+    /// - `condition` - The quoted condition of the option, such as that no subcommand is given
+    fn write_fish_flag(&self, writer: &mut impl std::io::Write, flag: &Flag, condition: &str) -> std::io::Result<()> {
+        write!(writer, "complete -c {} -n {condition}", self.name)?;
+        if let Some(short) = flag.short {
+            write!(writer, " -s {short}")?;
+        }
+        if let Some(long) = &flag.long {
+            write!(writer, " -l {long}")?;
+        }
+        match &flag.values {
+            Values::None => {}
+            Values::Text => write!(writer, " -x")?,
+            Values::Choices(choices) => write!(writer, " -x -a {}", fish_quote(&choices.join(" ")))?,
+            Values::Languages => {
+                let languages = self
+                    .languages
+                    .iter()
+                    .map(|language| {
+                        format!(
+                            "\"{}\"",
+                            language.replace('\\', r"\\").replace('"', "\\\"").replace('$', r"\$")
+                        )
+                    })
+                    .collect::<Vec<_>>();
+                write!(writer, " -x -a {}", fish_quote(&languages.join(" ")))?;
+            }
+            Values::Files => write!(writer, " -r -F")?,
+            Values::Directories => write!(writer, " -x -a '(__fish_complete_directories (commandline -ct))'")?,
+        }
+        writeln!(writer, " -d {}", fish_quote(&flag.help))
+    }
+
+    /// Writes a PowerShell completion script, to be run from the PowerShell profile, such as with
+    /// `splik completions powershell | Out-String | Invoke-Expression`.
+    fn write_powershell(&self, writer: &mut impl std::io::Write) -> std::io::Result<()> {
+        let results = |indent: &str, words: &[(String, String)], kind: &str| {
+            words
+                .iter()
+                .map(|(word, help)| {
+                    format!(
+                        "{indent}[CompletionResult]::new({}, {}, [CompletionResultType]::{kind}, {})\n",
+                        powershell_quote(word),
+                        powershell_quote(word),
+                        powershell_quote(if help.is_empty() { word } else { help })
+                    )
+                })
+                .collect::<String>()
+        };
+        let flag_words = |flags: &[Flag]| {
+            flags
+                .iter()
+                .flat_map(|flag| flag.names().into_iter().map(|name| (name, flag.help.clone())))
+                .collect::<Vec<_>>()
+        };
+        let value_words =
+            |values: &[String]| values.iter().map(|value| (value.clone(), String::new())).collect::<Vec<_>>();
+        let names = |flag: &Flag| flag.names().iter().map(|name| powershell_quote(name)).collect::<Vec<_>>().join(", ");
+
+        writeln!(writer, "using namespace System.Management.Automation")?;
+        writeln!(writer)?;
+        writeln!(
+            writer,
+            "Register-ArgumentCompleter -Native -CommandName {} -ScriptBlock {{",
+            powershell_quote(&self.name)
+        )?;
+        writeln!(writer, "    param($wordToComplete, $commandAst, $cursorPosition)")?;
+        writeln!(writer)?;
+        let languages = self.languages.iter().map(|language| powershell_quote(language)).collect::<Vec<_>>();
+        writeln!(writer, "    $languages = @({})", languages.join(", "))?;
+        writeln!(writer, "    $words = @($commandAst.CommandElements |")?;
+        writeln!(
+            writer,
+            "        Where-Object {{ $_.Extent.EndOffset -lt ($cursorPosition - $wordToComplete.Length) }} |"
+        )?;
+        writeln!(writer, "        ForEach-Object {{ $_.Extent.Text }})")?;
+        writeln!(
+            writer,
+            "    $previous = if ($words.Count -gt 1) {{ $words[-1] }} else {{ '' }}"
+        )?;
+        writeln!(writer)?;
+        // The values of the options of each subcommand, and the subcommand's own arguments and
+        // options, come before those of the main command
+        let value_branches = |condition: &str, flags: &[Flag]| {
+            flags
+                .iter()
+                .filter_map(|flag| {
+                    let completions = match &flag.values {
+                        Values::None => return None,
+                        // Nothing is completed, so PowerShell falls back to completing paths
+                        Values::Text | Values::Files | Values::Directories => "return".to_owned(),
+                        Values::Choices(choices) => format!(
+                            "$completions = @(\n{}        )",
+                            results("            ", &value_words(choices), "ParameterValue")
+                        ),
+                        Values::Languages => concat!(
+                            "$completions = @($languages | ForEach-Object {\n",
+                            "            $text = if ($_ -match ' ') { \"'$_'\" } else { $_ }\n",
+                            "            [CompletionResult]::new($text, $_, [CompletionResultType]::ParameterValue, $_)\n",
+                            "        })"
+                        )
+                        .to_owned(),
+                    };
+                    Some((format!("{condition}$previous -in @({})", names(flag)), completions))
+                })
+                .collect::<Vec<_>>()
+        };
+        let mut branches = Vec::new();
+        for subcommand in &self.subcommands {
+            let condition = format!(
+                "$words.Count -gt 1 -and $words[1] -eq {}",
+                powershell_quote(&subcommand.name)
+            );
+            branches.extend(value_branches(&format!("{condition} -and "), &subcommand.flags));
+            let choices = subcommand
+                .arguments
+                .iter()
+                .flat_map(|argument| match &argument.values {
+                    Values::Choices(choices) => choices.clone(),
+                    _ => Vec::new(),
+                })
+                .collect::<Vec<_>>();
+            let completions = format!(
+                "$completions = @(\n{}{}        )",
+                results("            ", &value_words(&choices), "ParameterValue"),
+                results("            ", &flag_words(&subcommand.flags), "ParameterName")
+            );
+            branches.push((condition, completions));
+        }
+        branches.extend(value_branches("", &self.flags));
+        branches.push((
+            "$wordToComplete -like '-*'".to_owned(),
+            format!(
+                "$completions = @(\n{}        )",
+                results("            ", &flag_words(&self.flags), "ParameterName")
+            ),
+        ));
+        if !self.subcommands.is_empty() {
+            let subcommands = self
+                .subcommands
+                .iter()
+                .map(|subcommand| (subcommand.name.clone(), subcommand.help.clone()))
+                .collect::<Vec<_>>();
+            branches.push((
+                "$words.Count -eq 1 -and $wordToComplete".to_owned(),
+                format!(
+                    "$completions = @(\n{}        )",
+                    results("            ", &subcommands, "ParameterValue")
+                ),
+            ));
+        }
+        for (index, (condition, completions)) in branches.iter().enumerate() {
+            let branch = if index == 0 { "if" } else { "} elseif" };
+            writeln!(writer, "    {branch} ({condition}) {{")?;
+            writeln!(writer, "        {completions}")?;
+        }
+        writeln!(writer, "    }} else {{")?;
+        writeln!(writer, "        return")?;
+        writeln!(writer, "    }}")?;
+        writeln!(writer)?;
+        writeln!(
+            writer,
+            "    $completions | Where-Object {{ $_.ListItemText -like \"$wordToComplete*\" }}"
+        )?;
+        writeln!(writer, "}}")
+    }
+
+    /// Writes an elvish completion script, to be evaluated from `rc.elv`, such as with
+    /// `eval (splik completions elvish | slurp)`.
+    fn write_elvish(&self, writer: &mut impl std::io::Write) -> std::io::Result<()> {
+        let list = |words: &mut dyn Iterator<Item = &str>| words.map(elvish_quote).collect::<Vec<_>>().join(" ");
+        let candidates = |indent: &str, flags: &[Flag]| {
+            flags
+                .iter()
+                .flat_map(|flag| {
+                    flag.names().into_iter().map(move |name| {
+                        format!(
+                            "{indent}edit:complex-candidate {} &display={}\n",
+                            elvish_quote(&name),
+                            elvish_quote(&format!("{name}  {}", flag.help))
+                        )
+                    })
+                })
+                .collect::<String>()
+        };
+
+        writeln!(writer, "use path")?;
+        writeln!(writer, "use str")?;
+        writeln!(writer)?;
+        writeln!(writer, "set edit:completion:arg-completer[{}] = {{|@words|", self.name)?;
+        writeln!(
+            writer,
+            "    var languages = [{}]",
+            list(&mut self.languages.iter().copied())
+        )?;
+        writeln!(writer, "    var current = $words[-1]")?;
+        writeln!(
+            writer,
+            "    var previous = (if (> (count $words) 2) {{ put $words[-2] }} else {{ put '' }})"
+        )?;
+        writeln!(writer)?;
+        // The values of the options of each subcommand, and the subcommand's own arguments and
+        // options, come before those of the main command
+        let completion = |values: &Values| match values {
+            Values::None => None,
+            Values::Text => Some("# Nothing is completed for free text".to_owned()),
+            Values::Choices(choices) => Some(format!("put {}", list(&mut choices.iter().map(String::as_str)))),
+            Values::Languages => Some("all $languages".to_owned()),
+            Values::Files => Some("edit:complete-filename $current".to_owned()),
+            Values::Directories => Some(ELVISH_DIRECTORIES.to_owned()),
+        };
+        let value_branches = |condition: &dyn Fn(String) -> String, flags: &[Flag]| {
+            flags
+                .iter()
+                .filter_map(|flag| {
+                    let names = flag.names().iter().map(|name| elvish_quote(name)).collect::<Vec<_>>();
+                    let condition = condition(format!("(has-value [{}] $previous)", names.join(" ")));
+                    Some((condition, format!("        {}\n", completion(&flag.values)?)))
+                })
+                .collect::<Vec<_>>()
+        };
+        let mut branches = Vec::new();
+        for subcommand in &self.subcommands {
+            let name = elvish_quote(&subcommand.name);
+            let condition = |rest: String| format!("(and (> (count $words) 2) (eq $words[1] {name}) {rest})");
+            branches.extend(value_branches(&condition, &subcommand.flags));
+            let mut completions = subcommand
+                .arguments
+                .iter()
+                .filter(|argument| !matches!(argument.values, Values::Text))
+                .filter_map(|argument| completion(&argument.values))
+                .map(|completion| format!("        {completion}\n"))
+                .collect::<Vec<_>>();
+            completions.dedup();
+            completions.push(candidates("        ", &subcommand.flags));
+            branches.push((
+                format!("(and (> (count $words) 2) (eq $words[1] {name}))"),
+                completions.concat(),
+            ));
+        }
+        branches.extend(value_branches(&|condition| condition, &self.flags));
+        let mut branch = "if";
+        for (condition, completions) in &branches {
+            writeln!(writer, "    {branch} {condition} {{")?;
+            write!(writer, "{completions}")?;
+            branch = "} elif";
+        }
+        writeln!(writer, "    {branch} (str:has-prefix $current -) {{")?;
+        write!(writer, "{}", candidates("        ", &self.flags))?;
+        writeln!(writer, "    }} else {{")?;
+        if !self.subcommands.is_empty() {
+            let subcommands = self.subcommands.iter().map(|subcommand| subcommand.name.as_str());
+            writeln!(writer, "        if (== (count $words) 2) {{")?;
+            writeln!(writer, "            put {}", list(&mut subcommands.into_iter()))?;
+            writeln!(writer, "        }}")?;
+        }
+        writeln!(writer, "        {ELVISH_DIRECTORIES}")?;
+        writeln!(writer, "    }}")?;
+        writeln!(writer, "}}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    /// Completes the value of `--find` in the bash script, returning the words offered, or `None`
+    /// if bash isn't installed.
+    fn complete_bash_language(current: &str) -> Option<Vec<String>> {
+        let mut command = clap::Command::new("splik").arg(clap::Arg::new("find").long("find"));
+        let mut script = Vec::new();
+        super::write_completions(&mut script, super::Shell::Bash, &mut command).unwrap();
+        let test = "compopt() { :; }; COMP_WORDS=(splik --find \"$1\"); COMP_CWORD=2; _splik; \
+                    printf '%s\\n' \"${COMPREPLY[@]}\"";
+        let output = std::process::Command::new("bash")
+            .arg("-c")
+            .arg(format!("{}\n{test}", String::from_utf8(script).unwrap()))
+            .arg("bash")
+            .arg(current)
+            .output()
+            .ok()?;
+        assert!(output.status.success());
+        Some(String::from_utf8(output.stdout).unwrap().lines().map(str::to_owned).collect())
+    }
+
+    #[test]
+    fn bash_escapes_languages_with_spaces() {
+        let Some(words) = complete_bash_language("JavaScript") else {
+            return;
+        };
+        assert_eq!(words, ["JavaScript", r"JavaScript\ React"]);
+        assert_eq!(
+            complete_bash_language(r"JavaScript\ R").unwrap(),
+            [r"JavaScript\ React"]
+        );
+        assert_eq!(complete_bash_language("'C/C").unwrap(), [r"C/C++\ Header"]);
+    }
+}
@@ -0,0 +1,146 @@
+//! Splitting single-file components, such as Vue and Svelte files, into the languages of their
+//! `<script>` and `<style>` blocks.
+
+/// Languages whose files are single-file components that can be split with `split_component`.
+pub(crate) const COMPONENT_LANGUAGES: &[&str] = &["Vue", "Svelte"];
+
+/// A region of a file that is written in a single language.
+pub(crate) struct Region {
+    /// The name of the language of the region.
+    pub(crate) language: &'static str,
+    /// The byte range of the region in the file.
+    pub(crate) range: std::ops::Range<usize>,
+}
+
+/// Splits a single-file component into the regions of its `<script>` and `<style>` blocks and
+/// the template around them. The lines inside a block are attributed to the block's language,
+/// based on its `lang` attribute, and everything else, including the lines with the opening and
+/// closing tags, is attributed to the component's own language.
+///
+/// # Parameters
+/// - `contents` - The contents of the file
+/// - `component` - The language of the component, such as `Vue`
+///
+/// # Returns
+/// - The regions of the file in order, or `None` if the component is malformed, such as when a
+///   block is never closed.
+pub(crate) fn split_component(contents: &[u8], component: &'static str) -> Option<Vec<Region>> {
+    let lines = line_ranges(contents);
+    let mut regions = Vec::new();
+    let mut template_start = 0;
+    let mut index = 0;
+
+    while index < lines.len() {
+        let line = &contents[lines[index].clone()];
+        let Some(tag) = opening_tag(line) else {
+            index += 1;
+            continue;
+        };
+
+        // Find the end of the opening tag, which may span several lines
+        let tag_start = lines[index].start + line.iter().position(|byte| *byte == b'<')?;
+        let tag_end = tag_start + contents[tag_start..].iter().position(|byte| *byte == b'>')?;
+        let attributes = std::str::from_utf8(&contents[tag_start..tag_end]).ok()?;
+        index = lines.iter().position(|range| range.contains(&tag_end))?;
+
+        // Blocks that are closed on the same line as they are opened are part of the template
+        let closing_tag = format!("</{tag}");
+        let opening_rest = &contents[tag_end..lines[index].end];
+        if contains(opening_rest, closing_tag.as_bytes()) {
+            index += 1;
+            continue;
+        }
+
+        // Find the closing tag
+        let content_start = lines[index].end;
+        let closing_line = index
+            + 1
+            + lines[index + 1..]
+                .iter()
+                .position(|range| contains(&contents[range.clone()], closing_tag.as_bytes()))?;
+        let content_end = lines[closing_line].start;
+
+        if let Some(language) = block_language(tag, lang_attribute(attributes)) {
+            if content_start < content_end {
+                if template_start < content_start {
+                    regions.push(Region {
+                        language: component,
+                        range: template_start..content_start,
+                    });
+                }
+                regions.push(Region {
+                    language,
+                    range: content_start..content_end,
+                });
+                template_start = content_end;
+            }
+        }
+
+        index = closing_line + 1;
+    }
+
+    if template_start < contents.len() {
+        regions.push(Region {
+            language: component,
+            range: template_start..contents.len(),
+        });
+    }
+
+    Some(regions)
+}
+
+/// Returns the byte ranges of each line of some contents, including their trailing newlines.
+fn line_ranges(contents: &[u8]) -> Vec<std::ops::Range<usize>> {
+    let mut start = 0;
+    contents
+        .split_inclusive(|byte| *byte == b'\n')
+        .map(|line| {
+            let range = start..start + line.len();
+            start = range.end;
+            range
+        })
+        .collect()
+}
+
+/// Returns the name of the tag of a `<script>` or `<style>` block that is opened at the start of a
+/// line, if any.
+fn opening_tag(line: &[u8]) -> Option<&'static str> {
+    let line = line.trim_ascii_start();
+    ["script", "style"].into_iter().find(|tag| {
+        line.strip_prefix(b"<")
+            .and_then(|rest| rest.strip_prefix(tag.as_bytes()))
+            .is_some_and(|rest| rest.first().is_none_or(|byte| byte.is_ascii_whitespace() || *byte == b'>'))
+    })
+}
+
+/// Returns the value of the `lang` attribute of an opening tag, if it has one.
+fn lang_attribute(tag: &str) -> Option<&str> {
+    let value = tag.split_once("lang=")?.1;
+    match value.chars().next()? {
+        quote @ ('"' | '\'') => value[1..].split(quote).next(),
+        _ => value.split(|character: char| character.is_ascii_whitespace() || character == '/').next(),
+    }
+}
+
+/// Returns the language of a `<script>` or `<style>` block with the given `lang` attribute, or
+/// `None` if the language isn't recognized.
+fn block_language(tag: &str, lang: Option<&str>) -> Option<&'static str> {
+    let lang = lang.map(str::to_lowercase);
+    Some(match (tag, lang.as_deref()) {
+        ("script", None | Some("js" | "javascript")) => "JavaScript",
+        ("script", Some("ts" | "typescript")) => "TypeScript",
+        ("script", Some("jsx")) => "JavaScript React",
+        ("script", Some("tsx")) => "TypeScript React",
+        ("style", None | Some("css")) => "CSS",
+        ("style", Some("scss")) => "SCSS",
+        ("style", Some("sass")) => "Sass",
+        ("style", Some("less")) => "Less",
+        ("style", Some("styl" | "stylus")) => "Stylus",
+        _ => return None,
+    })
+}
+
+/// Returns whether some bytes contain the given needle.
+fn contains(haystack: &[u8], needle: &[u8]) -> bool {
+    haystack.windows(needle.len()).any(|window| window == needle)
+}
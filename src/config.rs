@@ -0,0 +1,547 @@
+/// The names of the configuration files that are loaded from the project root, in order of
+/// priority.
+pub const CONFIG_FILE_NAMES: &[&str] = &["splik.toml", ".splik.toml"];
+
+/// The default categories of languages that are counted, see `--categories`.
+pub const DEFAULT_CATEGORIES: [crate::Category; 2] = [crate::Category::Programming, crate::Category::Markup];
+
+/// Returns the path of the global configuration file of the user, which is loaded before the
+/// configuration file of the project: `splik/config.toml` in `$XDG_CONFIG_HOME` if it is set,
+/// or else in `~/.config` on Linux and other Unix systems, `~/Library/Application Support` on
+/// macOS, and `%APPDATA%` on Windows.
+///
+/// # Returns
+/// - The path, or `None` if the home directory isn't known.
+pub fn global_config_path() -> Option<std::path::PathBuf> {
+    let variable = |name: &str| std::env::var_os(name).map(std::path::PathBuf::from).filter(|path| path.is_absolute());
+    let directory = match variable("XDG_CONFIG_HOME") {
+        Some(directory) if !cfg!(windows) => directory,
+        _ if cfg!(windows) => variable("APPDATA")?,
+        _ if cfg!(target_os = "macos") => variable("HOME")?.join("Library").join("Application Support"),
+        _ => variable("HOME")?.join(".config"),
+    };
+    Some(directory.join("splik").join("config.toml"))
+}
+
+/// The contents of a configuration file, which provides defaults for the command line arguments:
+/// the `splik.toml` of a project, or the global configuration file of the user, see
+/// `global_config_path`.
+#[derive(Default, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    /// Languages to exclude, see `--exclude`.
+    pub exclude: Option<Vec<String>>,
+    /// Languages to exclude in addition to those of the configuration files below this one.
+    pub exclude_extend: Option<Vec<String>>,
+    /// Categories of languages to count, see `--categories`.
+    pub categories: Option<Vec<crate::Category>>,
+    /// Categories of languages to count in addition to those of the configuration files below
+    /// this one, or to the default ones.
+    pub categories_extend: Option<Vec<crate::Category>>,
+    /// Files and directories to include, see `--include`.
+    pub include: Option<Vec<String>>,
+    /// Files and directories to include in addition to those of the configuration files below
+    /// this one.
+    pub include_extend: Option<Vec<String>>,
+    /// Whether to include dotfiles, see `--include-dotfiles`.
+    pub include_dotfiles: Option<bool>,
+    /// The output format, see `--output`.
+    pub output: Option<OutputFormat>,
+    /// When the human-readable output is colored, see `--color`.
+    pub color: Option<ColorChoice>,
+    /// Custom project root indicators, see `--root-indicator`. These are read from the
+    /// nearest configuration file above the starting directory, rather than from the project
+    /// root's.
+    pub root_indicators: Option<Vec<String>>,
+    /// Custom project root indicators in addition to those of the configuration files below this
+    /// one.
+    pub root_indicators_extend: Option<Vec<String>>,
+    /// Names of directories to skip instead of the default ones, see
+    /// `ScanOptions::ignored_directories`.
+    pub ignored_directories: Option<Vec<String>>,
+    /// Names of directories to skip in addition to those of the configuration files below this
+    /// one, or to the default ones.
+    pub ignored_directories_extend: Option<Vec<String>>,
+    /// Globs of paths to exclude, relative to the project root.
+    pub exclude_path: Option<Vec<String>>,
+    /// Globs of paths to exclude in addition to those of the configuration files below this one.
+    pub exclude_path_extend: Option<Vec<String>>,
+    /// Globs of the paths of test files, relative to the project root, see `--tests`.
+    pub test_path: Option<Vec<String>>,
+    /// Globs of the paths of test files in addition to those of the configuration files below
+    /// this one.
+    pub test_path_extend: Option<Vec<String>>,
+    /// The markers counted by `--todos`, see `--todo-markers`.
+    pub todo_markers: Option<Vec<String>>,
+    /// The markers counted by `--todos` in addition to those of the configuration files below
+    /// this one, or to the default ones.
+    pub todo_markers_extend: Option<Vec<String>>,
+    /// Custom mappings of extensions to language names, where an empty name stops the extension
+    /// from being detected, see `--map`.
+    pub extensions: Option<std::collections::HashMap<String, String>>,
+    /// New languages, or replacements of built-in languages, keyed by their names.
+    pub languages: Option<std::collections::BTreeMap<String, LanguageConfig>>,
+    /// Whether to count dialects as their parent languages, see `--merge-dialects`.
+    pub merge_dialects: Option<bool>,
+    /// The languages of each group of `--groups`, keyed by the names of the groups.
+    pub groups: Option<std::collections::BTreeMap<String, Vec<String>>>,
+    /// The configuration files that set each argument, keyed by the ids of the arguments, see
+    /// `--explain-config`.
+    #[serde(skip)]
+    pub origins: std::collections::BTreeMap<&'static str, Vec<std::path::PathBuf>>,
+}
+
+impl Config {
+    /// Returns the path of the configuration file of the given project root, if there is one.
+    pub fn path(root: &std::path::Path) -> Option<std::path::PathBuf> {
+        CONFIG_FILE_NAMES.iter().map(|name| root.join(name)).find(|path| path.is_file())
+    }
+
+    /// Loads the global configuration file and the configuration file of the given project root,
+    /// and layers the project's over the global one, see `layer_over`.
+    ///
+    /// # Parameters
+    /// - `root` - The project root directory, or `None` to only load the global configuration file
+    ///
+    /// # Returns
+    /// - The configuration, or `None` if there is neither configuration file.
+    ///
+    /// # Errors
+    /// - If a configuration file couldn't be read or parsed, with a message naming the file and
+    ///   the offending key.
+    pub fn load_layers(root: Option<&std::path::Path>) -> std::io::Result<Option<Self>> {
+        let global = global_config_path().filter(|path| path.is_file());
+        let project = root.and_then(Self::path);
+        let paths = global.into_iter().chain(project).collect::<Vec<_>>();
+        let Some(last) = paths.last() else {
+            return Ok(None);
+        };
+
+        let mut config = Self::default();
+        for path in &paths {
+            config = Self::load(path)?.layer_over(config);
+        }
+        config
+            .check_languages()
+            .map_err(|(key, message)| invalid(last, format!("`{key}`: {message}")))?;
+        Ok(Some(config))
+    }
+
+    /// Layers this configuration over another one that it takes priority over, such as the
+    /// configuration file of a project over the global one. Values of this configuration replace
+    /// those of the other one, lists given with an `_extend` key, such as `exclude_extend`, are
+    /// added to the lists of the other one (or to the defaults), and the `extensions`, `languages`,
+    /// and `groups` of both are merged.
+    fn layer_over(self, mut base: Self) -> Self {
+        /// Returns a list that replaces, or is extended by, the list of a lower configuration.
+        fn list<T>(
+            base: Option<Vec<T>>,
+            replace: Option<Vec<T>>,
+            extend: Option<Vec<T>>,
+            default: impl FnOnce() -> Vec<T>,
+        ) -> Option<Vec<T>> {
+            let mut list = replace.or(base);
+            if let Some(extend) = extend {
+                list.get_or_insert_with(default).extend(extend);
+            }
+            list
+        }
+
+        /// Returns the union of a map and the map of a lower configuration, with the entries of
+        /// this one taking priority.
+        fn map<M: Extend<T> + IntoIterator<Item = T>, T>(base: Option<M>, over: Option<M>) -> Option<M> {
+            match (base, over) {
+                (Some(mut base), Some(over)) => {
+                    base.extend(over);
+                    Some(base)
+                }
+                (base, over) => over.or(base),
+            }
+        }
+
+        let replaces = [
+            ("exclude", self.exclude.is_some()),
+            ("categories", self.categories.is_some()),
+            ("include", self.include.is_some()),
+        ];
+        for (id, paths) in self.origins {
+            let replaced = replaces.iter().all(|&(list, replaced)| list != id || replaced);
+            let origins = base.origins.entry(id).or_default();
+            if replaced {
+                origins.clear();
+            }
+            origins.extend(paths);
+        }
+
+        let owned = |names: &[&str]| names.iter().map(|name| (*name).to_owned()).collect();
+        Self {
+            exclude: list(base.exclude, self.exclude, self.exclude_extend, Vec::new),
+            exclude_extend: None,
+            categories: list(base.categories, self.categories, self.categories_extend, || {
+                DEFAULT_CATEGORIES.to_vec()
+            }),
+            categories_extend: None,
+            include: list(base.include, self.include, self.include_extend, Vec::new),
+            include_extend: None,
+            include_dotfiles: self.include_dotfiles.or(base.include_dotfiles),
+            output: self.output.or(base.output),
+            color: self.color.or(base.color),
+            root_indicators: list(
+                base.root_indicators,
+                self.root_indicators,
+                self.root_indicators_extend,
+                Vec::new,
+            ),
+            root_indicators_extend: None,
+            ignored_directories: list(
+                base.ignored_directories,
+                self.ignored_directories,
+                self.ignored_directories_extend,
+                || owned(crate::IGNORED_DIRECTORIES),
+            ),
+            ignored_directories_extend: None,
+            exclude_path: list(base.exclude_path, self.exclude_path, self.exclude_path_extend, Vec::new),
+            exclude_path_extend: None,
+            test_path: list(base.test_path, self.test_path, self.test_path_extend, Vec::new),
+            test_path_extend: None,
+            todo_markers: list(base.todo_markers, self.todo_markers, self.todo_markers_extend, || {
+                owned(crate::TODO_MARKERS)
+            }),
+            todo_markers_extend: None,
+            extensions: map(base.extensions, self.extensions),
+            languages: map(base.languages, self.languages),
+            merge_dialects: self.merge_dialects.or(base.merge_dialects),
+            groups: map(base.groups, self.groups),
+            origins: base.origins,
+        }
+    }
+
+    /// Loads a configuration file.
+    ///
+    /// # Parameters
+    /// - `path` - The path of the configuration file
+    ///
+    /// # Returns
+    /// - The configuration.
+    ///
+    /// # Errors
+    /// - If the file couldn't be read or parsed, with a message naming the file and the offending
+    ///   key.
+    fn load(path: &std::path::Path) -> std::io::Result<Self> {
+        let contents = std::fs::read_to_string(path)
+            .map_err(|error| std::io::Error::new(error.kind(), format!("couldn't read {}: {error}", path.display())))?;
+        let deserializer = toml::Deserializer::parse(&contents).map_err(|error| invalid(path, error.message()))?;
+        let mut config = serde_path_to_error::deserialize::<_, Self>(deserializer)
+            .map_err(|error| invalid(path, format!("`{}`: {}", error.path(), error.inner().message())))?;
+        config
+            .check_languages()
+            .map_err(|(key, message)| invalid(path, format!("`{key}`: {message}")))?;
+
+        let settings = [
+            ("exclude", config.exclude.is_some() || config.exclude_extend.is_some()),
+            (
+                "categories",
+                config.categories.is_some() || config.categories_extend.is_some(),
+            ),
+            ("include", config.include.is_some() || config.include_extend.is_some()),
+            ("include_dotfiles", config.include_dotfiles.is_some()),
+            ("output", config.output.is_some()),
+            ("color", config.color.is_some()),
+            ("merge_dialects", config.merge_dialects.is_some()),
+        ];
+        for (id, _) in settings.into_iter().filter(|&(_, set)| set) {
+            config.origins.insert(id, vec![path.to_owned()]);
+        }
+        Ok(config)
+    }
+
+    /// Checks that the `languages` of the configuration are valid, with names, files to detect,
+    /// valid colors, non-empty comment markers, and ASCII quotes, and that no two of them claim
+    /// the same file.
+    ///
+    /// # Returns
+    /// - The key of the first invalid value, such as `languages.Flow.color`, and what is wrong
+    ///   with it.
+    fn check_languages(&self) -> Result<(), (String, String)> {
+        let mut extensions = std::collections::HashMap::new();
+        let mut filenames = std::collections::HashMap::new();
+        for (name, language) in self.languages.iter().flatten() {
+            let key = |field: &str| format!("languages.{name}{field}");
+            if name.trim().is_empty() {
+                return Err(("languages".to_owned(), "language names can't be empty".to_owned()));
+            }
+            if language.extensions.is_empty() && language.filenames.is_empty() {
+                return Err((
+                    key(""),
+                    "expected `extensions` or `filenames` to detect the language by".to_owned(),
+                ));
+            }
+            for extension in &language.extensions {
+                let extension = extension.trim_start_matches('.');
+                if extension.is_empty() {
+                    return Err((key(".extensions"), "extensions can't be empty".to_owned()));
+                }
+                if let Some(other) = extensions.insert(extension, name) {
+                    let message = format!("the extension \"{extension}\" is already an extension of {other}");
+                    return Err((key(".extensions"), message));
+                }
+            }
+            for filename in &language.filenames {
+                if filename.is_empty() {
+                    return Err((key(".filenames"), "file names can't be empty".to_owned()));
+                }
+                if let Some(other) = filenames.insert(filename, name) {
+                    let message = format!("the file name \"{filename}\" is already a file name of {other}");
+                    return Err((key(".filenames"), message));
+                }
+            }
+            if let Some(color) = &language.color {
+                let digits = color.strip_prefix('#').unwrap_or_default();
+                if digits.len() != 6 || !digits.chars().all(|digit| digit.is_ascii_hexdigit()) {
+                    let message = format!("expected a hex color such as \"#ff8800\", found \"{color}\"");
+                    return Err((key(".color"), message));
+                }
+            }
+            if language.line_comments.iter().any(String::is_empty) {
+                return Err((key(".line_comments"), "comment markers can't be empty".to_owned()));
+            }
+            if language.block_comments.iter().any(|(start, end)| start.is_empty() || end.is_empty()) {
+                return Err((key(".block_comments"), "comment markers can't be empty".to_owned()));
+            }
+            if language.quotes.iter().any(|quote| !quote.is_ascii_punctuation()) {
+                return Err((
+                    key(".quotes"),
+                    "quotes must be ASCII punctuation such as \"'\"".to_owned(),
+                ));
+            }
+            if language.nested_comments && language.block_comments.is_empty() {
+                return Err((
+                    key(".nested_comments"),
+                    "only block comments can be nested, but there are no `block_comments`".to_owned(),
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Returns the error of an invalid configuration file.
+///
+/// # Parameters
+/// - `path` - The path of the configuration file
+/// - `message` - What is wrong with it, such as the offending key and its problem
+fn invalid(path: &std::path::Path, message: impl std::fmt::Display) -> std::io::Error {
+    std::io::Error::new(
+        std::io::ErrorKind::InvalidData,
+        format!("invalid config file {}: {message}", path.display()),
+    )
+}
+
+/// A language defined in the `languages` of a configuration file, see `LanguageDefinition`.
+#[derive(serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct LanguageConfig {
+    /// The extensions of the files of the language, with or without a leading dot.
+    #[serde(default)]
+    pub extensions: Vec<String>,
+    /// The exact names of the files of the language.
+    #[serde(default)]
+    pub filenames: Vec<String>,
+    /// The category of the language, which is `programming` if it isn't given.
+    pub category: Option<crate::Category>,
+    /// The color of the language, as a hex code such as `#ff8800`.
+    pub color: Option<String>,
+    /// Markers that begin a comment running until the end of the line, such as `#`.
+    #[serde(default)]
+    pub line_comments: Vec<String>,
+    /// The start and end markers of block comments, such as `["/*", "*/"]`.
+    #[serde(default)]
+    pub block_comments: Vec<(String, String)>,
+    /// Whether block comments can be nested inside of each other.
+    #[serde(default)]
+    pub nested_comments: bool,
+    /// The characters that begin and end string literals, such as `["\"", "'"]`, inside of which
+    /// comment markers aren't recognized.
+    #[serde(default)]
+    pub quotes: Vec<char>,
+}
+
+impl LanguageConfig {
+    /// Returns the definition of this language, for `define_languages`. The names and markers of
+    /// the language live for the rest of the process, like the languages of `LANGUAGES`.
+    ///
+    /// # Parameters
+    /// - `name` - The name the language is defined under
+    pub fn definition(self, name: String) -> crate::LanguageDefinition {
+        let leak = |text: String| -> &'static str { Box::leak(text.into_boxed_str()) };
+        let has_comments = !self.line_comments.is_empty() || !self.block_comments.is_empty();
+        crate::LanguageDefinition {
+            name: leak(name),
+            extensions: self
+                .extensions
+                .iter()
+                .map(|extension| extension.trim_start_matches('.').to_owned())
+                .collect(),
+            filenames: self.filenames,
+            category: self.category.unwrap_or(crate::Category::Programming),
+            color: self.color.map(leak),
+            comments: has_comments.then(|| crate::CommentSyntax {
+                line: self.line_comments.into_iter().map(leak).collect::<Vec<_>>().leak(),
+                block: self
+                    .block_comments
+                    .into_iter()
+                    .map(|(start, end)| (leak(start), leak(end)))
+                    .collect::<Vec<_>>()
+                    .leak(),
+                nested: self.nested_comments,
+                quotes: self.quotes.iter().map(|quote| *quote as u8).collect::<Vec<_>>().leak(),
+            }),
+        }
+    }
+}
+
+/// The formats a report can be written in, see `--output`.
+#[derive(Clone, Debug, serde::Deserialize)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+#[serde(rename_all = "kebab-case")]
+pub enum OutputFormat {
+    HumanReadable,
+    Json,
+    Yaml,
+    Jsonl,
+    Toml,
+    Xml,
+    Html,
+    Svg,
+    Mermaid,
+    Badge,
+    Prometheus,
+    TokeiJson,
+    ClocJson,
+    Csv,
+    Markdown,
+    GhSummary,
+    GhAnnotations,
+}
+
+impl OutputFormat {
+    /// Returns whether this format can write the differences of `--baseline`, which the formats
+    /// of other tools, the HTML page, the charts, badges, and metrics have no place for.
+    pub fn compares(&self) -> bool {
+        !matches!(
+            self,
+            Self::Html | Self::Svg | Self::Mermaid | Self::Badge | Self::Prometheus | Self::TokeiJson | Self::ClocJson
+        )
+    }
+
+    /// Returns whether this format lists the paths of the files of each language, unless
+    /// `--no-files` is given.
+    pub fn lists_paths(&self) -> bool {
+        matches!(
+            self,
+            Self::Json | Self::Yaml | Self::Toml | Self::Xml | Self::Html | Self::TokeiJson
+        )
+    }
+
+    /// Returns whether this format can write the languages of `--list-languages`, which only the
+    /// general purpose formats can.
+    pub fn lists_languages(&self) -> bool {
+        matches!(
+            self,
+            Self::HumanReadable
+                | Self::Json
+                | Self::Yaml
+                | Self::Jsonl
+                | Self::Toml
+                | Self::Xml
+                | Self::Csv
+                | Self::Markdown
+        )
+    }
+}
+
+/// When the human-readable output is colored, see `--color`.
+#[derive(Clone, serde::Deserialize)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+#[serde(rename_all = "kebab-case")]
+pub enum ColorChoice {
+    /// Only when stdout is a terminal and `NO_COLOR` isn't set.
+    Auto,
+    /// Always, even when stdout isn't a terminal.
+    Always,
+    /// Never.
+    Never,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Config;
+
+    /// Writes a configuration file in a new temporary directory and loads it.
+    fn load(name: &str, contents: &str) -> std::io::Result<Config> {
+        let directory = std::env::temp_dir().join(format!("splik-test-{}-{name}", std::process::id()));
+        std::fs::create_dir_all(&directory).unwrap();
+        let path = directory.join("splik.toml");
+        std::fs::write(&path, contents).unwrap();
+        let config = Config::load(&path);
+        std::fs::remove_dir_all(&directory).unwrap();
+        config
+    }
+
+    #[test]
+    fn project_values_replace_global_ones() {
+        let global = load("replace-global", "exclude = [\"Markdown\"]\ninclude_dotfiles = true").unwrap();
+        let project = load("replace-project", "exclude = [\"JSON\"]").unwrap();
+        let config = project.layer_over(global);
+        assert_eq!(config.exclude, Some(vec!["JSON".to_owned()]));
+        assert_eq!(config.include_dotfiles, Some(true));
+        assert!(config.origins["exclude"][0].ends_with("splik.toml"));
+        assert_eq!(config.origins["exclude"].len(), 1);
+    }
+
+    #[test]
+    fn extended_lists_are_added() {
+        let global = load("extend-global", "exclude = [\"Markdown\"]").unwrap();
+        let project = load(
+            "extend-project",
+            "exclude_extend = [\"JSON\"]\ncategories_extend = [\"data\"]",
+        )
+        .unwrap();
+        let config = project.layer_over(global);
+        assert_eq!(config.exclude, Some(vec!["Markdown".to_owned(), "JSON".to_owned()]));
+        assert_eq!(config.exclude_extend, None);
+        assert_eq!(config.origins["exclude"].len(), 2);
+
+        // Extending a list that no file sets adds to the defaults
+        let mut categories = super::DEFAULT_CATEGORIES.to_vec();
+        categories.push(crate::Category::Data);
+        assert_eq!(config.categories, Some(categories));
+    }
+
+    #[test]
+    fn maps_are_merged() {
+        let global = load("merge-global", "[extensions]\nm = \"MATLAB\"\nh = \"C\"").unwrap();
+        let project = load("merge-project", "[extensions]\nh = \"C++\"").unwrap();
+        let extensions = project.layer_over(global).extensions.unwrap();
+        assert_eq!(extensions["m"], "MATLAB");
+        assert_eq!(extensions["h"], "C++");
+    }
+
+    #[test]
+    fn errors_name_the_offending_key() {
+        let error = load("unknown", "exclude = [\"Markdown\"]\noutptu = \"json\"").err().unwrap();
+        assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+        assert!(
+            error.to_string().contains("splik.toml: `outptu`: unknown field"),
+            "{error}"
+        );
+
+        let error = load("color", "[languages.Flow]\nextensions = [\"flow\"]\ncolor = \"orange\"")
+            .err()
+            .unwrap();
+        assert!(
+            error.to_string().contains("`languages.Flow.color`: expected a hex color"),
+            "{error}"
+        );
+    }
+}
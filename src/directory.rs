@@ -0,0 +1,276 @@
+//! The results of a scan broken down by directory, see `scan_by_directory`.
+
+/// The languages found by `scan_by_directory`, for each directory that was counted separately.
+#[derive(Default)]
+pub struct DirectoryList {
+    /// The languages of each directory, keyed by the directory's path relative to the scan root.
+    /// Files directly in the scan root are under `.`.
+    pub directories: std::collections::BTreeMap<String, crate::LanguageList>,
+    /// Whether directories deeper than `ScanOptions::max_depth` were left out of the scan, see
+    /// `LanguageList::depth_limited`.
+    pub depth_limited: bool,
+    /// The directories that were skipped by name, see `LanguageList::ignored_directories`.
+    pub ignored_directories: Vec<String>,
+    /// The nested projects that were skipped, see `LanguageList::skipped_projects`.
+    pub skipped_projects: Vec<String>,
+    /// How long the scan took, if it was timed, see `LanguageList::timing`.
+    pub timing: Option<crate::Timing>,
+}
+
+impl DirectoryList {
+    /// Returns the language list of the given directory, inserting an empty one if that directory
+    /// hasn't been found before.
+    pub(crate) fn get_or_insert(&mut self, directory: &str) -> &mut crate::LanguageList {
+        if !self.directories.contains_key(directory) {
+            self.directories.insert(directory.to_owned(), crate::LanguageList::default());
+        }
+        self.directories.get_mut(directory).unwrap()
+    }
+
+    /// Merges another directory list into this one, merging the language lists of directories
+    /// that appear in both.
+    pub fn merge(&mut self, other: DirectoryList) {
+        for (directory, languages) in other.directories {
+            self.get_or_insert(&directory).merge(languages);
+        }
+        self.depth_limited |= other.depth_limited;
+        self.ignored_directories.extend(other.ignored_directories);
+        self.skipped_projects.extend(other.skipped_projects);
+    }
+
+    /// Returns the languages of every directory combined, such as those of the only directory of a
+    /// scan that didn't break its counts down by directory, or an empty list if nothing was found.
+    pub fn into_languages(self) -> crate::LanguageList {
+        let mut languages = crate::LanguageList::default();
+        for directory_languages in self.directories.into_values() {
+            languages.merge(directory_languages);
+        }
+        languages.depth_limited |= self.depth_limited;
+        languages.ignored_directories.extend(self.ignored_directories);
+        languages.skipped_projects.extend(self.skipped_projects);
+        languages.timing = self.timing;
+        languages
+    }
+
+    /// Moves every directory of this list below the given path, for combining the scans of several
+    /// roots, so that `.` becomes the path itself, and `src` becomes `<path>/src`.
+    ///
+    /// # Parameters
+    /// - `path` - The path to move the directories below
+    /// - `files` - Whether to move the paths of the counted files below the path too, which should
+    ///   only be done when they are relative to the scan root
+    pub fn nest_under(&mut self, path: &str, files: bool) {
+        let path = path.trim_end_matches(['/', '\\']);
+        let path = if path.is_empty() { "." } else { path };
+        let directories = std::mem::take(&mut self.directories);
+        for (directory, mut languages) in directories {
+            if files {
+                for language_info in &mut languages.languages {
+                    for file in &mut language_info.files {
+                        file.path = format!("{path}/{}", file.path);
+                    }
+                }
+            }
+            let directory = if directory == "." {
+                path.to_owned()
+            } else {
+                format!("{path}/{directory}")
+            };
+            self.get_or_insert(&directory).merge(languages);
+        }
+    }
+
+    /// Removes the directories that had no counted or skipped files, such as directories that only
+    /// contain files of excluded languages.
+    pub(crate) fn remove_empty(&mut self) {
+        self.directories.retain(|_, languages| {
+            !languages.languages.is_empty()
+                || !languages.skipped.is_empty()
+                || languages.skipped_large_files != 0
+                || languages.binary_skipped != 0
+                || !languages.generated.is_empty()
+                || !languages.unknown.is_empty()
+        });
+    }
+
+    /// Counts the dialects of each directory as their parent languages, see
+    /// `LanguageList::merge_dialects`.
+    pub fn merge_dialects(&mut self) {
+        for languages in self.directories.values_mut() {
+            languages.merge_dialects();
+        }
+    }
+
+    /// Counts the languages of each group of each directory together, see `LanguageList::group`.
+    pub fn group(&mut self, groups: &[crate::LanguageGroup]) {
+        for languages in self.directories.values_mut() {
+            languages.group(groups);
+        }
+    }
+
+    /// Calculates the statistics of the files of each language of each directory, see
+    /// `LanguageList::compute_stats`.
+    pub fn compute_stats(&mut self) {
+        for languages in self.directories.values_mut() {
+            languages.compute_stats();
+        }
+    }
+
+    /// Sorts the languages of each directory, see `LanguageList::sort`.
+    pub fn sort(&mut self, key: crate::SortKey, reverse: bool) {
+        for languages in self.directories.values_mut() {
+            languages.sort(key, reverse);
+        }
+    }
+
+    /// Calculates the total files, lines, and bytes across all directories. These are the same as
+    /// the totals of a whole-repository scan.
+    pub fn totals(&self) -> crate::Totals {
+        let mut totals = crate::Totals::default();
+        for languages in self.directories.values() {
+            for language_info in &languages.languages {
+                totals.add(language_info);
+            }
+        }
+        totals
+    }
+
+    /// Calculates the totals of the test files across all directories, or returns `None` if test
+    /// files weren't counted separately, see `LanguageList::test_totals`.
+    pub fn test_totals(&self) -> Option<crate::Totals> {
+        let mut tests = None;
+        for directory_tests in self.directories.values().filter_map(crate::LanguageList::test_totals) {
+            tests.get_or_insert_with(crate::Totals::default).add_totals(&directory_tests);
+        }
+        tests
+    }
+
+    /// Calculates the line endings and indentation of the files across all directories, or returns
+    /// `None` if they weren't tallied, see `LanguageList::style_totals`.
+    pub fn style_totals(&self) -> Option<crate::StyleStats> {
+        let mut style = None;
+        for directory_style in self.directories.values().filter_map(crate::LanguageList::style_totals) {
+            style.get_or_insert_with(crate::StyleStats::default).add_stats(&directory_style);
+        }
+        style
+    }
+
+    /// Calculates the encodings of the files across all directories, or returns `None` if they
+    /// weren't tallied, see `LanguageList::encoding_totals`.
+    pub fn encoding_totals(&self) -> Option<crate::EncodingStats> {
+        let mut encodings = None;
+        for directory_encodings in self.directories.values().filter_map(crate::LanguageList::encoding_totals) {
+            encodings
+                .get_or_insert_with(crate::EncodingStats::default)
+                .add_stats(&directory_encodings);
+        }
+        encodings
+    }
+
+    /// Returns whether files were counted without counting their lines in any directory, see
+    /// `LanguageList::lines_skipped`.
+    pub fn lines_skipped(&self) -> bool {
+        self.directories.values().any(|languages| languages.lines_skipped)
+    }
+
+    /// Returns the number of files that were skipped for being too large in every directory, see
+    /// `LanguageList::skipped_large_files`.
+    pub fn skipped_large_files(&self) -> usize {
+        self.directories.values().map(|languages| languages.skipped_large_files).sum()
+    }
+
+    /// Returns the number of files that were skipped for being binary in every directory, see
+    /// `LanguageList::binary_skipped`.
+    pub fn binary_skipped(&self) -> usize {
+        self.directories.values().map(|languages| languages.binary_skipped).sum()
+    }
+
+    /// Returns the files that were left out for being generated or minified in every directory, see
+    /// `LanguageList::generated`.
+    pub fn generated(&self) -> crate::GeneratedFiles {
+        let mut generated = crate::GeneratedFiles::default();
+        for languages in self.directories.values() {
+            generated.add(languages.generated);
+        }
+        generated
+    }
+
+    /// Returns the files whose language wasn't detected in every directory, see
+    /// `LanguageList::unknown`.
+    pub fn unknown(&self) -> std::collections::BTreeMap<String, crate::UnknownFiles> {
+        let mut unknown = std::collections::BTreeMap::<String, crate::UnknownFiles>::new();
+        for languages in self.directories.values() {
+            for (extension, files) in &languages.unknown {
+                unknown.entry(extension.clone()).or_default().add(*files);
+            }
+        }
+        unknown
+    }
+
+    /// Returns the files and directories that couldn't be read in every directory.
+    pub fn skipped(&self) -> impl Iterator<Item = &crate::SkippedPath> {
+        self.directories.values().flat_map(|languages| &languages.skipped)
+    }
+
+    /// Returns the serializable report of this list, which is what the JSON and YAML output
+    /// formats print.
+    ///
+    /// # Parameters
+    /// - `top` - The number of languages to include in the report of each directory, see
+    ///   `LanguageList::report`.
+    pub fn report(&self, top: Option<usize>) -> DirectoryReport<'_> {
+        DirectoryReport {
+            schema_version: crate::SCHEMA_VERSION,
+            directories: self
+                .directories
+                .iter()
+                .map(|(directory, languages)| (directory.as_str(), languages.report(top)))
+                .collect(),
+            totals: self.totals(),
+            tests: self.test_totals(),
+            depth_limited: self.depth_limited,
+            skipped_large_files: self.skipped_large_files(),
+            binary_skipped: self.binary_skipped(),
+            generated: self.generated(),
+            unknown: crate::list::unknown_extensions(&self.unknown()),
+            timing: self.timing,
+        }
+    }
+}
+
+/// The serialized form of a `DirectoryList`.
+#[derive(serde::Serialize)]
+pub struct DirectoryReport<'a> {
+    /// The version of this structure, see `SCHEMA_VERSION`.
+    pub schema_version: u32,
+    /// The report of each directory, keyed by the directory's path relative to the scan root.
+    pub directories: std::collections::BTreeMap<&'a str, crate::Report<'a>>,
+    /// The totals across all directories.
+    pub totals: crate::Totals,
+    /// The totals of the test files across all directories, see `Report::tests`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tests: Option<crate::Totals>,
+    /// Whether directories deeper than `--max-depth` weren't scanned. This is left out when it is
+    /// false.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub depth_limited: bool,
+    /// The number of files skipped for being larger than `--max-file-size` across all
+    /// directories. This is left out when it is 0.
+    #[serde(skip_serializing_if = "crate::list::is_zero")]
+    pub skipped_large_files: usize,
+    /// The number of files skipped for being binary across all directories. This is left out when
+    /// it is 0.
+    #[serde(skip_serializing_if = "crate::list::is_zero")]
+    pub binary_skipped: usize,
+    /// The files left out for being generated or minified across all directories. This is left
+    /// out when there are none.
+    #[serde(skip_serializing_if = "crate::GeneratedFiles::is_empty")]
+    pub generated: crate::GeneratedFiles,
+    /// The files whose language wasn't detected across all directories, see `Report::unknown`.
+    /// This is left out when there are none.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub unknown: Vec<crate::UnknownExtension>,
+    /// How long the scan took, see `Report::timing`. This is left out when the scan wasn't timed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timing: Option<crate::Timing>,
+}
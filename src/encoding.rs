@@ -0,0 +1,174 @@
+//! Detecting the encodings of files by their byte order marks, and decoding UTF-16 files as they
+//! are read, so that their lines are counted like those of UTF-8 files.
+
+/// The number of bytes read from a UTF-16 file at a time when decoding it.
+const DECODE_BUFFER_SIZE: usize = 8 * 1024;
+
+/// The encoding of a file, see `ScanOptions::encoding_stats`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, serde::Serialize, serde::Deserialize)]
+pub enum Encoding {
+    /// UTF-8, with or without a byte order mark, which includes plain ASCII.
+    #[serde(rename = "utf-8")]
+    Utf8,
+    /// UTF-16 in little-endian byte order, as found by its byte order mark.
+    #[serde(rename = "utf-16le")]
+    Utf16Le,
+    /// UTF-16 in big-endian byte order, as found by its byte order mark.
+    #[serde(rename = "utf-16be")]
+    Utf16Be,
+    /// Anything that isn't valid UTF-8 and doesn't start with a UTF-16 byte order mark, such as
+    /// Latin-1. These files are counted byte by byte like UTF-8 files.
+    #[serde(rename = "other")]
+    Other,
+}
+
+/// Returns the encoding of a file that starts with a UTF-16 byte order mark, or `None` if it
+/// doesn't start with one.
+pub(crate) fn utf16_encoding(prefix: &[u8]) -> Option<Encoding> {
+    if prefix.starts_with(&[0xFF, 0xFE]) {
+        Some(Encoding::Utf16Le)
+    } else if prefix.starts_with(&[0xFE, 0xFF]) {
+        Some(Encoding::Utf16Be)
+    } else {
+        None
+    }
+}
+
+/// Decodes a UTF-16 file into UTF-8 as it is read, a buffer at a time, so that the whole file is
+/// never held in memory. Unpaired surrogates and a trailing odd byte are decoded as U+FFFD.
+pub(crate) struct Utf16Decoder<R> {
+    /// The UTF-16 contents of the file, after its byte order mark.
+    reader: R,
+    /// Whether the code units are big-endian.
+    big_endian: bool,
+    /// The bytes read from `reader` that haven't been decoded yet, because they don't make up a
+    /// whole code unit, or are a high surrogate whose low surrogate hasn't been read yet.
+    undecoded: Vec<u8>,
+    /// The UTF-8 bytes that were decoded, which are read from `position` on.
+    decoded: Vec<u8>,
+    /// The position in `decoded` of the next byte to read.
+    position: usize,
+    /// The number of decoded bytes that have been read, see `decoded_bytes`.
+    decoded_bytes: u64,
+}
+
+impl<R: std::io::Read> Utf16Decoder<R> {
+    /// Creates a decoder for the UTF-16 contents of a file, after its byte order mark.
+    ///
+    /// # Parameters
+    /// - `reader` - The contents of the file after its byte order mark
+    /// - `encoding` - The encoding of the file, which must be `Encoding::Utf16Le` or
+    ///   `Encoding::Utf16Be`
+    pub(crate) fn new(reader: R, encoding: Encoding) -> Self {
+        Self {
+            reader,
+            big_endian: encoding == Encoding::Utf16Be,
+            undecoded: Vec::new(),
+            decoded: Vec::new(),
+            position: 0,
+            decoded_bytes: 0,
+        }
+    }
+
+    /// Returns the number of UTF-8 bytes that have been read from the decoder, which is the size of
+    /// the file as UTF-8 once it has been read entirely.
+    pub(crate) fn decoded_bytes(&self) -> u64 {
+        self.decoded_bytes
+    }
+
+    /// Decodes the whole code units of `undecoded` into `decoded`, keeping a trailing high
+    /// surrogate for the next read, since its low surrogate may follow.
+    fn decode(&mut self) {
+        let whole = self.undecoded.len() / 2 * 2;
+        let mut units = self.undecoded[..whole]
+            .chunks_exact(2)
+            .map(|pair| {
+                let pair = [pair[0], pair[1]];
+                if self.big_endian {
+                    u16::from_be_bytes(pair)
+                } else {
+                    u16::from_le_bytes(pair)
+                }
+            })
+            .collect::<Vec<_>>();
+        let mut decoded_length = whole;
+        if units.last().is_some_and(|unit| (0xD800..0xDC00).contains(unit)) {
+            units.pop();
+            decoded_length -= 2;
+        }
+
+        self.decoded.clear();
+        self.position = 0;
+        let mut encoded = [0; 4];
+        for character in char::decode_utf16(units) {
+            let character = character.unwrap_or(char::REPLACEMENT_CHARACTER);
+            self.decoded.extend_from_slice(character.encode_utf8(&mut encoded).as_bytes());
+        }
+        self.undecoded.drain(..decoded_length);
+    }
+}
+
+impl<R: std::io::Read> std::io::Read for Utf16Decoder<R> {
+    fn read(&mut self, buffer: &mut [u8]) -> std::io::Result<usize> {
+        while self.position == self.decoded.len() {
+            let mut input = [0; DECODE_BUFFER_SIZE];
+            let read = self.reader.read(&mut input)?;
+
+            // At the end of the file, whatever couldn't be decoded is a single invalid character
+            if read == 0 {
+                if self.undecoded.is_empty() {
+                    return Ok(0);
+                }
+                self.undecoded.clear();
+                self.decoded.clear();
+                self.position = 0;
+                let mut encoded = [0; 4];
+                self.decoded
+                    .extend_from_slice(char::REPLACEMENT_CHARACTER.encode_utf8(&mut encoded).as_bytes());
+                break;
+            }
+            self.undecoded.extend_from_slice(&input[..read]);
+            self.decode();
+        }
+
+        let length = buffer.len().min(self.decoded.len() - self.position);
+        buffer[..length].copy_from_slice(&self.decoded[self.position..self.position + length]);
+        self.position += length;
+        self.decoded_bytes += length as u64;
+        Ok(length)
+    }
+}
+
+/// The encodings of the files of a language, see `LanguageInfo::encodings`.
+#[derive(Default, Clone, Debug, PartialEq, Eq, serde::Serialize)]
+pub struct EncodingStats {
+    /// The number of files in UTF-8, including plain ASCII.
+    pub utf8_files: usize,
+    /// The number of files in UTF-16, in either byte order.
+    pub utf16_files: usize,
+    /// The number of files that aren't valid UTF-8 and don't have a UTF-16 byte order mark.
+    pub other_files: usize,
+}
+
+impl EncodingStats {
+    /// Adds a file of the given encoding to these statistics.
+    pub fn add_file(&mut self, encoding: Encoding) {
+        match encoding {
+            Encoding::Utf8 => self.utf8_files += 1,
+            Encoding::Utf16Le | Encoding::Utf16Be => self.utf16_files += 1,
+            Encoding::Other => self.other_files += 1,
+        }
+    }
+
+    /// Adds other statistics to these statistics.
+    pub fn add_stats(&mut self, other: &EncodingStats) {
+        self.utf8_files += other.utf8_files;
+        self.utf16_files += other.utf16_files;
+        self.other_files += other.other_files;
+    }
+
+    /// Returns the number of files that aren't UTF-8.
+    pub fn non_utf8_files(&self) -> usize {
+        self.utf16_files + self.other_files
+    }
+}
@@ -0,0 +1,394 @@
+//! Explaining why a scan counts a file or leaves it out, rule by rule, see `explain`.
+
+/// Why a scan counts a file or leaves it out, see `explain`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Explanation {
+    /// The path of the file relative to the scan root.
+    pub path: String,
+    /// Each rule that the file was checked against, in the order that the scan applies them.
+    pub checks: Vec<RuleCheck>,
+    /// The language of the file, or `None` if it wasn't detected.
+    pub language: Option<&'static str>,
+    /// The index in `checks` of the rule that leaves the file out, which is the first one that the
+    /// scan would apply if several of them would, or `None` if the file is counted.
+    pub reason: Option<usize>,
+}
+
+/// The verdict of a rule of a scan for a file, see `Explanation::checks`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RuleCheck {
+    /// The name of the rule, such as `dotfiles`.
+    pub rule: &'static str,
+    /// What the rule found, such as ``.github` is a dotfile``.
+    pub verdict: String,
+    /// Whether the rule leaves the file out.
+    pub skips: bool,
+}
+
+impl Explanation {
+    /// Returns whether the file is counted, which is the case when no rule leaves it out.
+    pub fn is_counted(&self) -> bool {
+        self.reason.is_none()
+    }
+
+    /// Returns the rule that leaves the file out, or `None` if it is counted.
+    pub fn reason(&self) -> Option<&RuleCheck> {
+        self.reason.map(|reason| &self.checks[reason])
+    }
+}
+
+/// The checks of an explanation, along with which of them leaves the file out first.
+#[derive(Default)]
+struct Checks {
+    /// The checks so far.
+    checks: Vec<RuleCheck>,
+    /// The directory that the earliest check of the directories containing the file skips, and
+    /// the index of that check. Directories are checked from the root down, so a check of a
+    /// directory closer to the root is applied before any check of the directories below it.
+    directory_skip: Option<(usize, usize)>,
+}
+
+impl Checks {
+    /// Checks a rule that the scan applies to every directory containing the file, and to the file
+    /// itself, from the root down.
+    ///
+    /// # Parameters
+    /// - `rule` - The name of the rule
+    /// - `paths` - The paths of the directories containing the file from the root down, followed
+    ///   by the path of the file
+    /// - `pass` - The verdict of the rule if it doesn't skip any of the paths
+    /// - `skips` - Returns the verdict of the rule for the path at the given index, if it skips it
+    fn paths(
+        &mut self,
+        rule: &'static str,
+        paths: &[std::path::PathBuf],
+        pass: impl Into<String>,
+        skips: impl Fn(usize, &std::path::Path) -> Option<String>,
+    ) {
+        let skip = paths.iter().enumerate().find_map(|(index, path)| Some((index, skips(index, path)?)));
+        let Some((index, verdict)) = skip else {
+            return self.file(rule, Ok(pass.into()));
+        };
+        if self.directory_skip.is_none_or(|(skipped, _)| index < skipped) {
+            self.directory_skip = Some((index, self.checks.len()));
+        }
+        self.checks.push(RuleCheck {
+            rule,
+            verdict,
+            skips: true,
+        });
+    }
+
+    /// Checks a rule that the scan applies to the file once it is found.
+    ///
+    /// # Parameters
+    /// - `rule` - The name of the rule
+    /// - `verdict` - The verdict of the rule, which is an error if it skips the file
+    fn file(&mut self, rule: &'static str, verdict: Result<String, String>) {
+        let skips = verdict.is_err();
+        self.checks.push(RuleCheck {
+            rule,
+            verdict: verdict.unwrap_or_else(|verdict| verdict),
+            skips,
+        });
+    }
+
+    /// Returns the index of the check that leaves the file out first, see `Explanation::reason`.
+    fn reason(&self) -> Option<usize> {
+        let skipped_directory = self.directory_skip.map(|(_, check)| check);
+        skipped_directory.or_else(|| self.checks.iter().position(|check| check.skips))
+    }
+}
+
+/// Explains why a scan of a directory counts a file or leaves it out, by checking the file against
+/// each of the rules of the scan in the order that the scan applies them: excluded paths,
+/// `--include` overrides, dotfiles, symbolic links, ignored directories, the maximum depth, nested
+/// projects, `.gitattributes`, the language of the file and whether it is counted, test files, the
+/// maximum file size, and binary and generated files. Every rule is checked, even after one of
+/// them leaves the file out, so that each reason it is left out is found. When symbolic links are
+/// followed, a file that the scan finds through several paths is only counted through the first
+/// one, which depends on the order the scan finds them in, so this isn't checked.
+///
+/// # Parameters
+/// - `root` - The directory that the scan starts at
+/// - `path` - The path of the file, which must be below `root`
+/// - `options` - The options of the scan
+///
+/// # Errors
+/// Returns an error if `root` or the file couldn't be read, if the file isn't below `root`, or if
+/// it isn't a file.
+pub fn explain(
+    root: &std::path::Path,
+    path: &std::path::Path,
+    options: &crate::ScanOptions,
+) -> std::io::Result<Explanation> {
+    // The root is canonical, as in a scan, but the file isn't, so that symbolic links on the way
+    // to it are checked
+    let root = root.canonicalize()?;
+    let path = normalize(&std::path::absolute(path)?);
+    let path = match path.strip_prefix(&root) {
+        Ok(_) => path,
+        Err(_) => {
+            let parent = path.parent().unwrap_or(&path).canonicalize()?;
+            parent.join(path.file_name().unwrap_or_default())
+        }
+    };
+    let relative_path = path.strip_prefix(&root).map_err(|_| {
+        let message = format!("{} isn't below {}", path.display(), root.display());
+        std::io::Error::new(std::io::ErrorKind::InvalidInput, message)
+    })?;
+    if relative_path.as_os_str().is_empty() || !std::fs::metadata(&path)?.is_file() {
+        let message = format!("{} isn't a file", path.display());
+        return Err(std::io::Error::new(std::io::ErrorKind::InvalidInput, message));
+    }
+    let paths = relative_path
+        .ancestors()
+        .filter(|ancestor| !ancestor.as_os_str().is_empty())
+        .map(|ancestor| root.join(ancestor))
+        .collect::<Vec<_>>()
+        .into_iter()
+        .rev()
+        .collect::<Vec<_>>();
+    let file_index = paths.len() - 1;
+    let relative = |path: &std::path::Path| path.strip_prefix(&root).unwrap_or(path).display().to_string();
+    let name_of = |path: &std::path::Path| path.file_name().unwrap_or_default().to_string_lossy().into_owned();
+    let mut checks = Checks::default();
+
+    // The rules of the scan for each entry of a directory
+    checks.paths("excluded paths", &paths, "no path glob excludes it", |_, path| {
+        let excluded = options.exclude_paths.is_match(path.strip_prefix(&root).unwrap_or(path));
+        excluded.then(|| format!("`{}` is excluded by a path glob", relative(path)))
+    });
+    let included = paths
+        .iter()
+        .filter(|path| crate::scan::is_included(path, &root, options))
+        .map(|path| format!("`{}`", relative(path)))
+        .collect::<Vec<_>>();
+    checks.file(
+        "include",
+        Ok(if included.is_empty() {
+            "nothing on the way to it is included explicitly".to_owned()
+        } else {
+            format!(
+                "{} included explicitly, so the default ignore rules don't apply to it",
+                included.join(" and ") + if included.len() == 1 { " is" } else { " are" }
+            )
+        }),
+    );
+    let dotfiles_pass = if options.include_dotfiles {
+        "dotfiles are counted"
+    } else {
+        "no dotfile is on the way to it"
+    };
+    checks.paths("dotfiles", &paths, dotfiles_pass, |_, path| {
+        let is_dotfile = !crate::scan::is_included(path, &root, options)
+            && !options.include_dotfiles
+            && name_of(path).starts_with('.');
+        is_dotfile.then(|| format!("`{}` is a dotfile", relative(path)))
+    });
+    checks.paths("cache", &paths, "it isn't in the cache directory", |_, path| {
+        (name_of(path) == crate::CACHE_DIRECTORY).then(|| format!("`{}` is the cache of splik", relative(path)))
+    });
+    let links_pass = if options.follow_symlinks {
+        "symbolic links are followed"
+    } else {
+        "no symbolic link is on the way to it"
+    };
+    checks.paths("symbolic links", &paths, links_pass, |_, path| {
+        let is_link = std::fs::symlink_metadata(path).is_ok_and(|metadata| metadata.is_symlink());
+        if !is_link {
+            None
+        } else if !options.follow_symlinks {
+            Some(format!("`{}` is a symbolic link, which isn't followed", relative(path)))
+        } else if std::fs::metadata(path).is_err() {
+            Some(format!("`{}` is a broken symbolic link", relative(path)))
+        } else {
+            None
+        }
+    });
+    checks.paths(
+        "ignored directories",
+        &paths,
+        "it isn't in an ignored directory",
+        |index, path| {
+            let name = name_of(path);
+            if index == file_index
+                || crate::scan::is_included(path, &root, options)
+                || !crate::scan::is_ignored_directory(path, &name, options)
+            {
+                None
+            } else if options.ignores_directory(&name) {
+                Some(format!("`{}` is a directory that is ignored by name", relative(path)))
+            } else {
+                Some(format!("`{}` looks like build output", relative(path)))
+            }
+        },
+    );
+    let depth_pass = match options.max_depth {
+        Some(max_depth) => format!("it is within the maximum depth of {max_depth}"),
+        None => "there is no maximum depth".to_owned(),
+    };
+    checks.paths("maximum depth", &paths, depth_pass, |index, path| {
+        // The directories at the maximum depth are read, but the directories in them aren't
+        let max_depth = options.max_depth.filter(|max_depth| index == *max_depth && index < file_index)?;
+        Some(format!(
+            "`{}` is deeper than the maximum depth of {max_depth}",
+            relative(path)
+        ))
+    });
+    let projects = paths[..file_index]
+        .iter()
+        .filter(|path| options.nested_projects != crate::NestedProjects::Include && crate::root::is_project_root(path))
+        .collect::<Vec<_>>();
+    let projects_pass = match projects.last() {
+        Some(project) => format!("it is counted under the nested project `{}`", relative(project)),
+        None => "it isn't in a nested project that is skipped".to_owned(),
+    };
+    checks.paths("nested projects", &paths, projects_pass, |_, path| {
+        let is_skipped = options.nested_projects == crate::NestedProjects::Skip
+            && projects.iter().any(|project| project.as_path() == path);
+        is_skipped.then(|| format!("`{}` is a nested project, which is skipped", relative(path)))
+    });
+
+    // The rules of the scan for the file itself
+    let mut attributes = crate::attributes::GitAttributes::default();
+    if options.gitattributes {
+        for directory in std::iter::once(root.as_path()).chain(paths[..file_index].iter().map(|path| path.as_path())) {
+            attributes = attributes.with_directory(directory).unwrap_or(attributes);
+        }
+    }
+    let file_attributes = attributes.file(&path);
+    let is_included = paths.iter().any(|path| crate::scan::is_included(path, &root, options));
+    checks.file(
+        "gitattributes",
+        if !options.gitattributes {
+            Ok("`.gitattributes` files aren't read".to_owned())
+        } else if file_attributes.ignored && !is_included {
+            Err("it is marked as vendored or generated by `.gitattributes`".to_owned())
+        } else {
+            Ok("it isn't marked as vendored or generated by `.gitattributes`".to_owned())
+        },
+    );
+    let extension = path.extension().and_then(|extension| extension.to_str());
+    let mapped = extension.and_then(|extension| options.extension_map.get(extension));
+    let language = file_attributes.language.or_else(|| crate::detect_language(&path, &options.extension_map));
+    checks.file(
+        "language",
+        match (language, mapped) {
+            (Some(language), _) if file_attributes.language.is_some() => {
+                Ok(format!("{language}, as set by `linguist-language` in `.gitattributes`"))
+            }
+            (Some(language), Some(_)) => Ok(format!(
+                "{language}, as `.{}` files are mapped to",
+                extension.unwrap_or("")
+            )),
+            (Some(language), None) => Ok(language.to_owned()),
+            (None, Some(_)) => Err(format!(
+                "`.{}` files are mapped to no language",
+                extension.unwrap_or("")
+            )),
+            (None, None) => Err("its language isn't recognized".to_owned()),
+        },
+    );
+    if let Some(language) = language {
+        let excluded = options.exclude.iter().any(|excluded| crate::language_matches(language, excluded));
+        let category = crate::language_category(language);
+        checks.file(
+            "languages",
+            if excluded {
+                Err(format!("{language} is excluded"))
+            } else if !options.only.is_empty()
+                && !options.only.iter().any(|only| crate::language_matches(language, only))
+            {
+                Err(format!("{language} isn't one of the only languages that are counted"))
+            } else if !options.categories.is_empty() && !options.categories.contains(&category) {
+                Err(format!(
+                    "{language} is a {} language, which isn't counted",
+                    category.label()
+                ))
+            } else {
+                Ok(format!("{language} is counted"))
+            },
+        );
+    }
+    let is_test = crate::scan::is_test_file(relative_path, options);
+    checks.file(
+        "test files",
+        match options.tests {
+            crate::TestFiles::Include => Ok("test files are counted like other files".to_owned()),
+            _ if !is_test => Ok("it isn't a test file".to_owned()),
+            crate::TestFiles::Exclude => Err("it is a test file, which are left out".to_owned()),
+            crate::TestFiles::Separate => Ok("it is a test file, which are counted separately".to_owned()),
+        },
+    );
+    let bytes = std::fs::metadata(&path)?.len();
+    checks.file(
+        "maximum file size",
+        match options.max_file_size {
+            Some(max_file_size) if bytes > max_file_size => Err(format!(
+                "it is {bytes} bytes, larger than the maximum of {max_file_size} bytes"
+            )),
+            Some(max_file_size) => Ok(format!(
+                "it is {bytes} bytes, within the maximum of {max_file_size} bytes"
+            )),
+            None => Ok("there is no maximum file size".to_owned()),
+        },
+    );
+
+    // Binary and generated files are found by the start of the file, unless lines aren't counted
+    let mut prefix = Vec::new();
+    if !options.skip_lines {
+        let file = std::fs::File::open(&path)?;
+        let length = crate::lines::BINARY_PREFIX_LENGTH as u64;
+        std::io::Read::read_to_end(&mut std::io::Read::take(file, length), &mut prefix)?;
+    }
+    checks.file(
+        "binary files",
+        if options.count_binary {
+            Ok("binary files are counted".to_owned())
+        } else if options.skip_lines {
+            Ok("files aren't checked for being binary when their lines aren't counted".to_owned())
+        } else if crate::lines::is_binary(&prefix) {
+            Err("it looks like binary data".to_owned())
+        } else {
+            Ok("it doesn't look like binary data".to_owned())
+        },
+    );
+    let name = match options.paths {
+        crate::PathStyle::Relative => relative_path,
+        crate::PathStyle::Absolute => path.as_path(),
+    };
+    checks.file(
+        "generated files",
+        if options.include_generated {
+            Ok("generated files are counted".to_owned())
+        } else if crate::generated::is_generated(&name.to_string_lossy(), &prefix) {
+            Err("it looks generated or minified".to_owned())
+        } else {
+            Ok("it doesn't look generated or minified".to_owned())
+        },
+    );
+
+    Ok(Explanation {
+        path: relative_path.to_string_lossy().into_owned(),
+        reason: checks.reason(),
+        checks: checks.checks,
+        language,
+    })
+}
+
+/// Returns an absolute path without its `.` and `..` components, which are removed by their names
+/// alone rather than by following symbolic links like `canonicalize`.
+fn normalize(path: &std::path::Path) -> std::path::PathBuf {
+    let mut normalized = std::path::PathBuf::new();
+    for component in path.components() {
+        match component {
+            std::path::Component::CurDir => {}
+            std::path::Component::ParentDir => {
+                normalized.pop();
+            }
+            component => normalized.push(component),
+        }
+    }
+    normalized
+}
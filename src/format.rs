@@ -0,0 +1,76 @@
+//! Formatting numbers for the human-readable output.
+
+/// The binary prefixes that `format_size` uses, from kibibytes up.
+const SIZE_UNITS: &[&str] = &["KiB", "MiB", "GiB", "TiB", "PiB", "EiB"];
+
+/// Formats a percentage, as a whole number when it is at least 1, and with two decimal places
+/// otherwise, so that small languages aren't shown as 0%.
+pub(crate) fn format_number(number: f64) -> String {
+    if number >= 1.0 {
+        return format!("{}", number as i32);
+    }
+
+    format!("{:.2}", number)
+}
+
+/// Formats a number of bytes with a binary prefix and one decimal place, such as `46.0 MiB`, or as
+/// a whole number of bytes below 1 KiB, such as `1023 B`.
+pub fn format_size(bytes: u64) -> String {
+    if bytes < 1024 {
+        return format!("{bytes} B");
+    }
+
+    // Move up a unit when the value would be rounded up to 1024.0 of the current one
+    let mut value = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while (value * 10.0).round() >= 10240.0 && unit + 1 < SIZE_UNITS.len() {
+        value /= 1024.0;
+        unit += 1;
+    }
+    format!("{value:.1} {}", SIZE_UNITS[unit])
+}
+
+/// Formats a count with commas separating its thousands, such as `48,203,117`.
+pub fn format_count(count: u64) -> String {
+    let digits = count.to_string();
+    let mut formatted = String::with_capacity(digits.len() + digits.len() / 3);
+    for (index, digit) in digits.chars().enumerate() {
+        if index != 0 && (digits.len() - index).is_multiple_of(3) {
+            formatted.push(',');
+        }
+        formatted.push(digit);
+    }
+    formatted
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn sizes_below_a_kibibyte_are_bytes() {
+        assert_eq!(super::format_size(0), "0 B");
+        assert_eq!(super::format_size(1023), "1023 B");
+        assert_eq!(super::format_size(1024), "1.0 KiB");
+    }
+
+    #[test]
+    fn sizes_use_binary_prefixes() {
+        assert_eq!(super::format_size(1536), "1.5 KiB");
+        assert_eq!(super::format_size(3 * 1024 * 1024 * 1024 / 2), "1.5 GiB");
+        assert_eq!(super::format_size(u64::MAX), "16.0 EiB");
+    }
+
+    #[test]
+    fn sizes_that_round_up_move_up_a_unit() {
+        assert_eq!(super::format_size(1_048_575), "1.0 MiB");
+        assert_eq!(super::format_size(1_048_524), "1023.9 KiB");
+        assert_eq!(super::format_size(1024 * 1024 * 1024 - 1), "1.0 GiB");
+    }
+
+    #[test]
+    fn counts_separate_thousands() {
+        assert_eq!(super::format_count(0), "0");
+        assert_eq!(super::format_count(999), "999");
+        assert_eq!(super::format_count(1000), "1,000");
+        assert_eq!(super::format_count(48_203_117), "48,203,117");
+    }
+}
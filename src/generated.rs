@@ -0,0 +1,74 @@
+//! Detecting generated and minified files, which are left out of scans unless
+//! `ScanOptions::include_generated` is set.
+
+/// The endings of the names of files that are generated by common tools, such as protobuf
+/// compilers, or that are minified bundles.
+const GENERATED_SUFFIXES: &[&str] = &[
+    ".pb.go",
+    ".pb.cc",
+    ".pb.h",
+    "_pb2.py",
+    "_pb2_grpc.py",
+    ".pb.swift",
+    ".g.dart",
+    ".freezed.dart",
+    ".designer.cs",
+    ".min.js",
+    ".min.mjs",
+    ".min.css",
+];
+
+/// Comments that mark a file as generated when they appear in its first `MARKER_LINES` lines.
+const GENERATED_MARKERS: &[&str] = &["@generated", "DO NOT EDIT", "Code generated by", "<auto-generated"];
+
+/// The number of lines at the start of a file that are searched for `GENERATED_MARKERS`, and for
+/// lines longer than `MINIFIED_LINE_LENGTH`.
+const MARKER_LINES: usize = 5;
+
+/// The length in bytes above which a line is considered to be minified code.
+const MINIFIED_LINE_LENGTH: usize = 1000;
+
+/// The files that were left out of a scan for being generated or minified.
+#[derive(Default, Clone, Copy, serde::Serialize)]
+pub struct GeneratedFiles {
+    /// The number of files that were left out.
+    pub files: usize,
+    /// The number of bytes of the files that were left out.
+    pub bytes: u64,
+}
+
+impl GeneratedFiles {
+    /// Adds the counts of other generated files to these.
+    pub(crate) fn add(&mut self, other: GeneratedFiles) {
+        self.files += other.files;
+        self.bytes += other.bytes;
+    }
+
+    /// Returns whether no files were left out, for leaving these out of a report.
+    pub fn is_empty(&self) -> bool {
+        self.files == 0
+    }
+}
+
+/// Returns whether a file looks generated or minified, either from its name, or from the markers
+/// and line lengths of its first few lines.
+///
+/// # Parameters
+/// - `name` - The name the file is listed under, of which only the file name is checked
+/// - `prefix` - The start of the file's contents, which is all that is searched, so that the
+///   contents of large files aren't searched to the end
+pub(crate) fn is_generated(name: &str, prefix: &[u8]) -> bool {
+    // Check the file name
+    let file_name = name.rsplit(['/', '\\']).next().unwrap_or(name);
+    if file_name.contains(".generated.") || GENERATED_SUFFIXES.iter().any(|suffix| file_name.ends_with(suffix)) {
+        return true;
+    }
+
+    // Check the first lines for markers, and for minified code
+    prefix.split(|byte| *byte == b'\n').take(MARKER_LINES).any(|line| {
+        line.len() > MINIFIED_LINE_LENGTH
+            || GENERATED_MARKERS
+                .iter()
+                .any(|marker| line.windows(marker.len()).any(|window| window == marker.as_bytes()))
+    })
+}
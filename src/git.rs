@@ -0,0 +1,163 @@
+//! Listing the files of a git repository.
+
+/// Lists the files in a directory that are tracked by git, by running `git ls-files`. Files that
+/// are tracked but have been deleted from the working tree are still listed.
+///
+/// # Parameters
+/// - `root` - The path of the directory, which must be in a git repository
+/// - `include_untracked` - Whether to also list files that aren't tracked, but aren't ignored by a
+///   `.gitignore` either
+///
+/// # Returns
+/// - The paths of the files, joined onto `root`.
+///
+/// # Errors
+/// Returns an error if git couldn't be run, or if `root` isn't in a git repository.
+pub fn git_files(root: &std::path::Path, include_untracked: bool) -> std::io::Result<Vec<std::path::PathBuf>> {
+    let mut arguments = vec!["ls-files", "-z", "--cached"];
+    if include_untracked {
+        arguments.extend(["--others", "--exclude-standard"]);
+    }
+    let output = git_output(root, &arguments)?;
+
+    let mut files = output
+        .split(|byte| *byte == 0)
+        .filter(|path| !path.is_empty())
+        .map(|path| root.join(String::from_utf8_lossy(path).as_ref()))
+        .collect::<Vec<_>>();
+    files.dedup();
+    Ok(files)
+}
+
+/// A file in a git revision.
+pub struct GitBlob {
+    /// The path of the file, relative to the directory the revision was listed from.
+    pub path: String,
+    /// The object ID of the file's contents.
+    pub id: String,
+}
+
+/// Lists the files of a git revision in a directory, by running `git ls-tree`. Submodules and
+/// symbolic links are left out.
+///
+/// # Parameters
+/// - `root` - The path of the directory, which must be in a git repository
+/// - `revision` - The revision to list the files of, such as a tag or commit hash
+///
+/// # Returns
+/// - The files of the revision under `root`, with paths relative to `root`.
+///
+/// # Errors
+/// Returns an error if git couldn't be run, if `root` isn't in a git repository, or if the revision
+/// doesn't exist.
+pub fn git_revision_blobs(root: &std::path::Path, revision: &str) -> std::io::Result<Vec<GitBlob>> {
+    let output = git_output(root, &["ls-tree", "-r", "-z", revision])?;
+    Ok(output
+        .split(|byte| *byte == 0)
+        .filter_map(|entry| {
+            // Each entry is `<mode> <type> <id>\t<path>`
+            let entry = std::str::from_utf8(entry).ok()?;
+            let (info, path) = entry.split_once('\t')?;
+            let mut info = info.split(' ');
+            let (mode, kind, id) = (info.next()?, info.next()?, info.next()?);
+            (kind == "blob" && mode != "120000").then(|| GitBlob {
+                path: path.to_owned(),
+                id: id.to_owned(),
+            })
+        })
+        .collect())
+}
+
+/// Reads the contents of git blobs, by running `git cat-file --batch`.
+///
+/// # Parameters
+/// - `root` - The path of a directory in the git repository the blobs are in
+/// - `blobs` - The blobs to read
+/// - `read` - Called with each blob and its contents, in order
+///
+/// # Errors
+/// Returns an error if git couldn't be run, or if a blob couldn't be read.
+pub fn read_git_blobs(
+    root: &std::path::Path,
+    blobs: &[GitBlob],
+    mut read: impl FnMut(&GitBlob, Vec<u8>),
+) -> std::io::Result<()> {
+    let mut child = std::process::Command::new("git")
+        .arg("-C")
+        .arg(root)
+        .args(["cat-file", "--batch"])
+        .stdin(std::process::Stdio::piped())
+        .stdout(std::process::Stdio::piped())
+        .spawn()
+        .map_err(|error| std::io::Error::new(error.kind(), format!("couldn't run git: {error}")))?;
+
+    // Write the IDs from another thread, so that git never blocks on a full stdout pipe
+    let mut stdin = child.stdin.take().unwrap();
+    let ids = blobs.iter().map(|blob| format!("{}\n", blob.id)).collect::<String>();
+    let writer = std::thread::spawn(move || std::io::Write::write_all(&mut stdin, ids.as_bytes()));
+
+    // Each blob is `<id> blob <size>\n<contents>\n`
+    let mut stdout = std::io::BufReader::new(child.stdout.take().unwrap());
+    for blob in blobs {
+        let mut header = String::new();
+        std::io::BufRead::read_line(&mut stdout, &mut header)?;
+        let size = match header.trim_end().split(' ').collect::<Vec<_>>()[..] {
+            [_, "blob", size] => size.parse::<usize>().ok(),
+            _ => None,
+        };
+        let size = size
+            .ok_or_else(|| std::io::Error::other(format!("couldn't read git blob {}: {}", blob.id, header.trim())))?;
+        let mut contents = vec![0; size + 1];
+        std::io::Read::read_exact(&mut stdout, &mut contents)?;
+        contents.pop();
+        read(blob, contents);
+    }
+
+    writer.join().unwrap()?;
+    child.wait()?;
+    Ok(())
+}
+
+/// Returns whether a path is ignored by git, such as by a `.gitignore`, by running
+/// `git check-ignore`. Paths outside of a git repository, or that couldn't be checked because git
+/// couldn't be run, aren't ignored.
+///
+/// # Parameters
+/// - `path` - The path to check
+pub(crate) fn is_git_ignored(path: &std::path::Path) -> bool {
+    let (Some(directory), Some(name)) = (path.parent(), path.file_name()) else {
+        return false;
+    };
+    std::process::Command::new("git")
+        .arg("-C")
+        .arg(directory)
+        .args(["check-ignore", "-q", "--"])
+        .arg(name)
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .status()
+        .is_ok_and(|status| status.success())
+}
+
+/// Runs a git command in a directory and returns its output.
+///
+/// # Errors
+/// Returns an error if git couldn't be run, or if the command failed.
+fn git_output(root: &std::path::Path, arguments: &[&str]) -> std::io::Result<Vec<u8>> {
+    let output = std::process::Command::new("git")
+        .arg("-C")
+        .arg(root)
+        .args(arguments)
+        .output()
+        .map_err(|error| std::io::Error::new(error.kind(), format!("couldn't run git: {error}")))?;
+    if !output.status.success() {
+        let message = String::from_utf8_lossy(&output.stderr);
+        let message = message.trim().trim_start_matches("fatal: ");
+        return Err(std::io::Error::other(format!(
+            "git {} failed in {}: {message}",
+            arguments[0],
+            root.display()
+        )));
+    }
+    Ok(output.stdout)
+}
@@ -0,0 +1,200 @@
+//! Writing reports as self-contained HTML pages, for the HTML output format.
+
+/// The styles of the page, which are inlined so that the page has no external resources.
+const STYLE: &str = "body { font-family: system-ui, sans-serif; margin: 2em auto; max-width: 60em; color: #24292f; }
+h1, h2 { font-weight: 600; }
+.chart { display: flex; justify-content: center; margin: 1em 0; }
+table { border-collapse: collapse; width: 100%; margin: 1em 0; }
+th, td { padding: 0.3em 0.6em; border-bottom: 1px solid #d0d7de; text-align: right; }
+th:first-child, td:first-child { text-align: left; }
+tfoot td { font-weight: 600; }
+.swatch { display: inline-block; width: 0.8em; height: 0.8em; margin-right: 0.5em; border-radius: 50%; }
+details { margin: 0.3em 0; }
+summary { cursor: pointer; }
+.meta { color: #57606a; }";
+
+/// Writes an HTML page with the languages of each section: a chart of their byte shares, a table
+/// of their counts, and a collapsible list of the files of each language. The page has no
+/// external scripts, styles, or images, so it can be opened from anywhere.
+///
+/// # Parameters
+/// - `writer` - Where to write the page
+/// - `source` - What was scanned, such as the root directories, which is stated with the time the
+///   page was written
+/// - `sections` - The languages of each section, along with its heading, such as the directories
+///   of `--by-dir`. A section without a heading is written without one.
+///
+/// # Errors
+/// Returns an error if the page couldn't be written.
+pub(crate) fn write_html(
+    writer: &mut impl std::io::Write,
+    source: &str,
+    sections: &[(Option<&str>, &crate::LanguageList)],
+) -> std::io::Result<()> {
+    let escape = crate::xml::escape;
+    writeln!(writer, "<!DOCTYPE html>")?;
+    writeln!(writer, r#"<html lang="en">"#)?;
+    writeln!(writer, "<head>")?;
+    writeln!(writer, r#"<meta charset="utf-8">"#)?;
+    writeln!(writer, "<title>splik report: {}</title>", escape(source))?;
+    writeln!(writer, "<style>\n{STYLE}\n</style>")?;
+    writeln!(writer, "</head>")?;
+    writeln!(writer, "<body>")?;
+    writeln!(writer, "<h1>Languages of {}</h1>", escape(source))?;
+    writeln!(
+        writer,
+        r#"<p class="meta">Scanned {} by splik {}</p>"#,
+        format_timestamp(std::time::SystemTime::now()),
+        env!("CARGO_PKG_VERSION")
+    )?;
+    for (heading, languages) in sections {
+        writeln!(writer, "<section>")?;
+        if let Some(heading) = heading {
+            writeln!(writer, "<h2>{}</h2>", escape(heading))?;
+        }
+        write_section(writer, languages)?;
+        writeln!(writer, "</section>")?;
+    }
+    writeln!(writer, "</body>")?;
+    writeln!(writer, "</html>")
+}
+
+/// Writes the chart, table, and file lists of some languages, see `write_html`.
+fn write_section(writer: &mut impl std::io::Write, languages: &crate::LanguageList) -> std::io::Result<()> {
+    let escape = crate::xml::escape;
+    let report = languages.report(None);
+    if report.languages.is_empty() {
+        return writeln!(writer, "<p>No files were counted.</p>");
+    }
+
+    // Chart of the byte shares
+    writeln!(writer, r#"<div class="chart">"#)?;
+    write_chart(writer, &report.languages)?;
+    writeln!(writer, "</div>")?;
+
+    // Table of the counts, with the lines of tests if they were counted separately
+    let test_cell = |tests: Option<&crate::Totals>| match &report.tests {
+        Some(_) => format!(
+            "<td>{}</td>",
+            crate::format::format_count(tests.map_or(0, |tests| tests.lines))
+        ),
+        None => String::new(),
+    };
+    writeln!(writer, "<table>")?;
+    writeln!(
+        writer,
+        "<thead><tr><th>Language</th><th>Files</th><th>Lines</th><th>Code</th><th>Comments</th><th>Blanks</th><th>Bytes</th><th>%</th>{}</tr></thead>",
+        if report.tests.is_some() { "<th>Test lines</th>" } else { "" }
+    )?;
+    writeln!(writer, "<tbody>")?;
+    for language in &report.languages {
+        writeln!(
+            writer,
+            r#"<tr><td><span class="swatch" style="background: {}"></span>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}%</td>{}</tr>"#,
+            language.color.unwrap_or(crate::svg::DEFAULT_COLOR),
+            escape(language.info.name),
+            crate::format::format_count(language.info.file_count as u64),
+            crate::format::format_count(language.info.lines),
+            crate::format::format_count(language.info.code),
+            crate::format::format_count(language.info.comments),
+            crate::format::format_count(language.info.blanks),
+            crate::format::format_size(language.info.bytes),
+            crate::format::format_number(language.byte_percent),
+            test_cell(language.info.tests.as_ref()),
+        )?;
+    }
+    writeln!(writer, "</tbody>")?;
+    let totals = &report.totals;
+    writeln!(
+        writer,
+        "<tfoot><tr><td>Total</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>100%</td>{}</tr></tfoot>",
+        crate::format::format_count(totals.files as u64),
+        crate::format::format_count(totals.lines),
+        crate::format::format_count(totals.code),
+        crate::format::format_count(totals.comments),
+        crate::format::format_count(totals.blanks),
+        crate::format::format_size(totals.bytes),
+        test_cell(report.tests.as_ref()),
+    )?;
+    writeln!(writer, "</table>")?;
+
+    // Collapsible file lists, unless the paths of files weren't kept
+    for language in report.languages.iter().filter(|language| !language.info.files.is_empty()) {
+        let files = &language.info.files;
+        let noun = if language.info.file_count == 1 { "file" } else { "files" };
+        writeln!(writer, "<details>")?;
+        writeln!(
+            writer,
+            "<summary>{} ({} {noun})</summary>",
+            escape(language.info.name),
+            crate::format::format_count(language.info.file_count as u64)
+        )?;
+        writeln!(writer, "<table>")?;
+        writeln!(
+            writer,
+            "<thead><tr><th>Path</th><th>Lines</th><th>Bytes</th></tr></thead>"
+        )?;
+        writeln!(writer, "<tbody>")?;
+        for file in files {
+            writeln!(
+                writer,
+                "<tr><td>{}</td><td>{}</td><td>{}</td></tr>",
+                escape(&file.path),
+                crate::format::format_count(file.lines),
+                crate::format::format_size(file.bytes),
+            )?;
+        }
+        writeln!(writer, "</tbody>")?;
+        writeln!(writer, "</table>")?;
+        writeln!(writer, "</details>")?;
+    }
+    Ok(())
+}
+
+/// Writes a pie chart of the byte shares of some languages as an inline SVG, with a slice in the
+/// color of each language.
+fn write_chart(writer: &mut impl std::io::Write, languages: &[crate::LanguageReport<'_>]) -> std::io::Result<()> {
+    let slices = languages
+        .iter()
+        .map(|language| crate::svg::Slice {
+            name: language.info.name,
+            color: language.color.unwrap_or(crate::svg::DEFAULT_COLOR),
+            share: language.byte_percent / 100.0,
+            label: format!("{}%", crate::format::format_number(language.byte_percent)),
+        })
+        .collect::<Vec<_>>();
+    writeln!(
+        writer,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="240" height="240" viewBox="-1 -1 2 2" role="img" aria-label="Bytes per language">"#
+    )?;
+    crate::svg::write_pie(writer, &slices, (0.0, 0.0), 1.0)?;
+    writeln!(writer, "</svg>")
+}
+
+/// Formats a time as a date and time in UTC, such as `2024-05-01 13:45:00 UTC`.
+fn format_timestamp(time: std::time::SystemTime) -> String {
+    let seconds = time.duration_since(std::time::UNIX_EPOCH).map_or(0, |duration| duration.as_secs());
+    let (days, seconds) = (seconds / 86_400, seconds % 86_400);
+
+    // Convert the days since 1970-01-01 into a date of the proleptic Gregorian calendar, counting
+    // from 0000-03-01 so that leap days are at the end of each year
+    let days = days + 719_468;
+    let era = days / 146_097;
+    let day_of_era = days % 146_097;
+    let year_of_era = (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = era * 400 + year_of_era + u64::from(month <= 2);
+    format!(
+        "{year:04}-{month:02}-{day:02} {:02}:{:02}:{:02} UTC",
+        seconds / 3_600,
+        seconds / 60 % 60,
+        seconds % 60
+    )
+}
@@ -0,0 +1,913 @@
+//! The languages splik recognizes, and how they are detected.
+
+/// Detects the language of a file. The language is looked up by the file's extension in
+/// `extension_map`, the languages of `define_languages`, and then `LANGUAGES`, then by the file's
+/// exact name in the defined languages and `FILENAMES` or its prefix in `FILENAME_PREFIXES`, and
+/// files without an extension finally fall back to their shebang line.
+/// Extensions that are shared by several languages, such as `.m`, are disambiguated by the start
+/// of the file's contents, unless they are mapped in `extension_map`.
+///
+/// # Parameters
+/// - `path` - The path of the file
+/// - `extension_map` - Custom extension to language mappings that take priority over `LANGUAGES`.
+///   Extensions mapped to `None` aren't detected as any language.
+///
+/// # Returns
+/// - The name of the detected language, or `None` if the language isn't recognized.
+pub fn detect_language(
+    path: &std::path::Path,
+    extension_map: &std::collections::HashMap<String, Option<&'static str>>,
+) -> Option<&'static str> {
+    detect_language_with(path, extension_map, &|length| read_prefix(path, length))
+}
+
+/// Detects the language of a file like `detect_language`, but from contents that are already in
+/// memory instead of reading the file, such as a blob from a git revision.
+///
+/// # Parameters
+/// - `path` - The path of the file, which doesn't need to exist
+/// - `contents` - The contents of the file
+/// - `extension_map` - Custom extension to language mappings that take priority over `LANGUAGES`
+///
+/// # Returns
+/// - The name of the detected language, or `None` if the language isn't recognized.
+pub fn detect_language_from_contents(
+    path: &std::path::Path,
+    contents: &[u8],
+    extension_map: &std::collections::HashMap<String, Option<&'static str>>,
+) -> Option<&'static str> {
+    detect_language_with(path, extension_map, &|length| {
+        Some(contents[..length.min(contents.len())].to_vec())
+    })
+}
+
+/// Detects the language of a file, reading the start of its contents with `read_prefix` when its
+/// name isn't enough. See `detect_language`.
+fn detect_language_with(
+    path: &std::path::Path,
+    extension_map: &std::collections::HashMap<String, Option<&'static str>>,
+    read_prefix: &dyn Fn(usize) -> Option<Vec<u8>>,
+) -> Option<&'static str> {
+    let extension = path.extension();
+    if let Some(extension) = extension.and_then(|extension| extension.to_str()) {
+        if let Some(language) = extension_map.get(extension) {
+            return *language;
+        }
+        if let Some(definition) = defined_languages().iter().find(|definition| definition.has_extension(extension)) {
+            return Some(definition.name);
+        }
+        if let Some(language) = LANGUAGES.get(extension) {
+            return Some(detect_ambiguous_language(extension, read_prefix).unwrap_or(language));
+        }
+    }
+
+    let filename = path.file_name()?.to_str()?;
+    if let Some(definition) = defined_languages()
+        .iter()
+        .find(|definition| definition.filenames.iter().any(|name| name == filename))
+    {
+        return Some(definition.name);
+    }
+    if let Some(language) = FILENAMES.get(filename) {
+        return Some(language);
+    }
+    if let Some((_, language)) = FILENAME_PREFIXES.iter().find(|(prefix, _)| filename.starts_with(prefix)) {
+        return Some(language);
+    }
+
+    extension
+        .is_none()
+        .then(|| detect_shebang_language(&read_prefix(SHEBANG_PREFIX_LENGTH)?))
+        .flatten()
+}
+
+/// The maximum number of bytes read from the start of a file when looking for a shebang line.
+const SHEBANG_PREFIX_LENGTH: usize = 256;
+
+/// Detects the language of a script from its shebang line, such as `#!/usr/bin/env python3` or
+/// `#!/bin/bash`. Only a small prefix of the file is read. A leading byte order mark is ignored,
+/// and scripts run through `env` (including `env -S`) use the interpreter `env` is given.
+///
+/// # Parameters
+/// - `prefix` - The start of the file
+///
+/// # Returns
+/// - The name of the language of the interpreter, or `None` if the file has no shebang or the
+///   interpreter isn't in `SHEBANG_INTERPRETERS`.
+fn detect_shebang_language(prefix: &[u8]) -> Option<&'static str> {
+    let prefix = prefix.strip_prefix(b"\xEF\xBB\xBF").unwrap_or(prefix);
+    let shebang = prefix.strip_prefix(b"#!")?;
+    let shebang = &shebang[..shebang.iter().position(|byte| *byte == b'\n').unwrap_or(shebang.len())];
+    let shebang = std::str::from_utf8(shebang).ok()?;
+
+    // Get the interpreter, skipping over `env` and its options and variable assignments
+    let mut words = shebang.split_whitespace();
+    let mut interpreter = words.next()?.rsplit('/').next()?;
+    if interpreter == "env" {
+        interpreter = words.find(|word| !word.starts_with('-') && !word.contains('='))?;
+    }
+
+    // Ignore version numbers, such as in `python3.12`
+    let interpreter = interpreter.trim_end_matches(|character: char| character.is_ascii_digit() || character == '.');
+    SHEBANG_INTERPRETERS.get(interpreter).copied()
+}
+
+/// The maximum number of bytes read from the start of a file when disambiguating its extension.
+const DISAMBIGUATION_PREFIX_LENGTH: usize = 8 * 1024;
+
+/// Reads at most `length` bytes from the start of a file.
+///
+/// # Returns
+/// - The bytes that were read, or `None` if the file couldn't be read.
+fn read_prefix(path: &std::path::Path, length: usize) -> Option<Vec<u8>> {
+    let mut prefix = Vec::with_capacity(length);
+    let file = std::fs::File::open(path).ok()?;
+    std::io::Read::read_to_end(&mut std::io::Read::take(file, length as u64), &mut prefix).ok()?;
+    Some(prefix)
+}
+
+/// Detects the language of a file whose extension is shared by several languages, from the start
+/// of its contents.
+///
+/// # Parameters
+/// - `extension` - The extension of the file
+/// - `read_prefix` - Reads at most the given number of bytes from the start of the file
+///
+/// # Returns
+/// - The name of the detected language, or `None` if the extension isn't ambiguous or the file
+///   couldn't be read, in which case the language in `LANGUAGES` is used.
+fn detect_ambiguous_language(extension: &str, read_prefix: &dyn Fn(usize) -> Option<Vec<u8>>) -> Option<&'static str> {
+    match extension {
+        "m" => {
+            let prefix = read_prefix(DISAMBIGUATION_PREFIX_LENGTH)?;
+            Some(if is_objective_c(&prefix) {
+                "Objective-C"
+            } else {
+                "MATLAB"
+            })
+        }
+        "h" => {
+            let prefix = read_prefix(DISAMBIGUATION_PREFIX_LENGTH)?;
+            Some(if is_cpp_header(&prefix) { "C++" } else { "C" })
+        }
+        "v" => Some(detect_v_language(&read_prefix(DISAMBIGUATION_PREFIX_LENGTH)?)),
+        _ => None,
+    }
+}
+
+/// Returns whether the start of a `.m` file looks like Objective-C rather than MATLAB. Lines that
+/// start with Objective-C directives such as `#import` or `@interface` are weighed against lines
+/// that start with MATLAB `function` definitions or `%` comments, and ties go to MATLAB.
+fn is_objective_c(prefix: &[u8]) -> bool {
+    let mut score = 0isize;
+    for line in prefix.split(|byte| *byte == b'\n') {
+        let line = line.trim_ascii_start();
+        if [
+            &b"#import"[..],
+            b"#include",
+            b"@interface",
+            b"@implementation",
+            b"@protocol",
+            b"@end",
+        ]
+        .iter()
+        .any(|directive| line.starts_with(directive))
+        {
+            score += 1;
+        } else if line.starts_with(b"function ") || line.starts_with(b"%") {
+            score -= 1;
+        }
+    }
+    score > 0
+}
+
+/// Returns whether the start of a `.h` file looks like C++ rather than C, which is the case if it
+/// uses the `class`, `namespace`, or `template` keywords, or the `::` scope operator.
+fn is_cpp_header(prefix: &[u8]) -> bool {
+    contains(prefix, b"::") || ["class", "namespace", "template"].iter().any(|keyword| contains_word(prefix, keyword))
+}
+
+/// Detects whether the start of a `.v` file is V, Verilog, or Coq. Each line that starts like one
+/// of the languages, such as `endmodule` or `always @` for Verilog, `Theorem` or `Qed.` for Coq, and
+/// `fn` or `import` for V, counts towards that language, and ties go to V.
+fn detect_v_language(prefix: &[u8]) -> &'static str {
+    let (mut v, mut verilog, mut coq) = (0, 0, 0);
+    for line in prefix.split(|byte| *byte == b'\n') {
+        let line = line.trim_ascii_start();
+        let starts_with_any = |prefixes: &[&str]| prefixes.iter().any(|prefix| line.starts_with(prefix.as_bytes()));
+        if line.starts_with(b"module ") {
+            // Verilog modules have ports or end in a semicolon, while V module declarations are a
+            // single name
+            if line.contains(&b'(') || line.contains(&b';') {
+                verilog += 1;
+            } else {
+                v += 1;
+            }
+        } else if starts_with_any(&["endmodule", "always @", "assign ", "`timescale", "`include"]) {
+            verilog += 1;
+        } else if starts_with_any(&[
+            "Theorem ",
+            "Lemma ",
+            "Proof.",
+            "Qed.",
+            "Require Import ",
+            "Definition ",
+            "Inductive ",
+            "Fixpoint ",
+        ]) {
+            coq += 1;
+        } else if starts_with_any(&["fn ", "pub fn ", "import ", "struct ", "mut "]) || contains(line, b" mut ") {
+            v += 1;
+        }
+    }
+
+    if verilog > v && verilog >= coq {
+        "Verilog"
+    } else if coq > v && coq > verilog {
+        "Coq"
+    } else {
+        "V"
+    }
+}
+
+/// Returns whether some bytes contain the given needle.
+fn contains(haystack: &[u8], needle: &[u8]) -> bool {
+    haystack.windows(needle.len()).any(|window| window == needle)
+}
+
+/// Returns whether some bytes contain the given word, not surrounded by other identifier
+/// characters.
+fn contains_word(haystack: &[u8], word: &str) -> bool {
+    let is_identifier = |byte: Option<&u8>| byte.is_some_and(|byte| byte.is_ascii_alphanumeric() || *byte == b'_');
+    haystack.windows(word.len()).enumerate().any(|(index, window)| {
+        window == word.as_bytes()
+            && !is_identifier(index.checked_sub(1).and_then(|before| haystack.get(before)))
+            && !is_identifier(haystack.get(index + word.len()))
+    })
+}
+
+/// Recognized file extensions, mapped to the names of their languages.
+pub const LANGUAGES: phf::Map<&'static str, &'static str> = phf::phf_map! {
+    "adoc" => "AsciiDoc",
+    "asciidoc" => "AsciiDoc",
+    "asm" => "Assembly",
+    "bat" => "Batch",
+    "cmd" => "Batch",
+    "bib" => "BibTeX",
+    "c" => "C",
+    "cmake" => "CMake",
+    "h" => "C",
+    "cpp" => "C++",
+    "c++" => "C++",
+    "cxx" => "C++",
+    "cc" => "C++",
+    "hpp" => "C++",
+    "hh" => "C++",
+    "h++" => "C++",
+    "hxx" => "C++",
+    "cs" => "C#",
+    "css" => "CSS",
+    "csv" => "CSV",
+    "fish" => "Fish",
+    "f" => "Fortran",
+    "for" => "Fortran",
+    "f90" => "Fortran",
+    "f95" => "Fortran",
+    "gleam" => "Gleam",
+    "go" => "Go",
+    "groovy" => "Groovy",
+    "gradle" => "Groovy",
+    "lhs" => "Haskell",
+    "hs" => "Haskell",
+    "html" => "HTML",
+    "htm" => "HTML",
+    "ini" => "INI",
+    "java" => "Java",
+    "js" => "JavaScript",
+    "mjs" => "JavaScript",
+    "cjs" => "JavaScript",
+    "jsx" => "JavaScript React",
+    "json" => "JSON",
+    "jsonc" => "JSON",
+    "jl" => "Julia",
+    "ipynb" => "Jupyter Notebook",
+    "kt" => "Kotlin",
+    "tex" => "LaTeX",
+    "less" => "Less",
+    "lua" => "Lua",
+    "m" => "MATLAB",
+    "mk" => "Make",
+    "md" => "Markdown",
+    "markdown" => "Markdown",
+    "php" => "PHP",
+    "pl" => "Perl",
+    "pm" => "Perl",
+    "ps1" => "PowerShell",
+    "psm1" => "PowerShell",
+    "psd1" => "PowerShell",
+    "py" => "Python",
+    "r" => "R",
+    "rst" => "reStructuredText",
+    "rb" => "Ruby",
+    "rs" => "Rust",
+    "sass" => "Sass",
+    "scss" => "SCSS",
+    "sh" => "Shell",
+    "bash" => "Shell",
+    "ksh" => "Shell",
+    "zsh" => "Shell",
+    "sql" => "SQL",
+    "styl" => "Stylus",
+    "svelte" => "Svelte",
+    "swift" => "Swift",
+    "sv" => "SystemVerilog",
+    "svh" => "SystemVerilog",
+    "toml" => "TOML",
+    "ts" => "TypeScript",
+    "tsx" => "TypeScript React",
+    "v" => "V",
+    "vue" => "Vue",
+    "xml" => "XML",
+    "yaml" => "YAML",
+    "yml" => "YAML",
+    "zig" => "Zig",
+};
+
+/// The kind of a language, which can be used to filter which languages are counted.
+#[derive(serde::Serialize, serde::Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+pub enum Category {
+    /// Programming languages, such as Rust or Python. This is the category of every language that
+    /// isn't in `CATEGORIES`.
+    Programming,
+    /// Markup and styling languages, such as HTML or CSS.
+    Markup,
+    /// Data and configuration languages, such as JSON or YAML.
+    Data,
+    /// Prose, such as Markdown or plain text.
+    Prose,
+}
+
+impl Category {
+    /// Returns the name of this category, as it is written in `--categories`.
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Programming => "programming",
+            Self::Markup => "markup",
+            Self::Data => "data",
+            Self::Prose => "prose",
+        }
+    }
+}
+
+/// The categories of languages that aren't programming languages, keyed by the language names in
+/// `LANGUAGES`.
+pub const CATEGORIES: phf::Map<&'static str, Category> = phf::phf_map! {
+    "AsciiDoc" => Category::Prose,
+    "BibTeX" => Category::Prose,
+    "CSS" => Category::Markup,
+    "CSV" => Category::Data,
+    "HTML" => Category::Markup,
+    "INI" => Category::Data,
+    "JSON" => Category::Data,
+    "LaTeX" => Category::Prose,
+    "Less" => Category::Markup,
+    "Markdown" => Category::Prose,
+    "reStructuredText" => Category::Prose,
+    "Sass" => Category::Markup,
+    "SCSS" => Category::Markup,
+    "Stylus" => Category::Markup,
+    "TOML" => Category::Data,
+    "XML" => Category::Data,
+    "YAML" => Category::Data,
+};
+
+/// Returns the category of a language, from its definition if it was defined with
+/// `define_languages`, or from `CATEGORIES` otherwise.
+pub fn language_category(language: &str) -> Category {
+    match defined_language(language) {
+        Some(definition) => definition.category,
+        None => CATEGORIES.get(language).copied().unwrap_or(Category::Programming),
+    }
+}
+
+/// The conventional colors of languages, as hex codes from the GitHub linguist palette, keyed by
+/// the language names in `LANGUAGES`. These color the names of languages in the human-readable
+/// output, and are included in reports for dashboards.
+pub const COLORS: phf::Map<&'static str, &'static str> = phf::phf_map! {
+    "AsciiDoc" => "#73a0c5",
+    "Assembly" => "#6e4c13",
+    "Batch" => "#c1f12e",
+    "BibTeX" => "#778899",
+    "C" => "#555555",
+    "C++" => "#f34b7d",
+    "C#" => "#178600",
+    "CMake" => "#da3434",
+    "Coq" => "#d0b68c",
+    "CSS" => "#663399",
+    "CSV" => "#237346",
+    "Dockerfile" => "#384d54",
+    "Fish" => "#4aae47",
+    "Fortran" => "#4d41b1",
+    "Gleam" => "#ffaff3",
+    "Go" => "#00add8",
+    "Groovy" => "#4298b8",
+    "Haskell" => "#5e5086",
+    "HTML" => "#e34c26",
+    "INI" => "#d1dbe0",
+    "Java" => "#b07219",
+    "JavaScript" => "#f1e05a",
+    "JavaScript React" => "#f1e05a",
+    "JSON" => "#292929",
+    "Julia" => "#a270ba",
+    "Jupyter Notebook" => "#da5b0b",
+    "Kotlin" => "#a97bff",
+    "LaTeX" => "#3d6117",
+    "Less" => "#1d365d",
+    "Lua" => "#000080",
+    "Make" => "#427819",
+    "Markdown" => "#083fa1",
+    "MATLAB" => "#e16737",
+    "Objective-C" => "#438eff",
+    "Perl" => "#0298c3",
+    "PHP" => "#4f5d95",
+    "PowerShell" => "#012456",
+    "Python" => "#3572a5",
+    "R" => "#198ce7",
+    "reStructuredText" => "#141414",
+    "Ruby" => "#701516",
+    "Rust" => "#dea584",
+    "Sass" => "#a53b70",
+    "SCSS" => "#c6538c",
+    "Shell" => "#89e051",
+    "SQL" => "#e38c00",
+    "Stylus" => "#ff6347",
+    "Svelte" => "#ff3e00",
+    "Swift" => "#f05138",
+    "SystemVerilog" => "#dae1c2",
+    "TOML" => "#9c4221",
+    "TypeScript" => "#3178c6",
+    "TypeScript React" => "#3178c6",
+    "V" => "#4f87c4",
+    "Verilog" => "#b2b7f8",
+    "Vue" => "#41b883",
+    "XML" => "#0060ac",
+    "YAML" => "#cb171e",
+    "Zig" => "#ec915c",
+};
+
+/// Returns the color of a language as a hex code, such as `#dea584` for Rust, from its definition
+/// if it was defined with `define_languages`, or from `COLORS` otherwise.
+pub fn language_color(language: &str) -> Option<&'static str> {
+    match defined_language(language) {
+        Some(definition) => definition.color,
+        None => COLORS.get(language).copied(),
+    }
+}
+
+/// Languages that are dialects of another language, mapped to the names of the languages they
+/// are dialects of. These are counted as their parent language by `LanguageList::merge_dialects`.
+pub const PARENT_LANGUAGES: phf::Map<&'static str, &'static str> = phf::phf_map! {
+    "JavaScript React" => "JavaScript",
+    "TypeScript React" => "TypeScript",
+};
+
+/// Returns the language that a language is a dialect of, see `PARENT_LANGUAGES`.
+pub fn parent_language(language: &str) -> Option<&'static str> {
+    PARENT_LANGUAGES.get(language).copied()
+}
+
+/// A group of languages that are counted together as a single language, such as a "Web" group of
+/// JavaScript, TypeScript, HTML, and CSS, see `LanguageList::group`.
+#[derive(Clone, Debug)]
+pub struct LanguageGroup {
+    /// The name the languages of the group are counted under.
+    pub name: &'static str,
+    /// The names of the languages in the group.
+    pub languages: Vec<&'static str>,
+}
+
+/// Common alternative names of languages, in lowercase, mapped to the names of the languages in
+/// `LANGUAGES`. These are accepted wherever a language is given by name, such as `--find` and
+/// `--exclude`.
+pub const LANGUAGE_ALIASES: phf::Map<&'static str, &'static str> = phf::phf_map! {
+    "bash" => "Shell",
+    "cpp" => "C++",
+    "cs" => "C#",
+    "csharp" => "C#",
+    "cxx" => "C++",
+    "golang" => "Go",
+    "js" => "JavaScript",
+    "jsx" => "JavaScript React",
+    "md" => "Markdown",
+    "objc" => "Objective-C",
+    "py" => "Python",
+    "rb" => "Ruby",
+    "rs" => "Rust",
+    "sh" => "Shell",
+    "ts" => "TypeScript",
+    "tsx" => "TypeScript React",
+    "yml" => "YAML",
+    "zsh" => "Shell",
+};
+
+/// Returns whether a language is the one given by a name, such as a name given to `--find`. Names
+/// match case-insensitively, and may also be one of the `LANGUAGE_ALIASES`.
+///
+/// # Parameters
+/// - `language` - The name of the language, from a value of `LANGUAGES`
+/// - `name` - The name to match against the language
+pub fn language_matches(language: &str, name: &str) -> bool {
+    let name = name.to_lowercase();
+    let name = LANGUAGE_ALIASES.get(&name).copied().unwrap_or(&name);
+    language.to_lowercase() == name.to_lowercase()
+}
+
+/// Returns the names of the known languages: the languages in `LANGUAGES`, `FILENAMES`, and
+/// `COMMENT_SYNTAX`, and those defined with `define_languages`. Names may appear more than once.
+pub fn known_language_names() -> impl Iterator<Item = &'static str> {
+    LANGUAGES
+        .values()
+        .chain(FILENAMES.values())
+        .chain(COMMENT_SYNTAX.keys())
+        .copied()
+        .chain(defined_languages().iter().map(|definition| definition.name))
+}
+
+/// Returns whether a name matches a known language with `language_matches`, such as `rust` or
+/// `js`, see `known_language_names`.
+pub fn is_known_language(name: &str) -> bool {
+    known_language_names().any(|language| language_matches(language, name))
+}
+
+/// Returns the known language with the name closest to the given name, for suggesting a
+/// correction of a misspelled language name, such as Python for `Pyhton`. Names are compared
+/// case-insensitively by edit distance.
+///
+/// # Returns
+/// - The closest language, or `None` if the name is already a known language or alias, or if no language
+///   is close enough to be a likely misspelling.
+pub fn closest_language(name: &str) -> Option<&'static str> {
+    let name = name.to_lowercase();
+    if LANGUAGE_ALIASES.contains_key(&name) {
+        return None;
+    }
+    let mut closest = None;
+    for language in known_language_names() {
+        let distance = edit_distance(&name, &language.to_lowercase());
+        if distance == 0 {
+            return None;
+        }
+        if closest.is_none_or(|(_, closest_distance)| distance < closest_distance) {
+            closest = Some((language, distance));
+        }
+    }
+
+    // Allow roughly one edit for every three characters of the name
+    closest
+        .filter(|(_, distance)| *distance <= (name.chars().count() / 3).max(1))
+        .map(|(language, _)| language)
+}
+
+/// Returns the Levenshtein distance between two strings: the number of characters that have to be
+/// inserted, deleted, or substituted to turn one into the other.
+fn edit_distance(string: &str, other: &str) -> usize {
+    let other = other.chars().collect::<Vec<_>>();
+    let mut previous = (0..=other.len()).collect::<Vec<_>>();
+    for (index, character) in string.chars().enumerate() {
+        let mut current = vec![index + 1];
+        for (other_index, other_character) in other.iter().enumerate() {
+            let substitution = previous[other_index] + usize::from(character != *other_character);
+            current.push(substitution.min(previous[other_index + 1] + 1).min(current[other_index] + 1));
+        }
+        previous = current;
+    }
+    previous[other.len()]
+}
+
+/// A language that splik can detect, along with how it is detected, see `known_languages`.
+#[derive(serde::Serialize, Clone)]
+pub struct KnownLanguage {
+    /// The name of the language.
+    pub name: &'static str,
+    /// The category of the language, see `CATEGORIES`.
+    pub category: Category,
+    /// The color of the language as a hex code, see `COLORS`. This is left out for languages
+    /// without a color.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub color: Option<&'static str>,
+    /// The extensions of the files of the language, without the leading dot, in sorted order.
+    pub extensions: Vec<String>,
+    /// The names of the files of the language, with names matched by their start written as
+    /// `Dockerfile.*`, in sorted order.
+    pub filenames: Vec<String>,
+    /// The interpreters of the shebang lines of the scripts of the language, in sorted order.
+    pub interpreters: Vec<String>,
+}
+
+/// The languages that splik can detect, sorted by name, for `--list-languages`.
+#[derive(serde::Serialize, Clone)]
+pub struct KnownLanguages {
+    /// Each language, along with how it is detected.
+    pub languages: Vec<KnownLanguage>,
+}
+
+impl KnownLanguages {
+    /// Gathers every language that splik can detect, with the extensions, file names, and
+    /// interpreters that it is detected by. Languages that only have comment syntax, such as the
+    /// languages of the blocks of single-file components, are listed without any.
+    ///
+    /// # Parameters
+    /// - `extension_map` - Custom extension to language mappings, such as those of `--map`, which
+    ///   take priority over `LANGUAGES`. Extensions mapped to `None` aren't listed.
+    pub fn new(extension_map: &std::collections::HashMap<String, Option<&'static str>>) -> Self {
+        type Languages = std::collections::BTreeMap<&'static str, KnownLanguage>;
+        fn entry<'a>(languages: &'a mut Languages, name: &'static str) -> &'a mut KnownLanguage {
+            languages.entry(name).or_insert_with(|| KnownLanguage {
+                name,
+                category: language_category(name),
+                color: language_color(name),
+                extensions: Vec::new(),
+                filenames: Vec::new(),
+                interpreters: Vec::new(),
+            })
+        }
+        let mut languages = Languages::new();
+
+        // Custom mappings and defined languages replace the languages of the extensions they map
+        let definitions = defined_languages();
+        for (extension, name) in &LANGUAGES {
+            let is_defined = definitions.iter().any(|definition| definition.has_extension(extension));
+            if !extension_map.contains_key(*extension) && !is_defined {
+                entry(&mut languages, name).extensions.push((*extension).to_owned());
+            }
+        }
+        for definition in definitions {
+            let language = entry(&mut languages, definition.name);
+            let mapped = |extension: &&String| extension_map.contains_key(extension.as_str());
+            language
+                .extensions
+                .extend(definition.extensions.iter().filter(|extension| !mapped(extension)).cloned());
+            language.filenames.extend(definition.filenames.iter().cloned());
+        }
+        for (extension, name) in extension_map {
+            if let Some(name) = name {
+                entry(&mut languages, name).extensions.push(extension.clone());
+            }
+        }
+        for (filename, name) in &FILENAMES {
+            entry(&mut languages, name).filenames.push((*filename).to_owned());
+        }
+        for (prefix, name) in FILENAME_PREFIXES {
+            entry(&mut languages, name).filenames.push(format!("{prefix}*"));
+        }
+        for (interpreter, name) in &SHEBANG_INTERPRETERS {
+            entry(&mut languages, name).interpreters.push((*interpreter).to_owned());
+        }
+        for name in COMMENT_SYNTAX.keys() {
+            entry(&mut languages, name);
+        }
+
+        let mut languages = languages.into_values().collect::<Vec<_>>();
+        languages.sort_by_key(|language| language.name.to_lowercase());
+        for language in &mut languages {
+            language.extensions.sort();
+            language.filenames.sort();
+            language.interpreters.sort();
+        }
+        Self { languages }
+    }
+
+    /// Keeps only the languages that match a name, case-insensitively or by an alias, see
+    /// `language_matches`.
+    pub fn retain_matching(&mut self, name: &str) {
+        self.languages.retain(|language| language_matches(language.name, name));
+    }
+}
+
+/// A language defined at runtime, such as in the `languages` of a configuration file, see
+/// `define_languages`.
+#[derive(Clone)]
+pub struct LanguageDefinition {
+    /// The name of the language.
+    pub name: &'static str,
+    /// The extensions of the files of the language, without the leading dot. These take priority
+    /// over `LANGUAGES`, including the detection of ambiguous extensions such as `.m`.
+    pub extensions: Vec<String>,
+    /// The exact names of the files of the language, which take priority over `FILENAMES`.
+    pub filenames: Vec<String>,
+    /// The category of the language.
+    pub category: Category,
+    /// The color of the language as a hex code, such as `#dea584`.
+    pub color: Option<&'static str>,
+    /// The comment syntax of the language. If this is `None`, the language's comment syntax from
+    /// `COMMENT_SYNTAX` is used if it has one, and otherwise every non-blank line is code.
+    pub comments: Option<CommentSyntax>,
+}
+
+impl LanguageDefinition {
+    /// Returns whether files with the given extension are of this language.
+    fn has_extension(&self, extension: &str) -> bool {
+        self.extensions.iter().any(|other| other == extension)
+    }
+}
+
+/// The languages defined with `define_languages`.
+static DEFINED_LANGUAGES: std::sync::OnceLock<Vec<LanguageDefinition>> = std::sync::OnceLock::new();
+
+/// Adds languages to the languages that splik knows, for the rest of the process. Defined
+/// languages are detected, categorized, colored, and have their comments counted as described
+/// by their definitions, taking priority over the built-in languages, so that a definition can
+/// reclaim a built-in extension or replace a built-in language of the same name.
+///
+/// Languages can only be defined once, before scanning, and any later definitions are ignored.
+///
+/// # Parameters
+/// - `definitions` - The languages to define. When several definitions have the same extension or
+///   file name, the first one is used.
+pub fn define_languages(definitions: Vec<LanguageDefinition>) {
+    let _ = DEFINED_LANGUAGES.set(definitions);
+}
+
+/// Returns the languages defined with `define_languages`, which is empty if none were defined.
+pub fn defined_languages() -> &'static [LanguageDefinition] {
+    DEFINED_LANGUAGES.get().map_or(&[], Vec::as_slice)
+}
+
+/// Returns the definition of the language with the given name, if it was defined with
+/// `define_languages`.
+fn defined_language(language: &str) -> Option<&'static LanguageDefinition> {
+    defined_languages().iter().find(|definition| definition.name == language)
+}
+
+/// Returns the comment syntax of a language, from its definition if it was defined with
+/// `define_languages` with comments of its own, or from `COMMENT_SYNTAX` otherwise.
+pub fn comment_syntax(language: &str) -> Option<&'static CommentSyntax> {
+    defined_language(language)
+        .and_then(|definition| definition.comments.as_ref())
+        .or_else(|| COMMENT_SYNTAX.get(language))
+}
+
+/// Files that are recognized by their exact name, mapped to the names of their languages. These
+/// are checked when a file's extension isn't in `LANGUAGES`.
+pub const FILENAMES: phf::Map<&'static str, &'static str> = phf::phf_map! {
+    "CMakeLists.txt" => "CMake",
+    "Dockerfile" => "Dockerfile",
+    "Containerfile" => "Dockerfile",
+    "Jenkinsfile" => "Groovy",
+    "Makefile" => "Make",
+    "makefile" => "Make",
+    "GNUmakefile" => "Make",
+    "Gemfile" => "Ruby",
+    "Rakefile" => "Ruby",
+    "Vagrantfile" => "Ruby",
+};
+
+/// Files that are recognized by the start of their name, such as `Dockerfile.dev`, mapped to the
+/// names of their languages. These are checked after `FILENAMES`.
+pub const FILENAME_PREFIXES: &[(&str, &str)] = &[("Dockerfile.", "Dockerfile"), ("Containerfile.", "Dockerfile")];
+
+/// Script interpreters that are recognized in shebang lines, mapped to the names of their
+/// languages in `LANGUAGES`. Version numbers are stripped from interpreters before lookup.
+pub const SHEBANG_INTERPRETERS: phf::Map<&'static str, &'static str> = phf::phf_map! {
+    "fish" => "Fish",
+    "lua" => "Lua",
+    "node" => "JavaScript",
+    "perl" => "Perl",
+    "pwsh" => "PowerShell",
+    "python" => "Python",
+    "ruby" => "Ruby",
+    "sh" => "Shell",
+    "bash" => "Shell",
+    "dash" => "Shell",
+    "ksh" => "Shell",
+    "zsh" => "Shell",
+};
+
+/// The comment syntax of a programming language, used to classify lines as code or comments.
+#[derive(Clone, Copy)]
+pub struct CommentSyntax {
+    /// Markers that begin a comment running until the end of the line, such as `//` or `#`.
+    pub line: &'static [&'static str],
+    /// The start and end markers of block comments, such as `/*` and `*/`.
+    pub block: &'static [(&'static str, &'static str)],
+    /// Whether block comments can be nested inside of each other, as in Rust or Haskell.
+    pub nested: bool,
+    /// The ASCII characters that begin and end string and character literals, such as `"` and
+    /// `'`, inside of which comment markers are part of the literal, as in `"/*"`. A backslash
+    /// escapes the character after it, and a literal that isn't closed ends with its line. This is
+    /// empty for languages whose literals can't be told apart from prose, such as HTML, or where
+    /// a quote also has other uses, such as the lifetimes of Rust.
+    pub quotes: &'static [u8],
+}
+
+/// Comment syntax for languages with `//` line comments and `/* */` block comments.
+const C_STYLE_COMMENTS: CommentSyntax = CommentSyntax {
+    line: &["//"],
+    block: &[("/*", "*/")],
+    nested: false,
+    quotes: b"\"'",
+};
+
+/// Comment syntax for languages with only `#` line comments.
+const HASH_COMMENTS: CommentSyntax = CommentSyntax {
+    line: &["#"],
+    block: &[],
+    nested: false,
+    quotes: b"\"'",
+};
+
+/// Comment syntax for each language, keyed by the language names in `LANGUAGES`. Languages that
+/// aren't listed here have every non-blank line counted as code.
+pub const COMMENT_SYNTAX: phf::Map<&'static str, CommentSyntax> = phf::phf_map! {
+    "Assembly" => CommentSyntax { line: &[";", "#"], block: &[], nested: false, quotes: b"\"'" },
+    "Batch" => CommentSyntax { line: &["REM ", "rem ", "@REM ", "@rem ", "::"], block: &[], nested: false, quotes: &[] },
+    "C" => C_STYLE_COMMENTS,
+    "C++" => C_STYLE_COMMENTS,
+    "C#" => C_STYLE_COMMENTS,
+    "C/C++ Header" => C_STYLE_COMMENTS,
+    "CMake" => CommentSyntax { line: &["#"], block: &[("#[[", "]]")], nested: false, quotes: b"\"" },
+    "Coq" => CommentSyntax { line: &[], block: &[("(*", "*)")], nested: true, quotes: b"\"" },
+    "CSS" => CommentSyntax { line: &[], block: &[("/*", "*/")], nested: false, quotes: b"\"'" },
+    "Dockerfile" => HASH_COMMENTS,
+    "Fish" => HASH_COMMENTS,
+    "Fortran" => CommentSyntax { line: &["!"], block: &[], nested: false, quotes: b"\"'" },
+    "Gleam" => CommentSyntax { line: &["//"], block: &[], nested: false, quotes: b"\"" },
+    "Go" => C_STYLE_COMMENTS,
+    "Groovy" => C_STYLE_COMMENTS,
+    "Haskell" => CommentSyntax { line: &["--"], block: &[("{-", "-}")], nested: true, quotes: b"\"" },
+    "HTML" => CommentSyntax { line: &[], block: &[("<!--", "-->")], nested: false, quotes: &[] },
+    "INI" => CommentSyntax { line: &[";", "#"], block: &[], nested: false, quotes: &[] },
+    "Java" => C_STYLE_COMMENTS,
+    "JavaScript" => C_STYLE_COMMENTS,
+    "JavaScript React" => C_STYLE_COMMENTS,
+    "JSON" => C_STYLE_COMMENTS,
+    "Julia" => CommentSyntax { line: &["#"], block: &[("#=", "=#")], nested: true, quotes: b"\"" },
+    "Kotlin" => CommentSyntax { line: &["//"], block: &[("/*", "*/")], nested: true, quotes: b"\"'" },
+    "LaTeX" => CommentSyntax { line: &["%"], block: &[], nested: false, quotes: &[] },
+    "Less" => C_STYLE_COMMENTS,
+    "Lua" => CommentSyntax { line: &["--"], block: &[("--[[", "]]")], nested: false, quotes: b"\"'" },
+    "Make" => HASH_COMMENTS,
+    "Markdown" => CommentSyntax { line: &[], block: &[("<!--", "-->")], nested: false, quotes: &[] },
+    "MATLAB" => CommentSyntax { line: &["%"], block: &[("%{", "%}")], nested: false, quotes: b"\"" },
+    "Objective-C" => C_STYLE_COMMENTS,
+    "Perl" => CommentSyntax { line: &["#"], block: &[("=pod", "=cut")], nested: false, quotes: b"\"'" },
+    "PHP" => CommentSyntax { line: &["//", "#"], block: &[("/*", "*/")], nested: false, quotes: b"\"'" },
+    "PowerShell" => CommentSyntax { line: &["#"], block: &[("<#", "#>")], nested: false, quotes: b"\"'" },
+    "Python" => HASH_COMMENTS,
+    "R" => HASH_COMMENTS,
+    "Ruby" => CommentSyntax { line: &["#"], block: &[("=begin", "=end")], nested: false, quotes: b"\"'" },
+    "Rust" => CommentSyntax { line: &["//"], block: &[("/*", "*/")], nested: true, quotes: b"\"" },
+    "Sass" => C_STYLE_COMMENTS,
+    "SCSS" => C_STYLE_COMMENTS,
+    "Shell" => HASH_COMMENTS,
+    "SQL" => CommentSyntax { line: &["--"], block: &[("/*", "*/")], nested: false, quotes: b"\"'" },
+    "Stylus" => C_STYLE_COMMENTS,
+    "Svelte" => CommentSyntax { line: &["//"], block: &[("<!--", "-->"), ("/*", "*/")], nested: false, quotes: &[] },
+    "Swift" => CommentSyntax { line: &["//"], block: &[("/*", "*/")], nested: true, quotes: b"\"" },
+    "SystemVerilog" => CommentSyntax { line: &["//"], block: &[("/*", "*/")], nested: false, quotes: b"\"" },
+    "TOML" => HASH_COMMENTS,
+    "TypeScript" => C_STYLE_COMMENTS,
+    "TypeScript React" => C_STYLE_COMMENTS,
+    "V" => C_STYLE_COMMENTS,
+    "Verilog" => CommentSyntax { line: &["//"], block: &[("/*", "*/")], nested: false, quotes: b"\"" },
+    "Vue" => CommentSyntax { line: &["//"], block: &[("<!--", "-->"), ("/*", "*/")], nested: false, quotes: &[] },
+    "XML" => CommentSyntax { line: &[], block: &[("<!--", "-->")], nested: false, quotes: &[] },
+    "YAML" => HASH_COMMENTS,
+    "Zig" => CommentSyntax { line: &["//"], block: &[], nested: false, quotes: b"\"'" },
+};
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn languages_match_case_insensitively() {
+        assert!(super::language_matches("JavaScript", "javascript"));
+        assert!(super::language_matches("JavaScript", "JavaScript"));
+        assert!(super::language_matches("JavaScript", "JAVASCRIPT"));
+        assert!(!super::language_matches("JavaScript React", "javascript"));
+        assert!(!super::language_matches("JavaScript", "java"));
+    }
+
+    #[test]
+    fn languages_match_aliases() {
+        assert!(super::language_matches("JavaScript", "js"));
+        assert!(super::language_matches("JavaScript", "JS"));
+        assert!(super::language_matches("C++", "cpp"));
+        assert!(!super::language_matches("TypeScript", "js"));
+    }
+
+    #[test]
+    fn misspelled_languages_are_suggested() {
+        assert_eq!(super::closest_language("javascirpt"), Some("JavaScript"));
+        assert_eq!(super::closest_language("Pyhton"), Some("Python"));
+        assert!(!super::is_known_language("javascirpt"));
+    }
+
+    #[test]
+    fn known_languages_are_not_suggested() {
+        assert_eq!(super::closest_language("javascript"), None);
+        assert_eq!(super::closest_language("js"), None);
+        assert_eq!(super::closest_language("qwertyuiop"), None);
+    }
+}
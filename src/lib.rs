@@ -0,0 +1,79 @@
+//! Splik (Simple Programming Language Identifier Kit) identifies the languages used in a project,
+//! and counts the files, lines, and bytes of each of them.
+//!
+//! The main entry point is `scan`, which walks a directory and returns a `LanguageList` that can
+//! be sorted, queried, and rendered in each of splik's output formats:
+//!
+//! ```no_run
+//! let root = splik::get_root_dir(std::path::Path::new(".")).unwrap();
+//! let mut languages = splik::scan(&root, &splik::ScanOptions::default())?;
+//! languages.sort(splik::SortKey::Bytes, false);
+//! languages.display(&mut std::io::stdout(), None, 1.0, splik::DisplayStyle::default())?;
+//! # Ok::<(), std::io::Error>(())
+//! ```
+
+mod attributes;
+mod baseline;
+mod cache;
+mod compat;
+mod component;
+mod config;
+mod directory;
+mod encoding;
+mod explain;
+mod format;
+mod generated;
+mod git;
+mod html;
+mod language;
+mod lines;
+mod list;
+mod mmap;
+mod notebook;
+mod output;
+mod progress;
+mod root;
+mod scan;
+mod style;
+mod svg;
+mod test_files;
+mod timing;
+mod xml;
+
+pub use baseline::{Baseline, BaselineLanguage, Comparison, Delta, DeltaStatus, LanguageDelta};
+pub use cache::{FileCache, CACHE_DIRECTORY};
+pub use config::{
+    global_config_path, ColorChoice, Config, LanguageConfig, OutputFormat, CONFIG_FILE_NAMES, DEFAULT_CATEGORIES,
+};
+pub use directory::{DirectoryList, DirectoryReport};
+pub use encoding::{Encoding, EncodingStats};
+pub use explain::{explain, Explanation, RuleCheck};
+pub use format::{format_count, format_size};
+pub use generated::GeneratedFiles;
+pub use git::{git_files, git_revision_blobs, read_git_blobs, GitBlob};
+pub use language::{
+    closest_language, comment_syntax, define_languages, defined_languages, detect_language,
+    detect_language_from_contents, is_known_language, known_language_names, language_category, language_color,
+    language_matches, parent_language, Category, CommentSyntax, KnownLanguage, KnownLanguages, LanguageDefinition,
+    LanguageGroup, CATEGORIES, COLORS, COMMENT_SYNTAX, FILENAMES, FILENAME_PREFIXES, LANGUAGES, LANGUAGE_ALIASES,
+    PARENT_LANGUAGES, SHEBANG_INTERPRETERS,
+};
+pub use lines::TODO_MARKERS;
+pub use list::{
+    FileInfo, FileListing, FileReport, FileStats, LanguageInfo, LanguageList, LanguageReport, Report, SkippedPath,
+    SortKey, Totals, UnknownExtension, UnknownFiles, NO_EXTENSION, SCHEMA_VERSION,
+};
+pub use mmap::DEFAULT_MMAP_THRESHOLD;
+pub use output::{DisplayStyle, MermaidValues};
+pub use progress::ScanProgress;
+pub use root::{find_nearest_root, find_root, get_root_dir, ProjectRoot, RootIndicators, RootTier, ROOT_INDICATORS};
+pub use scan::{
+    scan, scan_by_directory, scan_paths, scan_paths_by_directory, scan_revision, scan_revision_by_directory,
+    FileCallback, NestedProjects, PathStyle, ScanOptions, IGNORED_DIRECTORIES, OUTPUT_DIRECTORIES,
+};
+pub use style::{FileStyle, Indentation, LineEnding, StyleStats};
+pub use svg::{SvgOptions, SvgStyle};
+pub use test_files::TestFiles;
+pub use timing::{ScanTiming, Timing};
+
+pub(crate) use list::percent;
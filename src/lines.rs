@@ -0,0 +1,587 @@
+//! Counting and classifying the lines of source files.
+
+/// The kind of content on a single line of source code.
+pub(crate) enum LineKind {
+    /// The line contains code, possibly followed or preceded by a comment.
+    Code,
+    /// The line contains only comments.
+    Comment,
+    /// The line is empty or contains only whitespace.
+    Blank,
+}
+
+/// The number of bytes read from a file at a time when counting its lines.
+pub(crate) const READ_BUFFER_SIZE: usize = 64 * 1024;
+
+/// The markers of tech debt that are counted by default, see `ScanOptions::todo_markers`.
+pub const TODO_MARKERS: &[&str] = &["TODO", "FIXME", "HACK", "XXX"];
+
+/// The line counts of a single file.
+#[derive(Default, Clone, Copy, serde::Serialize, serde::Deserialize)]
+pub(crate) struct LineCounts {
+    /// The total number of lines.
+    pub(crate) lines: u64,
+    /// The number of lines containing code.
+    pub(crate) code: u64,
+    /// The number of lines containing only comments.
+    pub(crate) comments: u64,
+    /// The number of blank lines.
+    pub(crate) blanks: u64,
+    /// The number of markers such as `TODO` in the file, or `None` if they weren't counted, see
+    /// `ScanOptions::todo_markers`.
+    #[serde(default)]
+    pub(crate) todos: Option<u64>,
+    /// The line endings and indentation of the file, or `None` if they weren't counted, see
+    /// `ScanOptions::style_stats`.
+    #[serde(default)]
+    pub(crate) style: Option<crate::style::StyleCounts>,
+    /// The encoding of the file, or `None` if it wasn't checked, see `ScanOptions::encoding_stats`.
+    #[serde(default)]
+    pub(crate) encoding: Option<crate::Encoding>,
+    /// The size of the file once decoded into UTF-8, or `None` if it is already UTF-8. This is
+    /// counted as the bytes of the file instead of its size on disk, so that the bytes of files
+    /// can be compared across encodings.
+    #[serde(default)]
+    pub(crate) utf8_bytes: Option<u64>,
+}
+
+/// The counts of a file that are added to as its lines are read, see `LineClassifier::count_lines`.
+struct Tally<'a> {
+    /// The line counts so far.
+    counts: LineCounts,
+    /// The number of markers of `ScanOptions::todo_markers` found so far.
+    todos: u64,
+    /// The line endings and indentation found so far.
+    style: crate::style::StyleCounts,
+    /// Whether every line so far is valid UTF-8.
+    is_utf8: bool,
+    /// The options of the scan.
+    options: &'a crate::ScanOptions,
+}
+
+/// Classifies the lines of a single file as code, comments, or blanks. Lines must be passed in
+/// order, because block comments carry over from one line to the next. A line can be passed in
+/// several chunks using `feed`, and is finished with `end_line`.
+pub(crate) struct LineClassifier {
+    /// The comment syntax of the file's language, or `None` if the language has no known comment
+    /// syntax, in which case every non-blank line is code.
+    syntax: Option<&'static crate::CommentSyntax>,
+    /// The block comment that is currently open, as an index into `syntax.block`, along with how
+    /// deeply it is nested.
+    open_block: Option<(usize, usize)>,
+    /// Whether the rest of the current line is a line comment.
+    in_line_comment: bool,
+    /// The quote of the string or character literal that is currently open on this line, along
+    /// with whether the byte after the end of the last chunk is escaped by a backslash, see
+    /// `CommentSyntax::quotes`.
+    open_quote: Option<(u8, bool)>,
+    /// Whether code has been found on the current line.
+    has_code: bool,
+    /// Whether a comment has been found on the current line.
+    has_comment: bool,
+    /// Whether each byte is the first byte of a comment marker or a quote of the syntax. Other
+    /// bytes can't change how a line is classified once it is known to contain code, so they are
+    /// skipped.
+    starts_marker: [bool; 256],
+}
+
+impl LineClassifier {
+    /// Creates a new `LineClassifier` for a file with the given comment syntax.
+    pub(crate) fn new(syntax: Option<&'static crate::CommentSyntax>) -> Self {
+        let mut starts_marker = [false; 256];
+        if let Some(syntax) = syntax {
+            let blocks = syntax.block.iter().flat_map(|(start, end)| [start, end]);
+            for marker in syntax.line.iter().chain(blocks) {
+                if let Some(first) = marker.as_bytes().first() {
+                    starts_marker[usize::from(*first)] = true;
+                }
+            }
+            for quote in syntax.quotes {
+                starts_marker[usize::from(*quote)] = true;
+            }
+        }
+        Self {
+            syntax,
+            open_block: None,
+            in_line_comment: false,
+            open_quote: None,
+            has_code: false,
+            has_comment: false,
+            starts_marker,
+        }
+    }
+
+    /// Returns the length of the longest comment marker of the syntax. When a line is too long to
+    /// buffer, this many bytes are held back so that a marker is never split between two chunks.
+    fn longest_marker(&self) -> usize {
+        self.syntax.map_or(0, |syntax| {
+            let blocks = syntax.block.iter().flat_map(|(start, end)| [start, end]);
+            syntax.line.iter().chain(blocks).map(|marker| marker.len()).max().unwrap_or(0)
+        })
+    }
+
+    /// Reads an entire file and counts its lines. The file is streamed through the buffer of
+    /// `reader`, so memory use doesn't depend on the size of the file.
+    ///
+    /// The number of lines is the number of `\n` bytes, plus one if the file ends with any other
+    /// byte, so an empty file has no lines and a final line without a trailing newline still
+    /// counts. A `\r\n` ends a single line, with the `\r` treated as part of the line ending
+    /// rather than content, and a lone `\r` doesn't end a line. A leading UTF-8 byte order mark
+    /// is skipped, and a file that starts with a UTF-16 byte order mark is decoded into UTF-8 as
+    /// it is read, so that its lines are counted the same way.
+    ///
+    /// # Parameters
+    /// - `reader` - The contents of the file, which is either already in memory, or wrapped in a
+    ///   `std::io::BufReader` of `READ_BUFFER_SIZE`
+    /// - `options` - The options of the scan, which say whether to count the markers of
+    ///   `ScanOptions::todo_markers`, the styles of `ScanOptions::style_stats`, and the encoding of
+    ///   `ScanOptions::encoding_stats`
+    ///
+    /// # Returns
+    /// - The line counts of the file, or an error if the file couldn't be read.
+    pub(crate) fn count(
+        self,
+        mut reader: impl std::io::BufRead,
+        options: &crate::ScanOptions,
+    ) -> std::io::Result<LineCounts> {
+        let prefix = std::io::BufRead::fill_buf(&mut reader)?;
+
+        // Decode a UTF-16 file after its byte order mark
+        if let Some(encoding) = crate::encoding::utf16_encoding(prefix) {
+            std::io::BufRead::consume(&mut reader, 2);
+            let decoder = crate::encoding::Utf16Decoder::new(reader, encoding);
+            let mut decoded = std::io::BufReader::with_capacity(READ_BUFFER_SIZE, decoder);
+            let mut counts = self.count_lines(&mut decoded, options)?;
+            counts.encoding = options.encoding_stats.then_some(encoding);
+            counts.utf8_bytes = Some(decoded.get_ref().decoded_bytes());
+            return Ok(counts);
+        }
+
+        // Skip a leading UTF-8 byte order mark
+        if prefix.starts_with(b"\xEF\xBB\xBF") {
+            std::io::BufRead::consume(&mut reader, 3);
+        }
+        self.count_lines(reader, options)
+    }
+
+    /// Counts the lines of a file that has been decoded into UTF-8 if it needed to be, see
+    /// `count`. The encoding of the counts is `Encoding::Utf8` if every line is valid UTF-8, and
+    /// `Encoding::Other` otherwise.
+    ///
+    /// Newlines are found with `memchr`, and lines that lie entirely within the read buffer are
+    /// processed in place. Only the start of a line that runs past the end of the buffer is copied,
+    /// into a single buffer that is reused for every line of the file.
+    fn count_lines(
+        mut self,
+        mut reader: impl std::io::BufRead,
+        options: &crate::ScanOptions,
+    ) -> std::io::Result<LineCounts> {
+        let mut tally = Tally {
+            counts: LineCounts::default(),
+            todos: 0,
+            style: crate::style::StyleCounts::default(),
+            is_utf8: true,
+            options,
+        };
+        let mut line = Vec::new();
+
+        // Whether the start of the current line hasn't been processed yet, since long lines are
+        // processed in chunks
+        let mut at_line_start = true;
+
+        loop {
+            let buffer = std::io::BufRead::fill_buf(&mut reader)?;
+            if buffer.is_empty() {
+                break;
+            }
+
+            // Finish every line that ends in the buffer, along with the start of the first one if
+            // it was read earlier
+            let length = buffer.len();
+            let mut start = 0;
+            for newline in memchr::memchr_iter(b'\n', buffer) {
+                let is_whole_line = std::mem::replace(&mut at_line_start, true);
+                if line.is_empty() {
+                    self.finish_line(&buffer[start..=newline], is_whole_line, &mut tally);
+                } else {
+                    line.extend_from_slice(&buffer[start..=newline]);
+                    self.finish_line(&line, is_whole_line, &mut tally);
+                    line.clear();
+                }
+                start = newline + 1;
+            }
+
+            // Keep buffering the rest of the current line, processing it in chunks if it is long
+            line.extend_from_slice(&buffer[start..]);
+            std::io::BufRead::consume(&mut reader, length);
+            if line.len() > READ_BUFFER_SIZE {
+                let processed = line.len() - self.longest_marker();
+                self.feed(&line[..processed]);
+                tally.todos += count_markers(&line[..processed], &options.todo_markers);
+                let is_line_start = std::mem::replace(&mut at_line_start, false);
+                if is_line_start {
+                    tally.style.add_line_start(&line);
+                }
+                if options.encoding_stats {
+                    tally.is_utf8 &= is_utf8_chunk(&line[..processed], !is_line_start, true);
+                }
+                line.drain(..processed);
+            }
+        }
+
+        // Final line without a trailing newline, which may have been processed entirely in chunks
+        // if it is long
+        if !line.is_empty() || !at_line_start {
+            self.feed(&line);
+            tally.todos += count_markers(&line, &options.todo_markers);
+            if at_line_start {
+                tally.style.add_line_start(&line);
+            }
+            if options.encoding_stats {
+                tally.is_utf8 &= is_utf8_chunk(&line, !at_line_start, false);
+            }
+            tally.counts.add(self.end_line());
+        }
+
+        let mut counts = tally.counts;
+        counts.todos = (!options.todo_markers.is_empty()).then_some(tally.todos);
+        counts.style = options.style_stats.then_some(tally.style);
+        let encoding = if tally.is_utf8 {
+            crate::Encoding::Utf8
+        } else {
+            crate::Encoding::Other
+        };
+        counts.encoding = options.encoding_stats.then_some(encoding);
+        Ok(counts)
+    }
+
+    /// Processes the rest of a line up to and including its newline, and counts it.
+    ///
+    /// # Parameters
+    /// - `line` - The bytes of the line that haven't been processed yet, ending with its newline
+    /// - `is_whole_line` - Whether `line` is the whole line, rather than the rest of a long line
+    ///   whose start was processed in chunks
+    /// - `tally` - The counts of the file so far
+    fn finish_line(&mut self, line: &[u8], is_whole_line: bool, tally: &mut Tally) {
+        self.feed(line);
+        tally.todos += count_markers(line, &tally.options.todo_markers);
+        if is_whole_line {
+            tally.style.add_line_start(line);
+        }
+        if tally.options.encoding_stats {
+            tally.is_utf8 &= is_utf8_chunk(line, !is_whole_line, false);
+        }
+        tally.style.add_line_end(line);
+        tally.counts.add(self.end_line());
+    }
+
+    /// Processes the next chunk of the current line. Comment markers are recognized in code and
+    /// inside of block comments, but not inside of string and character literals, which end with
+    /// their line if they aren't closed.
+    ///
+    /// # Parameters
+    /// - `chunk` - The bytes of the line. Comment markers must not be split between chunks.
+    fn feed(&mut self, chunk: &[u8]) {
+        let Some(syntax) = self.syntax else {
+            self.has_code |= !chunk.trim_ascii().is_empty();
+            return;
+        };
+
+        let mut index = 0;
+        while index < chunk.len() && !self.in_line_comment {
+            let rest = &chunk[index..];
+
+            // Inside a block comment, look for the end (or a nested start) of the comment
+            if let Some((block, depth)) = self.open_block {
+                let (start, end) = syntax.block[block];
+                if rest.starts_with(end.as_bytes()) {
+                    self.open_block = (depth > 1).then(|| (block, depth - 1));
+                    self.has_comment = true;
+                    index += end.len();
+                } else if syntax.nested && rest.starts_with(start.as_bytes()) {
+                    self.open_block = Some((block, depth + 1));
+                    index += start.len();
+                } else {
+                    // Skip to the next byte that may begin the end or a nested start
+                    let (start, end) = (start.as_bytes()[0], end.as_bytes()[0]);
+                    let skipped = if syntax.nested {
+                        memchr::memchr2(start, end, &rest[1..])
+                    } else {
+                        memchr::memchr(end, &rest[1..])
+                    };
+                    let skipped = 1 + skipped.unwrap_or(rest.len() - 1);
+                    self.has_comment |= rest[..skipped].iter().any(|byte| !byte.is_ascii_whitespace());
+                    index += skipped;
+                }
+                continue;
+            }
+
+            // Inside a literal, look for the closing quote, skipping escaped characters
+            if let Some((quote, escaped)) = self.open_quote {
+                if escaped {
+                    self.open_quote = Some((quote, false));
+                    index += 1;
+                    continue;
+                }
+                match memchr::memchr2(quote, b'\\', rest) {
+                    Some(position) if rest[position] == quote => {
+                        self.open_quote = None;
+                        index += position + 1;
+                    }
+                    Some(position) => {
+                        self.open_quote = Some((quote, true));
+                        index += position + 1;
+                    }
+                    None => index = chunk.len(),
+                }
+                continue;
+            }
+
+            if rest[0].is_ascii_whitespace() {
+                index += 1;
+                continue;
+            }
+
+            // Block comment starts are checked first, since some (such as Lua's `--[[`) begin with
+            // a line comment marker
+            if let Some(block) = syntax.block.iter().position(|(start, _)| rest.starts_with(start.as_bytes())) {
+                self.has_comment = true;
+                self.open_block = Some((block, 1));
+                index += syntax.block[block].0.len();
+                continue;
+            }
+
+            // The rest of the line is a comment
+            if syntax.line.iter().any(|marker| rest.starts_with(marker.as_bytes())) {
+                self.has_comment = true;
+                self.in_line_comment = true;
+                break;
+            }
+
+            // Comment markers inside of a literal are part of it
+            if syntax.quotes.contains(&rest[0]) {
+                self.has_code = true;
+                self.open_quote = Some((rest[0], false));
+                index += 1;
+                continue;
+            }
+
+            // Once the line has code, only the bytes that may begin a comment marker matter
+            self.has_code = true;
+            index += 1;
+            index += chunk[index..]
+                .iter()
+                .position(|byte| self.starts_marker[usize::from(*byte)])
+                .unwrap_or(chunk.len() - index);
+        }
+    }
+
+    /// Finishes the current line and classifies it. Lines with both code and a comment are
+    /// classified as code.
+    ///
+    /// # Returns
+    /// - Whether the line is code, a comment, or blank.
+    fn end_line(&mut self) -> LineKind {
+        let kind = if self.has_code {
+            LineKind::Code
+        } else if self.has_comment {
+            LineKind::Comment
+        } else {
+            LineKind::Blank
+        };
+
+        self.in_line_comment = false;
+        self.open_quote = None;
+        self.has_code = false;
+        self.has_comment = false;
+        kind
+    }
+}
+
+/// Returns how many times the given markers appear in a line as whole words, such as `TODO` in
+/// `// TODO: handle errors` but not in `TODOS` or `MY_TODO`. Markers are matched case-sensitively,
+/// and aren't told apart from the contents of strings.
+fn count_markers(line: &[u8], markers: &[String]) -> u64 {
+    let is_word = |byte: &u8| byte.is_ascii_alphanumeric() || *byte == b'_';
+    let mut count = 0;
+    for marker in markers.iter().map(String::as_bytes).filter(|marker| !marker.is_empty()) {
+        for (start, window) in line.windows(marker.len()).enumerate() {
+            let is_whole_word = (start == 0 || !is_word(&line[start - 1]))
+                && line.get(start + marker.len()).is_none_or(|byte| !is_word(byte));
+            if window == marker && is_whole_word {
+                count += 1;
+            }
+        }
+    }
+    count
+}
+
+/// Returns whether a chunk of a line is valid UTF-8. The chunks of a long line are split without
+/// regard for characters, so a chunk after the first may start with the continuation bytes of a
+/// character, and a chunk before the last may end partway through one.
+///
+/// # Parameters
+/// - `chunk` - The bytes of the chunk
+/// - `continues_line` - Whether the chunk continues a line whose start was an earlier chunk
+/// - `is_continued` - Whether the line continues in a later chunk
+fn is_utf8_chunk(chunk: &[u8], continues_line: bool, is_continued: bool) -> bool {
+    let chunk = if continues_line {
+        let continuation_bytes = chunk.iter().take(3).take_while(|byte| (0x80..0xC0).contains(*byte)).count();
+        &chunk[continuation_bytes..]
+    } else {
+        chunk
+    };
+    match std::str::from_utf8(chunk) {
+        Ok(_) => true,
+        Err(error) => is_continued && error.error_len().is_none(),
+    }
+}
+
+impl LineCounts {
+    /// Adds the counts of another file, or of another region of the same file, to these. The
+    /// encoding is only UTF-8 if both are.
+    pub(crate) fn merge(&mut self, other: &LineCounts) {
+        self.lines = self.lines.saturating_add(other.lines);
+        self.code = self.code.saturating_add(other.code);
+        self.comments = self.comments.saturating_add(other.comments);
+        self.blanks = self.blanks.saturating_add(other.blanks);
+        if let Some(todos) = other.todos {
+            self.todos = Some(self.todos.unwrap_or(0).saturating_add(todos));
+        }
+        if let Some(style) = &other.style {
+            self.style.get_or_insert_with(Default::default).add(style);
+        }
+        if let Some(encoding) = other.encoding {
+            self.encoding = Some(self.encoding.map_or(encoding, |current| current.max(encoding)));
+        }
+    }
+
+    /// Counts a single line of the given kind.
+    fn add(&mut self, kind: LineKind) {
+        match kind {
+            LineKind::Code => self.code += 1,
+            LineKind::Comment => self.comments += 1,
+            LineKind::Blank => self.blanks += 1,
+        }
+        self.lines += 1;
+    }
+}
+
+/// The number of bytes at the start of a file that are checked by `is_binary`.
+pub(crate) const BINARY_PREFIX_LENGTH: usize = 8 * 1024;
+
+/// Returns whether the start of a file looks like binary data rather than text, because it
+/// contains a NUL byte, or because more than a third of it is control characters that don't
+/// appear in text. Files with a UTF-16 byte order mark are text, even though they contain NUL
+/// bytes.
+///
+/// # Parameters
+/// - `prefix` - The first `BINARY_PREFIX_LENGTH` bytes of the file, or all of it if it is shorter
+pub(crate) fn is_binary(prefix: &[u8]) -> bool {
+    if crate::encoding::utf16_encoding(prefix).is_some() {
+        return false;
+    }
+    if prefix.contains(&0) {
+        return true;
+    }
+    let control_bytes = prefix
+        .iter()
+        .filter(|byte| byte.is_ascii_control() && !matches!(byte, b'\t' | b'\n' | b'\r' | b'\x0C' | b'\x1B' | b'\x08'))
+        .count();
+    control_bytes * 3 > prefix.len()
+}
+
+#[cfg(test)]
+mod tests {
+    /// Counts the lines of `contents` as a file of `language`, with the default options.
+    ///
+    /// # Returns
+    /// - The lines, code, comments, and blanks of the file.
+    fn count(contents: &[u8], language: &str) -> (u64, u64, u64, u64) {
+        let classifier = super::LineClassifier::new(crate::comment_syntax(language));
+        let counts = classifier.count(contents, &crate::ScanOptions::default()).unwrap();
+        (counts.lines, counts.code, counts.comments, counts.blanks)
+    }
+
+    #[test]
+    fn empty_file_has_no_lines() {
+        assert_eq!(count(b"", "Rust"), (0, 0, 0, 0));
+    }
+
+    #[test]
+    fn single_newline_is_one_blank_line() {
+        assert_eq!(count(b"\n", "Rust"), (1, 0, 0, 1));
+    }
+
+    #[test]
+    fn crlf_ends_a_single_line() {
+        assert_eq!(count(b"fn main() {}\r\n\r\n// done\r\n", "Rust"), (3, 1, 1, 1));
+        assert_eq!(count(b"a\rb\n", "Rust"), (1, 1, 0, 0));
+    }
+
+    #[test]
+    fn final_line_without_newline_counts() {
+        assert_eq!(count(b"fn main() {}\n// done", "Rust"), (2, 1, 1, 0));
+        assert_eq!(count(b"x", "Rust"), (1, 1, 0, 0));
+    }
+
+    #[test]
+    fn lines_longer_than_the_read_buffer() {
+        // A long comment, a long run of whitespace, and a block comment that opens at the end of
+        // a long line of code, so that each crosses the chunks of `READ_BUFFER_SIZE`
+        let long = 2 * super::READ_BUFFER_SIZE + 1;
+        let mut contents = b"//".to_vec();
+        contents.resize(long, b'x');
+        contents.push(b'\n');
+        contents.resize(contents.len() + long, b' ');
+        contents.push(b'\n');
+        contents.resize(contents.len() + long, b'x');
+        contents.extend_from_slice(b"/*\nstill a comment */\nx");
+        assert_eq!(count(&contents, "Rust"), (5, 2, 2, 1));
+
+        // Read through a buffered reader, as files are, rather than from memory
+        let reader = std::io::BufReader::with_capacity(super::READ_BUFFER_SIZE, &contents[..]);
+        let classifier = super::LineClassifier::new(crate::comment_syntax("Rust"));
+        let counts = classifier.count(reader, &crate::ScanOptions::default()).unwrap();
+        assert_eq!(
+            (counts.lines, counts.code, counts.comments, counts.blanks),
+            (5, 2, 2, 1)
+        );
+    }
+
+    #[test]
+    fn comment_markers_in_strings_are_code() {
+        assert_eq!(count(b"let p = \"/*\";\nlet q = 1;\n", "Rust"), (2, 2, 0, 0));
+        assert_eq!(count(b"const p = '/*';\nconst q = 1;\n", "JavaScript"), (2, 2, 0, 0));
+        assert_eq!(count(b"char c = '\"'; /* one\ntwo */\n", "C"), (2, 1, 1, 0));
+    }
+
+    #[test]
+    fn escaped_quotes_do_not_close_strings() {
+        assert_eq!(count(b"s = \"\\\" /*\";\nt = 1;\n", "C"), (2, 2, 0, 0));
+        assert_eq!(
+            count(b"s = \"\\\\\"; /* open\nstill a comment\n*/\n", "C"),
+            (3, 1, 2, 0)
+        );
+    }
+
+    #[test]
+    fn unclosed_strings_end_with_their_line() {
+        assert_eq!(count(b"let s = \"open\n// comment\n", "Rust"), (2, 1, 1, 0));
+        assert_eq!(count(b"fn f<'a>(s: &'a str) {} /* one\ntwo */\n", "Rust"), (2, 1, 1, 0));
+    }
+
+    #[test]
+    fn quotes_inside_comments_are_ignored() {
+        assert_eq!(count(b"/* it's \" open\n*/\nx = 1;\n", "C"), (3, 1, 2, 0));
+        assert_eq!(count(b"// don't\n", "C"), (1, 0, 1, 0));
+    }
+
+    #[test]
+    fn literals_split_between_chunks() {
+        let mut line = b"let s = \"".to_vec();
+        line.resize(3 * super::READ_BUFFER_SIZE, b'a');
+        line.extend_from_slice(b" /* \\\"\"; /* open\nstill a comment */\n");
+        assert_eq!(count(&line, "Rust"), (2, 1, 1, 0));
+    }
+}
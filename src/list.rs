@@ -0,0 +1,1403 @@
+//! The results of a scan: each language that was found, and what it was found in.
+
+/// Information about a programming language within some directory context.
+#[derive(serde::Serialize, PartialEq)]
+pub struct LanguageInfo {
+    /// The name of the language. This should be fetched from the `LANGUAGES` map.
+    pub name: &'static str,
+    /// The category of the language, see `CATEGORIES`.
+    pub category: crate::Category,
+    /// The number of files of this language, which is counted whether or not their paths are
+    /// kept in `files`.
+    pub file_count: usize,
+    /// The files of this language type, unless they weren't kept, see `ScanOptions::skip_paths`.
+    /// Reports only list the paths of the files, and leave them out when they weren't kept. With
+    /// `ScanOptions::split_components`, this also lists the components with blocks of this
+    /// language, with the counts of those blocks, which aren't counted in `file_count`.
+    #[serde(serialize_with = "serialize_paths", skip_serializing_if = "Vec::is_empty")]
+    pub files: Vec<FileInfo>,
+    /// The number of lines of this language that exist.
+    pub lines: u64,
+    /// The number of lines of this language that contain code. Lines with both code and a
+    /// trailing comment count as code.
+    pub code: u64,
+    /// The number of lines of this language that contain only comments.
+    pub comments: u64,
+    /// The number of lines of this language that are empty or contain only whitespace.
+    pub blanks: u64,
+    /// The number of bytes of this language that exist.
+    pub bytes: u64,
+    /// The totals of the files of this language by their extension, without the leading dot,
+    /// with files without an extension under `NO_EXTENSION`. These are only tracked when
+    /// `ScanOptions::extension_breakdown` is set, and are left out of reports otherwise.
+    #[serde(skip_serializing_if = "std::collections::BTreeMap::is_empty")]
+    pub extensions: std::collections::BTreeMap<String, Totals>,
+    /// The totals of the test files of this language, which are also counted in the totals of the
+    /// language. These are only tracked when `ScanOptions::tests` is `TestFiles::Separate`, and
+    /// are left out of reports otherwise.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tests: Option<Totals>,
+    /// The statistics of the sizes of the files of this language. These are only calculated by
+    /// `LanguageList::compute_stats`, and are left out of reports otherwise.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stats: Option<FileStats>,
+    /// The number of markers such as `TODO` in the files of this language. These are only counted
+    /// when `ScanOptions::todo_markers` isn't empty, and are left out of reports otherwise.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub todos: Option<u64>,
+    /// The line endings and indentation of the files of this language. These are only tallied
+    /// when `ScanOptions::style_stats` is set, and are left out of reports otherwise.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub style: Option<crate::StyleStats>,
+    /// The encodings of the files of this language. These are only tallied when
+    /// `ScanOptions::encoding_stats` is set, and are left out of reports otherwise.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub encodings: Option<crate::EncodingStats>,
+}
+
+impl LanguageInfo {
+    /// Creates a new `LanguageInfo` with the given language name. The language name should come
+    /// from a value of the `LANGUAGES` map.
+    pub fn new(name: &'static str) -> Self {
+        Self {
+            name,
+            category: crate::language_category(name),
+            file_count: 0,
+            files: Vec::new(),
+            lines: 0,
+            code: 0,
+            comments: 0,
+            blanks: 0,
+            bytes: 0,
+            extensions: std::collections::BTreeMap::new(),
+            tests: None,
+            stats: None,
+            todos: None,
+            style: None,
+            encodings: None,
+        }
+    }
+
+    /// Adds the line counts and bytes of a file, or of a region of one, to this language.
+    pub(crate) fn add_counts(&mut self, counts: &crate::lines::LineCounts, bytes: u64) {
+        self.lines = self.lines.saturating_add(counts.lines);
+        self.code = self.code.saturating_add(counts.code);
+        self.comments = self.comments.saturating_add(counts.comments);
+        self.blanks = self.blanks.saturating_add(counts.blanks);
+        self.bytes = self.bytes.saturating_add(bytes);
+        if let Some(todos) = counts.todos {
+            self.todos = Some(self.todos.unwrap_or(0).saturating_add(todos));
+        }
+    }
+
+    /// Adds a file to this language, along with its line counts and bytes. Files that were decoded
+    /// into UTF-8 are counted by their size in UTF-8 rather than the given bytes.
+    pub(crate) fn add_file_counts(&mut self, path: String, counts: &crate::lines::LineCounts, bytes: u64) {
+        let bytes = counts.utf8_bytes.unwrap_or(bytes);
+        self.add_counts(counts, bytes);
+        self.files.push(FileInfo {
+            path,
+            lines: counts.lines,
+            code: counts.code,
+            comments: counts.comments,
+            blanks: counts.blanks,
+            bytes,
+            test: false,
+            todos: counts.todos,
+            style: counts.style.as_ref().map(crate::FileStyle::new),
+            encoding: counts.encoding,
+        });
+    }
+}
+
+/// A file that was counted towards a language.
+#[derive(serde::Serialize, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct FileInfo {
+    /// The path of the file, as given by `ScanOptions::paths`, or the name it was counted under,
+    /// such as `<revision>:<path>` for the files of a git revision.
+    pub path: String,
+    /// The number of lines of the file that were counted.
+    pub lines: u64,
+    /// The number of those lines that contain code.
+    pub code: u64,
+    /// The number of those lines that only contain comments.
+    pub comments: u64,
+    /// The number of those lines that are blank.
+    pub blanks: u64,
+    /// The number of bytes of the file that were counted.
+    pub bytes: u64,
+    /// Whether the file is test code, which is only tracked when `ScanOptions::tests` is
+    /// `TestFiles::Separate`.
+    pub test: bool,
+    /// The number of markers such as `TODO` in the file, which are only counted when
+    /// `ScanOptions::todo_markers` isn't empty.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub todos: Option<u64>,
+    /// The line endings and indentation of the file, which are only tallied when
+    /// `ScanOptions::style_stats` is set.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub style: Option<crate::FileStyle>,
+    /// The encoding of the file, which is only checked when `ScanOptions::encoding_stats` is set.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub encoding: Option<crate::Encoding>,
+}
+
+/// Serializes the files of a language as a list of their paths.
+fn serialize_paths<S: serde::Serializer>(files: &[FileInfo], serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.collect_seq(files.iter().map(|file| &file.path))
+}
+
+/// The languages found by a scan, along with their files, lines, and bytes.
+#[derive(Default)]
+pub struct LanguageList {
+    /// The languages that were found. These are in the order they were found until `sort` is
+    /// called.
+    pub languages: Vec<LanguageInfo>,
+    /// The files and directories that couldn't be read during the scan, and were skipped.
+    pub skipped: Vec<SkippedPath>,
+    /// Whether directories deeper than `ScanOptions::max_depth` were left out of the scan, so that
+    /// the counts only cover part of the scanned directory.
+    pub depth_limited: bool,
+    /// Whether files were counted without counting their lines, because of
+    /// `ScanOptions::skip_lines`, so that the counts of lines are all 0 and are left out of the
+    /// outputs.
+    pub lines_skipped: bool,
+    /// The directories that were skipped for having a name in `ScanOptions::ignored_directories`,
+    /// relative to the scan root. These are in no particular order.
+    pub ignored_directories: Vec<String>,
+    /// The nested projects that were skipped, relative to the scan root, when
+    /// `ScanOptions::nested_projects` skips them. These are in no particular order.
+    pub skipped_projects: Vec<String>,
+    /// The number of files of counted languages that were skipped for being larger than
+    /// `ScanOptions::max_file_size`.
+    pub skipped_large_files: usize,
+    /// The number of files of counted languages that were skipped for being binary, unless
+    /// `ScanOptions::count_binary` is set.
+    pub binary_skipped: usize,
+    /// The files of counted languages that were left out for being generated or minified, unless
+    /// `ScanOptions::include_generated` is set.
+    pub generated: crate::GeneratedFiles,
+    /// The files whose language wasn't detected, keyed by their extension, with files without an
+    /// extension under `NO_EXTENSION`. These are only tallied when `ScanOptions::collect_unknown`
+    /// is set.
+    pub unknown: std::collections::BTreeMap<String, UnknownFiles>,
+    /// How long the scan took, if it was timed, see `ScanTiming::report`. This isn't set by the
+    /// scan itself, since the scan doesn't know when it started.
+    pub timing: Option<crate::Timing>,
+}
+
+std::thread_local! {
+    /// The buffer of each thread that `ReadBuffer::take` takes, if it isn't taken already.
+    static READ_BUFFER: std::cell::Cell<Option<Box<[u8]>>> = const { std::cell::Cell::new(None) };
+}
+
+/// The buffer that the start of each file is read into by `LanguageList::add_file_as`, which is
+/// taken from the thread and given back when dropped, so that it is only allocated once for each
+/// thread of a scan rather than for each file.
+struct ReadBuffer(Box<[u8]>);
+
+impl ReadBuffer {
+    /// Takes the buffer of the thread, or allocates one if the thread doesn't have one yet.
+    fn take() -> Self {
+        Self(
+            READ_BUFFER
+                .take()
+                .unwrap_or_else(|| vec![0; crate::lines::READ_BUFFER_SIZE].into_boxed_slice()),
+        )
+    }
+}
+
+impl Drop for ReadBuffer {
+    fn drop(&mut self) {
+        READ_BUFFER.set(Some(std::mem::take(&mut self.0)));
+    }
+}
+
+/// Reads the start of a file into a buffer, until the buffer is full or the whole file has been
+/// read.
+///
+/// # Parameters
+/// - `file` - The file to read
+/// - `buffer` - The buffer to read into
+/// - `bytes` - The size of the file from its metadata. Reading stops once this many bytes have
+///   been read, which saves a read that would only find the end of the file.
+///
+/// # Returns
+/// - The number of bytes read, and whether that is the whole file.
+fn read_start(file: &mut std::fs::File, buffer: &mut [u8], bytes: u64) -> std::io::Result<(usize, bool)> {
+    let mut length = 0;
+    while length < buffer.len() {
+        let read = match std::io::Read::read(file, &mut buffer[length..]) {
+            Ok(read) => read,
+            Err(error) if error.kind() == std::io::ErrorKind::Interrupted => continue,
+            Err(error) => return Err(error),
+        };
+        length += read;
+        if read == 0 || (bytes > 0 && length as u64 == bytes) {
+            return Ok((length, true));
+        }
+    }
+    Ok((length, length as u64 == bytes))
+}
+
+/// The key of `LanguageList::unknown` and `LanguageInfo::extensions` that files without an
+/// extension are tallied under.
+pub const NO_EXTENSION: &str = "<none>";
+
+/// Returns the extension of a file, without the leading dot, or `NO_EXTENSION` if it has none.
+fn extension_of(path: &std::path::Path) -> std::borrow::Cow<'_, str> {
+    path.extension().map_or(std::borrow::Cow::Borrowed(NO_EXTENSION), |extension| {
+        extension.to_string_lossy()
+    })
+}
+
+/// Returns the entry of an extension in a map keyed by extensions, inserting an empty one if it
+/// isn't there yet. The extension is only copied when it is inserted.
+fn extension_entry<'a, T: Default>(
+    map: &'a mut std::collections::BTreeMap<String, T>,
+    extension: std::borrow::Cow<'_, str>,
+) -> &'a mut T {
+    if !map.contains_key(&*extension) {
+        map.insert(extension.to_string(), T::default());
+    }
+    map.get_mut(&*extension).unwrap()
+}
+
+/// The files of an extension whose language wasn't detected, see `LanguageList::unknown`.
+#[derive(Default, Clone, Copy, serde::Serialize)]
+pub struct UnknownFiles {
+    /// The number of files with the extension.
+    pub files: usize,
+    /// The number of bytes of the files with the extension.
+    pub bytes: u64,
+}
+
+impl UnknownFiles {
+    /// Adds the counts of other unknown files to these.
+    pub(crate) fn add(&mut self, other: UnknownFiles) {
+        self.files += other.files;
+        self.bytes += other.bytes;
+    }
+}
+
+/// The serialized form of an entry of `LanguageList::unknown`.
+#[derive(serde::Serialize)]
+pub struct UnknownExtension {
+    /// The extension, without the leading dot, or `NO_EXTENSION`.
+    pub extension: String,
+    /// The number of files with the extension.
+    pub files: usize,
+    /// The number of bytes of the files with the extension.
+    pub bytes: u64,
+}
+
+/// Returns the entries of some unknown files for a report, with the most bytes first, and then
+/// the most files.
+pub(crate) fn unknown_extensions(unknown: &std::collections::BTreeMap<String, UnknownFiles>) -> Vec<UnknownExtension> {
+    let mut extensions = unknown
+        .iter()
+        .map(|(extension, files)| UnknownExtension {
+            extension: extension.clone(),
+            files: files.files,
+            bytes: files.bytes,
+        })
+        .collect::<Vec<_>>();
+    extensions.sort_by(|extension, other| other.bytes.cmp(&extension.bytes).then(other.files.cmp(&extension.files)));
+    extensions
+}
+
+/// A file or directory that couldn't be read during a scan.
+#[derive(serde::Serialize, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct SkippedPath {
+    /// The path of the file or directory.
+    pub path: String,
+    /// Why the path couldn't be read.
+    pub reason: String,
+}
+
+impl SkippedPath {
+    /// Creates a new `SkippedPath` for the given path and the error that occurred when reading it.
+    pub fn new(path: &std::path::Path, error: &std::io::Error) -> Self {
+        Self {
+            path: path.to_string_lossy().into_owned(),
+            reason: error.to_string(),
+        }
+    }
+}
+
+impl LanguageList {
+    /// Returns the language info for the given language name, inserting an empty one if that
+    /// language hasn't been found before.
+    pub fn get_or_insert(&mut self, language: &'static str) -> &mut LanguageInfo {
+        if let Some(index) = self.languages.iter().position(|other_language| other_language.name == language) {
+            &mut self.languages[index]
+        } else {
+            self.languages.push(LanguageInfo::new(language));
+            self.languages.last_mut().unwrap()
+        }
+    }
+
+    /// Merges another language list into this one, summing the totals of languages that appear in
+    /// both. This is used to combine the partial lists produced by each thread during a scan.
+    pub fn merge(&mut self, other: LanguageList) {
+        for other_info in other.languages {
+            self.add_language(other_info);
+        }
+        self.skipped.extend(other.skipped);
+        self.depth_limited |= other.depth_limited;
+        self.lines_skipped |= other.lines_skipped;
+        self.ignored_directories.extend(other.ignored_directories);
+        self.skipped_projects.extend(other.skipped_projects);
+        self.skipped_large_files += other.skipped_large_files;
+        self.binary_skipped += other.binary_skipped;
+        self.generated.add(other.generated);
+        for (extension, files) in other.unknown {
+            self.unknown.entry(extension).or_default().add(files);
+        }
+    }
+
+    /// Adds the counts and files of a language to the language of the same name, inserting it if
+    /// that language hasn't been found before.
+    fn add_language(&mut self, other_info: LanguageInfo) {
+        let info = self.get_or_insert(other_info.name);
+        info.lines = info.lines.saturating_add(other_info.lines);
+        info.code = info.code.saturating_add(other_info.code);
+        info.comments = info.comments.saturating_add(other_info.comments);
+        info.blanks = info.blanks.saturating_add(other_info.blanks);
+        info.bytes = info.bytes.saturating_add(other_info.bytes);
+        info.file_count = info.file_count.saturating_add(other_info.file_count);
+        info.files.extend(other_info.files);
+        for (extension, totals) in other_info.extensions {
+            info.extensions.entry(extension).or_default().add_totals(&totals);
+        }
+        if let Some(tests) = other_info.tests {
+            info.tests.get_or_insert_with(Totals::default).add_totals(&tests);
+        }
+        if let Some(todos) = other_info.todos {
+            info.todos = Some(info.todos.unwrap_or(0).saturating_add(todos));
+        }
+        if let Some(style) = other_info.style {
+            info.style.get_or_insert_with(crate::StyleStats::default).add_stats(&style);
+        }
+        if let Some(encodings) = other_info.encodings {
+            info.encodings.get_or_insert_with(crate::EncodingStats::default).add_stats(&encodings);
+        }
+
+        // The statistics no longer cover every file of the language
+        info.stats = None;
+    }
+
+    /// Counts each dialect language as the language it is a dialect of, such as JavaScript React
+    /// as JavaScript, see `PARENT_LANGUAGES`. The files of a dialect are moved to its parent, and
+    /// its counts are added to those of the parent.
+    pub fn merge_dialects(&mut self) {
+        self.regroup(|name| crate::parent_language(name).map(|parent| (parent, crate::language_category(parent))));
+    }
+
+    /// Counts the languages of each group as a single language named after the group, such as
+    /// JavaScript, TypeScript, HTML, and CSS as "Web". The category of a group is the category of
+    /// its languages if they share one, and programming otherwise. Languages that aren't in any
+    /// group are left as they are.
+    ///
+    /// # Parameters
+    /// - `groups` - The groups to count the languages of together. If a language is in several
+    ///   groups, it is counted in the first one.
+    pub fn group(&mut self, groups: &[crate::LanguageGroup]) {
+        let categories = groups
+            .iter()
+            .map(|group| {
+                let mut categories = self
+                    .languages
+                    .iter()
+                    .filter(|language_info| group.languages.contains(&language_info.name))
+                    .map(|language_info| language_info.category);
+                let category = categories.next().unwrap_or(crate::Category::Programming);
+                if categories.all(|other| other == category) {
+                    category
+                } else {
+                    crate::Category::Programming
+                }
+            })
+            .collect::<Vec<_>>();
+        self.regroup(|name| {
+            groups
+                .iter()
+                .position(|group| group.languages.contains(&name))
+                .map(|index| (groups[index].name, categories[index]))
+        });
+    }
+
+    /// Renames languages and combines the counts and files of languages that end up with the same
+    /// name, keeping the order in which the languages were first found.
+    ///
+    /// # Parameters
+    /// - `rename` - Returns the new name and category of a language, or `None` to leave it as it
+    ///   is
+    fn regroup(&mut self, rename: impl Fn(&'static str) -> Option<(&'static str, crate::Category)>) {
+        for mut language_info in std::mem::take(&mut self.languages) {
+            if let Some((name, category)) = rename(language_info.name) {
+                language_info.name = name;
+                language_info.category = category;
+            }
+            self.add_language(language_info);
+        }
+    }
+
+    /// Tallies a file whose language wasn't detected in `unknown`, under its extension.
+    pub(crate) fn add_unknown(&mut self, path: &std::path::Path, bytes: u64) {
+        extension_entry(&mut self.unknown, extension_of(path)).add(UnknownFiles { files: 1, bytes });
+    }
+
+    /// Reads a file and counts it towards the language totals. This will detect the language based
+    /// on the file's extension (or its shebang, if it has no extension), and if it is recognized,
+    /// adds it to the languages file/line/byte count. The file is listed under its canonical path,
+    /// and is only recognized as a test file by its name, since there is no scan root to find the
+    /// test directories below.
+    /// Jupyter notebooks are counted by the source of their code cells, under the language of their
+    /// kernel.
+    ///
+    /// # Parameters
+    /// - `path` - The path of the file
+    /// - `options` - The options of the scan. This is used to check for custom extension mappings
+    ///   and excluded languages and categories.
+    ///
+    /// # Errors
+    /// Returns an error if the file is of a recognized language but couldn't be read, or if it is a
+    /// notebook that couldn't be parsed.
+    pub fn add_file(&mut self, path: &std::path::Path, options: &crate::ScanOptions) -> std::io::Result<()> {
+        match crate::detect_language(path, &options.extension_map) {
+            Some(language) => {
+                let name = path.canonicalize()?.to_string_lossy().into_owned();
+                let test = options.tests != crate::TestFiles::Include
+                    && path.file_name().is_some_and(|file_name| {
+                        crate::test_files::is_test_file(std::path::Path::new(file_name), options)
+                    });
+                self.add_file_as(path, name, test, language, options)
+            }
+            None => Ok(()),
+        }
+    }
+
+    /// Reads a file and counts it towards the totals of the given language, instead of detecting
+    /// its language. See `add_file`.
+    ///
+    /// # Parameters
+    /// - `path` - The path of the file
+    /// - `name` - The name the file is listed under in the language's `files`, such as its path
+    ///   relative to the scan root
+    /// - `test` - Whether the file is test code, which is left out or counted separately as set
+    ///   by `ScanOptions::tests`
+    /// - `language` - The name of the language of the file
+    /// - `options` - The options of the scan
+    ///
+    /// # Errors
+    /// Returns an error if the file couldn't be read, or if it is a notebook that couldn't be
+    /// parsed.
+    pub fn add_file_as(
+        &mut self,
+        path: &std::path::Path,
+        name: String,
+        test: bool,
+        language: &'static str,
+        options: &crate::ScanOptions,
+    ) -> std::io::Result<()> {
+        // Ignore excluded language, and excluded test files
+        if is_left_out(&name, test, language, options) {
+            return Ok(());
+        };
+
+        // Count only the size of the file, from its metadata, without opening it. Generated files
+        // can then only be told apart by their names.
+        if options.skip_lines {
+            let bytes = std::fs::metadata(path)?.len();
+            if options.max_file_size.is_some_and(|max_file_size| bytes > max_file_size) {
+                log::debug!("skipped {name}: larger than the maximum file size");
+                self.skipped_large_files += 1;
+                return Ok(());
+            }
+            if !options.include_generated && crate::generated::is_generated(&name, &[]) {
+                log::debug!("skipped {name}: generated or minified");
+                self.generated.add(crate::GeneratedFiles { files: 1, bytes });
+                return Ok(());
+            }
+            self.add_file_without_lines(name, test, language, bytes, options);
+            return Ok(());
+        }
+
+        // Skip files larger than the maximum size, without reading them
+        let mut file = std::fs::File::open(path)?;
+        let metadata = file.metadata()?;
+        let bytes = metadata.len();
+        if options.max_file_size.is_some_and(|max_file_size| bytes > max_file_size) {
+            log::debug!("skipped {name}: larger than the maximum file size");
+            self.skipped_large_files += 1;
+            return Ok(());
+        }
+
+        // Notebooks and split components are read as a whole, and aren't cached since they may
+        // count towards several languages
+        let is_component = options.split_components && crate::component::COMPONENT_LANGUAGES.contains(&language);
+        let is_whole = language == crate::notebook::NOTEBOOK_LANGUAGE || is_component;
+
+        // Re-use the counts of files that haven't changed since they were cached. Files aren't
+        // cached while markers, styles, or encodings are counted, since the cached counts don't
+        // include them.
+        let counts_extra = !options.todo_markers.is_empty() || options.style_stats || options.encoding_stats;
+        let cache = options.cache.as_deref().filter(|_| !is_whole && !counts_extra);
+        let cached = cache.and_then(|cache| cache.get(path, &metadata, language));
+        if let Some(cached) = cached {
+            if !options.count_binary && cached.binary {
+                log::debug!("skipped {name}: binary");
+                self.binary_skipped += 1;
+                return Ok(());
+            }
+            if !options.include_generated && cached.generated {
+                log::debug!("skipped {name}: generated or minified");
+                self.generated.add(crate::GeneratedFiles { files: 1, bytes });
+                return Ok(());
+            }
+            if let Some(counts) = cached.counts {
+                self.get_or_insert(language).add_file_counts(name, &counts, bytes);
+                self.file_counted(language, test, options);
+                return Ok(());
+            }
+        }
+
+        // Read the start of the file, which is all of most files
+        let mut buffer = ReadBuffer::take();
+        let (length, is_whole_file) = read_start(&mut file, &mut buffer.0, bytes)?;
+        let start = &buffer.0[..length];
+
+        // Skip binary files, checking the start of the file before reading the rest of it
+        let prefix = &start[..start.len().min(crate::lines::BINARY_PREFIX_LENGTH)];
+        let binary = crate::lines::is_binary(prefix);
+        let generated = crate::generated::is_generated(&name, prefix);
+        // Cache whether the file is skipped now, since it may not be counted
+        let mut entry = crate::cache::CachedFile::new(&metadata, language, binary, generated);
+        if let Some(cache) = cache {
+            cache.insert(path, entry.clone());
+        }
+        if !options.count_binary && binary {
+            log::debug!("skipped {name}: binary");
+            self.binary_skipped += 1;
+            return Ok(());
+        }
+
+        // Leave out generated and minified files
+        if !options.include_generated && generated {
+            log::debug!("skipped {name}: generated or minified");
+            self.generated.add(crate::GeneratedFiles { files: 1, bytes });
+            return Ok(());
+        }
+
+        if is_whole {
+            if is_whole_file {
+                return self.add_contents_as(name, test, language, start, options);
+            }
+            let mut contents = start.to_vec();
+            std::io::Read::read_to_end(&mut file, &mut contents)?;
+            return self.add_contents_as(name, test, language, &contents, options);
+        }
+
+        // Count the file before getting the language info, so that a file that can't be read
+        // doesn't leave an empty language behind
+        let classifier = crate::lines::LineClassifier::new(crate::comment_syntax(language));
+        let counts = if is_whole_file {
+            classifier.count(start, options)?
+        } else if let Some(contents) = crate::mmap::map_large_file(&file, bytes, options) {
+            classifier.count(&contents[..], options)?
+        } else {
+            let rest = std::io::Read::chain(start, file);
+            classifier.count(
+                std::io::BufReader::with_capacity(crate::lines::READ_BUFFER_SIZE, rest),
+                options,
+            )?
+        };
+        if let Some(cache) = cache {
+            entry.counts = Some(counts);
+            cache.insert(path, entry);
+        }
+
+        // Get the language info, or generate it if that language hasn't been found before
+        let info = self.get_or_insert(language);
+
+        // Update the language info
+        info.add_file_counts(name, &counts, bytes);
+        self.file_counted(language, test, options);
+        Ok(())
+    }
+
+    /// Counts the contents of a file towards the totals of the given language, when the contents
+    /// are already in memory, such as a blob from a git revision. See `add_file`.
+    ///
+    /// # Parameters
+    /// - `name` - The name the file is listed under in the language's `files`
+    /// - `test` - Whether the file is test code, see `add_file_as`
+    /// - `language` - The name of the language of the file
+    /// - `contents` - The contents of the file
+    /// - `options` - The options of the scan
+    ///
+    /// # Errors
+    /// Returns an error if the file is a notebook that couldn't be parsed.
+    ///
+    /// # Examples
+    /// Lines are counted as the newlines of a file, plus one if it doesn't end with a newline, so
+    /// an empty file has no lines and `\r\n` ends a single line:
+    ///
+    /// ```
+    /// let options = splik::ScanOptions::default();
+    /// let mut languages = splik::LanguageList::default();
+    /// let files = [("empty.rs", ""), ("newline.rs", "\n"), ("crlf.rs", "a\r\nb\r\n"), ("unterminated.rs", "a\nb")];
+    /// for (name, contents) in files {
+    ///     languages.add_contents_as(name.to_owned(), false, "Rust", contents.as_bytes(), &options)?;
+    /// }
+    /// let lines = languages.find("rust").iter().map(|file| (file.path.as_str(), file.lines)).collect::<Vec<_>>();
+    /// assert_eq!(lines, [("empty.rs", 0), ("newline.rs", 1), ("crlf.rs", 2), ("unterminated.rs", 2)]);
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    pub fn add_contents_as(
+        &mut self,
+        name: String,
+        test: bool,
+        language: &'static str,
+        contents: &[u8],
+        options: &crate::ScanOptions,
+    ) -> std::io::Result<()> {
+        // Ignore excluded language, and excluded test files
+        if is_left_out(&name, test, language, options) {
+            return Ok(());
+        };
+
+        // Skip files larger than the maximum size
+        if options.max_file_size.is_some_and(|max_file_size| contents.len() as u64 > max_file_size) {
+            log::debug!("skipped {name}: larger than the maximum file size");
+            self.skipped_large_files += 1;
+            return Ok(());
+        }
+
+        // Skip binary files
+        let prefix = &contents[..contents.len().min(crate::lines::BINARY_PREFIX_LENGTH)];
+        if !options.count_binary && crate::lines::is_binary(prefix) {
+            log::debug!("skipped {name}: binary");
+            self.binary_skipped += 1;
+            return Ok(());
+        }
+
+        // Leave out generated and minified files
+        if !options.include_generated && crate::generated::is_generated(&name, prefix) {
+            log::debug!("skipped {name}: generated or minified");
+            let bytes = contents.len() as u64;
+            self.generated.add(crate::GeneratedFiles { files: 1, bytes });
+            return Ok(());
+        }
+
+        if options.skip_lines {
+            self.add_file_without_lines(name, test, language, contents.len() as u64, options);
+            return Ok(());
+        }
+
+        // Count only the code cells of notebooks, under the language of their kernel
+        if language == crate::notebook::NOTEBOOK_LANGUAGE {
+            let (language, code) = crate::notebook::notebook_code(contents)?;
+            if !options.counts_language(language) {
+                log::debug!("skipped {name}: {language} isn't counted");
+                return Ok(());
+            }
+            let counts =
+                crate::lines::LineClassifier::new(crate::comment_syntax(language)).count(&code[..], options)?;
+            self.get_or_insert(language).add_file_counts(name, &counts, code.len() as u64);
+            self.file_counted(language, test, options);
+            return Ok(());
+        }
+
+        // Split single-file components into the languages of their blocks
+        if options.split_components && crate::component::COMPONENT_LANGUAGES.contains(&language) {
+            if let Some(regions) = crate::component::split_component(contents, language) {
+                // Combine the regions of each language, starting with the component's own
+                let mut parts = vec![(language, crate::lines::LineCounts::default(), 0)];
+                for region in regions {
+                    if region.language != language && !options.counts_language(region.language) {
+                        continue;
+                    }
+                    let syntax = crate::comment_syntax(region.language);
+                    let counts =
+                        crate::lines::LineClassifier::new(syntax).count(&contents[region.range.clone()], options)?;
+                    let bytes = region.range.len() as u64;
+                    match parts.iter_mut().find(|(part_language, _, _)| *part_language == region.language) {
+                        Some((_, part_counts, part_bytes)) => {
+                            part_counts.merge(&counts);
+                            *part_bytes += bytes;
+                        }
+                        None => parts.push((region.language, counts, bytes)),
+                    }
+                }
+
+                // The line endings, indentation, and encoding of the file are those of all of its
+                // regions
+                let mut whole = crate::lines::LineCounts::default();
+                parts.iter().for_each(|(_, counts, _)| whole.merge(counts));
+                parts[0].1.style = whole.style;
+                parts[0].1.encoding = whole.encoding;
+
+                // The file is listed under each of its languages with the counts of its regions in
+                // that language, so that the files of each language add up to its totals, but it
+                // is only counted as a file of the component's language
+                for (part_language, counts, bytes) in parts {
+                    self.get_or_insert(part_language).add_file_counts(name.clone(), &counts, bytes);
+                    if part_language != language {
+                        self.region_counted(part_language, test, options);
+                    }
+                }
+                self.file_counted(language, test, options);
+                return Ok(());
+            }
+        }
+
+        let counts = crate::lines::LineClassifier::new(crate::comment_syntax(language)).count(contents, options)?;
+        self.get_or_insert(language).add_file_counts(name, &counts, contents.len() as u64);
+        self.file_counted(language, test, options);
+        Ok(())
+    }
+
+    /// Adds a file to the totals of a language by its bytes alone, for `ScanOptions::skip_lines`.
+    fn add_file_without_lines(
+        &mut self,
+        name: String,
+        test: bool,
+        language: &'static str,
+        bytes: u64,
+        options: &crate::ScanOptions,
+    ) {
+        self.lines_skipped = true;
+        self.get_or_insert(language)
+            .add_file_counts(name, &crate::lines::LineCounts::default(), bytes);
+        self.file_counted(language, test, options);
+    }
+
+    /// Counts the file that was added last to the files of a language, adding it to the totals of
+    /// its extension, if `ScanOptions::extension_breakdown` is set, and to the totals of the
+    /// language's tests if it is a test file that is counted separately, and passes it to
+    /// `ScanOptions::on_file`, if it is set. The file is then dropped if paths aren't kept, see
+    /// `ScanOptions::skip_paths`.
+    fn file_counted(&mut self, language: &'static str, test: bool, options: &crate::ScanOptions) {
+        let Some(language_info) = self.languages.iter_mut().find(|language_info| language_info.name == language) else {
+            return;
+        };
+        let Some(file) = language_info.files.last_mut() else {
+            return;
+        };
+        language_info.file_count = language_info.file_count.saturating_add(1);
+        log::debug!("counted {} as {language}", file.path);
+        if let Some(progress) = &options.progress {
+            progress.add_file(file.bytes);
+        }
+        if options.extension_breakdown {
+            let extension = extension_of(std::path::Path::new(&file.path));
+            extension_entry(&mut language_info.extensions, extension).add_file(file);
+        }
+        if let Some(style) = &file.style {
+            language_info.style.get_or_insert_with(crate::StyleStats::default).add_file(style);
+        }
+        if let Some(encoding) = file.encoding {
+            language_info
+                .encodings
+                .get_or_insert_with(crate::EncodingStats::default)
+                .add_file(encoding);
+        }
+        if options.tests == crate::TestFiles::Separate {
+            file.test = test;
+            let tests = language_info.tests.get_or_insert_with(Totals::default);
+            if test {
+                tests.add_file(file);
+            }
+        }
+        report_file(file, language, options);
+        if options.skip_paths {
+            language_info.files.pop();
+        }
+    }
+
+    /// Lists the region of a single-file component that was added last to the files of a
+    /// language, see `ScanOptions::split_components`. Unlike `file_counted`, the region isn't
+    /// counted as a file of the language, since the file is counted under the component's
+    /// language, but it is passed to `ScanOptions::on_file` and dropped if paths aren't kept the
+    /// same way.
+    fn region_counted(&mut self, language: &'static str, test: bool, options: &crate::ScanOptions) {
+        let Some(language_info) = self.languages.iter_mut().find(|language_info| language_info.name == language) else {
+            return;
+        };
+        let Some(file) = language_info.files.last_mut() else {
+            return;
+        };
+        file.test = test && options.tests == crate::TestFiles::Separate;
+        report_file(file, language, options);
+        if options.skip_paths {
+            language_info.files.pop();
+        }
+    }
+
+    /// Sorts the languages by the given key, and the files of each language by path. Ties are
+    /// broken by language name, so the result doesn't depend on the order in which files were
+    /// scanned.
+    ///
+    /// # Parameters
+    /// - `key` - The key to sort by. Numeric keys sort from most to least.
+    /// - `reverse` - Whether to reverse the order of the key. This doesn't affect tie-breaking.
+    pub fn sort(&mut self, key: SortKey, reverse: bool) {
+        self.languages.sort_by(|language, other| {
+            let ordering = match key {
+                SortKey::Bytes => other.bytes.cmp(&language.bytes),
+                SortKey::Lines => other.lines.cmp(&language.lines),
+                SortKey::Files => other.file_count.cmp(&language.file_count),
+                SortKey::Name => language.name.cmp(other.name),
+            };
+            let ordering = if reverse { ordering.reverse() } else { ordering };
+            ordering.then_with(|| language.name.cmp(other.name))
+        });
+        self.skipped.sort();
+        for language in &mut self.languages {
+            language.files.sort();
+        }
+    }
+
+    /// Returns the files of the given language, matched case-insensitively or by one of the
+    /// `LANGUAGE_ALIASES`. If the language wasn't found, this is empty.
+    pub fn find(&self, language_name: &str) -> &[FileInfo] {
+        self.languages
+            .iter()
+            .find(|language| crate::language_matches(language.name, language_name))
+            .map(|language| language.files.as_slice())
+            .unwrap_or_default()
+    }
+
+    /// Calculates the statistics of the sizes of the files of each language, see
+    /// `LanguageInfo::stats`. This should be called once the languages are merged, since merging
+    /// languages clears their statistics.
+    pub fn compute_stats(&mut self) {
+        for language_info in &mut self.languages {
+            language_info.stats = FileStats::of(&language_info.files);
+        }
+    }
+
+    /// Calculates the total files, lines, and bytes across all languages. Totals that would
+    /// overflow stop at the largest count instead.
+    ///
+    /// # Examples
+    /// Lines are counted in 64 bits, so totals past `u32::MAX` are kept, and totals past
+    /// `u64::MAX` saturate:
+    ///
+    /// ```
+    /// let mut languages = splik::LanguageList::default();
+    /// for (name, lines) in [("Rust", u64::from(u32::MAX)), ("C", 1)] {
+    ///     let mut language_info = splik::LanguageInfo::new(name);
+    ///     language_info.lines = lines;
+    ///     languages.languages.push(language_info);
+    /// }
+    /// assert_eq!(languages.totals().lines, u64::from(u32::MAX) + 1);
+    ///
+    /// languages.languages[1].lines = u64::MAX;
+    /// assert_eq!(languages.totals().lines, u64::MAX);
+    /// ```
+    pub fn totals(&self) -> Totals {
+        let mut totals = Totals::default();
+        for language_info in &self.languages {
+            totals.add(language_info);
+        }
+        totals
+    }
+
+    /// Calculates the totals of the test files across all languages, or returns `None` if test
+    /// files weren't counted separately, see `LanguageInfo::tests`.
+    pub fn test_totals(&self) -> Option<Totals> {
+        let mut tests = None;
+        for language_tests in self.languages.iter().filter_map(|language_info| language_info.tests.as_ref()) {
+            tests.get_or_insert_with(Totals::default).add_totals(language_tests);
+        }
+        tests
+    }
+
+    /// Calculates the line endings and indentation of the files of all languages, or returns
+    /// `None` if they weren't tallied, see `LanguageInfo::style`.
+    pub fn style_totals(&self) -> Option<crate::StyleStats> {
+        let mut style = None;
+        for language_style in self.languages.iter().filter_map(|language_info| language_info.style.as_ref()) {
+            style.get_or_insert_with(crate::StyleStats::default).add_stats(language_style);
+        }
+        style
+    }
+
+    /// Calculates the encodings of the files of all languages, or returns `None` if they weren't
+    /// tallied, see `LanguageInfo::encodings`.
+    pub fn encoding_totals(&self) -> Option<crate::EncodingStats> {
+        let mut encodings = None;
+        for language_encodings in self.languages.iter().filter_map(|language_info| language_info.encodings.as_ref()) {
+            encodings.get_or_insert_with(crate::EncodingStats::default).add_stats(language_encodings);
+        }
+        encodings
+    }
+
+    /// Returns every file of this list along with its language, which is what the `--files` output
+    /// lists. Files are in the order of their languages, and sorted by path within each language
+    /// once `sort` is called.
+    pub fn file_report(&self) -> FileListing<'_> {
+        FileListing {
+            files: self
+                .languages
+                .iter()
+                .flat_map(|language_info| file_reports(language_info, !self.lines_skipped))
+                .collect(),
+        }
+    }
+
+    /// Returns the largest files of this list along with their languages, which is what the
+    /// `--largest` output lists. Files are sorted by bytes from most to least, and files of the same
+    /// size are sorted by path.
+    ///
+    /// # Parameters
+    /// - `count` - The number of files to return
+    /// - `language_name` - The language to return the largest files of, matched like in `find`. If
+    ///   `None`, the largest files across all languages are returned.
+    pub fn largest_files(&self, count: usize, language_name: Option<&str>) -> FileListing<'_> {
+        let mut files = self
+            .languages
+            .iter()
+            .filter(|language| language_name.is_none_or(|name| crate::language_matches(language.name, name)))
+            .flat_map(|language_info| file_reports(language_info, !self.lines_skipped))
+            .collect::<Vec<_>>();
+        files.sort_by(|file, other| other.bytes.cmp(&file.bytes).then_with(|| file.path.cmp(other.path)));
+        files.truncate(count);
+        FileListing { files }
+    }
+
+    /// Returns the serializable report of this list, which is what the JSON and YAML output
+    /// formats print.
+    ///
+    /// # Parameters
+    /// - `top` - The number of languages to include in the report. The remaining languages are
+    ///   summed into the report's `other` field. If `None`, every language is included.
+    pub fn report(&self, top: Option<usize>) -> Report<'_> {
+        let totals = self.totals();
+        let shown = top.unwrap_or(self.languages.len()).min(self.languages.len());
+        Report {
+            schema_version: SCHEMA_VERSION,
+            other: top.map(|_| {
+                let mut other = Totals::default();
+                for language_info in &self.languages[shown..] {
+                    other.add(language_info);
+                }
+                other
+            }),
+            languages: self.languages[..shown]
+                .iter()
+                .map(|language_info| LanguageReport {
+                    info: language_info,
+                    color: crate::language_color(language_info.name),
+                    byte_percent: percent(language_info.bytes as f64, totals.bytes as f64),
+                    line_percent: percent(language_info.lines as f64, totals.lines as f64),
+                    file_percent: percent(language_info.file_count as f64, totals.files as f64),
+                })
+                .collect(),
+            totals,
+            tests: self.test_totals(),
+            errors: &self.skipped,
+            depth_limited: self.depth_limited,
+            skipped_large_files: self.skipped_large_files,
+            binary_skipped: self.binary_skipped,
+            generated: self.generated,
+            unknown: unknown_extensions(&self.unknown),
+            timing: self.timing,
+        }
+    }
+}
+
+/// Returns whether `options` leave out a file of the given language, for being of a language that
+/// isn't counted, or a test file when test files are excluded, logging why.
+///
+/// # Parameters
+/// - `name` - The name the file would be listed under
+/// - `test` - Whether the file is test code
+/// - `language` - The name of the language of the file
+/// - `options` - The options of the scan
+fn is_left_out(name: &str, test: bool, language: &str, options: &crate::ScanOptions) -> bool {
+    if !options.counts_language(language) {
+        log::debug!("skipped {name}: {language} isn't counted");
+        true
+    } else if test && options.tests == crate::TestFiles::Exclude {
+        log::debug!("skipped {name}: test file");
+        true
+    } else {
+        false
+    }
+}
+
+/// Returns the files of a language as `FileReport`s, with their lines if they were counted.
+fn file_reports(language_info: &LanguageInfo, lines_counted: bool) -> impl Iterator<Item = FileReport<'_>> {
+    language_info.files.iter().map(move |file| FileReport {
+        path: &file.path,
+        language: language_info.name,
+        lines: lines_counted.then_some(file.lines),
+        bytes: file.bytes,
+        test: file.test,
+        todos: file.todos,
+        style: file.style.as_ref(),
+        encoding: file.encoding,
+    })
+}
+
+/// Files of a `LanguageList` along with their languages, such as every file of the `--files`
+/// output or the largest files of `--largest`, which can be written in each format of those
+/// outputs.
+#[derive(Default)]
+pub struct FileListing<'a> {
+    /// The files, in the order they are written.
+    pub files: Vec<FileReport<'a>>,
+}
+
+/// A file of a `LanguageList`, along with its language, as listed by `LanguageList::file_report`.
+#[derive(serde::Serialize)]
+pub struct FileReport<'a> {
+    /// The path of the file.
+    pub path: &'a str,
+    /// The name of the language the file was counted as.
+    pub language: &'static str,
+    /// The number of lines of the file that were counted, or `None` if lines weren't counted, see
+    /// `ScanOptions::skip_lines`. This is left out when lines weren't counted.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lines: Option<u64>,
+    /// The number of bytes of the file that were counted.
+    pub bytes: u64,
+    /// Whether the file is test code, see `FileInfo::test`. This is left out when it is false.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub test: bool,
+    /// The number of markers such as `TODO` in the file, see `FileInfo::todos`. This is left out
+    /// when markers weren't counted.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub todos: Option<u64>,
+    /// The line endings and indentation of the file, see `FileInfo::style`. This is left out when
+    /// they weren't tallied.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub style: Option<&'a crate::FileStyle>,
+    /// The encoding of the file, see `FileInfo::encoding`. This is left out when it wasn't checked.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub encoding: Option<crate::Encoding>,
+}
+
+/// A key that languages can be sorted by.
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+pub enum SortKey {
+    /// Sort by bytes, from most to least.
+    Bytes,
+    /// Sort by lines, from most to least.
+    Lines,
+    /// Sort by files, from most to least.
+    Files,
+    /// Sort by name, alphabetically.
+    Name,
+}
+
+/// The version of the structure of `Report`. This should be incremented whenever the serialized
+/// output changes in a way that could break scripts parsing it.
+pub const SCHEMA_VERSION: u32 = 2;
+
+/// The serialized form of a `LanguageList`.
+#[derive(serde::Serialize)]
+pub struct Report<'a> {
+    /// The version of this structure, see `SCHEMA_VERSION`.
+    pub schema_version: u32,
+    /// The languages that were found, in sorted order.
+    pub languages: Vec<LanguageReport<'a>>,
+    /// The totals of the languages left out of `languages` by `--top`, if it was given.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub other: Option<Totals>,
+    /// The totals across all languages.
+    pub totals: Totals,
+    /// The totals of the test files across all languages, see `LanguageList::test_totals`. This is
+    /// left out when test files weren't counted separately.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tests: Option<Totals>,
+    /// The files and directories that couldn't be read, if any. When this isn't empty, the rest
+    /// of the report only covers part of the scanned directory.
+    #[serde(skip_serializing_if = "<[SkippedPath]>::is_empty")]
+    pub errors: &'a [SkippedPath],
+    /// Whether directories deeper than `--max-depth` weren't scanned, see
+    /// `LanguageList::depth_limited`. This is left out when it is false.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub depth_limited: bool,
+    /// The number of files skipped for being larger than `--max-file-size`. This is left out when
+    /// it is 0.
+    #[serde(skip_serializing_if = "is_zero")]
+    pub skipped_large_files: usize,
+    /// The number of files skipped for being binary. This is left out when it is 0.
+    #[serde(skip_serializing_if = "is_zero")]
+    pub binary_skipped: usize,
+    /// The files left out for being generated or minified. This is left out when there are none.
+    #[serde(skip_serializing_if = "crate::GeneratedFiles::is_empty")]
+    pub generated: crate::GeneratedFiles,
+    /// The files whose language wasn't detected, with the extensions with the most bytes first,
+    /// see `LanguageList::unknown`. This is left out when there are none.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub unknown: Vec<UnknownExtension>,
+    /// How long the scan took, see `LanguageList::timing`. This is left out when the scan wasn't
+    /// timed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timing: Option<crate::Timing>,
+}
+
+/// The serialized form of a `LanguageInfo`, along with the percentages of the totals it makes up.
+#[derive(serde::Serialize)]
+pub struct LanguageReport<'a> {
+    /// The information about the language.
+    #[serde(flatten)]
+    pub info: &'a LanguageInfo,
+    /// The color of the language as a hex code, see `COLORS`. This is left out for languages
+    /// without a color.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub color: Option<&'static str>,
+    /// The percent of the total bytes that are in this language.
+    pub byte_percent: f64,
+    /// The percent of the total lines that are in this language.
+    pub line_percent: f64,
+    /// The percent of the total files that are in this language.
+    pub file_percent: f64,
+}
+
+/// Statistics of the sizes of the files of a language, which tell a language of many small files
+/// apart from one of a few large files, see `LanguageInfo::stats`.
+#[derive(Clone, Debug, PartialEq, serde::Serialize)]
+pub struct FileStats {
+    /// The mean number of lines per file.
+    pub average_lines: f64,
+    /// The median number of lines per file. With an even number of files, this is the mean of
+    /// the two middle files.
+    pub median_lines: f64,
+    /// The number of lines of the longest file.
+    pub max_lines: u64,
+    /// The path of the longest file. If several files are the longest, this is the first of them
+    /// by path.
+    pub longest_file: String,
+}
+
+impl FileStats {
+    /// Calculates the statistics of some files, or returns `None` if there are none.
+    pub fn of(files: &[FileInfo]) -> Option<Self> {
+        let longest = files
+            .iter()
+            .max_by(|file, other| file.lines.cmp(&other.lines).then_with(|| other.path.cmp(&file.path)))?;
+        let mut lines = files.iter().map(|file| file.lines).collect::<Vec<_>>();
+        lines.sort_unstable();
+        let middle = lines.len() / 2;
+        let median_lines = if lines.len() % 2 == 0 {
+            (lines[middle - 1] as f64 + lines[middle] as f64) / 2.0
+        } else {
+            lines[middle] as f64
+        };
+        Some(Self {
+            average_lines: lines.iter().map(|lines| *lines as f64).sum::<f64>() / lines.len() as f64,
+            median_lines,
+            max_lines: longest.lines,
+            longest_file: longest.path.clone(),
+        })
+    }
+}
+
+/// The total files, lines, and bytes across every language in a `LanguageList`, or across some
+/// group of its languages.
+#[derive(Default, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct Totals {
+    /// The total number of files.
+    pub files: usize,
+    /// The total number of lines.
+    pub lines: u64,
+    /// The total number of lines containing code.
+    pub code: u64,
+    /// The total number of lines containing only comments.
+    pub comments: u64,
+    /// The total number of blank lines.
+    pub blanks: u64,
+    /// The total number of bytes.
+    pub bytes: u64,
+}
+
+impl Totals {
+    /// Adds the counts of a language to these totals.
+    pub fn add(&mut self, language_info: &LanguageInfo) {
+        self.files = self.files.saturating_add(language_info.file_count);
+        self.lines = self.lines.saturating_add(language_info.lines);
+        self.code = self.code.saturating_add(language_info.code);
+        self.comments = self.comments.saturating_add(language_info.comments);
+        self.blanks = self.blanks.saturating_add(language_info.blanks);
+        self.bytes = self.bytes.saturating_add(language_info.bytes);
+    }
+
+    /// Adds the counts of a single file to these totals.
+    pub fn add_file(&mut self, file: &FileInfo) {
+        self.files = self.files.saturating_add(1);
+        self.lines = self.lines.saturating_add(file.lines);
+        self.code = self.code.saturating_add(file.code);
+        self.comments = self.comments.saturating_add(file.comments);
+        self.blanks = self.blanks.saturating_add(file.blanks);
+        self.bytes = self.bytes.saturating_add(file.bytes);
+    }
+
+    /// Adds other totals to these totals.
+    pub fn add_totals(&mut self, other: &Totals) {
+        self.files = self.files.saturating_add(other.files);
+        self.lines = self.lines.saturating_add(other.lines);
+        self.code = self.code.saturating_add(other.code);
+        self.comments = self.comments.saturating_add(other.comments);
+        self.blanks = self.blanks.saturating_add(other.blanks);
+        self.bytes = self.bytes.saturating_add(other.bytes);
+    }
+}
+
+/// Passes a file that was counted to `ScanOptions::on_file`, if it is set.
+fn report_file(file: &FileInfo, language: &'static str, options: &crate::ScanOptions) {
+    if let Some(on_file) = &options.on_file {
+        on_file(&FileReport {
+            path: &file.path,
+            language,
+            lines: (!options.skip_lines).then_some(file.lines),
+            bytes: file.bytes,
+            test: file.test,
+            todos: file.todos,
+            style: file.style.as_ref(),
+            encoding: file.encoding,
+        });
+    }
+}
+
+/// Returns whether a count is 0, for leaving it out of a report.
+pub(crate) fn is_zero(count: &usize) -> bool {
+    *count == 0
+}
+
+/// Returns what percent `part` is of `total`, or 0 if `total` is 0.
+pub(crate) fn percent(part: f64, total: f64) -> f64 {
+    if total == 0.0 {
+        return 0.0;
+    }
+
+    100.0 * part / total
+}
+
+#[cfg(test)]
+mod tests {
+    /// Returns a language whose counts are all `count`.
+    fn language(name: &'static str, count: u64) -> super::LanguageInfo {
+        super::LanguageInfo {
+            file_count: usize::try_from(count).unwrap_or(usize::MAX),
+            lines: count,
+            code: count,
+            comments: count,
+            blanks: count,
+            bytes: count,
+            ..super::LanguageInfo::new(name)
+        }
+    }
+
+    #[test]
+    fn totals_pass_the_u32_boundary() {
+        let mut totals = super::Totals::default();
+        totals.add(&language("Rust", u64::from(u32::MAX)));
+        totals.add(&language("C", 1));
+        let expected = u64::from(u32::MAX) + 1;
+        assert_eq!(
+            (totals.lines, totals.code, totals.comments, totals.blanks, totals.bytes),
+            (expected, expected, expected, expected, expected)
+        );
+        assert_eq!(totals.files as u64, expected);
+    }
+
+    #[test]
+    fn totals_saturate() {
+        let mut totals = super::Totals::default();
+        totals.add(&language("Rust", u64::MAX - 1));
+        totals.add(&language("C", 2));
+        assert_eq!(
+            (totals.lines, totals.bytes, totals.files),
+            (u64::MAX, u64::MAX, usize::MAX)
+        );
+
+        let mut other = super::Totals::default();
+        other.add_totals(&totals);
+        other.add_totals(&totals);
+        assert!(other == totals);
+    }
+
+    #[test]
+    fn merged_languages_saturate() {
+        let mut languages = super::LanguageList::default();
+        languages.languages.push(language("Rust", u64::MAX - 1));
+        let mut other = super::LanguageList::default();
+        other.languages.push(language("Rust", u64::from(u32::MAX)));
+        other.languages.push(language("C", u64::from(u32::MAX) + 1));
+        languages.merge(other);
+
+        let rust = &languages.languages[0];
+        assert_eq!(
+            (rust.lines, rust.bytes, rust.file_count),
+            (u64::MAX, u64::MAX, usize::MAX)
+        );
+        assert_eq!(languages.languages[1].lines, u64::from(u32::MAX) + 1);
+        assert_eq!(languages.totals().lines, u64::MAX);
+    }
+
+    #[test]
+    fn file_counts_saturate() {
+        let mut rust = language("Rust", u64::MAX - 1);
+        let counts = crate::lines::LineCounts {
+            lines: 2,
+            code: 2,
+            todos: Some(1),
+            ..crate::lines::LineCounts::default()
+        };
+        rust.add_counts(&counts, 2);
+        assert_eq!(
+            (rust.lines, rust.code, rust.comments, rust.bytes),
+            (u64::MAX, u64::MAX, u64::MAX - 1, u64::MAX)
+        );
+        assert_eq!(rust.todos, Some(1));
+    }
+
+    #[test]
+    fn split_components_list_files_under_each_language() {
+        let options = crate::ScanOptions {
+            split_components: true,
+            ..crate::ScanOptions::default()
+        };
+        let component = "<template>\n  <p/>\n</template>\n<script lang=\"ts\">\nlet a = 1;\n</script>\n\
+            <style lang=\"scss\">\n.a {}\n</style>\n<script setup lang=\"ts\">\nlet b = 2;\n</script>\n";
+        let mut languages = super::LanguageList::default();
+        languages
+            .add_contents_as("App.vue".to_owned(), false, "Vue", component.as_bytes(), &options)
+            .unwrap();
+
+        for language in &languages.languages {
+            let lines = language.files.iter().map(|file| file.lines).sum::<u64>();
+            let bytes = language.files.iter().map(|file| file.bytes).sum::<u64>();
+            assert_eq!(
+                (language.files.len(), lines, bytes),
+                (1, language.lines, language.bytes)
+            );
+        }
+        let counts = |name| {
+            let language = languages.languages.iter().find(|language| language.name == name).unwrap();
+            (language.file_count, language.lines)
+        };
+        assert_eq!(counts("Vue"), (1, 9));
+        assert_eq!(counts("TypeScript"), (0, 2));
+        assert_eq!(counts("SCSS"), (0, 1));
+        assert_eq!(languages.totals().files, 1);
+    }
+
+    #[test]
+    fn percents_of_large_totals() {
+        assert_eq!(super::percent(u64::MAX as f64, u64::MAX as f64), 100.0);
+        assert_eq!(
+            super::percent(u64::from(u32::MAX) as f64, 2.0 * u64::from(u32::MAX) as f64),
+            50.0
+        );
+        assert_eq!(super::percent(1.0, 0.0), 0.0);
+    }
+}
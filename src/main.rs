@@ -1,91 +1,1817 @@
-use std::io::BufRead as _;
+use rayon::iter::{IntoParallelIterator as _, ParallelIterator as _};
+
+mod completions;
+mod tui;
 
 fn main() {
-    let arguments = <Arguments as clap::Parser>::parse();
-    let mut languages = LanguageList::default();
-
-    // Get the root directory
-    let source = arguments
-        .directory_path
-        .clone()
-        .unwrap_or(std::path::PathBuf::from(".").canonicalize().unwrap().to_str().unwrap().to_owned());
-    let root = if arguments.here {
-        source
+    let matches = <Arguments as clap::CommandFactory>::command().get_matches();
+    let mut arguments =
+        <Arguments as clap::FromArgMatches>::from_arg_matches(&matches).unwrap_or_else(|error| error.exit());
+    match arguments.command.take() {
+        Some(Command::Completions { shell }) => {
+            let mut command = <Arguments as clap::CommandFactory>::command();
+            let result = completions::write_completions(&mut std::io::stdout().lock(), shell, &mut command);
+            if let Err(error) = result {
+                if error.kind() != std::io::ErrorKind::BrokenPipe {
+                    exit_with_error(error);
+                }
+            }
+            std::process::exit(0);
+        }
+        Some(Command::Diff {
+            old,
+            new,
+            output,
+            fail_on_growth,
+        }) => {
+            // Without a second report, the scan of the current directory is compared against the
+            // first one, as with `--baseline`
+            arguments.output = output.into();
+            arguments.baseline = Some(old);
+            arguments.fail_on_growth = fail_on_growth;
+            if let Some(new) = new {
+                diff_reports(&arguments, &new);
+            }
+        }
+        None => {}
+    }
+    log::set_logger(&StderrLogger).unwrap_or_else(|error| exit_with_error(error));
+    log::set_max_level(arguments.log_level());
+    if let Some((only, exclude)) = arguments.conflicting_only() {
+        let message = format!("the language \"{only}\" is given to both --only and --exclude (as \"{exclude}\")");
+        <Arguments as clap::CommandFactory>::command()
+            .error(clap::error::ErrorKind::ArgumentConflict, message)
+            .exit();
+    }
+    if let Some((extension, language, other)) = arguments.conflicting_map() {
+        let message =
+            format!("the extension \"{extension}\" is mapped to both \"{language}\" and \"{other}\" by --map");
+        <Arguments as clap::CommandFactory>::command()
+            .error(clap::error::ErrorKind::ArgumentConflict, message)
+            .exit();
+    }
+    if arguments.baseline.is_some() && !arguments.output.compares() {
+        let output = clap::ValueEnum::to_possible_value(&arguments.output).unwrap();
+        let message = format!("--baseline can't be used with --output {}", output.get_name());
+        <Arguments as clap::CommandFactory>::command()
+            .error(clap::error::ErrorKind::ArgumentConflict, message)
+            .exit();
+    }
+    if arguments.root.is_some()
+        && !arguments.directory_paths.is_empty()
+        && matches.value_source("root") == Some(clap::parser::ValueSource::CommandLine)
+    {
+        <Arguments as clap::CommandFactory>::command()
+            .error(
+                clap::error::ErrorKind::ArgumentConflict,
+                "--root can't be used with directory paths",
+            )
+            .exit();
+    }
+    if arguments.list_languages.is_some() && !arguments.output.lists_languages() {
+        let output = clap::ValueEnum::to_possible_value(&arguments.output).unwrap();
+        let message = format!("--list-languages can't be used with --output {}", output.get_name());
+        <Arguments as clap::CommandFactory>::command()
+            .error(clap::error::ErrorKind::ArgumentConflict, message)
+            .exit();
+    }
+
+    // Get the root directory of each given directory. Directory paths given on the command line
+    // take priority over `SPLIK_ROOT`.
+    if !arguments.directory_paths.is_empty() {
+        arguments.root = None;
+    }
+    let sources = if let Some(root) = &arguments.root {
+        check_source(root);
+        if !root.is_dir() {
+            exit_with_error(format!("no such directory: {}", root.display()));
+        }
+        vec![root.clone()]
+    } else if arguments.directory_paths.is_empty() {
+        vec![std::env::current_dir().unwrap_or_else(|error| exit_with_error(error))]
+    } else {
+        let sources = arguments.directory_paths.iter().map(std::path::PathBuf::from).collect::<Vec<_>>();
+        sources.iter().for_each(|source| check_source(source));
+        sources
+    };
+    let project_roots = sources
+        .iter()
+        .map(|source| {
+            let project_root = arguments.project_root_of(source, &matches);
+            let root = project_root
+                .as_ref()
+                .map_or_else(|| source.clone(), |project_root| project_root.path.clone());
+            (root, project_root)
+        })
+        .collect::<Vec<_>>();
+
+    // Find root command
+    if arguments.find_root {
+        for (root, project_root) in &project_roots {
+            println!("{}", root.display());
+            if let Some(project_root) = project_root.as_ref() {
+                log::info!(
+                    "found {}, a {}",
+                    project_root.path.join(&project_root.indicator).display(),
+                    project_root.tier.label()
+                );
+            }
+        }
+        return;
+    }
+    let roots = distinct_roots(project_roots.iter().map(|(root, _)| root.clone()).collect());
+    let root = &roots[0];
+
+    // Load the global configuration file and the configuration file of the project
+    if !arguments.no_config {
+        match splik::Config::load_layers(Some(root)) {
+            Ok(Some(config)) => {
+                if let Err(error) = arguments.apply_config(config, &matches) {
+                    exit_with_error(error);
+                }
+            }
+            Ok(None) => {}
+            Err(error) => exit_with_error(error.to_string()),
+        }
+    }
+
+    arguments.apply_mappings();
+    for message in arguments.unknown_language_warnings() {
+        log::warn!("{message}");
+    }
+    if arguments.groups && arguments.language_groups.is_empty() {
+        log::warn!("--groups was given, but no `groups` are defined in a configuration file");
+    }
+
+    // Explain config command
+    if arguments.explain_config {
+        explain_config(&arguments, &matches, &sources, &project_roots);
+    }
+
+    // List languages command
+    if let Some(name) = &arguments.list_languages {
+        list_languages(&arguments, name.as_deref());
+    }
+
+    // Describe files given instead of directories, rather than scanning their roots
+    if sources.iter().all(|source| source.is_file()) && !arguments.directory_paths.is_empty() {
+        describe_files(&arguments, &sources);
+    }
+
+    // Generate the language information
+    let thread_pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(arguments.threads.map(std::num::NonZeroUsize::get).unwrap_or(0))
+        .build()
+        .unwrap_or_else(|error| exit_with_error(error));
+    let mut options = arguments.scan_options();
+
+    // Explain command
+    if let Some(path) = &arguments.explain {
+        explain(&roots, path, &options);
+    }
+
+    // Load the cache of each root, unless it is being refreshed
+    if arguments.cache {
+        let cache = std::sync::Arc::new(splik::FileCache::default());
+        for root in roots.iter().filter(|_| !arguments.refresh) {
+            if let Err(error) = cache.load(root) {
+                log::warn!("couldn't read the cache of {}: {error}", root.display());
+            }
+        }
+        options.cache = Some(cache);
+    }
+
+    // Stream the files of the JSON Lines output as they are counted. Stdout isn't locked for the
+    // whole run, since the files are written from the threads of the scan. Like the other outputs
+    // of `--files --style-stats`, only the files that drift from the style are listed. Output
+    // written to a file isn't streamed, so that the files are written there with the languages.
+    let streams_files = arguments.find.is_empty()
+        && arguments.largest.is_none()
+        && arguments.baseline.is_none()
+        && !arguments.watch
+        && !arguments.writes_to_file();
+    if matches!(arguments.output, splik::OutputFormat::Jsonl) && streams_files {
+        let nonconforming_only = arguments.files && arguments.style_stats;
+        options.on_file = Some(std::sync::Arc::new(move |file| {
+            if nonconforming_only && file.style.is_none_or(splik::FileStyle::is_conforming) {
+                return;
+            }
+            match file.display_jsonl(&mut std::io::stdout().lock()) {
+                Ok(()) => {}
+                Err(error) if error.kind() == std::io::ErrorKind::BrokenPipe => std::process::exit(0),
+                Err(error) => exit_with_error(error),
+            }
+        }));
+    }
+
+    // Write the files of `--find` as they are found, without reading them. Only the languages to
+    // find are counted, so that other files aren't even stat'ed, unless `--only` already limits
+    // the scan, in which case the languages to find are picked out of those.
+    let streams_found = arguments.streams_found_files(roots.len());
+    if streams_found {
+        options.skip_lines = true;
+        options.skip_paths = true;
+        if options.only.is_empty() {
+            options.only = arguments.find.clone();
+        }
+        let find = arguments.find.clone();
+        let terminator = if matches!(arguments.find_format, FindFormat::Null) {
+            '\0'
+        } else {
+            '\n'
+        };
+        options.on_file = Some(std::sync::Arc::new(move |file| {
+            if !find.iter().any(|language| splik::language_matches(file.language, language)) {
+                return;
+            }
+            match std::io::Write::write_fmt(&mut std::io::stdout().lock(), format_args!("{}{terminator}", file.path)) {
+                Ok(()) => {}
+                Err(error) if error.kind() == std::io::ErrorKind::BrokenPipe => std::process::exit(0),
+                Err(error) => exit_with_error(error),
+            }
+        }));
+    }
+
+    // Count the progress of the scan to show it, unless files are written to stdout as they are
+    // counted, which the progress would be drawn over
+    if arguments.shows_progress() && options.on_file.is_none() {
+        options.progress = Some(std::sync::Arc::default());
+    }
+    let mut writer = output_writer(&arguments);
+
+    // Read the files to count from stdin, instead of scanning the root for them
+    let stdin_files = (arguments.stdin || arguments.stdin0).then(|| {
+        let files = read_stdin_files(arguments.stdin0).unwrap_or_else(|error| exit_with_error(error));
+        let (files, missing) = files.into_iter().partition::<Vec<_>, _>(|file| file.exists());
+        if !missing.is_empty() {
+            log::warn!(
+                "ignored {} path{} from stdin that {} exist",
+                missing.len(),
+                if missing.len() == 1 { "" } else { "s" },
+                if missing.len() == 1 { "doesn't" } else { "don't" }
+            );
+        }
+        files
+    });
+
+    // Keep scanning and re-printing the report when files change
+    if arguments.watch {
+        watch(&arguments, &roots, &options, &thread_pool);
+    }
+
+    let (result, left_out, failures) = scan_and_write(
+        &arguments,
+        &roots,
+        &options,
+        &thread_pool,
+        stdin_files.as_deref(),
+        &mut writer,
+    );
+    let result = writer.finish(result);
+    save_caches(&options, &roots);
+
+    // A closed pipe, such as when piping into `head`, isn't an error
+    if let Err(error) = result {
+        if error.kind() != std::io::ErrorKind::BrokenPipe {
+            exit_with_error(error);
+        }
+    }
+
+    // Report what the scan left out, and fail `--strict-empty` if nothing was counted
+    let nothing_counted = left_out.nothing_counted;
+    left_out.report(arguments.verbose != 0);
+    if nothing_counted && arguments.strict_empty {
+        exit_with_error(format!(
+            "no recognized source files found under {}",
+            describe_roots(&roots)
+        ));
+    }
+
+    // Fail the `--fail-if-present` and `--max-percent` checks, and `--find` when nothing was
+    // found, after the output, so that it is still shown
+    if !failures.is_empty() {
+        for failure in &failures {
+            eprintln!("error: {failure}");
+        }
+        std::process::exit(1);
+    }
+}
+
+/// Scans the roots and writes the output chosen by the arguments.
+///
+/// # Parameters
+/// - `arguments` - The arguments of the command
+/// - `roots` - The root directories to scan, whose counts are combined
+/// - `options` - The options of the scan
+/// - `thread_pool` - The thread pool to scan in
+/// - `stdin_files` - The files read from stdin by `--stdin`, which are counted instead of scanning
+///   the roots for files
+/// - `writer` - Where to write the output
+///
+/// # Returns
+/// - The result of writing the output, what the scan left out, and the failed checks.
+fn scan_and_write(
+    arguments: &Arguments,
+    roots: &[std::path::PathBuf],
+    options: &splik::ScanOptions,
+    thread_pool: &rayon::ThreadPool,
+    stdin_files: Option<&[std::path::PathBuf]>,
+    writer: &mut impl std::io::Write,
+) -> (std::io::Result<()>, LeftOut, Vec<String>) {
+    let top = arguments.top.filter(|top| *top != 0);
+    let min_percent = if arguments.all { 0.0 } else { arguments.min_percent };
+    let style = arguments.display_style();
+    let source = describe_roots(roots);
+
+    // Scan each root, combining their counts. When there are several roots, the directories and
+    // files of each of them are listed under the root, so that they can be told apart.
+    let by_dir = arguments.by_dir.or((arguments.per_project || arguments.per_root).then_some(0));
+    let mut directories = splik::DirectoryList::default();
+    let started = std::time::Instant::now();
+    let progress = options.progress.clone().map(ProgressDisplay::start);
+    for root in roots {
+        let depth = by_dir.unwrap_or(0);
+        let mut scanned = thread_pool
+            .install(|| {
+                if let Some(files) = stdin_files {
+                    return Ok(splik::scan_paths_by_directory(root, files, options, depth));
+                }
+
+                // List the files tracked by git, instead of scanning the root for them
+                if let Some(git_tracked) = &arguments.git_tracked {
+                    let files = splik::git_files(root, *git_tracked == GitTracked::All)?;
+                    return Ok(splik::scan_paths_by_directory(root, &files, options, depth));
+                }
+                match &arguments.rev {
+                    Some(revision) => splik::scan_revision_by_directory(root, revision, options, depth),
+                    None => splik::scan_by_directory(root, options, depth),
+                }
+            })
+            .unwrap_or_else(|error| exit_with_error(error));
+        if roots.len() > 1 || arguments.per_root {
+            let nest_files = roots.len() > 1 && options.paths == splik::PathStyle::Relative;
+            scanned.nest_under(&root.to_string_lossy(), nest_files);
+        }
+        directories.merge(scanned);
+    }
+    if let Some(progress) = progress {
+        progress.finish();
+    }
+    let threads = thread_pool.current_num_threads();
+    let timing =
+        (options.timing.as_ref()).map(|timing| timing.report(started.elapsed(), &directories.totals(), threads));
+    directories.timing = timing;
+    let writing = std::time::Instant::now();
+
+    // Count dialects as their parent languages, and then the languages of each group together
+    if arguments.merge_dialects {
+        directories.merge_dialects();
+    }
+    if arguments.groups {
+        directories.group(&arguments.language_groups);
+    }
+
+    // A scan that found nothing is stated plainly in the human-readable report, instead of as an
+    // empty table, unless `--strict-empty` states it as its error instead
+    let nothing_counted = directories.totals().files == 0;
+    let write_empty = |writer: &mut dyn std::io::Write| {
+        if arguments.strict_empty {
+            return Ok(());
+        }
+        writeln!(writer, "no recognized source files found under {source}")
+    };
+
+    let (result, left_out, failures) = if by_dir.is_some() {
+        // Per-directory breakdown
+        if arguments.stats {
+            directories.compute_stats();
+        }
+        directories.sort(arguments.sort, arguments.reverse);
+        let result = match arguments.output {
+            splik::OutputFormat::HumanReadable if nothing_counted => write_empty(writer),
+            splik::OutputFormat::HumanReadable if arguments.summary => directories.display_totals(writer, style),
+            splik::OutputFormat::HumanReadable => directories.display(writer, top, min_percent, style),
+            splik::OutputFormat::Json => directories.display_json(writer, top),
+            splik::OutputFormat::Yaml => directories.display_yaml(writer, top),
+            splik::OutputFormat::Jsonl => directories.display_jsonl(writer, top, options.on_file.is_none()),
+            splik::OutputFormat::Toml => directories.display_toml(writer, top),
+            splik::OutputFormat::Xml => directories.display_xml(writer, top),
+            splik::OutputFormat::Html => directories.display_html(writer, &source),
+            splik::OutputFormat::Svg => directories.display_svg(writer, top, min_percent, arguments.svg_options()),
+            splik::OutputFormat::Mermaid => {
+                directories.display_mermaid(writer, top, min_percent, arguments.mermaid_values)
+            }
+            splik::OutputFormat::Badge => {
+                directories.display_badge(writer, &arguments.badge_label, arguments.badge_language.as_deref())
+            }
+            splik::OutputFormat::Prometheus => directories.display_prometheus(writer),
+            splik::OutputFormat::TokeiJson => directories.display_tokei_json(writer),
+            splik::OutputFormat::ClocJson => directories.display_cloc_json(writer),
+            splik::OutputFormat::Csv => directories.display_csv(writer, arguments.totals),
+            splik::OutputFormat::Markdown => directories.display_markdown(writer),
+            splik::OutputFormat::GhSummary => {
+                write_summary_heading(writer).and_then(|()| directories.display_markdown(writer))
+            }
+            splik::OutputFormat::GhAnnotations => Ok(()),
+        };
+
+        // The unknown extensions are listed below the report
+        let result = match arguments.output {
+            splik::OutputFormat::HumanReadable if arguments.unknown => {
+                result.and_then(|()| directories.display_unknown(writer, style))
+            }
+            _ => result,
+        };
+        let failures = arguments.check_violations(directories.directories.values().flat_map(|list| &list.languages));
+        let left_out = LeftOut {
+            skipped: directories.skipped().cloned().collect(),
+            ignored_directories: std::mem::take(&mut directories.ignored_directories),
+            skipped_projects: std::mem::take(&mut directories.skipped_projects),
+            large_files: directories.skipped_large_files(),
+            binary_files: directories.binary_skipped(),
+            generated: directories.generated(),
+            nothing_counted,
+        };
+        (result, left_out, failures)
     } else {
-        get_root_dir(&std::path::PathBuf::from(&source))
-            .map(|path| path.to_str().unwrap().to_owned())
-            .unwrap_or(source)
+        let mut languages = directories.into_languages();
+        if arguments.stats {
+            languages.compute_stats();
+        }
+
+        // Sort by most used languages
+        languages.sort(arguments.sort, arguments.reverse);
+
+        // The report is printed once the interface is closed, so that it is left in the scrollback
+        if arguments.tui {
+            if let Err(error) = tui::explore(&languages, roots, arguments.sort, arguments.reverse) {
+                exit_with_error(format!("couldn't open the terminal interface: {error}"));
+            }
+        }
+
+        let mut failures = arguments.check_violations(languages.languages.iter());
+        let result = if !arguments.find.is_empty() {
+            // Find command, listing the files of every given language together, or the largest
+            // files of each of them
+            let mut files = std::collections::BTreeSet::new();
+            let mut largest = splik::FileListing::default();
+            let mut missing = Vec::new();
+            for language in &arguments.find {
+                let found = languages.languages.iter().find(|info| splik::language_matches(info.name, language));
+                if found.is_none_or(|info| info.file_count == 0) {
+                    missing.push(arguments.not_found_message(language));
+                }
+                files.extend(languages.find(language));
+                if let Some(count) = arguments.largest {
+                    largest.files.extend(languages.largest_files(count, Some(language)).files);
+                }
+            }
+
+            // A language without files only fails the command if no other language had any
+            if missing.len() == arguments.find.len() {
+                failures.extend(missing);
+            } else {
+                for message in &missing {
+                    log::warn!("{message}");
+                }
+            }
+            if arguments.largest.is_some() {
+                write_file_listing(writer, arguments, &languages, &largest, &source)
+            } else if arguments.streams_found_files(roots.len()) {
+                // The files were already written as they were found
+                Ok(())
+            } else {
+                write_found_files(writer, &files, &arguments.find_format)
+            }
+        } else if let Some(count) = arguments.largest {
+            // Largest files across all languages
+            write_file_listing(
+                writer,
+                arguments,
+                &languages,
+                &languages.largest_files(count, None),
+                &source,
+            )
+        } else if arguments.files {
+            // Per-file listing, unless the files were already written as they were counted. With
+            // `--style-stats`, only the files that drift from the style are listed.
+            let mut listing = if options.on_file.is_some() {
+                splik::FileListing::default()
+            } else {
+                languages.file_report()
+            };
+            if arguments.style_stats {
+                listing.files.retain(|file| file.style.is_some_and(|style| !style.is_conforming()));
+            }
+            write_file_listing(writer, arguments, &languages, &listing, &source)
+        } else if let Some(baseline) = &arguments.baseline {
+            // Compare against a previous scan
+            let baseline = splik::Baseline::read(baseline).unwrap_or_else(|error| {
+                exit_with_error(format!("couldn't read baseline {}: {error}", baseline.display()))
+            });
+            let comparison = languages.compare(&baseline);
+            failures.extend(arguments.check_growth(&comparison));
+            write_comparison(writer, arguments, &comparison)
+        } else {
+            // No subcommand
+            match arguments.output {
+                splik::OutputFormat::HumanReadable if nothing_counted => write_empty(writer),
+                splik::OutputFormat::HumanReadable if arguments.summary => languages.display_totals(writer, style),
+                splik::OutputFormat::HumanReadable => languages.display(writer, top, min_percent, style),
+                splik::OutputFormat::Json => languages.display_json(writer, top),
+                splik::OutputFormat::Yaml => languages.display_yaml(writer, top),
+                splik::OutputFormat::Jsonl => languages.display_jsonl(writer, top, options.on_file.is_none()),
+                splik::OutputFormat::Toml => languages.display_toml(writer, top),
+                splik::OutputFormat::Xml => languages.display_xml(writer, top),
+                splik::OutputFormat::Html => languages.display_html(writer, &source),
+                splik::OutputFormat::Svg => languages.display_svg(writer, top, min_percent, arguments.svg_options()),
+                splik::OutputFormat::Mermaid => {
+                    languages.display_mermaid(writer, top, min_percent, arguments.mermaid_values)
+                }
+                splik::OutputFormat::Badge => {
+                    languages.display_badge(writer, &arguments.badge_label, arguments.badge_language.as_deref())
+                }
+                splik::OutputFormat::Prometheus => languages.display_prometheus(writer),
+                splik::OutputFormat::TokeiJson => languages.display_tokei_json(writer),
+                splik::OutputFormat::ClocJson => languages.display_cloc_json(writer),
+                splik::OutputFormat::Csv => languages.display_csv(writer, arguments.totals),
+                splik::OutputFormat::Markdown => languages.display_markdown(writer),
+                splik::OutputFormat::GhSummary => {
+                    write_summary_heading(writer).and_then(|()| languages.display_markdown(writer))
+                }
+                splik::OutputFormat::GhAnnotations => Ok(()),
+            }
+        };
+
+        // The unknown extensions are listed below the report, unless files were found instead
+        let result = match arguments.output {
+            splik::OutputFormat::HumanReadable if arguments.unknown && arguments.find.is_empty() => {
+                result.and_then(|()| languages.display_unknown(writer, style))
+            }
+            _ => result,
+        };
+        let left_out = LeftOut {
+            skipped: languages.skipped,
+            ignored_directories: languages.ignored_directories,
+            skipped_projects: languages.skipped_projects,
+            large_files: languages.skipped_large_files,
+            binary_files: languages.binary_skipped,
+            generated: languages.generated,
+            nothing_counted,
+        };
+        (result, left_out, failures)
+    };
+
+    // The failed checks are the whole output of the annotations, so they are written last
+    let result = match arguments.output {
+        splik::OutputFormat::GhAnnotations => result.and_then(|()| write_annotations(writer, &failures)),
+        _ => result,
+    };
+    if let Some(timing) = timing {
+        eprintln!("timing: {timing}");
+        eprintln!("timing: wrote the output in {:.2?}", writing.elapsed());
+    }
+    (result, left_out, failures)
+}
+
+/// Writes the differences of `--baseline` or `diff` in the format given by `--output`. Nothing is
+/// written for `--output gh-annotations`, whose annotations are the failed checks.
+fn write_comparison(
+    writer: &mut impl std::io::Write,
+    arguments: &Arguments,
+    comparison: &splik::Comparison,
+) -> std::io::Result<()> {
+    let style = arguments.display_style();
+    match arguments.output {
+        splik::OutputFormat::HumanReadable if arguments.summary => comparison.display_totals(writer, style),
+        splik::OutputFormat::HumanReadable => comparison.display(writer, style),
+        splik::OutputFormat::Json => comparison.display_json(writer),
+        splik::OutputFormat::Yaml => comparison.display_yaml(writer),
+        splik::OutputFormat::Jsonl => comparison.display_jsonl(writer),
+        splik::OutputFormat::Toml => comparison.display_toml(writer),
+        splik::OutputFormat::Xml => comparison.display_xml(writer),
+        splik::OutputFormat::Html
+        | splik::OutputFormat::Svg
+        | splik::OutputFormat::Mermaid
+        | splik::OutputFormat::Badge
+        | splik::OutputFormat::Prometheus
+        | splik::OutputFormat::TokeiJson
+        | splik::OutputFormat::ClocJson => {
+            unreachable!("rejected with --baseline")
+        }
+        splik::OutputFormat::Csv => comparison.display_csv(writer, arguments.totals),
+        splik::OutputFormat::Markdown => comparison.display_markdown(writer),
+        splik::OutputFormat::GhSummary => {
+            write_summary_heading(writer).and_then(|()| comparison.display_markdown(writer))
+        }
+        splik::OutputFormat::GhAnnotations => Ok(()),
+    }
+}
+
+/// Compares the report of `--baseline` against a later report, instead of scanning, writes the
+/// differences in the format given by `--output`, and exits. Exits with code 1 if a check of
+/// `--fail-on-growth` fails.
+///
+/// # Parameters
+/// - `arguments` - The arguments of the command
+/// - `new` - The path of the later report
+fn diff_reports(arguments: &Arguments, new: &std::path::Path) -> ! {
+    let read = |path: &std::path::Path| {
+        splik::Baseline::read(path)
+            .unwrap_or_else(|error| exit_with_error(format!("couldn't read report {}: {error}", path.display())))
+    };
+    let old = read(arguments.baseline.as_deref().expect("set by diff"));
+    let comparison = read(new).compare(&old);
+    let failures = arguments.check_growth(&comparison);
+
+    let mut writer = output_writer(arguments);
+    let result = match arguments.output {
+        splik::OutputFormat::GhAnnotations => write_annotations(&mut writer, &failures),
+        _ => write_comparison(&mut writer, arguments, &comparison),
+    };
+    let result = writer.finish(result);
+    if let Err(error) = result {
+        if error.kind() != std::io::ErrorKind::BrokenPipe {
+            exit_with_error(error);
+        }
+    }
+    for failure in &failures {
+        eprintln!("error: {failure}");
+    }
+    std::process::exit(i32::from(!failures.is_empty()));
+}
+
+/// Returns where the output is written: the file given to `--output-file`, the file named by
+/// `GITHUB_STEP_SUMMARY` for `--output gh-summary --gh-write`, or stdout otherwise, including
+/// when the variable isn't set outside of GitHub Actions.
+fn output_writer(arguments: &Arguments) -> Output {
+    if let Some(path) = arguments.output_file.as_ref().filter(|_| arguments.writes_to_file()) {
+        if path.file_name().is_none() {
+            exit_with_error(format!("{} isn't a file", path.display()));
+        }
+        return Output::File {
+            path: path.clone(),
+            mkdirs: arguments.mkdirs,
+            buffer: Vec::new(),
+        };
+    }
+
+    let summary = std::env::var_os("GITHUB_STEP_SUMMARY").filter(|summary| !summary.is_empty());
+    match summary {
+        Some(summary) if arguments.gh_write && matches!(arguments.output, splik::OutputFormat::GhSummary) => {
+            let file = std::fs::OpenOptions::new().create(true).append(true).open(&summary);
+            let file = file.unwrap_or_else(|error| {
+                exit_with_error(format!(
+                    "couldn't open the step summary {}: {error}",
+                    std::path::Path::new(&summary).display()
+                ))
+            });
+            Output::Append(std::io::BufWriter::new(file))
+        }
+        _ => Output::Stdout(std::io::stdout()),
+    }
+}
+
+/// Where the output is written, see `output_writer`.
+enum Output {
+    /// Stdout.
+    Stdout(std::io::Stdout),
+    /// A file that the output is appended to, such as the job summary of `--gh-write`.
+    Append(std::io::BufWriter<std::fs::File>),
+    /// The file of `--output-file`, which is kept in memory until the output is complete, so that
+    /// nothing is created on disk by a run that fails or exits early.
+    File {
+        /// The path of the output file.
+        path: std::path::PathBuf,
+        /// Whether the missing parent directories of the output file are created, see `--mkdirs`.
+        mkdirs: bool,
+        /// The output written so far.
+        buffer: Vec<u8>,
+    },
+}
+
+impl Output {
+    /// Finishes writing the output. The output of `--output-file` is written to a temporary file
+    /// next to the output file, which then replaces it, followed by a line saying so on stdout, so
+    /// that a failed run doesn't leave a partial report, and the rename can't cross file systems.
+    ///
+    /// # Parameters
+    /// - `result` - The result of writing the output
+    fn finish(self, result: std::io::Result<()>) -> std::io::Result<()> {
+        match self {
+            Self::Stdout(mut stdout) => result.and_then(|()| std::io::Write::flush(&mut stdout)),
+            Self::Append(mut file) => result.and_then(|()| std::io::Write::flush(&mut file)),
+            Self::File { path, mkdirs, buffer } => {
+                result?;
+                if mkdirs {
+                    if let Some(parent) = path.parent().filter(|parent| !parent.as_os_str().is_empty()) {
+                        std::fs::create_dir_all(parent).map_err(|error| {
+                            std::io::Error::new(error.kind(), format!("couldn't create {}: {error}", parent.display()))
+                        })?;
+                    }
+                }
+
+                let name = path.file_name().expect("checked by output_writer").to_string_lossy();
+                let temporary = path.with_file_name(format!(".{name}.{}.tmp", std::process::id()));
+                let result = std::fs::File::create(&temporary).and_then(|mut file| {
+                    std::io::Write::write_all(&mut file, &buffer)?;
+                    file.sync_all()?;
+                    std::fs::rename(&temporary, &path)
+                });
+                if let Err(error) = result {
+                    let _ = std::fs::remove_file(&temporary);
+                    let message = format!("couldn't write {}: {error}", path.display());
+                    return Err(std::io::Error::new(error.kind(), message));
+                }
+                println!("wrote {}", path.display());
+                Ok(())
+            }
+        }
+    }
+}
+
+impl std::io::Write for Output {
+    fn write(&mut self, buffer: &[u8]) -> std::io::Result<usize> {
+        match self {
+            Self::Stdout(stdout) => stdout.write(buffer),
+            Self::Append(file) => file.write(buffer),
+            Self::File { buffer: output, .. } => output.write(buffer),
+        }
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        match self {
+            Self::Stdout(stdout) => stdout.flush(),
+            Self::Append(file) => file.flush(),
+            Self::File { .. } => Ok(()),
+        }
+    }
+}
+
+/// How long a scan runs before its progress is shown, so that fast scans don't flicker.
+const PROGRESS_DELAY: std::time::Duration = std::time::Duration::from_secs(1);
+
+/// How often the progress of a scan is redrawn once it is shown.
+const PROGRESS_INTERVAL: std::time::Duration = std::time::Duration::from_millis(100);
+
+/// The frames of the spinner that shows the progress of a scan.
+const SPINNER: &[char] = &['|', '/', '-', '\\'];
+
+/// The progress of a scan, drawn on stderr by a thread of its own while the scan runs, see
+/// `--quiet`.
+struct ProgressDisplay {
+    /// Stops the thread, see `ProgressDisplay::finish`.
+    stop: std::sync::mpsc::Sender<()>,
+    /// The thread that draws the progress.
+    thread: std::thread::JoinHandle<()>,
+}
+
+impl ProgressDisplay {
+    /// Starts drawing the progress of a scan once it has run for `PROGRESS_DELAY`.
+    ///
+    /// # Parameters
+    /// - `progress` - The progress of the scan, which it updates as it runs
+    fn start(progress: std::sync::Arc<splik::ScanProgress>) -> Self {
+        let (stop, stopped) = std::sync::mpsc::channel::<()>();
+        let thread = std::thread::spawn(move || {
+            if stopped.recv_timeout(PROGRESS_DELAY) != Err(std::sync::mpsc::RecvTimeoutError::Timeout) {
+                return;
+            }
+            let mut stderr = std::io::stderr();
+            for frame in SPINNER.iter().cycle() {
+                // Errors are ignored, since the progress is only a courtesy
+                let line = format_args!("\r\x1b[2K{frame} Scanning: {progress}");
+                let _ = std::io::Write::write_fmt(&mut stderr, line);
+                if stopped.recv_timeout(PROGRESS_INTERVAL) != Err(std::sync::mpsc::RecvTimeoutError::Timeout) {
+                    break;
+                }
+            }
+            let _ = std::io::Write::write_all(&mut stderr, b"\r\x1b[2K");
+        });
+        Self { stop, thread }
+    }
+
+    /// Stops drawing the progress once the scan is done, clearing it if it was shown, so that the
+    /// report is printed on a clean line.
+    fn finish(self) {
+        let _ = self.stop.send(());
+        let _ = self.thread.join();
+    }
+}
+
+/// Writes the heading of `--output gh-summary`, which is followed by the Markdown output.
+fn write_summary_heading(writer: &mut impl std::io::Write) -> std::io::Result<()> {
+    writeln!(writer, "### Languages")?;
+    writeln!(writer)
+}
+
+/// Writes each failed check as a `::notice` workflow command of GitHub Actions, which shows it as
+/// an annotation of the run, for `--output gh-annotations`.
+fn write_annotations(writer: &mut impl std::io::Write, failures: &[String]) -> std::io::Result<()> {
+    for failure in failures {
+        // Workflow commands end at a newline, so they are escaped as described by GitHub
+        let message = failure.replace('%', "%25").replace('\r', "%0D").replace('\n', "%0A");
+        writeln!(writer, "::notice title=splik::{message}")?;
+    }
+    Ok(())
+}
+
+/// How often `--watch` checks the files of the last scan, and the directories containing them, for
+/// changes.
+const WATCH_POLL_INTERVAL: std::time::Duration = std::time::Duration::from_millis(100);
+
+/// How long `--watch` waits after the last change it noticed before scanning again, so that saving
+/// many files at once only shows one report.
+const WATCH_DEBOUNCE: std::time::Duration = std::time::Duration::from_millis(500);
+
+/// How often `--watch` scans the roots again without having noticed a change, to find the files
+/// added to directories that no counted file is in, which `WatchSnapshot` doesn't check.
+const WATCH_RESCAN_INTERVAL: std::time::Duration = std::time::Duration::from_secs(2);
+
+/// The modification times and sizes of the files found by the last scan of `--watch`, and of the
+/// directories containing them up to the roots, which change when a file is added, removed, or
+/// renamed in them. Comparing these notices most changes without walking the roots again.
+#[derive(PartialEq)]
+struct WatchSnapshot(Vec<(std::path::PathBuf, WatchStamp)>);
+
+/// The modification time and size of a file or directory, or `None` if it doesn't exist.
+type WatchStamp = Option<(Option<std::time::SystemTime>, u64)>;
+
+impl WatchSnapshot {
+    /// Reads the metadata of the files in the cache of a scan, and of their directories.
+    ///
+    /// # Parameters
+    /// - `cache` - The cache of the scan, with the files that it found
+    /// - `roots` - The root directories that were scanned
+    /// - `thread_pool` - The thread pool to read the metadata in
+    fn take(cache: &splik::FileCache, roots: &[std::path::PathBuf], thread_pool: &rayon::ThreadPool) -> Self {
+        let mut paths = cache.paths().into_iter().collect::<std::collections::BTreeSet<_>>();
+        let directories = paths
+            .iter()
+            .flat_map(|path| path.ancestors().skip(1))
+            .filter(|directory| roots.iter().any(|root| directory.starts_with(root)))
+            .map(std::path::Path::to_path_buf)
+            .collect::<Vec<_>>();
+        paths.extend(directories.into_iter().chain(roots.iter().cloned()));
+        let paths = paths.into_iter().collect::<Vec<_>>();
+        let stamps = thread_pool.install(|| {
+            paths
+                .into_par_iter()
+                .map(|path| {
+                    let metadata = std::fs::metadata(&path).ok();
+                    let stamp = metadata.map(|metadata| (metadata.modified().ok(), metadata.len()));
+                    (path, stamp)
+                })
+                .collect()
+        });
+        Self(stamps)
+    }
+}
+
+/// Prints the report like a normal run, then keeps watching the roots and prints it again whenever
+/// it changes, until the process is interrupted. Human-readable reports clear the screen before
+/// they are printed again, while other formats print another document. Only the files that changed
+/// since the previous scan are read again.
+///
+/// # Parameters
+/// - `arguments` - The arguments of the command
+/// - `roots` - The root directories to scan
+/// - `options` - The options of the scan
+/// - `thread_pool` - The thread pool to scan in
+fn watch(
+    arguments: &Arguments,
+    roots: &[std::path::PathBuf],
+    options: &splik::ScanOptions,
+    thread_pool: &rayon::ThreadPool,
+) -> ! {
+    let options = splik::ScanOptions {
+        cache: Some(options.cache.clone().unwrap_or_default()),
+        ..options.clone()
+    };
+    let cache = options.cache.clone().unwrap_or_default();
+    let mut shown: Option<Vec<u8>> = None;
+    loop {
+        let mut output = Vec::new();
+        let (result, left_out, failures) = scan_and_write(arguments, roots, &options, thread_pool, None, &mut output);
+        if let Err(error) = result {
+            exit_with_error(error);
+        }
+
+        if shown.as_ref() != Some(&output) {
+            let mut stdout = std::io::stdout().lock();
+            let clear = if matches!(arguments.output, splik::OutputFormat::HumanReadable) {
+                "\x1b[2J\x1b[H"
+            } else {
+                ""
+            };
+            let result = std::io::Write::write_all(&mut stdout, clear.as_bytes())
+                .and_then(|()| std::io::Write::write_all(&mut stdout, &output))
+                .and_then(|()| std::io::Write::flush(&mut stdout));
+            match result {
+                Ok(()) => {}
+                Err(error) if error.kind() == std::io::ErrorKind::BrokenPipe => std::process::exit(0),
+                Err(error) => exit_with_error(error),
+            }
+            left_out.report(arguments.verbose != 0);
+            for failure in &failures {
+                eprintln!("error: {failure}");
+            }
+            if arguments.cache {
+                save_caches(&options, roots);
+            }
+            shown = Some(output);
+        }
+
+        // Wait for a change, and for the changes to settle, before scanning again
+        let mut snapshot = WatchSnapshot::take(&cache, roots, thread_pool);
+        let scanned = std::time::Instant::now();
+        let mut changed: Option<std::time::Instant> = None;
+        loop {
+            std::thread::sleep(WATCH_POLL_INTERVAL);
+            let current = WatchSnapshot::take(&cache, roots, thread_pool);
+            if current != snapshot {
+                snapshot = current;
+                changed = Some(std::time::Instant::now());
+            }
+            let due = match changed {
+                Some(changed) => changed.elapsed() >= WATCH_DEBOUNCE,
+                None => scanned.elapsed() >= WATCH_RESCAN_INTERVAL,
+            };
+            if due {
+                break;
+            }
+        }
+    }
+}
+
+/// Returns the root directories of a scan as a list separated by commas, for stating what was
+/// scanned in the output.
+fn describe_roots(roots: &[std::path::PathBuf]) -> String {
+    roots.iter().map(|root| root.to_string_lossy()).collect::<Vec<_>>().join(", ")
+}
+
+/// Saves the cache of a scan to each of its roots, for `--cache`, warning about those that
+/// couldn't be written.
+fn save_caches(options: &splik::ScanOptions, roots: &[std::path::PathBuf]) {
+    let Some(cache) = &options.cache else {
+        return;
+    };
+    for root in roots {
+        if let Err(error) = cache.save(root) {
+            log::warn!("couldn't write the cache of {}: {error}", root.display());
+        }
+    }
+}
+
+/// What a scan skipped or left out, which is reported after the output.
+struct LeftOut {
+    /// The files and directories that couldn't be read.
+    skipped: Vec<splik::SkippedPath>,
+    /// The directories that were skipped by name, such as `node_modules`.
+    ignored_directories: Vec<String>,
+    /// The nested projects that were skipped by `--no-nested`.
+    skipped_projects: Vec<String>,
+    /// The number of files larger than `--max-file-size`.
+    large_files: usize,
+    /// The number of binary files.
+    binary_files: usize,
+    /// The generated and minified files.
+    generated: splik::GeneratedFiles,
+    /// Whether no files were counted at all, for `--strict-empty`.
+    nothing_counted: bool,
+}
+
+impl LeftOut {
+    /// Prints warnings about what was left out to stderr. Unreadable and large files are always
+    /// reported, and the rest only when `verbose` is set.
+    fn report(mut self, verbose: bool) {
+        // Report files that couldn't be read
+        if verbose {
+            for skipped in &self.skipped {
+                log::warn!("skipped {}: {}", skipped.path, skipped.reason);
+            }
+        } else if !self.skipped.is_empty() {
+            let count = self.skipped.len();
+            log::warn!(
+                "skipped {count} unreadable file{}, use -v for details",
+                if count == 1 { "" } else { "s" }
+            );
+        }
+        if verbose {
+            self.ignored_directories.sort();
+            for directory in &self.ignored_directories {
+                log::info!("ignored directory {directory}, use --include to count it");
+            }
+            self.skipped_projects.sort();
+            for project in &self.skipped_projects {
+                log::info!("skipped nested project {project}");
+            }
+        }
+        if verbose && self.binary_files != 0 {
+            log::warn!(
+                "skipped {} binary file{}, use --count-binary to count them",
+                self.binary_files,
+                if self.binary_files == 1 { "" } else { "s" }
+            );
+        }
+        if verbose && !self.generated.is_empty() {
+            log::warn!(
+                "left out {} generated or minified file{} ({} bytes), use --include-generated to count them",
+                self.generated.files,
+                if self.generated.files == 1 { "" } else { "s" },
+                self.generated.bytes
+            );
+        }
+        if self.large_files != 0 {
+            log::warn!(
+                "skipped {} file{} larger than --max-file-size",
+                self.large_files,
+                if self.large_files == 1 { "" } else { "s" }
+            );
+        }
+    }
+}
+
+/// Reads the paths of the files to count from stdin, for `--stdin` and `--stdin0`. Relative paths
+/// are relative to the current directory, and empty lines are skipped.
+///
+/// # Parameters
+/// - `null_separated` - Whether the paths are separated by NUL bytes instead of newlines
+///
+/// # Errors
+/// Returns an error if stdin couldn't be read.
+fn read_stdin_files(null_separated: bool) -> std::io::Result<Vec<std::path::PathBuf>> {
+    let mut input = Vec::new();
+    std::io::Read::read_to_end(&mut std::io::stdin().lock(), &mut input)?;
+    let current_dir = std::env::current_dir()?;
+    let separator = if null_separated { b'\0' } else { b'\n' };
+    Ok(input
+        .split(|byte| *byte == separator)
+        .map(|path| String::from_utf8_lossy(path).trim_end_matches('\r').to_owned())
+        .filter(|path| !path.is_empty())
+        .map(|path| current_dir.join(path))
+        .collect())
+}
+
+/// The name the files given to `describe_files` are listed under when their language isn't known.
+const UNKNOWN_LANGUAGE: &str = "Unknown";
+
+/// Prints the language, lines, and bytes of each of the given files, in the format given by
+/// `--output`, and exits. Files are counted regardless of the filters that would skip them in a
+/// scan, such as `--exclude`, and files whose language isn't known are listed as `Unknown`,
+/// which fails with exit code 1.
+///
+/// # Parameters
+/// - `arguments` - The arguments of the command
+/// - `files` - The paths of the files, as given on the command line
+fn describe_files(arguments: &Arguments, files: &[std::path::PathBuf]) -> ! {
+    let options = splik::ScanOptions {
+        extension_map: arguments.extension_map.clone(),
+        split_components: arguments.split_components,
+        count_binary: true,
+        include_generated: true,
+        ..splik::ScanOptions::default()
+    };
+
+    let mut languages = splik::LanguageList::default();
+    let mut failures = Vec::new();
+    for file in files {
+        let language = splik::detect_language(file, &options.extension_map).unwrap_or_else(|| {
+            failures.push(format!("unknown language of {}", file.display()));
+            UNKNOWN_LANGUAGE
+        });
+        let name = match arguments.paths {
+            splik::PathStyle::Relative => file.to_string_lossy().into_owned(),
+            splik::PathStyle::Absolute => {
+                file.canonicalize().unwrap_or_else(|_| file.clone()).to_string_lossy().into_owned()
+            }
+        };
+        if let Err(error) = languages.add_file_as(file, name, false, language, &options) {
+            exit_with_error(format!("couldn't read {}: {error}", file.display()));
+        }
+    }
+
+    let mut writer = output_writer(arguments);
+    let result = match arguments.output {
+        splik::OutputFormat::GhAnnotations => write_annotations(&mut writer, &failures),
+        _ => {
+            let source = files.iter().map(|file| file.to_string_lossy()).collect::<Vec<_>>().join(", ");
+            write_file_listing(&mut writer, arguments, &languages, &languages.file_report(), &source)
+        }
     };
+    let result = writer.finish(result);
+    if let Err(error) = result {
+        if error.kind() != std::io::ErrorKind::BrokenPipe {
+            exit_with_error(error);
+        }
+    }
+    for failure in &failures {
+        eprintln!("error: {failure}");
+    }
+    std::process::exit(i32::from(!failures.is_empty()));
+}
+
+/// Prints why a scan counts a file or leaves it out for `--explain`, and exits with code 0 if it
+/// is counted, or 1 if it isn't.
+///
+/// # Parameters
+/// - `roots` - The roots that would be scanned. The file is explained in the first root that it is
+///   below.
+/// - `path` - The path of the file
+/// - `options` - The options of the scan
+fn explain(roots: &[std::path::PathBuf], path: &std::path::Path, options: &splik::ScanOptions) -> ! {
+    let absolute = std::path::absolute(path).unwrap_or_else(|_| path.to_owned());
+    let canonical = absolute.canonicalize().unwrap_or_else(|_| absolute.clone());
+    let root = roots
+        .iter()
+        .find(|root| {
+            let root = root.canonicalize().unwrap_or_else(|_| root.to_path_buf());
+            absolute.starts_with(&root) || canonical.starts_with(&root)
+        })
+        .unwrap_or(&roots[0]);
+    let explanation = splik::explain(root, path, options)
+        .unwrap_or_else(|error| exit_with_error(format!("couldn't explain {}: {error}", path.display())));
+
+    if let Err(error) = write_explanation(&mut std::io::stdout().lock(), &explanation) {
+        if error.kind() != std::io::ErrorKind::BrokenPipe {
+            exit_with_error(error);
+        }
+    }
+    std::process::exit(i32::from(!explanation.is_counted()));
+}
+
+/// Writes the rules checked by `--explain`, with the verdict of each of them, followed by the
+/// language the file is counted as or the rule that leaves it out.
+fn write_explanation(writer: &mut impl std::io::Write, explanation: &splik::Explanation) -> std::io::Result<()> {
+    writeln!(writer, "{}", explanation.path)?;
+    for check in &explanation.checks {
+        let verdict = if check.skips { "skip" } else { "pass" };
+        writeln!(writer, "  {verdict}  {}: {}", check.rule, check.verdict)?;
+    }
+    match (explanation.reason(), explanation.language) {
+        (None, Some(language)) => writeln!(writer, "counted as {language}"),
+        (Some(reason), _) => writeln!(writer, "not counted, because of {}: {}", reason.rule, reason.verdict),
+        (None, None) => writeln!(writer, "not counted"),
+    }
+}
+
+/// Prints the settings that can be given by an environment variable or the configuration file for
+/// `--explain-config`, with where each of them came from, and exits.
+///
+/// # Parameters
+/// - `arguments` - The arguments, with the configuration file applied
+/// - `matches` - The matches the arguments were parsed from
+/// - `sources` - The directories that the roots were searched from
+/// - `project_roots` - The root of each source, and the project root it was found as, if any
+fn explain_config(
+    arguments: &Arguments,
+    matches: &clap::ArgMatches,
+    sources: &[std::path::PathBuf],
+    project_roots: &[(std::path::PathBuf, Option<splik::ProjectRoot>)],
+) -> ! {
+    let command = <Arguments as clap::CommandFactory>::command();
+    let source_of = |id: &str| match matches.value_source(id) {
+        Some(clap::parser::ValueSource::CommandLine) => "command line".to_owned(),
+        Some(clap::parser::ValueSource::EnvVariable) => {
+            let argument = command.get_arguments().find(|argument| argument.get_id() == id);
+            let variable = argument.and_then(clap::Arg::get_env).unwrap_or_default();
+            format!("{} environment variable", variable.to_string_lossy())
+        }
+        _ => match arguments.config_settings.get(id) {
+            Some(paths) => paths.iter().map(|path| path.display().to_string()).collect::<Vec<_>>().join(", "),
+            None => "default".to_owned(),
+        },
+    };
+    let describe_config = |path: Option<std::path::PathBuf>| match path {
+        Some(path) if arguments.no_config => format!("{} (not loaded, because of --no-config)", path.display()),
+        Some(path) if path.is_file() => path.display().to_string(),
+        Some(path) => format!("{} (doesn't exist)", path.display()),
+        None => "none".to_owned(),
+    };
+
+    let mut settings = Vec::new();
+    settings.push(("global config".to_owned(), describe_config(splik::global_config_path())));
+    let root = &project_roots[0].0;
+    let project_config = splik::Config::path(root).unwrap_or_else(|| root.join(splik::CONFIG_FILE_NAMES[0]));
+    settings.push(("project config".to_owned(), describe_config(Some(project_config))));
+    for ((root, project_root), source) in project_roots.iter().zip(sources) {
+        let origin = if arguments.root.is_some() {
+            source_of("root")
+        } else if let Some(project_root) = project_root {
+            format!("found from {} by {}", source.display(), project_root.indicator)
+        } else if arguments.here {
+            "the given directory, because of --here".to_owned()
+        } else {
+            "the given directory, because no project root was found".to_owned()
+        };
+        settings.push(("root".to_owned(), format!("{} ({origin})", root.display())));
+    }
+    let list = |values: Vec<String>| {
+        if values.is_empty() {
+            "none".to_owned()
+        } else {
+            values.join(", ")
+        }
+    };
+    let output = clap::ValueEnum::to_possible_value(&arguments.output).unwrap();
+    let categories = arguments
+        .categories
+        .iter()
+        .filter_map(clap::ValueEnum::to_possible_value)
+        .map(|category| category.get_name().to_owned())
+        .collect();
+    let threads = arguments.threads.map_or_else(|| "one per core".to_owned(), |threads| threads.to_string());
+    let color = clap::ValueEnum::to_possible_value(&arguments.color).unwrap();
+    let values = [
+        ("output", output.get_name().to_owned()),
+        ("exclude", list(arguments.exclude.clone())),
+        ("categories", list(categories)),
+        ("include", list(arguments.include.clone())),
+        ("include_dotfiles", arguments.include_dotfiles.to_string()),
+        ("merge_dialects", arguments.merge_dialects.to_string()),
+        ("color", color.get_name().to_owned()),
+        ("threads", threads),
+    ];
+    for (id, value) in values {
+        settings.push((id.replace('_', "-"), format!("{value} ({})", source_of(id))));
+    }
+
+    if let Err(error) = write_settings(&mut std::io::stdout().lock(), &settings) {
+        if error.kind() != std::io::ErrorKind::BrokenPipe {
+            exit_with_error(error);
+        }
+    }
+    std::process::exit(0);
+}
+
+/// Writes the settings of `--explain-config`, one per line, with their values aligned.
+fn write_settings(writer: &mut impl std::io::Write, settings: &[(String, String)]) -> std::io::Result<()> {
+    let width = settings.iter().map(|(name, _)| name.len() + 1).max().unwrap_or_default();
+    for (name, value) in settings {
+        writeln!(writer, "{:width$} {value}", format!("{name}:"))?;
+    }
+    Ok(())
+}
+
+/// Writes the languages that splik can detect for `--list-languages`, and exits. Exits with an
+/// error if a language is given that splik can't detect.
+///
+/// # Parameters
+/// - `arguments` - The arguments, whose extension map includes the languages of `--map` and the
+///   configuration file
+/// - `name` - The language to list, or `None` to list every language
+fn list_languages(arguments: &Arguments, name: Option<&str>) -> ! {
+    let mut languages = splik::KnownLanguages::new(&arguments.extension_map);
+    if let Some(name) = name {
+        languages.retain_matching(name);
+        if languages.languages.is_empty() {
+            match splik::closest_language(name) {
+                Some(closest) => eprintln!("error: \"{name}\" isn't a known language, did you mean {closest}?"),
+                None => eprintln!("error: \"{name}\" isn't a known language"),
+            }
+            std::process::exit(1);
+        }
+    }
+
+    let mut writer = output_writer(arguments);
+    let result = match arguments.output {
+        splik::OutputFormat::HumanReadable => languages.display(&mut writer, arguments.display_style()),
+        splik::OutputFormat::Json => languages.display_json(&mut writer),
+        splik::OutputFormat::Yaml => languages.display_yaml(&mut writer),
+        splik::OutputFormat::Jsonl => languages.display_jsonl(&mut writer),
+        splik::OutputFormat::Toml => languages.display_toml(&mut writer),
+        splik::OutputFormat::Xml => languages.display_xml(&mut writer),
+        splik::OutputFormat::Csv => languages.display_csv(&mut writer),
+        splik::OutputFormat::Markdown => languages.display_markdown(&mut writer),
+        splik::OutputFormat::Html
+        | splik::OutputFormat::Svg
+        | splik::OutputFormat::Mermaid
+        | splik::OutputFormat::Badge
+        | splik::OutputFormat::Prometheus
+        | splik::OutputFormat::TokeiJson
+        | splik::OutputFormat::ClocJson
+        | splik::OutputFormat::GhSummary
+        | splik::OutputFormat::GhAnnotations => {
+            unreachable!("rejected with --list-languages")
+        }
+    };
+    if let Err(error) = writer.finish(result) {
+        if error.kind() != std::io::ErrorKind::BrokenPipe {
+            exit_with_error(error);
+        }
+    }
+    std::process::exit(0);
+}
+
+/// Exits with status code 2 if a path given on the command line doesn't exist, isn't a directory
+/// or a file, or is a directory that can't be opened, rather than scanning it as if it were empty.
+fn check_source(source: &std::path::Path) {
+    match std::fs::metadata(source) {
+        Ok(metadata) if metadata.is_dir() => {
+            if let Err(error) = std::fs::read_dir(source) {
+                exit_with_error(format!("couldn't open {}: {error}", source.display()));
+            }
+        }
+        Ok(metadata) if metadata.is_file() => {}
+        Err(error) if error.kind() != std::io::ErrorKind::NotFound => {
+            exit_with_error(format!("couldn't open {}: {error}", source.display()));
+        }
+        _ => exit_with_error(format!("no such directory: {}", source.display())),
+    }
+}
+
+/// Removes the roots that are the same as, or inside of, another root, so that their files aren't
+/// counted twice. Roots are compared by their canonical paths, and kept in order.
+fn distinct_roots(roots: Vec<std::path::PathBuf>) -> Vec<std::path::PathBuf> {
+    let canonical_roots = roots
+        .iter()
+        .map(|root| root.canonicalize().unwrap_or_else(|_| root.clone()))
+        .collect::<Vec<_>>();
+    roots
+        .into_iter()
+        .enumerate()
+        .filter(|(index, _)| {
+            let canonical_root = &canonical_roots[*index];
+            !canonical_roots.iter().enumerate().any(|(other_index, other)| {
+                let is_inside = canonical_root.starts_with(other) && canonical_root != other;
+                is_inside || (canonical_root == other && other_index < *index)
+            })
+        })
+        .map(|(_, root)| root)
+        .collect()
+}
+
+/// Writes a listing of files, such as those of `--files` and `--largest`, in the format given by
+/// `--output`. Formats that can't list files write the report of the languages instead.
+///
+/// # Parameters
+/// - `writer` - Where to write the listing
+/// - `arguments` - The arguments of the command
+/// - `languages` - The languages the files were counted in
+/// - `listing` - The files to list
+/// - `source` - What was scanned, see `LanguageList::display_html`
+fn write_file_listing(
+    writer: &mut impl std::io::Write,
+    arguments: &Arguments,
+    languages: &splik::LanguageList,
+    listing: &splik::FileListing<'_>,
+    source: &str,
+) -> std::io::Result<()> {
+    let top = arguments.top.filter(|top| *top != 0);
+    let min_percent = if arguments.all { 0.0 } else { arguments.min_percent };
+    match arguments.output {
+        splik::OutputFormat::HumanReadable => listing.display(writer, arguments.display_style()),
+        splik::OutputFormat::Json => listing.display_json(writer),
+        splik::OutputFormat::Yaml => listing.display_yaml(writer),
+        splik::OutputFormat::Jsonl => listing.display_jsonl(writer),
+        splik::OutputFormat::Toml => listing.display_toml(writer),
+        splik::OutputFormat::Xml => listing.display_xml(writer),
+        splik::OutputFormat::Html => languages.display_html(writer, source),
+        splik::OutputFormat::Svg => languages.display_svg(writer, top, min_percent, arguments.svg_options()),
+        splik::OutputFormat::Mermaid => languages.display_mermaid(writer, top, min_percent, arguments.mermaid_values),
+        splik::OutputFormat::Badge => {
+            languages.display_badge(writer, &arguments.badge_label, arguments.badge_language.as_deref())
+        }
+        splik::OutputFormat::Prometheus => languages.display_prometheus(writer),
+        splik::OutputFormat::TokeiJson => languages.display_tokei_json(writer),
+        splik::OutputFormat::ClocJson => languages.display_cloc_json(writer),
+        splik::OutputFormat::Csv => listing.display_csv(writer),
+        splik::OutputFormat::Markdown => listing.display_markdown(writer),
+        splik::OutputFormat::GhSummary => write_summary_heading(writer).and_then(|()| listing.display_markdown(writer)),
+        splik::OutputFormat::GhAnnotations => Ok(()),
+    }
+}
+
+/// Writes the files listed by `--find` in the given format.
+fn write_found_files(
+    writer: &mut impl std::io::Write,
+    files: &std::collections::BTreeSet<&splik::FileInfo>,
+    format: &FindFormat,
+) -> std::io::Result<()> {
+    match format {
+        FindFormat::Plain => files.iter().try_for_each(|file| writeln!(writer, "{}", file.path)),
+        FindFormat::Null => files.iter().try_for_each(|file| write!(writer, "{}\0", file.path)),
+        FindFormat::Json => {
+            serde_json::to_writer(&mut *writer, files)?;
+            writeln!(writer)
+        }
+    }
+}
 
-    // Find root command
-    if arguments.find_root {
-        println!("{root}");
-        return;
+/// Returns the width of the terminal that stdout is written to, from the `COLUMNS` environment
+/// variable or `stty size`, or `None` if stdout isn't a terminal or its width is unknown.
+fn terminal_width() -> Option<usize> {
+    if !std::io::IsTerminal::is_terminal(&std::io::stdout()) {
+        return None;
+    }
+    let columns = std::env::var("COLUMNS").ok().and_then(|columns| columns.parse().ok());
+    if let Some(columns) = columns.filter(|columns| *columns != 0) {
+        return Some(columns);
     }
 
-    // Generate the language information
-    analyze_directory(&root, &arguments, &mut languages);
+    // `stty` reads the size of the terminal it is given as stdin
+    let terminal = std::fs::File::open("/dev/tty").ok()?;
+    let output = std::process::Command::new("stty")
+        .arg("size")
+        .stdin(terminal)
+        .stderr(std::process::Stdio::null())
+        .output()
+        .ok()?;
+    String::from_utf8(output.stdout).ok()?.split_whitespace().nth(1)?.parse().ok()
+}
 
-    // Sort by most used languages
-    languages.sort();
+/// Prints the messages logged by splik on stderr, prefixed by their level like the other messages
+/// of the command, such as `warning: ...`, see `Arguments::log_level`. Messages of other crates
+/// are left out.
+struct StderrLogger;
 
-    // Find command
-    if let Some(language) = arguments.find {
-        languages.find(&language);
-        return;
+impl log::Log for StderrLogger {
+    fn enabled(&self, metadata: &log::Metadata) -> bool {
+        metadata.level() <= log::max_level() && metadata.target().starts_with("splik")
     }
 
-    // No subcommand
-    match arguments.output {
-        OutputFormat::HumanReadable => languages.display(),
-        OutputFormat::Json => println!("{}", serde_json::to_string(&languages).unwrap()),
-        OutputFormat::Yaml => println!("{}", serde_yaml::to_string(&languages).unwrap()),
+    fn log(&self, record: &log::Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+        let prefix = match record.level() {
+            log::Level::Error => "error",
+            log::Level::Warn => "warning",
+            log::Level::Info => "note",
+            log::Level::Debug => "debug",
+            log::Level::Trace => "trace",
+        };
+        eprintln!("{prefix}: {}", record.args());
     }
+
+    fn flush(&self) {}
+}
+
+/// Prints an error message to stderr and exits with status code 2.
+fn exit_with_error(error: impl std::fmt::Display) -> ! {
+    eprintln!("error: {error}");
+    std::process::exit(2);
 }
 
 /// splik (Simple Programming Language Identifier Kit)
 #[derive(clap::Parser)]
+#[command(args_conflicts_with_subcommands = true, disable_help_subcommand = true)]
 struct Arguments {
-    /// The directory path to run splik on. If not specified, splik will default to the
-    /// current directory.
-    #[clap()]
-    directory_path: Option<String>,
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    /// The directory paths to run splik on. If not specified, splik will default to the
+    /// current directory. The root of each of them is found separately, unless `--here` is given,
+    /// and their counts are combined, with roots inside another root only counted once. The
+    /// configuration file is loaded from the first root. If every path is a file, the language,
+    /// lines, and bytes of each file are listed instead, in the format given by `--output`.
+    #[clap(value_hint = clap::ValueHint::DirPath)]
+    directory_paths: Vec<String>,
+
+    /// Show the counts of each root given as a directory path separately, followed by the totals
+    /// across all of them, instead of combining them.
+    #[arg(long, conflicts_with_all = ["find", "files", "largest", "baseline", "by_dir", "per_project"])]
+    per_root: bool,
 
     /// Include files and folders that begin with a dot (.). By default, this is false, so
     /// directories such as `.git`, `.vscode`, and `.cargo` are not included, as well as
     /// files such as `.gitignore`. Setting this to true will include these files in the counts.
-    #[arg(long, short = 'd')]
+    /// Can also be set with the `SPLIK_INCLUDE_DOTFILES` environment variable, such as to `1`.
+    #[arg(long, short = 'd', env = "SPLIK_INCLUDE_DOTFILES", value_parser = clap::builder::BoolishValueParser::new())]
     include_dotfiles: bool,
 
     /// The format of the output. The default is human-readable, which outputs in a pretty
     /// format; But other formats such as JSON and YAML are available for tasks such as
     /// script parsing.
-    #[arg(value_enum, long, short, default_value_t = OutputFormat::HumanReadable)]
-    output: OutputFormat,
+    ///
+    /// `prometheus` writes gauges in the Prometheus text format, labeled by `language` (and by
+    /// `directory` with `--by-dir`): `splik_language_bytes`, `splik_language_lines`,
+    /// `splik_language_code_lines`, `splik_language_comment_lines`, `splik_language_blank_lines`,
+    /// and `splik_language_files` for each language, and `splik_total_bytes`,
+    /// `splik_total_lines`, and `splik_total_files` for the totals.
+    ///
+    /// Can also be set with the `SPLIK_OUTPUT` environment variable.
+    #[arg(value_enum, long, short, env = "SPLIK_OUTPUT", default_value_t = splik::OutputFormat::HumanReadable)]
+    output: splik::OutputFormat,
 
     /// List all files of the specified language. This will only list files which match
-    /// the given language, and each file will be listed with its absolute path.
+    /// the given language, and each file will be listed with its path, see `--paths`. Can be given
+    /// multiple times to list the files of several languages together, and accepts common aliases
+    /// such as `js` and `golang`. If no files are found, this fails with exit code 1, suggesting
+    /// the closest language if the name is misspelled. The files are listed as they are found, in
+    /// no particular order and without being read, unless `--sorted` is given or something else
+    /// needs their counts.
     #[arg(long, short)]
-    find: Option<String>,
+    find: Vec<String>,
+
+    /// The format of the files listed by `--find`: `plain` lists one path per line, `null`
+    /// terminates each path with a NUL byte instead of a newline, for `xargs -0`, and `json`
+    /// writes an array of objects with the `path`, `lines`, and `bytes` of each file.
+    #[arg(value_enum, long, value_name = "FORMAT", default_value_t = FindFormat::Plain, requires = "find")]
+    find_format: FindFormat,
+
+    /// List the files of `--find` sorted by path. Otherwise, when nothing else needs the counts of
+    /// the scan, the files are listed as they are found, in no particular order, without reading
+    /// them or keeping them in memory. The JSON format of `--find-format`, and `--largest`, always
+    /// list them sorted.
+    #[arg(long, requires = "find")]
+    sorted: bool,
+
+    /// Only count the files tracked by git, listed with `git ls-files`, instead of scanning the
+    /// root directory with the default ignore rules. With `--git-tracked=all`, files that aren't
+    /// tracked but aren't ignored by a `.gitignore` are also counted. The root must be in a git
+    /// repository.
+    #[arg(value_enum, long, value_name = "FILES", num_args = 0..=1, require_equals = true, default_missing_value = "tracked")]
+    git_tracked: Option<GitTracked>,
+
+    /// Count the files of a git revision, such as a tag or commit hash, instead of the files in
+    /// the working tree. Files are listed as `<REVISION>:<PATH>`, and `.gitattributes` aren't
+    /// read. The root must be in a git repository.
+    #[arg(long, value_name = "REVISION", conflicts_with = "git_tracked")]
+    rev: Option<String>,
+
+    /// Count the files listed on stdin, one path per line, instead of scanning the root, such as
+    /// `git diff --name-only main | splik --stdin`. Paths are relative to the current directory,
+    /// and the default ignore rules aren't applied. Paths that don't exist are ignored, with a
+    /// warning saying how many there were.
+    #[arg(long, conflicts_with_all = ["directory_paths", "git_tracked", "rev", "per_root", "per_project", "no_nested"])]
+    stdin: bool,
+
+    /// Count the files listed on stdin like `--stdin`, but with the paths separated by NUL bytes,
+    /// such as the output of `find -print0`.
+    #[arg(long, conflicts_with_all = ["stdin", "directory_paths", "git_tracked", "rev", "per_root", "per_project", "no_nested"])]
+    stdin0: bool,
+
+    /// Cache the counts of each file in a `.splik-cache` directory in the root, and only read the
+    /// files whose modification time or size changed since the previous run with `--cache`. The
+    /// cache directory ignores itself with a `.gitignore`, and is never counted.
+    #[arg(long, overrides_with = "no_cache", conflicts_with = "rev")]
+    cache: bool,
+
+    /// Don't use the cache, overriding an earlier `--cache`, such as one in a shell alias.
+    #[arg(long, overrides_with = "cache")]
+    no_cache: bool,
+
+    /// Read every file again instead of using the cache, and replace the cache with the new
+    /// counts.
+    #[arg(long, requires = "cache")]
+    refresh: bool,
+
+    /// Keep running after printing the report, watching the roots for changes and printing the
+    /// report again when it changes, once nothing has changed for half a second, until interrupted
+    /// with Ctrl-C. The screen is cleared before each human-readable report, and other formats
+    /// print another document for each change. Files that haven't changed aren't read again.
+    #[arg(long, conflicts_with_all = ["stdin", "stdin0", "rev", "find_root", "output_file"])]
+    watch: bool,
+
+    /// Explore the languages in a terminal interface once the scan is done, before printing the
+    /// report as usual. The table of languages can be sorted by each of its columns with the keys
+    /// `1` to `7`, and `enter` shows the files of the selected language from the largest to the
+    /// smallest, which `e` opens in `$VISUAL` or `$EDITOR`, and `y` copies the path of. Text
+    /// typed after `/` filters both the languages and their files. `q` quits.
+    #[arg(
+        long,
+        conflicts_with_all = [
+            "watch", "find", "files", "largest", "baseline", "by_dir", "per_root", "per_project", "no_files",
+            "find_root", "list_languages", "explain", "explain_config"
+        ]
+    )]
+    tui: bool,
+
+    /// Break the counts down by directory, counting each directory at the given depth below the
+    /// root (1 by default) separately. Files that aren't that deep are counted under the deepest
+    /// directory containing them, with files directly in the root counted under `.`.
+    #[arg(long, value_name = "DEPTH", num_args = 0..=1, default_missing_value = "1", conflicts_with = "find")]
+    by_dir: Option<usize>,
+
+    /// Skip the directories below the root that are projects of their own, because they have a
+    /// build manifest such as `Cargo.toml` or a version control marker such as `.git`. The
+    /// skipped projects are listed with `--verbose`.
+    #[arg(long, conflicts_with_all = ["git_tracked", "rev"])]
+    no_nested: bool,
+
+    /// Count each project below the root separately, like `--by-dir`, where a project is a
+    /// directory with a build manifest or version control marker, as with `--no-nested`. Files
+    /// that aren't in a nested project are counted under `.`, or under their directory with
+    /// `--by-dir`.
+    #[arg(long, conflicts_with_all = ["find", "files", "largest", "baseline", "no_nested", "git_tracked", "rev"])]
+    per_project: bool,
+
+    /// How the paths of files are listed by `--find`, `--files`, `--largest`, and the `files` of
+    /// the JSON and YAML outputs: relative to the root directory, or as absolute paths.
+    #[arg(value_enum, long, value_name = "STYLE", default_value_t = splik::PathStyle::Relative)]
+    paths: splik::PathStyle,
+
+    /// List every counted file with its language, lines, and bytes, in the format given by
+    /// `--output`, instead of the totals of each language. Files are listed in the order of their
+    /// languages given by `--sort`.
+    #[arg(long, conflicts_with_all = ["find", "by_dir", "summary"])]
+    files: bool,
+
+    /// List the given number of largest files with their languages, lines, and bytes, from most to
+    /// least bytes, in the format given by `--output`. With `--find`, the largest files of each of
+    /// the given languages are listed instead.
+    #[arg(long, value_name = "N", conflicts_with_all = ["files", "by_dir", "summary", "baseline"])]
+    largest: Option<usize>,
+
+    /// Leave the paths of files out of the JSON, YAML, TOML, XML, HTML, and tokei outputs, which
+    /// otherwise list the files of each language, so that each language only has its
+    /// `file_count`. The paths of files are only kept in memory when they are listed, so this
+    /// keeps scans of huge trees lean with those outputs too.
+    #[arg(long, conflicts_with_all = ["files", "find", "largest", "stats"])]
+    no_files: bool,
+
+    /// Compare the scan against the JSON output of a previous scan, such as one saved with
+    /// `--output json`, and show how many files, lines, and bytes of each language were added and
+    /// removed instead of the counts. A baseline saved with `--files --output json` tells them
+    /// apart file by file, and a report of the languages only by how much each language grew or
+    /// shrank. The baseline should be saved without `--top`, since languages grouped into `other`
+    /// are treated as removed.
+    #[arg(long, value_name = "FILE", conflicts_with_all = ["find", "by_dir", "files"])]
+    baseline: Option<std::path::PathBuf>,
+
+    /// Fail with exit code 1 if a language grew by more than the given number of lines since
+    /// `--baseline`, such as `JavaScript=500`. A language that wasn't in the baseline grew by all
+    /// of its lines. The output is still printed first. Can be given multiple times.
+    #[arg(long, value_name = "LANGUAGE=LINES", value_parser = parse_growth, requires = "baseline")]
+    fail_on_growth: Vec<(String, u64)>,
 
     /// List the root directory for the current project. This will print nothing if no root
-    /// directory can be identified.
+    /// directory can be identified. With `--verbose`, the indicator that identified the root is
+    /// also printed.
     #[arg(long)]
     find_root: bool,
 
+    /// List every language that splik can detect, with its category, color, and the extensions,
+    /// file names, and shebang interpreters it is detected by, instead of scanning. Languages of
+    /// `--map` and the configuration file are included. Given a language, such as `rust`, only
+    /// that language is listed. This is written in the format of `--output`, which can be
+    /// `human-readable`, `json`, `yaml`, `jsonl`, `toml`, `xml`, `csv`, or `markdown`.
+    #[arg(long, value_name = "LANGUAGE", num_args = 0..=1, conflicts_with_all = ["find", "by_dir", "files", "baseline"])]
+    list_languages: Option<Option<String>>,
+
+    /// Explain why a scan of the root counts the given file or leaves it out, instead of scanning:
+    /// each rule of the scan is listed in the order it is applied, with its verdict for the file,
+    /// followed by the language the file is counted as, or the rule that leaves it out first. The
+    /// rules are those of the options and configuration file given. Exits with code 0 if the file
+    /// would be counted, and 1 if it wouldn't.
+    #[arg(
+        long,
+        value_name = "PATH",
+        conflicts_with_all = ["stdin", "stdin0", "git_tracked", "rev", "watch", "find_root", "list_languages"]
+    )]
+    explain: Option<std::path::PathBuf>,
+
     /// Languages to exclude (case-insensitive). Language names specified here will not be
-    /// counted or displayed.
-    #[arg(long, short)]
+    /// counted or displayed. Common aliases such as `js` are accepted, as with `--find`. Names
+    /// that aren't a known language are reported in a warning. Several languages can be given
+    /// separated by commas, such as `--exclude JSON,YAML`, which is also how they are given by the
+    /// `SPLIK_EXCLUDE` environment variable.
+    #[arg(long, short, value_delimiter = ',', env = "SPLIK_EXCLUDE")]
     exclude: Vec<String>,
 
+    /// Only count the given languages (case-insensitive), instead of every language that isn't
+    /// excluded. Common aliases such as `js` are accepted, as with `--exclude`. Percentages are
+    /// relative to the given languages, and their categories don't need to be in `--categories`.
+    /// Can be given multiple times.
+    #[arg(long, value_name = "LANGUAGE")]
+    only: Vec<String>,
+
+    /// Categories of languages to count, separated by commas. Markup includes languages such as
+    /// HTML and CSS, data includes languages such as JSON and YAML, and prose includes languages
+    /// such as Markdown.
+    #[arg(value_enum, long, value_delimiter = ',', default_values_t = splik::DEFAULT_CATEGORIES)]
+    categories: Vec<splik::Category>,
+
+    /// Count languages of every category, instead of only those given by `--categories`.
+    #[arg(long, conflicts_with = "categories")]
+    all_categories: bool,
+
     /// Files and directories to include, which are excluded by default. For example, dotfiles,
     /// such as `.git` and `.vscode` are ignored, but you can exclusively include one of them
     /// with something like `splik --include .git`, while still ignoring all other dotfiles.
     /// To include all dotfiles, use `--include-dotfiles`. Additionally, this can be used to
     /// include non-dotfiles that are ignored by default, such as `node_modules`, `target`, etc.
+    /// Entries containing a path separator, such as `packages/app/dist`, match that path relative
+    /// to the root directory instead of any file or directory with that name.
     #[arg(long, short)]
     include: Vec<String>,
 
+    /// Don't skip the directories that are ignored by default, such as `node_modules`, `target`,
+    /// and `vendor`, or those given by `ignored_directories` in the configuration file. Dotfiles
+    /// are still skipped unless `--include-dotfiles` is given.
+    #[arg(long)]
+    no_default_ignores: bool,
+
+    /// Only scan directories up to the given depth below the root, where 0 only counts the files
+    /// directly in the root. Deeper directories aren't read at all, and the human-readable output
+    /// notes that the scan was limited when any were left out.
+    #[arg(long, value_name = "DEPTH")]
+    max_depth: Option<usize>,
+
+    /// Skip files larger than the given size, such as `10M` or `1G`, without reading them. Sizes
+    /// without a suffix are in bytes, and the `K`, `M`, `G`, and `T` suffixes are powers of 1024.
+    /// How many files were skipped is reported at the end, and in the JSON and YAML outputs.
+    #[arg(long, value_name = "SIZE", value_parser = parse_size)]
+    max_file_size: Option<u64>,
+
+    /// Memory-map files larger than the given size to count their lines, rather than reading them
+    /// through a buffer, which is faster for files of hundreds of megabytes such as generated C or
+    /// data tables. Sizes are given like `--max-file-size`. Files that can't be mapped are read
+    /// through a buffer instead, with the same counts. This has no effect on platforms without
+    /// memory mapping, or if splik was built without the `mmap` feature.
+    #[arg(long, value_name = "SIZE", value_parser = parse_size, default_value_t = splik::DEFAULT_MMAP_THRESHOLD)]
+    mmap_threshold: u64,
+
+    /// Count files that look like binary data, such as `.js` files that are actually compiled
+    /// blobs, or `.c` test fixtures full of NUL bytes. By default, these are skipped, and how many
+    /// were skipped is shown with `--verbose` and in the JSON and YAML outputs.
+    #[arg(long)]
+    count_binary: bool,
+
+    /// Count generated and minified files, which are left out by default. These are files such as
+    /// `*.pb.go`, `*_pb2.py`, `*.generated.ts`, and `*.min.js`, files with `@generated` or `DO NOT
+    /// EDIT` in their first few lines, and files whose first lines are longer than 1000 characters.
+    /// How much was left out is shown with `--verbose` and in the JSON and YAML outputs.
+    #[arg(long)]
+    include_generated: bool,
+
+    /// Tally the files whose language isn't recognized by their extension, and list the
+    /// extensions with the most bytes after the report, with files without an extension listed as
+    /// `<none>`. In the JSON, YAML, TOML, and XML outputs, they are listed in `unknown`. This
+    /// shows which extensions might need a `--map`.
+    #[arg(long)]
+    unknown: bool,
+
+    /// Break the counts of each language down by the extensions of its files, such as how much of
+    /// C++ is in `.cpp`, `.cc`, and `.cxx` files. These are shown as indented rows below each
+    /// language, and as an `extensions` map of each language in the other outputs.
+    #[arg(long)]
+    extensions: bool,
+
+    /// Count dialects as the languages they are dialects of, such as JavaScript React as
+    /// JavaScript and TypeScript React as TypeScript, for a briefer summary of a project.
+    #[arg(long)]
+    merge_dialects: bool,
+
+    /// Show the average, median, and maximum lines per file of each language, along with the path
+    /// of its longest file, as extra columns of the human-readable output and as a `stats` object
+    /// of each language in the other outputs.
+    #[arg(long)]
+    stats: bool,
+
+    /// Count the markers of tech debt in the files of each language, `TODO`, `FIXME`, `HACK`, and
+    /// `XXX` by default, or the `todo_markers` of the configuration file. Markers are matched
+    /// case-sensitively as whole words, including inside strings. With `--files`, the markers of
+    /// each file are listed.
+    #[arg(long)]
+    todos: bool,
+
+    /// Tally the line endings and indentation of the files of each language: the lines ending in
+    /// LF and in CRLF, the files with both, and the files indented mostly with tabs or with
+    /// spaces. With `--files`, only the files with CRLF or mixed line endings or tab indentation
+    /// are listed.
+    #[arg(long)]
+    style_stats: bool,
+
+    /// Tally the encodings of the files of each language, showing how many aren't UTF-8: UTF-16
+    /// files, which are found by their byte order marks, and files that aren't valid UTF-8, such
+    /// as Latin-1. With `--files`, the encoding of each file is listed. UTF-16 files are decoded
+    /// into UTF-8 to count their lines, and their bytes are those of the UTF-8 text, whether or
+    /// not this is given.
+    #[arg(long)]
+    encoding_stats: bool,
+
+    /// Count only the files and bytes of each language, from the metadata of each file, without
+    /// opening it to count its lines, for a much faster scan such as for a shell prompt. The
+    /// columns of lines are left out of the human-readable output, and the counts of lines are
+    /// left out of the other outputs. Files aren't checked for being binary, and generated files
+    /// are only recognized by their names.
+    #[arg(long, conflicts_with_all = ["stats", "todos", "style_stats", "encoding_stats", "baseline"])]
+    no_lines: bool,
+
+    /// Count how much of each language is tests, such as the files in `tests`, `test`,
+    /// `__tests__`, and `spec` directories, `*_test.go`, `*.spec.ts`, `*.test.js`, `test_*.py`, and
+    /// `*Test.java` files, and the `test_path` globs of the configuration file. The counts of test
+    /// files are also counted in their languages.
+    #[arg(long)]
+    tests: bool,
+
+    /// Leave test files out of the counts entirely, see `--tests`.
+    #[arg(long, conflicts_with = "tests")]
+    no_tests: bool,
+
+    /// Count the languages of each of the `groups` of the configuration file as a single language
+    /// named after the group, such as a "Web" group of JavaScript, TypeScript, HTML, and CSS.
+    #[arg(long)]
+    groups: bool,
+
+    /// Follow symbolic links to files and directories, which are skipped by default. Files and
+    /// directories that are linked to more than once are only counted once, and links to a
+    /// directory containing them are only followed the first time.
+    #[arg(long)]
+    follow_symlinks: bool,
+
     /// Do not search for a project root. By default, splik recursively searches up directories
     /// for a "project root" directory, by looking for common indicators such as `.git`,
     /// `node_modules`, `Cargo.toml`, etc. This allows splik to be run from within a project,
@@ -96,288 +1822,932 @@ struct Arguments {
     /// raw on the given directory.
     #[arg(long, short = 'r')]
     here: bool,
+
+    /// The directory to use as the root as is, instead of searching for the project root, like
+    /// `splik <ROOT> --here`. Can also be set with the `SPLIK_ROOT` environment variable, which
+    /// is ignored when directory paths are given.
+    #[arg(long, value_name = "DIRECTORY", env = "SPLIK_ROOT", value_hint = clap::ValueHint::DirPath)]
+    root: Option<std::path::PathBuf>,
+
+    /// Use the nearest directory with any project root indicator as the root, instead of ranking
+    /// them. By default, version control markers such as `.git` are preferred over build manifests
+    /// such as `Cargo.toml`, which are preferred over weak markers such as `README.md`.
+    #[arg(long, conflicts_with = "here")]
+    nearest_root: bool,
+
+    /// Use the root of the project the directory is in as the root, which is the nearest directory
+    /// with a build manifest such as `Cargo.toml` or `package.json`, even if it is a member of a
+    /// workspace or repository above it.
+    #[arg(long, conflicts_with_all = ["here", "nearest_root", "workspace_root"])]
+    project_root: bool,
+
+    /// Use the root of the workspace or repository the directory is in as the root, such as the
+    /// directory of a Cargo workspace's `Cargo.toml` rather than that of one of its crates. This is
+    /// the default.
+    #[arg(long, conflicts_with_all = ["here", "nearest_root"])]
+    workspace_root: bool,
+
+    /// A file or directory that marks the directory containing it as the project root, such as
+    /// `workspace.star`, or a glob matching one, such as `*.workspace`. These are preferred over
+    /// the built-in indicators. Can be given multiple times.
+    #[arg(long, value_name = "NAME", conflicts_with = "here")]
+    root_indicator: Vec<String>,
+
+    /// Don't search for the built-in project root indicators, such as `.git` and `Cargo.toml`,
+    /// only for those given by `--root-indicator`.
+    #[arg(long, conflicts_with = "here")]
+    no_default_root_indicators: bool,
+
+    /// The number of threads to use when scanning files. By default, this is the number of
+    /// available cores. Using `--threads 1` scans every file sequentially on a single thread,
+    /// which can be useful for debugging. Can also be set with the `SPLIK_THREADS` environment
+    /// variable.
+    #[arg(long, short, env = "SPLIK_THREADS")]
+    threads: Option<std::num::NonZeroUsize>,
+
+    /// The key to sort languages by. Bytes, lines, and files are sorted from most to least, and
+    /// names are sorted alphabetically. Languages that are tied are sorted by name. This applies
+    /// to every output format.
+    #[arg(value_enum, long, default_value_t = splik::SortKey::Bytes)]
+    sort: splik::SortKey,
+
+    /// Reverse the sort order given by `--sort`.
+    #[arg(long)]
+    reverse: bool,
+
+    /// Only show the given number of languages, in the order given by `--sort`. The remaining
+    /// languages are grouped into "Other" in the human-readable output, and into an `other`
+    /// object in the JSON and YAML outputs. `--top 0` shows every language.
+    #[arg(long, conflicts_with = "all")]
+    top: Option<usize>,
+
+    /// The percent of the total bytes that a language must make up to be shown on its own line
+    /// in the human-readable output. Smaller languages are grouped into "Other". The other output
+    /// formats always include every language.
+    #[arg(long, default_value_t = 1.0)]
+    min_percent: f64,
+
+    /// Show every language on its own line in the human-readable output, instead of grouping
+    /// small languages into "Other".
+    #[arg(long, short)]
+    all: bool,
+
+    /// Include a final row with the totals across all languages. This currently only affects
+    /// the CSV output format; The human-readable output always ends with the totals.
+    #[arg(long, global = true)]
+    totals: bool,
+
+    /// Print only the totals across all languages, on a single line, without the per-language
+    /// breakdown. This only affects the human-readable output format.
+    #[arg(long, global = true)]
+    summary: bool,
+
+    /// When to color the human-readable output: each language in its conventional color, the
+    /// percentages dimmed, and the totals in bold. By default, the output is only colored when
+    /// stdout is a terminal and the `NO_COLOR` environment variable isn't set.
+    #[arg(value_enum, long, value_name = "WHEN", default_value_t = splik::ColorChoice::Auto, global = true)]
+    color: splik::ColorChoice,
+
+    /// End each row of the human-readable table with a bar proportional to the language's share
+    /// of the bytes. When the table doesn't fit in the terminal, the bars are shortened first, and
+    /// then left out.
+    #[arg(long, global = true)]
+    bars: bool,
+
+    /// Write sizes in the human-readable output with binary prefixes, such as `46.0 MiB`, and
+    /// counts with thousands separators, such as `1,024`. Use `--human-sizes=false` for plain
+    /// numbers. The JSON, YAML, CSV, and Markdown outputs always use plain numbers.
+    #[arg(long, value_name = "BOOL", num_args = 0..=1, require_equals = true, default_value_t = true, default_missing_value = "true", action = clap::ArgAction::Set, global = true)]
+    human_sizes: bool,
+
+    /// The kind of chart drawn by `--output svg`: a horizontal bar split into a segment for each
+    /// language, like the language bar of a GitHub repository, or a pie chart.
+    #[arg(value_enum, long, default_value_t = splik::SvgStyle::Bar)]
+    svg_style: splik::SvgStyle,
+
+    /// The width of the image written by `--output svg`, in pixels. If only one of `--svg-width`
+    /// and `--svg-height` is given, the other keeps the proportions of the chart.
+    #[arg(long, value_name = "PIXELS")]
+    svg_width: Option<u32>,
+
+    /// The height of the image written by `--output svg`, in pixels, see `--svg-width`.
+    #[arg(long, value_name = "PIXELS")]
+    svg_height: Option<u32>,
+
+    /// Write the output to this file instead of stdout, printing only a line saying that it was
+    /// written. The output is written to a temporary file that replaces the file once it is
+    /// complete, so a failed run doesn't leave a partial report. `-` is stdout.
+    #[arg(long, value_name = "PATH", global = true)]
+    output_file: Option<std::path::PathBuf>,
+
+    /// Create the parent directories of `--output-file` if they don't exist.
+    #[arg(long, requires = "output_file", global = true)]
+    mkdirs: bool,
+
+    /// Write the output of `--output gh-summary` to the job summary of GitHub Actions, by
+    /// appending it to the file named by the `GITHUB_STEP_SUMMARY` environment variable. Outside
+    /// of GitHub Actions, where the variable isn't set, the output is written to stdout.
+    #[arg(long, conflicts_with = "output_file", global = true)]
+    gh_write: bool,
+
+    /// What the slices of `--output mermaid` are sized by: the bytes of each language, or their
+    /// percent of the total bytes.
+    #[arg(value_enum, long, default_value_t = splik::MermaidValues::Bytes)]
+    mermaid_values: splik::MermaidValues,
+
+    /// The label on the left of the badge written by `--output badge`.
+    #[arg(long, value_name = "LABEL", default_value = "language")]
+    badge_label: String,
+
+    /// Write the badge of `--output badge` for the share of this language, instead of the
+    /// language with the most bytes, such as `--badge-language ts`.
+    #[arg(long, value_name = "LANGUAGE")]
+    badge_language: Option<String>,
+
+    /// Print details about what the scan left out on stderr: every file and directory that
+    /// couldn't be read instead of only how many there were, the directories that were skipped,
+    /// and how many files were binary or generated. Given twice, such as `-vv`, every file and
+    /// directory that the scan counted or skipped is logged too, with the rule that skipped it.
+    #[arg(long, short, action = clap::ArgAction::Count, conflicts_with = "quiet", global = true)]
+    verbose: u8,
+
+    /// Don't print warnings or notes on stderr, or the progress of the scan, only the report and
+    /// errors. Otherwise, when stderr and the output are both a terminal, a scan that takes longer
+    /// than a second shows how many directories, files, and bytes it has counted so far, which is
+    /// cleared before the report is printed.
+    #[arg(long, short, global = true)]
+    quiet: bool,
+
+    /// Print how long the scan took on stderr, with how much of it the threads of the scan spent
+    /// reading directories and reading and counting files, how fast files and bytes were counted,
+    /// and how long it took to write the output. The reports of the JSON, YAML, TOML, and XML
+    /// outputs include the time of the scan as `timing`.
+    #[arg(long, conflicts_with = "watch")]
+    timing: bool,
+
+    /// Split Vue and Svelte files into the languages of their `<script>` and `<style>` blocks,
+    /// such as TypeScript or SCSS, with the rest of each file counted as Vue or Svelte. Files are
+    /// listed under each of their languages, but only counted as files of Vue or Svelte, so
+    /// languages found only in blocks have no files.
+    #[arg(long)]
+    split_components: bool,
+
+    /// How to count `.h` header files: `c` counts them all as C, `cpp` counts them all as C++, and
+    /// `separate` counts them as their own "C/C++ Header" language. By default, each header is
+    /// counted as C or C++ based on its contents.
+    #[arg(value_enum, long, value_name = "LANGUAGE")]
+    headers_as: Option<HeadersAs>,
+
+    /// Map an extension to a language, such as `m=MATLAB` or `.h=C++`. This takes priority over
+    /// the built-in languages, the detection of ambiguous extensions such as `.m`, and the
+    /// `extensions` of the configuration file. Known languages are matched case-insensitively,
+    /// and other names are counted as new languages. An empty language, such as `bak=`, stops the
+    /// extension from being detected. Can be given multiple times, but not with different
+    /// languages for the same extension.
+    #[arg(long, value_name = "EXTENSION=LANGUAGE", value_parser = parse_mapping)]
+    map: Vec<(String, String)>,
+
+    /// Fail with exit code 1 if the given language is counted, such as JavaScript in a
+    /// TypeScript-only project. The output is still printed first. Can be given multiple times.
+    #[arg(long, value_name = "LANGUAGE")]
+    fail_if_present: Vec<String>,
+
+    /// Fail with exit code 1 if a language makes up more than the given percent of the total
+    /// bytes, such as `C++=40`. The output is still printed first. Can be given multiple times.
+    #[arg(long, value_name = "LANGUAGE=PERCENT", value_parser = parse_max_percent)]
+    max_percent: Vec<(String, f64)>,
+
+    /// Fail with exit code 2 if no files of a recognized language are found, such as when CI
+    /// scans the wrong directory. The output is still printed first, except for the note of the
+    /// human-readable output that nothing was found, which is printed as the error instead.
+    #[arg(long)]
+    strict_empty: bool,
+
+    /// Don't read the GitHub linguist attributes of `.gitattributes` files. By default, files
+    /// marked as `linguist-vendored` or `linguist-generated` are skipped unless they are
+    /// included with `--include`, and files with a `linguist-language` are counted as that
+    /// language.
+    #[arg(long)]
+    no_gitattributes: bool,
+
+    /// Don't load the `splik.toml` (or `.splik.toml`) configuration file from the project root, or
+    /// the global configuration file, such as `~/.config/splik/config.toml`.
+    #[arg(long, global = true)]
+    no_config: bool,
+
+    /// Print the resolved value of each setting that can be given by an environment variable or
+    /// the configuration file, and where it came from, instead of scanning: the command line, an
+    /// environment variable such as `SPLIK_OUTPUT`, the configuration file of the project, the
+    /// global configuration file, or the default, in that order of priority.
+    #[arg(long, conflicts_with_all = ["find_root", "list_languages", "explain"])]
+    explain_config: bool,
+
+    /// Paths to exclude, as globs relative to the root directory. This can only be set in the
+    /// configuration file.
+    #[arg(skip)]
+    exclude_paths: globset::GlobSet,
+
+    /// Paths of test files for `--tests` and `--no-tests`, as globs relative to the root
+    /// directory. This can only be set in the configuration file.
+    #[arg(skip)]
+    test_paths: globset::GlobSet,
+
+    /// The markers counted by `--todos` instead of `TODO_MARKERS`. This can only be set in the
+    /// configuration file.
+    #[arg(skip)]
+    todo_markers: Option<Vec<String>>,
+
+    /// Custom extension to language mappings, which take priority over `LANGUAGES`. This is built
+    /// from `--map`, `--headers-as`, and the `extensions` of the configuration file.
+    #[arg(skip)]
+    extension_map: std::collections::HashMap<String, Option<&'static str>>,
+
+    /// Names of directories to skip instead of those in `IGNORED_DIRECTORIES`. This can only be
+    /// set in the configuration file.
+    #[arg(skip)]
+    ignored_directories: Option<Vec<String>>,
+
+    /// The groups of languages counted together by `--groups`. This can only be set in the
+    /// configuration file.
+    #[arg(skip)]
+    language_groups: Vec<splik::LanguageGroup>,
+
+    /// The configuration files that set each argument, keyed by the ids of the arguments, see
+    /// `--explain-config`.
+    #[arg(skip)]
+    config_settings: std::collections::BTreeMap<&'static str, Vec<std::path::PathBuf>>,
 }
 
-/// Returns the root directory of the project that the given directory is located in, if one could
-/// be detected. This recursively checks the parent directory, looking for common project root
-/// indicators like `.git` or `node_modules`. If the system root is reached and no directory was
-/// identified as a recognized project root, `None` is returned.
-///
-/// # Parameters
-///
-/// - `directory_path` - The path of the directory to start at. This should be a directory *inside*
-/// the project.
-///
-/// # Returns
-/// - The project root directory path, or `None` if none couldbe identified.
-fn get_root_dir(directory_path: &std::path::PathBuf) -> Option<std::path::PathBuf> {
-    for root in ROOT_INDICATORS {
-        if directory_path.join(root).exists() {
-            return Some(directory_path.to_owned());
+impl Arguments {
+    /// Returns whether the paths of files are kept by the scan, because they are listed by
+    /// `--find`, `--files`, `--largest`, `--stats`, or the output format, unless `--no-files` is
+    /// given. The JSON Lines output lists them when it is written to `--output-file`, where its
+    /// files aren't streamed, and `--baseline` compares them when the baseline has the counts of
+    /// its files.
+    fn needs_paths(&self) -> bool {
+        !self.no_files
+            && (self.files
+                || self.tui
+                || !self.find.is_empty()
+                || self.largest.is_some()
+                || self.stats
+                || self.output.lists_paths()
+                || self.baseline.is_some()
+                || (matches!(self.output, splik::OutputFormat::Jsonl) && self.writes_to_file()))
+    }
+
+    /// Returns whether the output is written to the file of `--output-file`, rather than to stdout
+    /// when it isn't given or is `-`.
+    fn writes_to_file(&self) -> bool {
+        self.output_file.as_ref().is_some_and(|path| path.as_os_str() != "-")
+    }
+
+    /// Returns whether the files of `--find` are written to stdout as they are found, without
+    /// reading their contents or keeping them in the language list, see `--sorted`. This is only
+    /// done when nothing else needs the counts of the scan, and when the paths of the files are
+    /// listed as they are counted, which isn't the case when several roots are listed under their
+    /// own names, or when languages are merged after the scan.
+    ///
+    /// # Parameters
+    /// - `roots` - The number of roots that are scanned
+    fn streams_found_files(&self, roots: usize) -> bool {
+        !self.find.is_empty()
+            && !self.sorted
+            && !matches!(self.find_format, FindFormat::Json)
+            && self.largest.is_none()
+            && !self.writes_to_file()
+            && !self.watch
+            && self.fail_if_present.is_empty()
+            && self.max_percent.is_empty()
+            && !self.strict_empty
+            && !self.merge_dialects
+            && !self.groups
+            && self.by_dir.is_none()
+            && !self.per_project
+            && !self.per_root
+            && roots == 1
+    }
+
+    /// Returns whether the progress of the scan is shown on stderr, see `--quiet`. It is never
+    /// shown when stderr or the output is redirected, for `--watch`, which prints its own reports
+    /// over and over, or with `-vv`, whose messages it would be drawn over.
+    fn shows_progress(&self) -> bool {
+        !self.quiet
+            && self.verbose < 2
+            && !self.watch
+            && std::io::IsTerminal::is_terminal(&std::io::stderr())
+            && (self.output_file.is_some() || std::io::IsTerminal::is_terminal(&std::io::stdout()))
+    }
+
+    /// Returns the most verbose level of the messages printed on stderr, see `--verbose` and
+    /// `--quiet`. Warnings are printed by default, notes with `-v`, and the decisions of the scan
+    /// about each file with `-vv`.
+    fn log_level(&self) -> log::LevelFilter {
+        match self.verbose {
+            _ if self.quiet => log::LevelFilter::Off,
+            0 => log::LevelFilter::Warn,
+            1 => log::LevelFilter::Info,
+            2 => log::LevelFilter::Debug,
+            _ => log::LevelFilter::Trace,
         }
     }
 
-    directory_path.parent().map(|parent| get_root_dir(&parent.to_path_buf())).flatten()
-}
+    /// Returns the options of the scan given by these arguments.
+    fn scan_options(&self) -> splik::ScanOptions {
+        splik::ScanOptions {
+            include_dotfiles: self.include_dotfiles,
+            exclude: self.exclude.clone(),
+            only: self.only.clone(),
+            include: self.include.clone(),
+            exclude_paths: self.exclude_paths.clone(),
+            extension_map: self.extension_map.clone(),
+            split_components: self.split_components,
+            gitattributes: !self.no_gitattributes,
+            paths: self.paths,
+            follow_symlinks: self.follow_symlinks,
+            max_depth: self.max_depth,
+            max_file_size: self.max_file_size,
+            mmap_threshold: Some(self.mmap_threshold),
+            count_binary: self.count_binary,
+            include_generated: self.include_generated,
+            collect_unknown: self.unknown,
+            extension_breakdown: self.extensions,
+            tests: if self.tests {
+                splik::TestFiles::Separate
+            } else if self.no_tests {
+                splik::TestFiles::Exclude
+            } else {
+                splik::TestFiles::Include
+            },
+            test_paths: self.test_paths.clone(),
+            todo_markers: match &self.todo_markers {
+                _ if !self.todos => Vec::new(),
+                Some(todo_markers) => todo_markers.clone(),
+                None => splik::TODO_MARKERS.iter().map(|marker| (*marker).to_owned()).collect(),
+            },
+            style_stats: self.style_stats,
+            skip_lines: self.no_lines,
+            skip_paths: !self.needs_paths(),
+            encoding_stats: self.encoding_stats,
+            nested_projects: if self.per_project {
+                splik::NestedProjects::Separate
+            } else if self.no_nested {
+                splik::NestedProjects::Skip
+            } else {
+                splik::NestedProjects::Include
+            },
+            cache: None,
+            on_file: None,
+            progress: None,
+            timing: self.timing.then(std::sync::Arc::default),
+            ignored_directories: if self.no_default_ignores {
+                Some(Vec::new())
+            } else {
+                self.ignored_directories.clone()
+            },
+            // Listing the files of a language with `--find` works regardless of its category
+            categories: if self.all_categories || !self.find.is_empty() || !self.only.is_empty() {
+                Vec::new()
+            } else {
+                self.categories.clone()
+            },
+        }
+    }
 
-/// Directory names that are ignored by default.
-const IGNORED_DIRECTORIES: &'static [&'static str] = &["node_modules", "target", "dist", "build", "public", "out"];
+    /// Finds the project root of a directory given on the command line, as chosen by `--here`,
+    /// `--nearest-root`, and `--project-root`. Exits if the root indicators are invalid.
+    ///
+    /// # Returns
+    /// - The project root, or `None` if `--here` or `--root` was given or no root was found.
+    fn project_root_of(&self, source: &std::path::Path, matches: &clap::ArgMatches) -> Option<splik::ProjectRoot> {
+        if self.here || self.root.is_some() {
+            return None;
+        }
+        let indicators = self.root_indicators(source, matches).unwrap_or_else(|error| exit_with_error(error));
+        if self.nearest_root {
+            indicators.find_nearest_root(source)
+        } else if self.project_root {
+            indicators.find_project_root(source)
+        } else {
+            indicators.find_root(source)
+        }
+    }
 
-/// Information about a programming language within some directory context.
-#[derive(serde::Serialize, PartialEq, Eq)]
-struct LanguageInfo {
-    /// The name of the language. This should be fetched from the `LANGUAGES` map.
-    name: &'static str,
-    /// The files of this language type.
-    files: Vec<String>,
-    /// The number of lines of this language that exist.
-    lines: u32,
-    /// The number of bytes of this language that exist.
-    bytes: u64,
-}
+    /// Returns the project root indicators given by `--root-indicator`, or by the `root_indicators`
+    /// of the nearest configuration file above the starting directory, since the configuration
+    /// file of the project root can't be found before the root is.
+    ///
+    /// # Parameters
+    /// - `source` - The directory the search for the root starts at
+    /// - `matches` - The matches these arguments were parsed from
+    ///
+    /// # Returns
+    /// - An error message if the configuration file couldn't be read, or an indicator is an invalid
+    ///   glob.
+    fn root_indicators(
+        &self,
+        source: &std::path::Path,
+        matches: &clap::ArgMatches,
+    ) -> Result<splik::RootIndicators, String> {
+        let mut custom = self.root_indicator.clone();
+        let from_command_line = matches.value_source("root_indicator") == Some(clap::parser::ValueSource::CommandLine);
+        if !from_command_line && !self.no_config {
+            let nearest = source.ancestors().find(|directory| splik::Config::path(directory).is_some());
+            if let Some(config) = splik::Config::load_layers(nearest).map_err(|error| error.to_string())? {
+                custom = config.root_indicators.unwrap_or_default();
+            }
+        }
+        splik::RootIndicators::new(&custom, !self.no_default_root_indicators)
+            .map_err(|error| format!("invalid --root-indicator: {}", error.kind()))
+    }
+
+    /// Applies the values of a configuration file to these arguments. Values that were given on
+    /// the command line take priority over the configuration file.
+    ///
+    /// # Parameters
+    /// - `config` - The configuration file to apply
+    /// - `matches` - The matches these arguments were parsed from, used to check which values were
+    ///   given on the command line
+    ///
+    /// # Returns
+    /// - An error message if a value of the configuration file is invalid.
+    fn apply_config(&mut self, mut config: splik::Config, matches: &clap::ArgMatches) -> Result<(), String> {
+        // The command line and environment variables take priority over the configuration file,
+        // including the values given to a subcommand, such as the `--output` of `diff`
+        let given = |id: &str| {
+            let subcommand = matches.subcommand().map(|(_, matches)| matches);
+            std::iter::once(matches).chain(subcommand).any(|matches| {
+                matches.try_contains_id(id).is_ok()
+                    && matches!(
+                        matches.value_source(id),
+                        Some(clap::parser::ValueSource::CommandLine | clap::parser::ValueSource::EnvVariable)
+                    )
+            })
+        };
+        let mut origins = std::mem::take(&mut config.origins);
+        let mut applied = |id: &'static str| {
+            let applies = !given(id);
+            if let Some(paths) = origins.remove(id).filter(|_| applies) {
+                self.config_settings.insert(id, paths);
+            }
+            applies
+        };
+
+        if let Some(exclude) = config.exclude.filter(|_| applied("exclude")) {
+            self.exclude = exclude;
+        }
+        if let Some(categories) = config.categories.filter(|_| applied("categories")) {
+            self.categories = categories;
+        }
+        if let Some(include) = config.include.filter(|_| applied("include")) {
+            self.include = include;
+        }
+        if let Some(include_dotfiles) = config.include_dotfiles.filter(|_| applied("include_dotfiles")) {
+            self.include_dotfiles = include_dotfiles;
+        }
+        if let Some(output) = config.output.filter(|_| applied("output")) {
+            self.output = output;
+        }
+        if let Some(merge_dialects) = config.merge_dialects.filter(|_| applied("merge_dialects")) {
+            self.merge_dialects = merge_dialects;
+        }
+        if let Some(color) = config.color.filter(|_| applied("color")) {
+            self.color = color;
+        }
+        self.ignored_directories = config.ignored_directories;
+
+        self.exclude_paths = path_globs(config.exclude_path.unwrap_or_default(), "exclude_path")?;
+        self.test_paths = path_globs(config.test_path.unwrap_or_default(), "test_path")?;
+        if let Some(todo_markers) = config.todo_markers {
+            if todo_markers.iter().any(String::is_empty) {
+                return Err("invalid `todo_markers`: markers can't be empty".to_owned());
+            }
+            self.todo_markers = Some(todo_markers);
+        }
+
+        // Define the languages before the extensions, so that the extensions can map to them
+        splik::define_languages(
+            config
+                .languages
+                .unwrap_or_default()
+                .into_iter()
+                .map(|(name, language)| language.definition(name))
+                .collect(),
+        );
+        for (extension, language) in config.extensions.unwrap_or_default() {
+            self.add_extension_mapping(&extension, language);
+        }
 
-impl PartialOrd for LanguageInfo {
-    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        other.bytes.partial_cmp(&self.bytes)
+        // Resolve the languages of the groups after the languages are defined, so that groups can
+        // include them
+        self.language_groups = config
+            .groups
+            .unwrap_or_default()
+            .into_iter()
+            .map(|(name, languages)| splik::LanguageGroup {
+                name: Box::leak(name.into_boxed_str()),
+                languages: languages.into_iter().map(resolve_language).collect(),
+            })
+            .collect();
+
+        Ok(())
     }
-}
 
-impl Ord for LanguageInfo {
-    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        other.bytes.cmp(&self.bytes)
+    /// Returns how the human-readable output is laid out and formatted, as chosen by `--color`,
+    /// `--bars`, and `--human-sizes`.
+    fn display_style(&self) -> splik::DisplayStyle {
+        splik::DisplayStyle {
+            color: self.uses_color(),
+            bars: self.bars,
+            width: terminal_width(),
+            human_sizes: self.human_sizes,
+        }
     }
-}
 
-impl LanguageInfo {
-    /// Creates a new `LanguageInfo` with the given language name. The language name should come
-    /// from a value of the `LANGUAGES` map.
-    fn new(name: &'static str) -> Self {
-        Self {
-            name,
-            files: Vec::new(),
-            lines: 0,
-            bytes: 0,
+    /// Returns how the chart of `--output svg` is drawn, from `--svg-style`, `--svg-width`, and
+    /// `--svg-height`.
+    fn svg_options(&self) -> splik::SvgOptions {
+        splik::SvgOptions {
+            style: self.svg_style,
+            width: self.svg_width,
+            height: self.svg_height,
         }
     }
-}
 
-#[derive(Default, serde::Serialize)]
-struct LanguageList {
-    languages: Vec<LanguageInfo>,
-}
+    /// Returns whether to color the human-readable output, see `--color`.
+    fn uses_color(&self) -> bool {
+        match self.color {
+            splik::ColorChoice::Always => true,
+            splik::ColorChoice::Never => false,
+            splik::ColorChoice::Auto => {
+                std::env::var_os("NO_COLOR").is_none_or(|no_color| no_color.is_empty())
+                    && std::io::IsTerminal::is_terminal(&std::io::stdout())
+            }
+        }
+    }
+
+    /// Returns an extension that is mapped to different languages by `--map`, along with the two
+    /// languages, ignoring case. Mapping an extension to the same language twice is allowed.
+    fn conflicting_map(&self) -> Option<(&str, &str, &str)> {
+        self.map.iter().enumerate().find_map(|(index, (extension, language))| {
+            let (_, other) = self.map[..index].iter().find(|(other_extension, other)| {
+                other_extension == extension && !other.eq_ignore_ascii_case(language)
+            })?;
+            Some((extension.as_str(), other.as_str(), language.as_str()))
+        })
+    }
+
+    /// Returns a language given to `--only` that is also excluded by `--exclude`, along with the
+    /// name it was excluded by.
+    fn conflicting_only(&self) -> Option<(&str, &str)> {
+        self.only.iter().find_map(|only| {
+            let language = splik::LANGUAGE_ALIASES.get(&only.to_lowercase()).copied().unwrap_or(only);
+            let exclude = self.exclude.iter().find(|exclude| splik::language_matches(language, exclude))?;
+            Some((only.as_str(), exclude.as_str()))
+        })
+    }
 
-impl LanguageList {
-    /// Reads a file and counts it towards the language totals. This will detect the language based
-    /// on the file's extension, and if it is recognized, adds it to the languages file/line/byte
-    /// count.
+    /// Returns a message for each `--fail-if-present` and `--max-percent` check that the counted
+    /// languages fail.
     ///
     /// # Parameters
-    /// - `path` - The path of the file
-    /// - `arguments` - The arguments provided to splik at the command line. This is used to check
-    /// for special inclusions/exclusions, see the `--include` and `--exclude` flags on
-    /// `Arguments`.
-    fn add_file(&mut self, path: &std::path::PathBuf, arguments: &Arguments) {
-        if let Some(Ok(extension)) = path.extension().map(|os_str| {
-            Ok::<String, std::io::Error>(
-                os_str
-                    .to_str()
-                    .ok_or_else(|| std::io::Error::new(std::io::ErrorKind::Other, "OsStr isn't a valid str"))?
-                    .to_string(),
-            )
-        }) {
-            if let Some(language) = LANGUAGES.get(&extension) {
-                // Ignore excluded language
-                if arguments.exclude.contains(&(*language).to_owned()) {
-                    return;
-                };
+    /// - `languages` - The languages that were counted. The same language may appear more than
+    ///   once, such as once per directory of `--by-dir`.
+    fn check_violations<'a>(&self, languages: impl Iterator<Item = &'a splik::LanguageInfo>) -> Vec<String> {
+        // Sum up the counts of each language
+        let mut totals = std::collections::BTreeMap::<&str, splik::Totals>::new();
+        for language_info in languages {
+            totals.entry(language_info.name).or_default().add(language_info);
+        }
+        let total_bytes = totals.values().map(|totals| totals.bytes).sum::<u64>();
+        let find = |name: &str| totals.iter().find(|(language, _)| splik::language_matches(language, name));
 
-                // Get the language info, or generate it if that language hasn't been found before
-                let info = self.languages.iter_mut().find(|other_language| &other_language.name == language);
-                let info = if let Some(info) = info {
-                    info
+        let mut violations = Vec::new();
+        for name in &self.fail_if_present {
+            if let Some((language, totals)) = find(name) {
+                violations.push(format!(
+                    "check failed: {language} is present in {} file{}",
+                    totals.files,
+                    if totals.files == 1 { "" } else { "s" }
+                ));
+            }
+        }
+        for (name, max_percent) in &self.max_percent {
+            if let Some((language, totals)) = find(name) {
+                let percent = if total_bytes == 0 {
+                    0.0
                 } else {
-                    let value = LanguageInfo::new(language);
-                    self.languages.push(value);
-                    self.languages.last_mut().unwrap()
+                    100.0 * totals.bytes as f64 / total_bytes as f64
                 };
+                if percent > *max_percent {
+                    violations.push(format!(
+                        "check failed: {language} is {percent:.2}% of the bytes, above the maximum of {max_percent}%"
+                    ));
+                }
+            }
+        }
+        violations
+    }
 
-                // Update the language info
-                info.lines += std::fs::read(&path).unwrap().lines().count() as u32;
-                info.bytes += std::fs::metadata(&path).unwrap().len();
-                info.files.push(path.canonicalize().unwrap().to_str().unwrap().to_owned());
+    /// Returns a message for each language that grew by more lines than its maximum given to
+    /// `--fail-on-growth`.
+    fn check_growth(&self, comparison: &splik::Comparison) -> Vec<String> {
+        let mut violations = Vec::new();
+        for (name, max_lines) in &self.fail_on_growth {
+            let grown = comparison
+                .languages
+                .iter()
+                .filter(|language| splik::language_matches(&language.name, name));
+            for language in grown {
+                if language.delta.lines > i64::try_from(*max_lines).unwrap_or(i64::MAX) {
+                    violations.push(format!(
+                        "check failed: {} grew by {} line{}, above the maximum of {max_lines}",
+                        language.name,
+                        language.delta.lines,
+                        if language.delta.lines == 1 { "" } else { "s" }
+                    ));
+                }
             }
         }
+        violations
     }
 
-    fn sort(&mut self) {
-        self.languages.sort();
+    /// Returns a warning for each language given to `--exclude` or `--only`, or in one of the
+    /// groups of `--groups`, that isn't a known language or a language of the extension map, such
+    /// as a misspelled one, which would otherwise be silently ignored.
+    fn unknown_language_warnings(&self) -> Vec<String> {
+        let excludes = self.exclude.iter().map(|language| ("--exclude".to_owned(), language.as_str()));
+        let onlys = self.only.iter().map(|language| ("--only".to_owned(), language.as_str()));
+        let groups = self.language_groups.iter().filter(|_| self.groups).flat_map(|group| {
+            let argument = format!("`groups.{}`", group.name);
+            group.languages.iter().map(move |language| (argument.clone(), *language))
+        });
+        excludes
+            .chain(onlys)
+            .chain(groups)
+            .filter(|(_, language)| !splik::is_known_language(language) && !self.is_mapped(language))
+            .map(|(argument, language)| match splik::closest_language(language) {
+                Some(closest) => format!("{argument} \"{language}\" isn't a known language, did you mean {closest}?"),
+                None => format!("{argument} \"{language}\" isn't a known language"),
+            })
+            .collect()
     }
 
-    fn find(&self, language_name: &str) {
-        let language_name = language_name.to_lowercase();
-        for file in self
-            .languages
-            .iter()
-            .find(|language| language.name.to_lowercase() == language_name)
-            .map(|language| language.files.iter())
-            .unwrap_or_else(|| [].iter())
-        {
-            println!("{file}");
-        }
-    }
-
-    fn display(&self) {
-        // Calculate the total lines/files/bytes
-        let mut total_files = 0;
-        let mut total_bytes = 0;
-        let mut total_lines = 0;
-        for language_info in &self.languages {
-            total_files += language_info.files.len();
-            total_lines += language_info.lines;
-            total_bytes += language_info.bytes;
-        }
-
-        let mut other_bytes = 0;
-        let mut other_files = 0;
-        let mut other_lines = 0;
-
-        for language_info in &self.languages {
-            let byte_percent = 100.0 * (language_info.bytes as f64) / (total_bytes as f64);
-
-            if byte_percent >= 1. {
-                println!(
-                    "{}: {} bytes ({}%), {} lines ({}%), {} files ({}%)",
-                    language_info.name,
-                    language_info.bytes,
-                    100 * language_info.bytes / total_bytes,
-                    language_info.lines,
-                    100 * language_info.lines / total_lines,
-                    language_info.files.len(),
-                    100 * language_info.files.len() / total_files
-                );
-            } else {
-                other_bytes += language_info.bytes;
-                other_files += language_info.files.len();
-                other_lines += language_info.lines;
-            }
-        }
-
-        // Print "other" languages
-        if other_bytes != 0 {
-            println!(
-                "Other: {} bytes ({}%), {} lines ({}%), {} files ({}%)",
-                other_bytes,
-                format_number(100.0 * (other_bytes as f64) / (total_bytes as f64)),
-                other_lines,
-                format_number(100.0 * (other_lines as f64) / (total_lines as f64)),
-                other_files,
-                format_number(100.0 * (other_files as f64) / (total_files as f64)),
-            );
+    /// Returns whether a language is one that an extension is mapped to by the extension map.
+    fn is_mapped(&self, language: &str) -> bool {
+        self.extension_map
+            .values()
+            .flatten()
+            .any(|mapped| splik::language_matches(mapped, language))
+    }
+
+    /// Returns the message for a language given to `--find` that has no files, suggesting the
+    /// closest known language if the name looks misspelled.
+    fn not_found_message(&self, language: &str) -> String {
+        match splik::closest_language(language).filter(|_| !self.is_mapped(language)) {
+            Some(closest) => format!("no files found for language \"{language}\", did you mean {closest}?"),
+            None => format!("no files found for language \"{language}\""),
+        }
+    }
+
+    /// Applies the `--headers-as` and `--map` mappings to the extension map. These are applied after
+    /// the configuration file, so that they take priority over its `extensions`, and `--map` takes
+    /// priority over `--headers-as`.
+    fn apply_mappings(&mut self) {
+        if let Some(headers_as) = &self.headers_as {
+            let language = match headers_as {
+                HeadersAs::C => "C",
+                HeadersAs::Cpp => "C++",
+                HeadersAs::Separate => "C/C++ Header",
+            };
+            self.add_extension_mapping("h", language.to_owned());
         }
+        for (extension, language) in std::mem::take(&mut self.map) {
+            self.add_extension_mapping(&extension, language);
+        }
+    }
+
+    /// Maps an extension to a language in the extension map. Names of known languages are matched
+    /// with `language_matches`, so that `lua` maps to Lua, and languages that splik doesn't know
+    /// are given a name of their own.
+    ///
+    /// # Parameters
+    /// - `extension` - The extension, with or without a leading dot
+    /// - `language` - The name of the language, or an empty name to stop detecting the extension
+    fn add_extension_mapping(&mut self, extension: &str, language: String) {
+        let language = (!language.is_empty()).then(|| resolve_language(language));
+        self.extension_map.insert(extension.trim_start_matches('.').to_owned(), language);
+    }
+}
+
+/// Returns the name of the known language that a name matches with `language_matches`, such as
+/// Lua for `lua`, or the name itself if it doesn't match a known language.
+fn resolve_language(language: String) -> &'static str {
+    splik::known_language_names()
+        .find(|known_language| splik::language_matches(known_language, &language))
+        .unwrap_or_else(|| Box::leak(language.into_boxed_str()))
+}
+
+/// Builds the globs of paths given in the configuration file, such as `exclude_path`. Separators
+/// are only matched literally, so that `*` doesn't match across directories.
+///
+/// # Parameters
+/// - `patterns` - The globs to build
+/// - `key` - The key of the configuration file the globs were given in, for error messages
+///
+/// # Returns
+/// - The globs, or an error message naming the invalid glob.
+fn path_globs(patterns: Vec<String>, key: &str) -> Result<globset::GlobSet, String> {
+    let mut globs = globset::GlobSetBuilder::new();
+    for pattern in patterns {
+        let glob = globset::GlobBuilder::new(&pattern)
+            .literal_separator(true)
+            .build()
+            .map_err(|error| format!("invalid glob \"{pattern}\" in `{key}`: {}", error.kind()))?;
+        globs.add(glob);
+    }
+    globs.build().map_err(|error| format!("invalid `{key}`: {error}"))
+}
+
+/// Parses an `EXTENSION=LANGUAGE` mapping given to `--map`.
+fn parse_mapping(mapping: &str) -> Result<(String, String), String> {
+    let Some((extension, language)) = mapping.split_once('=') else {
+        return Err(format!("expected EXTENSION=LANGUAGE, found \"{mapping}\""));
+    };
+    let extension = extension.trim_start_matches('.');
+    if extension.is_empty() {
+        return Err(format!("expected EXTENSION=LANGUAGE, found \"{mapping}\""));
     }
+    Ok((extension.to_owned(), language.to_owned()))
 }
 
-fn format_number(number: f64) -> String {
-    if number >= 1.0 {
-        return format!("{}", number as i32);
+/// Parses a `LANGUAGE=PERCENT` maximum given to `--max-percent`.
+fn parse_max_percent(maximum: &str) -> Result<(String, f64), String> {
+    let parsed = maximum.rsplit_once('=').and_then(|(language, percent)| {
+        let percent = percent.trim_end_matches('%').parse::<f64>().ok()?;
+        (!language.is_empty() && (0.0..=100.0).contains(&percent)).then(|| (language.to_owned(), percent))
+    });
+    parsed.ok_or_else(|| format!("expected LANGUAGE=PERCENT with a percent from 0 to 100, found \"{maximum}\""))
+}
+
+/// Parses a `LANGUAGE=LINES` maximum given to `--fail-on-growth`.
+fn parse_growth(maximum: &str) -> Result<(String, u64), String> {
+    let parsed = maximum.rsplit_once('=').and_then(|(language, lines)| {
+        let lines = lines.trim().parse::<u64>().ok()?;
+        (!language.is_empty()).then(|| (language.to_owned(), lines))
+    });
+    parsed.ok_or_else(|| format!("expected LANGUAGE=LINES with a number of lines, found \"{maximum}\""))
+}
+
+/// Parses a size given to `--max-file-size` or `--mmap-threshold`, such as `512`, `10M`, or `1.5GiB`.
+fn parse_size(size: &str) -> Result<u64, String> {
+    let invalid = || format!("expected a size such as 512, 10K, 10M, or 1G, found \"{size}\"");
+    let trimmed = size.trim().to_uppercase();
+    let trimmed = trimmed.strip_suffix("IB").or_else(|| trimmed.strip_suffix('B')).unwrap_or(&trimmed);
+    let (number, multiplier) = match trimmed.char_indices().last() {
+        Some((index, 'K')) => (&trimmed[..index], 1_u64 << 10),
+        Some((index, 'M')) => (&trimmed[..index], 1 << 20),
+        Some((index, 'G')) => (&trimmed[..index], 1 << 30),
+        Some((index, 'T')) => (&trimmed[..index], 1 << 40),
+        _ => (trimmed, 1),
+    };
+    let number = number.trim().parse::<f64>().map_err(|_| invalid())?;
+    if !number.is_finite() || number < 0.0 {
+        return Err(invalid());
     }
+    Ok((number * multiplier as f64) as u64)
+}
+
+/// The subcommands of splik, which run instead of a scan.
+#[derive(clap::Subcommand)]
+enum Command {
+    /// Print a completion script for the given shell to stdout.
+    ///
+    /// Install it such as with
+    /// `splik completions bash > ~/.local/share/bash-completion/completions/splik`. The values of
+    /// `--find`, `--exclude`, and `--only` are completed with the names of the known languages.
+    Completions {
+        /// The shell to print the completion script for.
+        #[arg(value_enum)]
+        shell: completions::Shell,
+    },
 
-    format!("{:.2}", number)
+    /// Show how the files, lines, and bytes of each language changed between two reports.
+    ///
+    /// The reports are the JSON output of splik, such as saved with
+    /// `splik --output json > old.json`, including reports of older versions of splik. Without a
+    /// second report, the current directory is scanned and compared against the first, as with
+    /// `--baseline`.
+    Diff {
+        /// The earlier report.
+        #[arg(value_name = "OLD", value_hint = clap::ValueHint::FilePath)]
+        old: std::path::PathBuf,
+
+        /// The later report. If it isn't given, the current directory is scanned instead.
+        #[arg(value_name = "NEW", value_hint = clap::ValueHint::FilePath)]
+        new: Option<std::path::PathBuf>,
+
+        /// The format of the differences, such as `json` for scripts or `markdown` for a comment
+        /// on a pull request. Can also be set with the `SPLIK_OUTPUT` environment variable.
+        #[arg(value_enum, long, short, env = "SPLIK_OUTPUT", default_value_t = DiffFormat::HumanReadable)]
+        output: DiffFormat,
+
+        /// Fail with exit code 1 if a language grew by more than the given number of lines, such
+        /// as `JavaScript=500`. A language that wasn't in the earlier report grew by all of its
+        /// lines. The differences are still printed first. Can be given multiple times.
+        #[arg(long, value_name = "LANGUAGE=LINES", value_parser = parse_growth)]
+        fail_on_growth: Vec<(String, u64)>,
+    },
 }
 
-#[derive(Clone, clap::ValueEnum, Debug)]
-enum OutputFormat {
+/// The formats of `diff`, which are the output formats that can write the differences between
+/// two reports, see `OutputFormat::compares`.
+#[derive(Clone, Copy, clap::ValueEnum)]
+enum DiffFormat {
     HumanReadable,
     Json,
     Yaml,
+    Jsonl,
+    Toml,
+    Xml,
+    Csv,
+    Markdown,
+    GhSummary,
+    GhAnnotations,
+}
+
+impl From<DiffFormat> for splik::OutputFormat {
+    fn from(format: DiffFormat) -> Self {
+        match format {
+            DiffFormat::HumanReadable => Self::HumanReadable,
+            DiffFormat::Json => Self::Json,
+            DiffFormat::Yaml => Self::Yaml,
+            DiffFormat::Jsonl => Self::Jsonl,
+            DiffFormat::Toml => Self::Toml,
+            DiffFormat::Xml => Self::Xml,
+            DiffFormat::Csv => Self::Csv,
+            DiffFormat::Markdown => Self::Markdown,
+            DiffFormat::GhSummary => Self::GhSummary,
+            DiffFormat::GhAnnotations => Self::GhAnnotations,
+        }
+    }
 }
 
-fn analyze_directory(directory_name: &str, arguments: &Arguments, languages: &mut LanguageList) {
-    let Ok(entries) = std::fs::read_dir(directory_name) else { return };
-    for entry in entries.filter_map(|entry| entry.ok()) {
-        // Get the path and pathname
-        let path = entry.path();
-        let filename = path.file_name().unwrap().to_str().unwrap();
-
-        // Dotifiles
-        if !arguments.include_dotfiles && filename.starts_with(".") {
-            continue;
-        }
-
-        // Directories
-        if path.is_dir() {
-            if IGNORED_DIRECTORIES.contains(&filename) && !arguments.include.contains(&filename.to_owned()) {
-                continue;
-            }
-            analyze_directory(path.to_str().unwrap(), arguments, languages);
-        }
-
-        // Files
-        if path.is_file() {
-            languages.add_file(&path, arguments);
-        }
-    }
-}
-
-const LANGUAGES: phf::Map<&'static str, &'static str> = phf::phf_map! {
-    "asm" => "Assembly",
-    "bash" => "Bash",
-    "c" => "C",
-    "h" => "C",
-    "cpp" => "C++",
-    "c++" => "C++",
-    "cxx" => "C++",
-    "cc" => "C++",
-    "hpp" => "C++",
-    "hh" => "C++",
-    "h++" => "C++",
-    "hxx" => "C++",
-    "cs" => "C#",
-    "f" => "Fortran",
-    "for" => "Fortran",
-    "f90" => "Fortran",
-    "f95" => "Fortran",
-    "gleam" => "Gleam",
-    "go" => "Go",
-    "lhs" => "Haskell",
-    "hs" => "Haskell",
-    "java" => "Java",
-    "js" => "JavaScript",
-    "mjs" => "JavaScript",
-    "cjs" => "JavaScript",
-    "jsx" => "JavaScript React",
-    "kt" => "Kotlin",
-    "lua" => "Lua",
-    "m" => "MATLAB",
-    "php" => "PHP",
-    "py" => "Python",
-    "r" => "R",
-    "rb" => "Ruby",
-    "rs" => "Rust",
-    "sql" => "SQL",
-    "svelte" => "Svelte",
-    "swift" => "Swift",
-    "ts" => "TypeScript",
-    "tsx" => "TypeScript React",
-    "v" => "V",
-    "vue" => "Vue",
-    "zig" => "Zig",
-};
-
-const ROOT_INDICATORS: &'static [&'static str] = &[
-    ".git",
-    ".gitignore",
-    "node_modules",
-    "Cargo.toml",
-    "build.zig",
-    "pyproject.toml",
-    ".luarc.json",
-    "tsconfig.json",
-    ".prettierrc",
-    ".prettierrc.json",
-    ".prettierrc.toml",
-    "README.md",
-    "README",
-    "LICENSE",
-    "index.html",
-];
+/// The formats of the files listed by `--find`, see `Arguments::find_format`.
+#[derive(Clone, clap::ValueEnum)]
+enum FindFormat {
+    Plain,
+    Json,
+    Null,
+}
+
+/// The languages `.h` header files can be counted as, see `Arguments::headers_as`.
+#[derive(Clone, clap::ValueEnum)]
+enum HeadersAs {
+    C,
+    Cpp,
+    Separate,
+}
+
+/// The files counted by `--git-tracked`.
+#[derive(Clone, PartialEq, clap::ValueEnum)]
+enum GitTracked {
+    /// Only files tracked by git.
+    Tracked,
+    /// Files tracked by git, and files that aren't tracked but aren't ignored either.
+    All,
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn command_is_valid() {
+        <super::Arguments as clap::CommandFactory>::command().debug_assert();
+    }
+
+    #[test]
+    fn diff_formats_compare() {
+        for format in <super::DiffFormat as clap::ValueEnum>::value_variants() {
+            let output = splik::OutputFormat::from(*format);
+            assert!(output.compares(), "{output:?}");
+            let name = |value: Option<clap::builder::PossibleValue>| value.unwrap().get_name().to_owned();
+            assert_eq!(
+                name(clap::ValueEnum::to_possible_value(format)),
+                name(clap::ValueEnum::to_possible_value(&output))
+            );
+        }
+    }
+
+    #[test]
+    fn diff_accepts_the_output_flags() {
+        let command = <super::Arguments as clap::CommandFactory>::command();
+        let matches = command
+            .try_get_matches_from(["splik", "diff", "old.json", "new.json", "--color", "never", "--summary"])
+            .unwrap();
+        let arguments = <super::Arguments as clap::FromArgMatches>::from_arg_matches(&matches).unwrap();
+        assert!(arguments.summary && matches!(arguments.color, splik::ColorChoice::Never));
+
+        let command = <super::Arguments as clap::CommandFactory>::command();
+        assert!(command.try_get_matches_from(["splik", "diff", "old.json", "-o", "svg"]).is_err());
+    }
+}
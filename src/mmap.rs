@@ -0,0 +1,144 @@
+//! Memory-mapping large files to count their lines, see `ScanOptions::mmap_threshold`. Mapping is
+//! only available on 64-bit Unix platforms with the `mmap` feature, and files are read through a
+//! buffer everywhere else.
+
+/// The default of `ScanOptions::mmap_threshold` used by the command line, above which files are
+/// mapped rather than read through a buffer.
+pub const DEFAULT_MMAP_THRESHOLD: u64 = 4 * 1024 * 1024;
+
+/// Maps a file into memory if it is larger than `ScanOptions::mmap_threshold`.
+///
+/// # Parameters
+/// - `file` - The file to map
+/// - `bytes` - The size of the file from its metadata
+/// - `options` - The options of the scan
+///
+/// # Returns
+/// - The whole contents of the file, or `None` if it isn't larger than the threshold, or if it
+///   couldn't be mapped, in which case it should be read through a buffer instead.
+#[cfg(all(feature = "mmap", unix, target_pointer_width = "64"))]
+pub(crate) fn map_large_file(
+    file: &std::fs::File,
+    bytes: u64,
+    options: &crate::ScanOptions,
+) -> Option<impl std::ops::Deref<Target = [u8]>> {
+    let threshold = options.mmap_threshold?;
+    if bytes <= threshold {
+        return None;
+    }
+    Mmap::new(file, usize::try_from(bytes).ok()?).ok()
+}
+
+/// Maps a file into memory if it is larger than `ScanOptions::mmap_threshold`, which is never the
+/// case on platforms where mapping isn't available.
+#[cfg(not(all(feature = "mmap", unix, target_pointer_width = "64")))]
+pub(crate) fn map_large_file(
+    _file: &std::fs::File,
+    _bytes: u64,
+    _options: &crate::ScanOptions,
+) -> Option<impl std::ops::Deref<Target = [u8]>> {
+    None::<Box<[u8]>>
+}
+
+/// The functions of the C library that map files, which std already links against.
+#[cfg(all(feature = "mmap", unix, target_pointer_width = "64"))]
+mod ffi {
+    /// Pages may be read, which is the same on every Unix platform.
+    pub(super) const PROT_READ: std::ffi::c_int = 1;
+    /// Changes to the mapping aren't written back to the file, which is the same on every Unix
+    /// platform.
+    pub(super) const MAP_PRIVATE: std::ffi::c_int = 2;
+    /// The pages will be read in order, which is the same on every Unix platform.
+    pub(super) const MADV_SEQUENTIAL: std::ffi::c_int = 2;
+
+    extern "C" {
+        pub(super) fn mmap(
+            address: *mut std::ffi::c_void,
+            length: usize,
+            protection: std::ffi::c_int,
+            flags: std::ffi::c_int,
+            descriptor: std::ffi::c_int,
+            offset: i64,
+        ) -> *mut std::ffi::c_void;
+        pub(super) fn munmap(address: *mut std::ffi::c_void, length: usize) -> std::ffi::c_int;
+        pub(super) fn madvise(
+            address: *mut std::ffi::c_void,
+            length: usize,
+            advice: std::ffi::c_int,
+        ) -> std::ffi::c_int;
+    }
+}
+
+/// A read-only mapping of a whole file, which is unmapped when dropped.
+///
+/// The file must not be truncated while it is mapped, which would make reading past its new end
+/// fail with `SIGBUS`. A scan only maps a file for as long as it takes to count its lines.
+#[cfg(all(feature = "mmap", unix, target_pointer_width = "64"))]
+struct Mmap {
+    /// The address of the first byte of the mapping.
+    address: *mut std::ffi::c_void,
+    /// The length of the mapping, which is the size of the file.
+    length: usize,
+}
+
+#[cfg(all(feature = "mmap", unix, target_pointer_width = "64"))]
+impl Mmap {
+    /// Maps the whole of a file into memory.
+    ///
+    /// # Parameters
+    /// - `file` - The file to map
+    /// - `length` - The size of the file, which must not be 0
+    ///
+    /// # Errors
+    /// Returns an error if the file couldn't be mapped, such as on a filesystem that doesn't
+    /// support it.
+    fn new(file: &std::fs::File, length: usize) -> std::io::Result<Self> {
+        let descriptor = std::os::fd::AsRawFd::as_raw_fd(file);
+        // SAFETY: A new private read-only mapping is created at an address of the system's
+        // choosing, so no existing memory is affected
+        let address = unsafe {
+            ffi::mmap(
+                std::ptr::null_mut(),
+                length,
+                ffi::PROT_READ,
+                ffi::MAP_PRIVATE,
+                descriptor,
+                0,
+            )
+        };
+        // `MAP_FAILED` is -1 on every Unix platform
+        if address as isize == -1 {
+            return Err(std::io::Error::last_os_error());
+        }
+
+        // The advice only makes reading ahead more aggressive, so whether it is taken doesn't matter
+        // SAFETY: The range is exactly the mapping that was just created
+        unsafe { ffi::madvise(address, length, ffi::MADV_SEQUENTIAL) };
+        let mapping = Self { address, length };
+
+        // A file that is being written to may be truncated under the mapping, so one whose size
+        // changed since its metadata was read is read through a buffer instead
+        if file.metadata()?.len() != length as u64 {
+            return Err(std::io::Error::other("the file changed size while it was mapped"));
+        }
+        Ok(mapping)
+    }
+}
+
+#[cfg(all(feature = "mmap", unix, target_pointer_width = "64"))]
+impl std::ops::Deref for Mmap {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        // SAFETY: The mapping is readable for `length` bytes until it is dropped
+        unsafe { std::slice::from_raw_parts(self.address.cast::<u8>(), self.length) }
+    }
+}
+
+#[cfg(all(feature = "mmap", unix, target_pointer_width = "64"))]
+impl Drop for Mmap {
+    fn drop(&mut self) {
+        // SAFETY: The mapping was created by `Mmap::new`, and no slice of it outlives `self`
+        unsafe { ffi::munmap(self.address, self.length) };
+    }
+}
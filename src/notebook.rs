@@ -0,0 +1,115 @@
+//! Extracting the source code of Jupyter notebooks.
+
+/// The name `LANGUAGES` gives to Jupyter notebooks. Notebooks are never counted under this name,
+/// but under the language of their kernel instead.
+pub(crate) const NOTEBOOK_LANGUAGE: &str = "Jupyter Notebook";
+
+/// The language of a notebook's code cells when its kernel's language isn't given or recognized.
+const DEFAULT_KERNEL_LANGUAGE: &str = "Python";
+
+/// The parts of a Jupyter notebook (nbformat 4) that are needed to count its code.
+#[derive(serde::Deserialize)]
+struct Notebook {
+    /// The cells of the notebook, in order.
+    cells: Vec<Cell>,
+    /// The metadata of the notebook, which describes its kernel.
+    #[serde(default)]
+    metadata: Metadata,
+}
+
+/// A single cell of a Jupyter notebook.
+#[derive(serde::Deserialize)]
+struct Cell {
+    /// The type of the cell, such as `code` or `markdown`.
+    cell_type: String,
+    /// The source of the cell.
+    #[serde(default)]
+    source: Source,
+}
+
+/// The source of a cell, which notebooks store either as a single string or as a list of lines.
+#[derive(serde::Deserialize)]
+#[serde(untagged)]
+enum Source {
+    /// The source as a single string.
+    Text(String),
+    /// The source as a list of lines, each including its trailing newline.
+    Lines(Vec<String>),
+}
+
+impl Default for Source {
+    fn default() -> Self {
+        Self::Text(String::new())
+    }
+}
+
+/// The metadata of a Jupyter notebook.
+#[derive(serde::Deserialize, Default)]
+struct Metadata {
+    /// The kernel the notebook was written for.
+    kernelspec: Option<KernelSpec>,
+    /// Information about the language of the kernel, which older notebooks have instead of a
+    /// language in their `kernelspec`.
+    language_info: Option<KernelLanguageInfo>,
+}
+
+/// The kernel a Jupyter notebook was written for.
+#[derive(serde::Deserialize)]
+struct KernelSpec {
+    /// The name of the kernel's language, such as `python` or `julia`.
+    language: Option<String>,
+}
+
+/// Information about the language of a Jupyter notebook's kernel.
+#[derive(serde::Deserialize)]
+struct KernelLanguageInfo {
+    /// The name of the kernel's language, such as `python` or `julia`.
+    name: Option<String>,
+}
+
+/// Extracts the source code of the code cells of a Jupyter notebook. Markdown and raw cells are
+/// left out, and each code cell is ended with a newline so that cells aren't joined together.
+///
+/// # Parameters
+/// - `contents` - The contents of the notebook file
+///
+/// # Returns
+/// - The language of the notebook's kernel, matched case-insensitively against the languages in
+///   `LANGUAGES` and defaulting to Python, along with the source of its code cells.
+///
+/// # Errors
+/// Returns an error if the notebook isn't valid JSON or isn't structured like a notebook.
+pub(crate) fn notebook_code(contents: &[u8]) -> std::io::Result<(&'static str, Vec<u8>)> {
+    let notebook = serde_json::from_slice::<Notebook>(contents)
+        .map_err(|error| std::io::Error::new(std::io::ErrorKind::InvalidData, format!("invalid notebook: {error}")))?;
+
+    // Get the language of the kernel
+    let kernel_language = notebook
+        .metadata
+        .kernelspec
+        .and_then(|kernelspec| kernelspec.language)
+        .or_else(|| notebook.metadata.language_info.and_then(|language_info| language_info.name));
+    let language = kernel_language
+        .and_then(|kernel_language| {
+            crate::LANGUAGES
+                .values()
+                .find(|language| **language != NOTEBOOK_LANGUAGE && language.eq_ignore_ascii_case(&kernel_language))
+                .copied()
+        })
+        .unwrap_or(DEFAULT_KERNEL_LANGUAGE);
+
+    // Join the source of the code cells
+    let mut code = Vec::new();
+    for cell in notebook.cells.into_iter().filter(|cell| cell.cell_type == "code") {
+        let start = code.len();
+        match cell.source {
+            Source::Text(text) => code.extend_from_slice(text.as_bytes()),
+            Source::Lines(lines) => lines.iter().for_each(|line| code.extend_from_slice(line.as_bytes())),
+        }
+        if code.len() > start && !code.ends_with(b"\n") {
+            code.push(b'\n');
+        }
+    }
+
+    Ok((language, code))
+}
@@ -0,0 +1,2236 @@
+//! Rendering a `LanguageList` in each of the output formats.
+
+use crate::format::{format_count, format_number, format_size};
+use crate::{percent, Comparison, Delta, DirectoryList, LanguageList, Totals};
+
+impl LanguageList {
+    /// Writes the languages in a human-readable format, one per line, followed by the totals.
+    /// Languages making up less than `min_percent` of the total bytes are grouped into a single
+    /// "Other" line. Prose languages are listed after the other languages, separated from them by a
+    /// blank line.
+    ///
+    /// # Parameters
+    /// - `writer` - Where to write the output
+    /// - `top` - The maximum number of languages to show on their own line. The remaining
+    ///   languages are grouped into "Other". If `None`, there is no limit.
+    /// - `min_percent` - The percent of the total bytes a language must make up to be shown on its
+    ///   own line. If this is 0, only languages beyond `top` are grouped into "Other".
+    /// - `style` - How the table of languages is laid out and colored
+    pub fn display(
+        &self,
+        writer: &mut impl std::io::Write,
+        top: Option<usize>,
+        min_percent: f64,
+        style: DisplayStyle,
+    ) -> std::io::Result<()> {
+        self.display_rows(writer, top, min_percent, style)?;
+        self.display_totals(writer, style)
+    }
+
+    /// Writes a table of the extensions of the files whose language wasn't detected, with the
+    /// most bytes first, after a blank line. Only the first `UNKNOWN_SHOWN` extensions are
+    /// listed, followed by how many more there are. Nothing is written if there are none, see
+    /// `LanguageList::unknown`.
+    pub fn display_unknown(&self, writer: &mut impl std::io::Write, style: DisplayStyle) -> std::io::Result<()> {
+        write_unknown(writer, &crate::list::unknown_extensions(&self.unknown), style)
+    }
+
+    /// Writes the table of languages for `display`, without the totals.
+    fn display_rows(
+        &self,
+        writer: &mut impl std::io::Write,
+        top: Option<usize>,
+        min_percent: f64,
+        style: DisplayStyle,
+    ) -> std::io::Result<()> {
+        let rows = self.grouped_rows(top, min_percent);
+
+        // Round the percentages of each column so that they add up to 100
+        let byte_percents = round_percentages(&rows.iter().map(|(_, _, row)| row.bytes as f64).collect::<Vec<_>>());
+        let line_percents = round_percentages(&rows.iter().map(|(_, _, row)| row.lines as f64).collect::<Vec<_>>());
+        let file_percents = round_percentages(&rows.iter().map(|(_, _, row)| row.files as f64).collect::<Vec<_>>());
+
+        // Separate the prose languages from the others with a blank line
+        let breaks = (1..rows.len())
+            .filter(|index| rows[*index].1 == Some(crate::Category::Prose) && rows[index - 1].1 != rows[*index].1)
+            .collect::<Vec<_>>();
+
+        // Follow each language with an indented row for each of its extensions, if they were
+        // tracked. The percentages of these rows are of the totals of their language, while their
+        // bars are of the total bytes, like those of the languages.
+        let total_bytes = self.totals().bytes;
+        let mut table = Vec::new();
+        let mut bar_percents = Vec::new();
+        let mut table_tests = Vec::new();
+        let mut table_todos = Vec::new();
+        let mut table_non_utf8 = Vec::new();
+        let mut table_stats = Vec::new();
+        let mut table_breaks = Vec::new();
+        let shown = rows.iter().map(|(name, _, _)| *name).collect::<Vec<_>>();
+        for (index, (name, _, row)) in rows.into_iter().enumerate() {
+            if breaks.contains(&index) {
+                table_breaks.push(table.len());
+            }
+            let language_info = self.languages.iter().find(|language_info| language_info.name == name);
+            let extensions = language_info.map(|language_info| &language_info.extensions);
+
+            // The tests, markers, and encodings of the "Other" row are those of the languages
+            // without a row of their own
+            let row_languages = self.languages.iter().filter(|other_info| {
+                language_info.map_or(!shown.contains(&other_info.name), |_| other_info.name == name)
+            });
+            let test_lines = row_languages
+                .clone()
+                .filter_map(|other_info| other_info.tests.as_ref())
+                .map(|tests| tests.lines)
+                .fold(0, u64::saturating_add);
+            table_tests.push(Some(test_lines));
+            table_todos.push(Some(
+                row_languages
+                    .clone()
+                    .filter_map(|other_info| other_info.todos)
+                    .fold(0, u64::saturating_add),
+            ));
+            table_non_utf8.push(Some(
+                row_languages
+                    .filter_map(|other_info| other_info.encodings.as_ref())
+                    .map(crate::EncodingStats::non_utf8_files)
+                    .sum::<usize>(),
+            ));
+            table_stats.push(language_info.and_then(|language_info| language_info.stats.as_ref()));
+            table.push((
+                name.to_owned(),
+                row,
+                [byte_percents[index], line_percents[index], file_percents[index]],
+            ));
+            bar_percents.push(byte_percents[index]);
+            let extensions = extensions.into_iter().flatten().collect::<Vec<_>>();
+            let shares = |count: fn(&Totals) -> f64| {
+                round_percentages(&extensions.iter().map(|(_, row)| count(row)).collect::<Vec<_>>())
+            };
+            let (byte_shares, line_shares, file_shares) = (
+                shares(|row| row.bytes as f64),
+                shares(|row| row.lines as f64),
+                shares(|row| row.files as f64),
+            );
+            for (extension_index, (extension, row)) in extensions.into_iter().enumerate() {
+                let label = match extension.as_str() {
+                    crate::NO_EXTENSION => format!("  {extension}"),
+                    extension => format!("  .{extension}"),
+                };
+                let percents = [
+                    byte_shares[extension_index],
+                    line_shares[extension_index],
+                    file_shares[extension_index],
+                ];
+                table.push((label, row.clone(), percents));
+                bar_percents.push(percent(row.bytes as f64, total_bytes as f64));
+                table_tests.push(None);
+                table_todos.push(None);
+                table_non_utf8.push(None);
+                table_stats.push(None);
+            }
+        }
+
+        let table_percents = |index: usize| table.iter().map(|(_, _, percents)| percents[index]).collect::<Vec<_>>();
+        let column = |header, priority, cells: Vec<String>| Column {
+            header,
+            right_aligned: true,
+            dimmed: false,
+            priority,
+            cells,
+        };
+        let percent_column = |priority, percents: &[f64]| Column {
+            header: "%",
+            dimmed: true,
+            ..column(
+                "",
+                priority,
+                percents.iter().map(|percent| format!("{}%", format_number(*percent))).collect(),
+            )
+        };
+        let mut columns = vec![
+            Column {
+                right_aligned: false,
+                ..column("Language", 0, table.iter().map(|(label, _, _)| label.clone()).collect())
+            },
+            column(
+                "Bytes",
+                0,
+                table.iter().map(|(_, row, _)| style.size(row.bytes)).collect(),
+            ),
+            percent_column(0, &table_percents(0)),
+            column(
+                "Lines",
+                0,
+                table.iter().map(|(_, row, _)| style.count(row.lines)).collect(),
+            ),
+            percent_column(1, &table_percents(1)),
+            column(
+                "Files",
+                0,
+                table.iter().map(|(_, row, _)| style.count(row.files as u64)).collect(),
+            ),
+            percent_column(1, &table_percents(2)),
+            column(
+                "Code",
+                2,
+                table.iter().map(|(_, row, _)| style.count(row.code)).collect(),
+            ),
+            column(
+                "Comments",
+                2,
+                table.iter().map(|(_, row, _)| style.count(row.comments)).collect(),
+            ),
+            column(
+                "Blanks",
+                2,
+                table.iter().map(|(_, row, _)| style.count(row.blanks)).collect(),
+            ),
+        ];
+
+        // Leave out the columns of lines, code, comments, and blanks, and the percentages of the
+        // lines, if lines weren't counted
+        if self.lines_skipped {
+            for index in [9, 8, 7, 4, 3] {
+                columns.remove(index);
+            }
+        }
+
+        // Show the lines of tests of each language, and which part of its lines they are, if the
+        // tests were counted separately. The rows of extensions are left empty.
+        if self.test_totals().is_some() && !self.lines_skipped {
+            let cells = |cell: fn(u64, &Totals) -> String| {
+                table
+                    .iter()
+                    .zip(&table_tests)
+                    .map(|((_, row, _), tests)| tests.map_or_else(String::new, |tests| cell(tests, row)))
+                    .collect()
+            };
+            let counts = cells(|tests, _| format_count(tests));
+            let percents = cells(|tests, row| format!("{}%", format_number(percent(tests as f64, row.lines as f64))));
+            columns.push(column("Tests", 1, counts));
+            columns.push(Column {
+                header: "%",
+                dimmed: true,
+                ..column("", 1, percents)
+            });
+        }
+
+        // Show the markers of each language, if they were counted. The rows of extensions are left
+        // empty.
+        if self.languages.iter().any(|language_info| language_info.todos.is_some()) {
+            let counts = table_todos
+                .iter()
+                .map(|todos| todos.map_or_else(String::new, |todos| style.count(todos)))
+                .collect();
+            columns.push(column("TODOs", 1, counts));
+        }
+
+        // Show the files of each language that aren't UTF-8, if encodings were tallied. The rows of
+        // extensions are left empty.
+        if self.languages.iter().any(|language_info| language_info.encodings.is_some()) {
+            let counts = table_non_utf8
+                .iter()
+                .map(|files| files.map_or_else(String::new, |files| style.count(files as u64)))
+                .collect();
+            columns.push(column("Non-UTF-8", 1, counts));
+        }
+
+        // Show the statistics of the sizes of the files of each language, if they were calculated.
+        // The rows of "Other" and of extensions are left empty.
+        if table_stats.iter().any(Option::is_some) {
+            let cells = |cell: &dyn Fn(&crate::FileStats) -> String| {
+                table_stats.iter().map(|stats| stats.map_or_else(String::new, cell)).collect()
+            };
+            columns.push(column(
+                "Avg lines",
+                3,
+                cells(&|stats| format!("{:.1}", stats.average_lines)),
+            ));
+            columns.push(column(
+                "Median",
+                3,
+                cells(&|stats| format!("{:.1}", stats.median_lines)),
+            ));
+            columns.push(column("Max lines", 3, cells(&|stats| style.count(stats.max_lines))));
+            columns.push(Column {
+                right_aligned: false,
+                ..column("Longest file", 3, cells(&|stats| stats.longest_file.clone()))
+            });
+        }
+        let names = table.iter().map(|(label, _, _)| label.as_str()).collect::<Vec<_>>();
+        write_table(writer, columns, &names, &bar_percents, &table_breaks, style)
+    }
+
+    /// Writes the totals across all languages on a single line, followed by a note if the scan was
+    /// limited by depth. The totals are formatted as described by `style`.
+    pub fn display_totals(&self, writer: &mut impl std::io::Write, style: DisplayStyle) -> std::io::Result<()> {
+        write_totals(writer, "Total", &self.totals(), !self.lines_skipped, style)?;
+        write_test_totals(writer, self.test_totals(), !self.lines_skipped, style)?;
+        write_style_totals(writer, self.style_totals(), style)?;
+        write_encoding_totals(writer, self.encoding_totals(), style)?;
+        write_depth_note(writer, self.depth_limited)
+    }
+
+    /// Writes the report of the languages as a single line of JSON, see `LanguageList::report`.
+    pub fn display_json(&self, writer: &mut impl std::io::Write, top: Option<usize>) -> std::io::Result<()> {
+        serde_json::to_writer(&mut *writer, &self.report_without_lines(&self.report(top)))?;
+        writeln!(writer)
+    }
+
+    /// Writes the report of the languages as YAML, see `LanguageList::report`.
+    pub fn display_yaml(&self, writer: &mut impl std::io::Write, top: Option<usize>) -> std::io::Result<()> {
+        let report = self.report(top);
+        serde_yaml::to_writer(&mut *writer, &self.report_without_lines(&report)).map_err(std::io::Error::other)?;
+        writeln!(writer)
+    }
+
+    /// Writes the languages as JSON Lines, with one JSON object per line and a `type` field saying
+    /// what each object is: a `file` record for each file, a `language` record for each language
+    /// with the same counts as the JSON output, an `other` record for the languages left out by
+    /// `top`, an `error` record for each path that couldn't be read, a `tests` record with the
+    /// totals of the test files if they were counted separately, and a final `totals` record.
+    ///
+    /// # Parameters
+    /// - `writer` - Where to write the output
+    /// - `top` - The number of languages to write records for, see `LanguageList::report`
+    /// - `files` - Whether to write the `file` records, which should be left out when they were
+    ///   already written by `ScanOptions::on_file` while the files were counted
+    pub fn display_jsonl(
+        &self,
+        writer: &mut impl std::io::Write,
+        top: Option<usize>,
+        files: bool,
+    ) -> std::io::Result<()> {
+        if files {
+            self.file_report().display_jsonl(writer)?;
+        }
+        self.write_jsonl_summary(writer, top, None)?;
+        if let Some(tests) = self.test_totals() {
+            write_record(writer, "tests", &self.report_without_lines(&tests))?;
+        }
+        write_record(writer, "totals", &self.report_without_lines(&self.totals()))
+    }
+
+    /// Writes the `language`, `other`, and `error` records of `display_jsonl`.
+    ///
+    /// # Parameters
+    /// - `writer` - Where to write the records
+    /// - `top` - The number of languages to write records for
+    /// - `directory` - The directory of the languages, which is written as the `directory` field
+    ///   of each record if it is given
+    fn write_jsonl_summary(
+        &self,
+        writer: &mut impl std::io::Write,
+        top: Option<usize>,
+        directory: Option<&str>,
+    ) -> std::io::Result<()> {
+        let report = self.report(top);
+        for language in &report.languages {
+            let record = LanguageRecord {
+                directory,
+                name: language.info.name,
+                category: language.info.category,
+                color: language.color,
+                files: language.info.file_count,
+                lines: language.info.lines,
+                code: language.info.code,
+                comments: language.info.comments,
+                blanks: language.info.blanks,
+                bytes: language.info.bytes,
+                byte_percent: language.byte_percent,
+                line_percent: language.line_percent,
+                file_percent: language.file_percent,
+                extensions: &language.info.extensions,
+                tests: language.info.tests.as_ref(),
+                stats: language.info.stats.as_ref(),
+                todos: language.info.todos,
+                style: language.info.style.as_ref(),
+                encodings: language.info.encodings.as_ref(),
+            };
+            write_record(writer, "language", &self.report_without_lines(&record))?;
+        }
+        if let Some(other) = &report.other {
+            write_record(writer, "other", &self.report_without_lines(other))?;
+        }
+        for error in report.errors {
+            write_record(writer, "error", error)?;
+        }
+        Ok(())
+    }
+
+    /// Writes the report of the languages as TOML, see `LanguageList::report`.
+    pub fn display_toml(&self, writer: &mut impl std::io::Write, top: Option<usize>) -> std::io::Result<()> {
+        write_toml(writer, &self.report_without_lines(&self.report(top)))
+    }
+
+    /// Writes the report of the languages as XML, with a `<report>` root element, see
+    /// `LanguageList::report`.
+    pub fn display_xml(&self, writer: &mut impl std::io::Write, top: Option<usize>) -> std::io::Result<()> {
+        crate::xml::write_xml(writer, "report", &self.report_without_lines(&self.report(top)))
+    }
+
+    /// Returns a report of these languages that is serialized without its counts of lines if they
+    /// weren't counted, see `LanguageList::lines_skipped`.
+    fn report_without_lines<'a, T>(&self, report: &'a T) -> WithoutLines<'a, T> {
+        WithoutLines {
+            report,
+            lines_skipped: self.lines_skipped,
+        }
+    }
+
+    /// Returns the rows of `display`: the name, category, and counts of each language, with the
+    /// languages left out by `top` and `min_percent` grouped into a final "Other" row without a
+    /// category, and prose languages after the others.
+    fn grouped_rows(
+        &self,
+        top: Option<usize>,
+        min_percent: f64,
+    ) -> Vec<(&'static str, Option<crate::Category>, Totals)> {
+        // Group the languages into rows, folding small languages into "Other"
+        let total_bytes = self.totals().bytes;
+        let mut rows = Vec::new();
+        let mut other = Totals::default();
+        for (index, language_info) in self.languages.iter().enumerate() {
+            let is_top = top.is_none_or(|top| index < top);
+            if is_top && percent(language_info.bytes as f64, total_bytes as f64) >= min_percent {
+                let mut counts = Totals::default();
+                counts.add(language_info);
+                rows.push((language_info.name, Some(language_info.category), counts));
+            } else {
+                other.add(language_info);
+            }
+        }
+        if other.files != 0 {
+            rows.push(("Other", None, other));
+        }
+
+        // Move prose languages after the others, keeping the "Other" row last
+        rows.sort_by_key(|(_, category, _)| match category {
+            Some(crate::Category::Prose) => 1,
+            Some(_) => 0,
+            None => 2,
+        });
+        rows
+    }
+
+    /// Writes a chart of the byte share of each language as an SVG image, with a legend listing
+    /// the name and percentage of each language. Languages are grouped into "Other" as with
+    /// `display`, and colored with their colors from `COLORS`.
+    ///
+    /// # Parameters
+    /// - `writer` - Where to write the image
+    /// - `top` - The maximum number of languages to show on their own, see `display`
+    /// - `min_percent` - The percent of the total bytes a language must make up to be shown on its
+    ///   own, see `display`
+    /// - `options` - The kind and size of the chart
+    pub fn display_svg(
+        &self,
+        writer: &mut impl std::io::Write,
+        top: Option<usize>,
+        min_percent: f64,
+        options: crate::SvgOptions,
+    ) -> std::io::Result<()> {
+        let rows = self.grouped_rows(top, min_percent);
+        let total_bytes = self.totals().bytes as f64;
+        let byte_percents = round_percentages(&rows.iter().map(|(_, _, row)| row.bytes as f64).collect::<Vec<_>>());
+        let slices = rows
+            .iter()
+            .zip(byte_percents)
+            .map(|((name, _, row), byte_percent)| crate::svg::Slice {
+                name,
+                color: crate::language_color(name).unwrap_or(crate::svg::DEFAULT_COLOR),
+                share: if total_bytes == 0.0 {
+                    0.0
+                } else {
+                    row.bytes as f64 / total_bytes
+                },
+                label: format!("{}%", format_number(byte_percent)),
+            })
+            .collect::<Vec<_>>();
+        crate::svg::write_svg(writer, &slices, options)
+    }
+
+    /// Writes the counts of each language as Prometheus gauges in the text exposition format, such
+    /// as `splik_language_bytes{language="Rust"} 123456`, followed by the totals, such as
+    /// `splik_total_bytes 123456`. Each metric family starts with its `# HELP` and `# TYPE` lines,
+    /// see `METRICS`.
+    pub fn display_prometheus(&self, writer: &mut impl std::io::Write) -> std::io::Result<()> {
+        write_prometheus(writer, &[(None, self)])
+    }
+
+    /// Writes a Mermaid pie chart of the languages, with a slice for each language and the
+    /// languages grouped into "Other" as with `display`, such as `pie title Languages` followed by
+    /// `    "Rust" : 4520`. Slices are in the order of the languages, so the chart only changes when
+    /// the counts do.
+    ///
+    /// # Parameters
+    /// - `writer` - Where to write the chart
+    /// - `top` - The maximum number of languages to give slices of their own, see `display`
+    /// - `min_percent` - The percent of the total bytes a language must make up to have a slice of
+    ///   its own, see `display`
+    /// - `values` - Whether the slices are sized by bytes or by percent of the bytes
+    pub fn display_mermaid(
+        &self,
+        writer: &mut impl std::io::Write,
+        top: Option<usize>,
+        min_percent: f64,
+        values: MermaidValues,
+    ) -> std::io::Result<()> {
+        let rows = self.grouped_rows(top, min_percent);
+        let byte_percents = round_percentages(&rows.iter().map(|(_, _, row)| row.bytes as f64).collect::<Vec<_>>());
+        writeln!(writer, "pie title Languages")?;
+        for ((name, _, row), byte_percent) in rows.iter().zip(byte_percents) {
+            let value = match values {
+                MermaidValues::Bytes => row.bytes.to_string(),
+                MermaidValues::Percent => format_number(byte_percent),
+            };
+            writeln!(writer, "    \"{}\" : {value}", name.replace('"', "#quot;"))?;
+        }
+        Ok(())
+    }
+
+    /// Writes a shields.io endpoint badge of the share of the bytes of the language with the most
+    /// bytes, such as `{"schemaVersion":1,"label":"language","message":"Rust 72%","color":"dea584"}`.
+    /// The badge is in the color of the language from `COLORS`, or in gray for languages without
+    /// a color.
+    ///
+    /// # Parameters
+    /// - `writer` - Where to write the badge
+    /// - `label` - The label on the left of the badge
+    /// - `language` - The language to write the share of instead, matched as with `find`. A
+    ///   language without any files has a share of 0%.
+    pub fn display_badge(
+        &self,
+        writer: &mut impl std::io::Write,
+        label: &str,
+        language: Option<&str>,
+    ) -> std::io::Result<()> {
+        // Round the percentages as in the human-readable output, so that the badge matches it
+        let byte_percents = round_percentages(&self.languages.iter().map(|info| info.bytes as f64).collect::<Vec<_>>());
+        let index = match language {
+            Some(name) => self.languages.iter().position(|info| crate::language_matches(info.name, name)),
+            None => (0..self.languages.len()).max_by_key(|index| self.languages[*index].bytes),
+        };
+        let (name, message) = match (index, language) {
+            (Some(index), _) => {
+                let name = self.languages[index].name;
+                (name, format!("{name} {}%", format_number(byte_percents[index])))
+            }
+            // Write the name of a language without files as it is known, such as Python for `py`
+            (None, Some(name)) => {
+                let known = crate::known_language_names().find(|language| crate::language_matches(language, name));
+                let name = known.unwrap_or(name);
+                (name, format!("{name} 0%"))
+            }
+            (None, None) => ("none", "none".to_owned()),
+        };
+        let color = crate::language_color(name).unwrap_or(BADGE_COLOR);
+        let badge = Badge {
+            schema_version: 1,
+            label,
+            message,
+            color: color.trim_start_matches('#'),
+        };
+        serde_json::to_writer(&mut *writer, &badge)?;
+        writeln!(writer)
+    }
+
+    /// Writes the languages as a self-contained HTML page, with a chart of their byte shares, a
+    /// table of their counts, and a collapsible list of the files of each language.
+    ///
+    /// # Parameters
+    /// - `writer` - Where to write the page
+    /// - `source` - What was scanned, such as the root directory, which the page states along with
+    ///   the time it was written
+    pub fn display_html(&self, writer: &mut impl std::io::Write, source: &str) -> std::io::Result<()> {
+        crate::html::write_html(writer, source, &[(None, self)])
+    }
+
+    /// Writes the languages in the JSON format of tokei, with the line counts of each file.
+    pub fn display_tokei_json(&self, writer: &mut impl std::io::Write) -> std::io::Result<()> {
+        crate::compat::write_tokei_json(writer, self.languages.iter())
+    }
+
+    /// Writes the languages in the JSON format of cloc.
+    pub fn display_cloc_json(&self, writer: &mut impl std::io::Write) -> std::io::Result<()> {
+        crate::compat::write_cloc_json(writer, self.languages.iter())
+    }
+
+    /// Writes the languages as CSV, with a header row followed by one row per language. Fields
+    /// are quoted as described in RFC 4180 when necessary. If test files were counted separately,
+    /// the files, lines, and bytes of the tests of each language are written in three more
+    /// columns.
+    ///
+    /// # Parameters
+    /// - `writer` - Where to write the output
+    /// - `include_totals` - Whether to write a final row with the totals across all languages
+    pub fn display_csv(&self, writer: &mut impl std::io::Write, include_totals: bool) -> std::io::Result<()> {
+        let tests = self.test_totals().is_some();
+        writeln!(
+            writer,
+            "language,files,lines,bytes,byte_percent,line_percent,file_percent{}",
+            csv_test_headers(tests)
+        )?;
+        self.write_csv_rows(writer, None, include_totals, tests)
+    }
+
+    /// Writes the CSV rows of the languages, see `display_csv`.
+    ///
+    /// # Parameters
+    /// - `writer` - Where to write the output
+    /// - `directory` - The directory of the languages, which is written as the first field of each
+    ///   row if it is given
+    /// - `include_totals` - Whether to write a final row with the totals across all languages
+    /// - `tests` - Whether to write the columns of the tests
+    fn write_csv_rows(
+        &self,
+        writer: &mut impl std::io::Write,
+        directory: Option<&str>,
+        include_totals: bool,
+        tests: bool,
+    ) -> std::io::Result<()> {
+        let totals = self.totals();
+        let mut write_row = |name: &str, files: usize, lines: u64, bytes: u64, test_totals: Option<&Totals>| {
+            if let Some(directory) = directory {
+                write!(writer, "{},", csv_field(directory))?;
+            }
+            write!(
+                writer,
+                "{},{},{},{},{:.2},{:.2},{:.2}",
+                csv_field(name),
+                files,
+                lines,
+                bytes,
+                percent(bytes as f64, totals.bytes as f64),
+                percent(lines as f64, totals.lines as f64),
+                percent(files as f64, totals.files as f64),
+            )?;
+            if tests {
+                let test_totals = test_totals.cloned().unwrap_or_default();
+                write!(
+                    writer,
+                    ",{},{},{}",
+                    test_totals.files, test_totals.lines, test_totals.bytes
+                )?;
+            }
+            writeln!(writer)
+        };
+
+        for language_info in &self.languages {
+            write_row(
+                language_info.name,
+                language_info.file_count,
+                language_info.lines,
+                language_info.bytes,
+                language_info.tests.as_ref(),
+            )?;
+        }
+
+        if include_totals {
+            write_row(
+                "Total",
+                totals.files,
+                totals.lines,
+                totals.bytes,
+                self.test_totals().as_ref(),
+            )?;
+        }
+
+        Ok(())
+    }
+
+    /// Writes the languages as a GitHub-flavored Markdown table, followed by a bold row with the
+    /// totals across all languages. If test files were counted separately, the lines of the tests
+    /// of each language are written in one more column.
+    pub fn display_markdown(&self, writer: &mut impl std::io::Write) -> std::io::Result<()> {
+        let totals = self.totals();
+        let test_totals = self.test_totals();
+        let byte_percents = round_percentages(&self.languages.iter().map(|info| info.bytes as f64).collect::<Vec<_>>());
+        let test_cell = |tests: Option<&Totals>, bold: &str| match &test_totals {
+            Some(_) => format!(" {bold}{}{bold} |", tests.map_or(0, |tests| tests.lines)),
+            None => String::new(),
+        };
+
+        let test_header = if test_totals.is_some() {
+            (" Test lines |", "-----------:|")
+        } else {
+            ("", "")
+        };
+        writeln!(
+            writer,
+            "| Language | Files | Lines | Bytes | % (bytes) |{}",
+            test_header.0
+        )?;
+        writeln!(
+            writer,
+            "|:---------|------:|------:|------:|----------:|{}",
+            test_header.1
+        )?;
+        for (language_info, byte_percent) in self.languages.iter().zip(byte_percents) {
+            writeln!(
+                writer,
+                "| {} | {} | {} | {} | {}% |{}",
+                markdown_escape(language_info.name),
+                language_info.file_count,
+                language_info.lines,
+                language_info.bytes,
+                format_number(byte_percent),
+                test_cell(language_info.tests.as_ref(), ""),
+            )?;
+        }
+        writeln!(
+            writer,
+            "| **Total** | **{}** | **{}** | **{}** | **{}%** |{}",
+            totals.files,
+            totals.lines,
+            totals.bytes,
+            if totals.files == 0 { 0 } else { 100 },
+            test_cell(test_totals.as_ref(), "**"),
+        )
+    }
+}
+
+impl crate::FileListing<'_> {
+    /// Writes the files in a human-readable table, with their languages, lines, and bytes, and
+    /// their markers, styles, and encodings if they were counted. The sizes and lines of the files are
+    /// formatted as described by `style`.
+    pub fn display(&self, writer: &mut impl std::io::Write, style: DisplayStyle) -> std::io::Result<()> {
+        let files = &self.files;
+        let cells = |cell: &dyn Fn(&crate::FileReport<'_>) -> String| files.iter().map(cell).collect::<Vec<_>>();
+
+        // The columns before the path, along with whether they are right-aligned. The lines,
+        // markers, styles, and encodings are only shown if they were counted.
+        let mut columns = vec![("Language", false, cells(&|file| file.language.to_owned()))];
+        if !lines_skipped(files) {
+            let lines = cells(&|file| file.lines.map_or_else(String::new, |lines| style.count(lines)));
+            columns.push(("Lines", true, lines));
+        }
+        columns.push(("Bytes", true, cells(&|file| style.size(file.bytes))));
+        if files.iter().any(|file| file.todos.is_some()) {
+            let todos = cells(&|file| file.todos.map_or_else(String::new, |todos| style.count(todos)));
+            columns.push(("TODOs", true, todos));
+        }
+        if files.iter().any(|file| file.style.is_some()) {
+            let endings = cells(&|file| {
+                let line_ending = file.style.and_then(|style| style.line_ending);
+                line_ending.map_or_else(String::new, |line_ending| line_ending_name(line_ending).to_owned())
+            });
+            let indentation = cells(&|file| match file.style.and_then(|style| style.indentation) {
+                Some(crate::Indentation::Tabs) => "tabs".to_owned(),
+                Some(crate::Indentation::Spaces) => "spaces".to_owned(),
+                None => String::new(),
+            });
+            columns.push(("Endings", false, endings));
+            columns.push(("Indent", false, indentation));
+        }
+        if files.iter().any(|file| file.encoding.is_some()) {
+            let encodings =
+                cells(&|file| file.encoding.map_or_else(String::new, |encoding| encoding_name(encoding).to_owned()));
+            columns.push(("Encoding", false, encodings));
+        }
+
+        let widths = columns
+            .iter()
+            .map(|(header, _, cells)| cells.iter().map(String::len).chain([header.len()]).max().unwrap())
+            .collect::<Vec<_>>();
+        let row = |cells: Vec<&str>| {
+            let mut row = String::new();
+            for (index, cell) in cells.into_iter().enumerate() {
+                let width = widths[index];
+                if columns[index].1 {
+                    row.push_str(&format!("{cell:>width$}  "));
+                } else {
+                    row.push_str(&format!("{cell:width$}  "));
+                }
+            }
+            row
+        };
+        writeln!(
+            writer,
+            "{}Path",
+            row(columns.iter().map(|(header, _, _)| *header).collect())
+        )?;
+        for (file_index, file) in files.iter().enumerate() {
+            let cells = columns.iter().map(|(_, _, cells)| cells[file_index].as_str()).collect();
+            writeln!(writer, "{}{}", row(cells), file.path)?;
+        }
+        Ok(())
+    }
+
+    /// Writes the files as a single line of JSON, as an array of objects.
+    pub fn display_json(&self, writer: &mut impl std::io::Write) -> std::io::Result<()> {
+        serde_json::to_writer(&mut *writer, &self.files)?;
+        writeln!(writer)
+    }
+
+    /// Writes the files as YAML.
+    pub fn display_yaml(&self, writer: &mut impl std::io::Write) -> std::io::Result<()> {
+        serde_yaml::to_writer(&mut *writer, &self.files).map_err(std::io::Error::other)?;
+        writeln!(writer)
+    }
+
+    /// Writes each file as a `file` record of JSON Lines, see `LanguageList::display_jsonl`.
+    pub fn display_jsonl(&self, writer: &mut impl std::io::Write) -> std::io::Result<()> {
+        self.files.iter().try_for_each(|file| file.display_jsonl(writer))
+    }
+
+    /// Writes the files as TOML, as an array of tables named `files`, since TOML documents can't
+    /// be arrays.
+    pub fn display_toml(&self, writer: &mut impl std::io::Write) -> std::io::Result<()> {
+        #[derive(serde::Serialize)]
+        struct Files<'a, 'b> {
+            files: &'b [crate::list::FileReport<'a>],
+        }
+        write_toml(writer, &Files { files: &self.files })
+    }
+
+    /// Writes the files as XML, as `<file>` elements in a `<files>` root element.
+    pub fn display_xml(&self, writer: &mut impl std::io::Write) -> std::io::Result<()> {
+        crate::xml::write_xml(writer, "files", &self.files)
+    }
+
+    /// Writes the files as CSV, with a header row followed by one row per file. A `todos` column
+    /// is added if markers were counted, and the `lines` column is empty if lines weren't.
+    pub fn display_csv(&self, writer: &mut impl std::io::Write) -> std::io::Result<()> {
+        let has_todos = self.files.iter().any(|file| file.todos.is_some());
+        writeln!(
+            writer,
+            "path,language,lines,bytes{}",
+            if has_todos { ",todos" } else { "" }
+        )?;
+        for file in &self.files {
+            let todos = match file.todos {
+                Some(todos) => format!(",{todos}"),
+                None if has_todos => ",".to_owned(),
+                None => String::new(),
+            };
+            writeln!(
+                writer,
+                "{},{},{},{}{todos}",
+                csv_field(file.path),
+                csv_field(file.language),
+                file.lines.map_or_else(String::new, |lines| lines.to_string()),
+                file.bytes
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Writes the files as a GitHub-flavored Markdown table, with a "TODOs" column if markers were
+    /// counted. The "Lines" column is empty if lines weren't counted.
+    pub fn display_markdown(&self, writer: &mut impl std::io::Write) -> std::io::Result<()> {
+        let has_todos = self.files.iter().any(|file| file.todos.is_some());
+        let (todos_header, todos_separator) = if has_todos { (" TODOs |", "------:|") } else { ("", "") };
+        writeln!(writer, "| Path | Language | Lines | Bytes |{todos_header}")?;
+        writeln!(writer, "|:-----|:---------|------:|------:|{todos_separator}")?;
+        for file in &self.files {
+            let todos = match file.todos {
+                Some(todos) => format!(" {todos} |"),
+                None if has_todos => "  |".to_owned(),
+                None => String::new(),
+            };
+            writeln!(
+                writer,
+                "| {} | {} | {} | {} |{todos}",
+                markdown_escape(file.path),
+                markdown_escape(file.language),
+                file.lines.map_or_else(String::new, |lines| lines.to_string()),
+                file.bytes
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl DirectoryList {
+    /// Writes the languages of each directory in the human-readable format, see
+    /// `LanguageList::display`. Each directory is written as its own section, and the sections are
+    /// followed by the totals across all directories.
+    pub fn display(
+        &self,
+        writer: &mut impl std::io::Write,
+        top: Option<usize>,
+        min_percent: f64,
+        style: DisplayStyle,
+    ) -> std::io::Result<()> {
+        for (directory, languages) in &self.directories {
+            writeln!(writer, "{directory}:")?;
+            languages.display_rows(writer, top, min_percent, style)?;
+            write_totals(writer, "Total", &languages.totals(), !languages.lines_skipped, style)?;
+            write_test_totals(writer, languages.test_totals(), !languages.lines_skipped, style)?;
+            write_style_totals(writer, languages.style_totals(), style)?;
+            write_encoding_totals(writer, languages.encoding_totals(), style)?;
+            writeln!(writer)?;
+        }
+        write_totals(writer, "Total", &self.totals(), !self.lines_skipped(), style)?;
+        write_test_totals(writer, self.test_totals(), !self.lines_skipped(), style)?;
+        write_style_totals(writer, self.style_totals(), style)?;
+        write_encoding_totals(writer, self.encoding_totals(), style)?;
+        write_depth_note(writer, self.depth_limited)
+    }
+
+    /// Writes the totals of each directory on a line of its own, followed by the totals across all
+    /// directories.
+    pub fn display_totals(&self, writer: &mut impl std::io::Write, style: DisplayStyle) -> std::io::Result<()> {
+        for (directory, languages) in &self.directories {
+            write_totals(writer, directory, &languages.totals(), !languages.lines_skipped, style)?;
+        }
+        write_totals(writer, "Total", &self.totals(), !self.lines_skipped(), style)?;
+        write_test_totals(writer, self.test_totals(), !self.lines_skipped(), style)?;
+        write_style_totals(writer, self.style_totals(), style)?;
+        write_encoding_totals(writer, self.encoding_totals(), style)?;
+        write_depth_note(writer, self.depth_limited)
+    }
+
+    /// Writes a table of the extensions of the files whose language wasn't detected across all
+    /// directories, see `LanguageList::display_unknown`.
+    pub fn display_unknown(&self, writer: &mut impl std::io::Write, style: DisplayStyle) -> std::io::Result<()> {
+        write_unknown(writer, &crate::list::unknown_extensions(&self.unknown()), style)
+    }
+
+    /// Writes the report of each directory as a single line of JSON, see `DirectoryList::report`.
+    pub fn display_json(&self, writer: &mut impl std::io::Write, top: Option<usize>) -> std::io::Result<()> {
+        serde_json::to_writer(&mut *writer, &self.report_without_lines(&self.report(top)))?;
+        writeln!(writer)
+    }
+
+    /// Writes the report of each directory as YAML, see `DirectoryList::report`.
+    pub fn display_yaml(&self, writer: &mut impl std::io::Write, top: Option<usize>) -> std::io::Result<()> {
+        let report = self.report(top);
+        serde_yaml::to_writer(&mut *writer, &self.report_without_lines(&report)).map_err(std::io::Error::other)?;
+        writeln!(writer)
+    }
+
+    /// Writes the languages of each directory as JSON Lines, like `LanguageList::display_jsonl`,
+    /// with the directory as the `directory` field of each `language` record, followed by the
+    /// totals across all directories.
+    pub fn display_jsonl(
+        &self,
+        writer: &mut impl std::io::Write,
+        top: Option<usize>,
+        files: bool,
+    ) -> std::io::Result<()> {
+        for (directory, languages) in &self.directories {
+            if files {
+                languages.file_report().display_jsonl(writer)?;
+            }
+            languages.write_jsonl_summary(writer, top, Some(directory))?;
+        }
+        if let Some(tests) = self.test_totals() {
+            write_record(writer, "tests", &self.report_without_lines(&tests))?;
+        }
+        write_record(writer, "totals", &self.report_without_lines(&self.totals()))
+    }
+
+    /// Writes the report of each directory as TOML, see `DirectoryList::report`.
+    pub fn display_toml(&self, writer: &mut impl std::io::Write, top: Option<usize>) -> std::io::Result<()> {
+        write_toml(writer, &self.report_without_lines(&self.report(top)))
+    }
+
+    /// Writes the report of each directory as XML, with a `<directory path="...">` element for
+    /// each directory, see `DirectoryList::report`.
+    pub fn display_xml(&self, writer: &mut impl std::io::Write, top: Option<usize>) -> std::io::Result<()> {
+        crate::xml::write_xml(writer, "report", &self.report_without_lines(&self.report(top)))
+    }
+
+    /// Returns a report of these directories that is serialized without its counts of lines if
+    /// they weren't counted, see `DirectoryList::lines_skipped`.
+    fn report_without_lines<'a, T>(&self, report: &'a T) -> WithoutLines<'a, T> {
+        WithoutLines {
+            report,
+            lines_skipped: self.lines_skipped(),
+        }
+    }
+
+    /// Writes the languages of each directory as a self-contained HTML page, with a section for
+    /// each directory, see `LanguageList::display_html`.
+    pub fn display_html(&self, writer: &mut impl std::io::Write, source: &str) -> std::io::Result<()> {
+        let sections = self
+            .directories
+            .iter()
+            .map(|(directory, languages)| (Some(directory.as_str()), languages))
+            .collect::<Vec<_>>();
+        crate::html::write_html(writer, source, &sections)
+    }
+
+    /// Writes a chart of the byte share of each language across every directory as an SVG image,
+    /// see `LanguageList::display_svg`.
+    pub fn display_svg(
+        &self,
+        writer: &mut impl std::io::Write,
+        top: Option<usize>,
+        min_percent: f64,
+        options: crate::SvgOptions,
+    ) -> std::io::Result<()> {
+        self.combined().display_svg(writer, top, min_percent, options)
+    }
+
+    /// Writes the counts of each language of each directory as Prometheus gauges, like
+    /// `LanguageList::display_prometheus`, with the directory as the `directory` label of each
+    /// metric, such as `splik_language_bytes{directory="src",language="Rust"} 123456`.
+    pub fn display_prometheus(&self, writer: &mut impl std::io::Write) -> std::io::Result<()> {
+        let sections = self
+            .directories
+            .iter()
+            .map(|(directory, languages)| (Some(directory.as_str()), languages))
+            .collect::<Vec<_>>();
+        write_prometheus(writer, &sections)
+    }
+
+    /// Writes a Mermaid pie chart of the languages of every directory combined, see
+    /// `LanguageList::display_mermaid`.
+    pub fn display_mermaid(
+        &self,
+        writer: &mut impl std::io::Write,
+        top: Option<usize>,
+        min_percent: f64,
+        values: MermaidValues,
+    ) -> std::io::Result<()> {
+        self.combined().display_mermaid(writer, top, min_percent, values)
+    }
+
+    /// Writes a badge of the language with the most bytes across every directory, or of the given
+    /// language, see `LanguageList::display_badge`.
+    pub fn display_badge(
+        &self,
+        writer: &mut impl std::io::Write,
+        label: &str,
+        language: Option<&str>,
+    ) -> std::io::Result<()> {
+        self.combined().display_badge(writer, label, language)
+    }
+
+    /// Writes the languages of every directory combined in the JSON format of tokei, which has no
+    /// breakdown by directory, see `LanguageList::display_tokei_json`.
+    pub fn display_tokei_json(&self, writer: &mut impl std::io::Write) -> std::io::Result<()> {
+        crate::compat::write_tokei_json(writer, self.languages())
+    }
+
+    /// Writes the languages of every directory combined in the JSON format of cloc, see
+    /// `LanguageList::display_cloc_json`.
+    pub fn display_cloc_json(&self, writer: &mut impl std::io::Write) -> std::io::Result<()> {
+        crate::compat::write_cloc_json(writer, self.languages())
+    }
+
+    /// Returns the languages of every directory combined into a single list, sorted by bytes, for
+    /// the outputs that have no breakdown by directory.
+    fn combined(&self) -> LanguageList {
+        let mut combined = LanguageList::default();
+        for language_info in self.languages() {
+            let info = combined.get_or_insert(language_info.name);
+            info.lines = info.lines.saturating_add(language_info.lines);
+            info.code = info.code.saturating_add(language_info.code);
+            info.comments = info.comments.saturating_add(language_info.comments);
+            info.blanks = info.blanks.saturating_add(language_info.blanks);
+            info.bytes = info.bytes.saturating_add(language_info.bytes);
+            info.file_count = info.file_count.saturating_add(language_info.file_count);
+            info.files.extend(language_info.files.iter().cloned());
+        }
+        combined.sort(crate::SortKey::Bytes, false);
+        combined
+    }
+
+    /// Returns the languages of every directory, where the same language may appear once per
+    /// directory.
+    fn languages(&self) -> impl Iterator<Item = &crate::LanguageInfo> {
+        self.directories.values().flat_map(|languages| &languages.languages)
+    }
+
+    /// Writes the languages of each directory as CSV, like `LanguageList::display_csv` but with
+    /// the directory as the first field of each row. Percentages are of the directory's totals.
+    ///
+    /// # Parameters
+    /// - `writer` - Where to write the output
+    /// - `include_totals` - Whether to write a final row with the totals of each directory
+    pub fn display_csv(&self, writer: &mut impl std::io::Write, include_totals: bool) -> std::io::Result<()> {
+        let tests = self.test_totals().is_some();
+        writeln!(
+            writer,
+            "directory,language,files,lines,bytes,byte_percent,line_percent,file_percent{}",
+            csv_test_headers(tests)
+        )?;
+        for (directory, languages) in &self.directories {
+            languages.write_csv_rows(writer, Some(directory), include_totals, tests)?;
+        }
+        Ok(())
+    }
+
+    /// Writes the languages of each directory as a Markdown table under a heading with the
+    /// directory's name, see `LanguageList::display_markdown`.
+    pub fn display_markdown(&self, writer: &mut impl std::io::Write) -> std::io::Result<()> {
+        for (index, (directory, languages)) in self.directories.iter().enumerate() {
+            if index != 0 {
+                writeln!(writer)?;
+            }
+            writeln!(writer, "### {}", markdown_escape(directory))?;
+            writeln!(writer)?;
+            languages.display_markdown(writer)?;
+        }
+        Ok(())
+    }
+}
+
+impl Comparison {
+    /// Writes the differences of each language in a human-readable format, one per line, followed
+    /// by the differences of the totals, each with its percent of the baseline. Languages that were
+    /// added or removed are marked as such.
+    /// Languages are colored and the differences are formatted as described by `style`, as with
+    /// `LanguageList::display`.
+    pub fn display(&self, writer: &mut impl std::io::Write, style: DisplayStyle) -> std::io::Result<()> {
+        for language in &self.languages {
+            let name = paint_language(&language.name, style.color);
+            let label = match language.status {
+                crate::DeltaStatus::Added | crate::DeltaStatus::Removed => {
+                    format!("{name} ({})", language.status.label())
+                }
+                crate::DeltaStatus::Changed | crate::DeltaStatus::Unchanged => name,
+            };
+            write_delta(writer, &label, &language.delta, style)?;
+        }
+        self.display_totals(writer, style)
+    }
+
+    /// Writes the differences of the totals across all languages on a single line, in bold with
+    /// `DisplayStyle::color`.
+    pub fn display_totals(&self, writer: &mut impl std::io::Write, style: DisplayStyle) -> std::io::Result<()> {
+        let mut line = Vec::new();
+        write_delta(&mut line, "Total", &self.totals, style)?;
+        let line = String::from_utf8_lossy(&line);
+        writeln!(writer, "{}", paint(line.trim_end(), BOLD, style.color))
+    }
+
+    /// Writes the comparison as a single line of JSON.
+    pub fn display_json(&self, writer: &mut impl std::io::Write) -> std::io::Result<()> {
+        serde_json::to_writer(&mut *writer, self)?;
+        writeln!(writer)
+    }
+
+    /// Writes the comparison as YAML.
+    pub fn display_yaml(&self, writer: &mut impl std::io::Write) -> std::io::Result<()> {
+        serde_yaml::to_writer(&mut *writer, self).map_err(std::io::Error::other)?;
+        writeln!(writer)
+    }
+
+    /// Writes the comparison as JSON Lines, with a `language` record for the differences of each
+    /// language, followed by a `totals` record with the differences of the totals.
+    pub fn display_jsonl(&self, writer: &mut impl std::io::Write) -> std::io::Result<()> {
+        for language in &self.languages {
+            write_record(writer, "language", language)?;
+        }
+        write_record(writer, "totals", &self.totals)
+    }
+
+    /// Writes the comparison as TOML.
+    pub fn display_toml(&self, writer: &mut impl std::io::Write) -> std::io::Result<()> {
+        write_toml(writer, self)
+    }
+
+    /// Writes the comparison as XML, with a `<comparison>` root element.
+    pub fn display_xml(&self, writer: &mut impl std::io::Write) -> std::io::Result<()> {
+        crate::xml::write_xml(writer, "comparison", self)
+    }
+
+    /// Writes the differences of each language as CSV, with a header row followed by one row per
+    /// language. The differences are followed by what was added and removed of each count, and
+    /// the percent columns are empty for languages that weren't in the baseline.
+    ///
+    /// # Parameters
+    /// - `writer` - Where to write the output
+    /// - `include_totals` - Whether to write a final row with the differences of the totals
+    pub fn display_csv(&self, writer: &mut impl std::io::Write, include_totals: bool) -> std::io::Result<()> {
+        let percent = |percent: Option<f64>| percent.map_or_else(String::new, |percent| format!("{percent:.2}"));
+        writeln!(
+            writer,
+            "language,status,files,lines,bytes,files_added,files_removed,lines_added,lines_removed,bytes_added,bytes_removed,files_percent,lines_percent,bytes_percent"
+        )?;
+        for language in &self.languages {
+            writeln!(
+                writer,
+                "{},{},{},{},{},{},{},{},{},{},{},{},{},{}",
+                csv_field(&language.name),
+                language.status.label(),
+                language.delta.files,
+                language.delta.lines,
+                language.delta.bytes,
+                language.delta.files_added,
+                language.delta.files_removed,
+                language.delta.lines_added,
+                language.delta.lines_removed,
+                language.delta.bytes_added,
+                language.delta.bytes_removed,
+                percent(language.delta.files_percent),
+                percent(language.delta.lines_percent),
+                percent(language.delta.bytes_percent),
+            )?;
+        }
+        if include_totals {
+            writeln!(
+                writer,
+                "Total,,{},{},{},{},{},{},{},{},{},{},{},{}",
+                self.totals.files,
+                self.totals.lines,
+                self.totals.bytes,
+                self.totals.files_added,
+                self.totals.files_removed,
+                self.totals.lines_added,
+                self.totals.lines_removed,
+                self.totals.bytes_added,
+                self.totals.bytes_removed,
+                percent(self.totals.files_percent),
+                percent(self.totals.lines_percent),
+                percent(self.totals.bytes_percent),
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Writes the differences of each language as a GitHub-flavored Markdown table, followed by a
+    /// bold row with the differences of the totals. Each count is shown as what was added and
+    /// what was removed, such as `+1200 / -300`, followed by the percent it changed by from the
+    /// baseline, unless the baseline didn't have the language.
+    pub fn display_markdown(&self, writer: &mut impl std::io::Write) -> std::io::Result<()> {
+        writeln!(writer, "| Language | Change | Files | Lines | Bytes |")?;
+        writeln!(writer, "|:---------|:-------|------:|------:|------:|")?;
+        for language in &self.languages {
+            writeln!(
+                writer,
+                "| {} | {} | +{} / -{}{} | +{} / -{}{} | +{} / -{}{} |",
+                markdown_escape(&language.name),
+                language.status.label(),
+                language.delta.files_added,
+                language.delta.files_removed,
+                format_change(language.delta.files_percent),
+                language.delta.lines_added,
+                language.delta.lines_removed,
+                format_change(language.delta.lines_percent),
+                language.delta.bytes_added,
+                language.delta.bytes_removed,
+                format_change(language.delta.bytes_percent),
+            )?;
+        }
+        writeln!(
+            writer,
+            "| **Total** | | **+{} / -{}**{} | **+{} / -{}**{} | **+{} / -{}**{} |",
+            self.totals.files_added,
+            self.totals.files_removed,
+            format_change(self.totals.files_percent),
+            self.totals.lines_added,
+            self.totals.lines_removed,
+            format_change(self.totals.lines_percent),
+            self.totals.bytes_added,
+            self.totals.bytes_removed,
+            format_change(self.totals.bytes_percent),
+        )
+    }
+}
+
+impl crate::FileReport<'_> {
+    /// Writes this file as a `file` record of JSON Lines, see `LanguageList::display_jsonl`.
+    pub fn display_jsonl(&self, writer: &mut impl std::io::Write) -> std::io::Result<()> {
+        write_record(writer, "file", self)
+    }
+}
+
+impl crate::KnownLanguages {
+    /// Writes the languages as a human-readable table, with the category, color, and what each
+    /// language is detected by. Lists of extensions, file names, and interpreters are separated
+    /// by commas, and empty ones are written as `-`.
+    pub fn display(&self, writer: &mut impl std::io::Write, style: DisplayStyle) -> std::io::Result<()> {
+        let list = |items: &[String]| {
+            if items.is_empty() {
+                "-".to_owned()
+            } else {
+                items.join(", ")
+            }
+        };
+        let column = |header, priority, cells| Column {
+            header,
+            right_aligned: false,
+            dimmed: false,
+            priority,
+            cells,
+        };
+        let columns = vec![
+            column(
+                "Language",
+                0,
+                self.languages.iter().map(|language| language.name.to_owned()).collect(),
+            ),
+            column(
+                "Category",
+                2,
+                self.languages.iter().map(|language| language.category.label().to_owned()).collect(),
+            ),
+            column(
+                "Color",
+                1,
+                self.languages.iter().map(|language| language.color.unwrap_or("-").to_owned()).collect(),
+            ),
+            column(
+                "Extensions",
+                0,
+                self.languages.iter().map(|language| list(&language.extensions)).collect(),
+            ),
+            column(
+                "Filenames",
+                0,
+                self.languages.iter().map(|language| list(&language.filenames)).collect(),
+            ),
+            column(
+                "Interpreters",
+                0,
+                self.languages.iter().map(|language| list(&language.interpreters)).collect(),
+            ),
+        ];
+        let names = self.languages.iter().map(|language| language.name).collect::<Vec<_>>();
+        let style = DisplayStyle { bars: false, ..style };
+        write_table(writer, columns, &names, &vec![0.0; names.len()], &[], style)
+    }
+
+    /// Writes the languages as a single line of JSON.
+    pub fn display_json(&self, writer: &mut impl std::io::Write) -> std::io::Result<()> {
+        serde_json::to_writer(&mut *writer, self)?;
+        writeln!(writer)
+    }
+
+    /// Writes the languages as YAML.
+    pub fn display_yaml(&self, writer: &mut impl std::io::Write) -> std::io::Result<()> {
+        serde_yaml::to_writer(&mut *writer, self).map_err(std::io::Error::other)?;
+        writeln!(writer)
+    }
+
+    /// Writes the languages as JSON Lines, with a `language` record for each language.
+    pub fn display_jsonl(&self, writer: &mut impl std::io::Write) -> std::io::Result<()> {
+        for language in &self.languages {
+            write_record(writer, "language", language)?;
+        }
+        Ok(())
+    }
+
+    /// Writes the languages as TOML.
+    pub fn display_toml(&self, writer: &mut impl std::io::Write) -> std::io::Result<()> {
+        write_toml(writer, self)
+    }
+
+    /// Writes the languages as XML, with a `<database>` root element.
+    pub fn display_xml(&self, writer: &mut impl std::io::Write) -> std::io::Result<()> {
+        crate::xml::write_xml(writer, "database", self)
+    }
+
+    /// Writes the languages as CSV, with a header row followed by one row per language. Lists of
+    /// extensions, file names, and interpreters are separated by spaces.
+    pub fn display_csv(&self, writer: &mut impl std::io::Write) -> std::io::Result<()> {
+        writeln!(writer, "language,category,color,extensions,filenames,interpreters")?;
+        for language in &self.languages {
+            writeln!(
+                writer,
+                "{},{},{},{},{},{}",
+                csv_field(language.name),
+                language.category.label(),
+                language.color.unwrap_or_default(),
+                csv_field(&language.extensions.join(" ")),
+                csv_field(&language.filenames.join(" ")),
+                csv_field(&language.interpreters.join(" ")),
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Writes the languages as a GitHub-flavored Markdown table, with extensions, file names, and
+    /// interpreters written as code.
+    pub fn display_markdown(&self, writer: &mut impl std::io::Write) -> std::io::Result<()> {
+        let list = |items: &[String]| items.iter().map(|item| format!("`{item}`")).collect::<Vec<_>>().join(", ");
+        writeln!(
+            writer,
+            "| Language | Category | Color | Extensions | Filenames | Interpreters |"
+        )?;
+        writeln!(
+            writer,
+            "|:---------|:---------|:------|:-----------|:----------|:-------------|"
+        )?;
+        for language in &self.languages {
+            writeln!(
+                writer,
+                "| {} | {} | {} | {} | {} | {} |",
+                markdown_escape(language.name),
+                language.category.label(),
+                language.color.unwrap_or_default(),
+                list(&language.extensions),
+                list(&language.filenames),
+                list(&language.interpreters),
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// A metric family of the Prometheus output, see `LanguageList::display_prometheus`.
+struct Metric {
+    /// The name of the metric. Families starting with `splik_language_` have a metric for each
+    /// language, and those starting with `splik_total_` have a single metric with the totals.
+    name: &'static str,
+    /// The text of the `# HELP` line of the family.
+    help: &'static str,
+    /// The count of the language or totals that the metric holds.
+    value: fn(&Totals) -> u64,
+    /// Whether the metric holds a count of the tests of the language or totals instead, in which
+    /// case the family is only written when test files were counted separately.
+    tests: bool,
+}
+
+/// The metric families of the Prometheus output, in the order they are written.
+const METRICS: &[Metric] = &[
+    Metric {
+        name: "splik_language_bytes",
+        help: "The number of bytes of each language.",
+        value: |totals| totals.bytes,
+        tests: false,
+    },
+    Metric {
+        name: "splik_language_lines",
+        help: "The number of lines of each language.",
+        value: |totals| totals.lines,
+        tests: false,
+    },
+    Metric {
+        name: "splik_language_code_lines",
+        help: "The number of lines of code of each language.",
+        value: |totals| totals.code,
+        tests: false,
+    },
+    Metric {
+        name: "splik_language_comment_lines",
+        help: "The number of comment lines of each language.",
+        value: |totals| totals.comments,
+        tests: false,
+    },
+    Metric {
+        name: "splik_language_blank_lines",
+        help: "The number of blank lines of each language.",
+        value: |totals| totals.blanks,
+        tests: false,
+    },
+    Metric {
+        name: "splik_language_files",
+        help: "The number of files of each language.",
+        value: |totals| totals.files as u64,
+        tests: false,
+    },
+    Metric {
+        name: "splik_language_test_lines",
+        help: "The number of lines of the test files of each language.",
+        value: |totals| totals.lines,
+        tests: true,
+    },
+    Metric {
+        name: "splik_language_test_files",
+        help: "The number of test files of each language.",
+        value: |totals| totals.files as u64,
+        tests: true,
+    },
+    Metric {
+        name: "splik_total_bytes",
+        help: "The number of bytes of every language.",
+        value: |totals| totals.bytes,
+        tests: false,
+    },
+    Metric {
+        name: "splik_total_lines",
+        help: "The number of lines of every language.",
+        value: |totals| totals.lines,
+        tests: false,
+    },
+    Metric {
+        name: "splik_total_files",
+        help: "The number of files of every language.",
+        value: |totals| totals.files as u64,
+        tests: false,
+    },
+];
+
+/// Writes the metrics of the Prometheus output, see `LanguageList::display_prometheus`.
+///
+/// # Parameters
+/// - `writer` - Where to write the metrics
+/// - `sections` - The languages to write the metrics of, along with the value of their
+///   `directory` label, if they have one
+fn write_prometheus(
+    writer: &mut impl std::io::Write,
+    sections: &[(Option<&str>, &LanguageList)],
+) -> std::io::Result<()> {
+    let counts_tests = sections.iter().any(|(_, languages)| languages.test_totals().is_some());
+    for Metric {
+        name,
+        help,
+        value,
+        tests,
+    } in METRICS
+    {
+        if *tests && !counts_tests {
+            continue;
+        }
+        writeln!(writer, "# HELP {name} {help}")?;
+        writeln!(writer, "# TYPE {name} gauge")?;
+        for (directory, languages) in sections {
+            let directory_label = directory.map(|directory| ("directory", directory));
+            if name.starts_with("splik_total_") {
+                let labels = prometheus_labels(directory_label.into_iter());
+                writeln!(writer, "{name}{labels} {}", value(&languages.totals()))?;
+                continue;
+            }
+            for language_info in &languages.languages {
+                let mut totals = Totals::default();
+                match &language_info.tests {
+                    Some(language_tests) if *tests => totals.add_totals(language_tests),
+                    None if *tests => {}
+                    _ => totals.add(language_info),
+                }
+                let labels = prometheus_labels(directory_label.into_iter().chain([("language", language_info.name)]));
+                writeln!(writer, "{name}{labels} {}", value(&totals))?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Formats the labels of a Prometheus metric, such as `{language="Rust"}`, with their values
+/// escaped as the exposition format requires, or nothing if there are no labels.
+fn prometheus_labels<'a>(labels: impl Iterator<Item = (&'a str, &'a str)>) -> String {
+    let labels = labels
+        .map(|(name, value)| {
+            let value = value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n");
+            format!("{name}=\"{value}\"")
+        })
+        .collect::<Vec<_>>();
+    if labels.is_empty() {
+        String::new()
+    } else {
+        format!("{{{}}}", labels.join(","))
+    }
+}
+
+/// The color of badges of languages without a color in `COLORS`, which is the light gray of
+/// shields.io.
+const BADGE_COLOR: &str = "9f9f9f";
+
+/// A shields.io endpoint badge, see `LanguageList::display_badge`.
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Badge<'a> {
+    /// The version of the endpoint schema, which is always 1.
+    schema_version: u32,
+    label: &'a str,
+    message: String,
+    /// The hex code of the color of the message, without a `#`.
+    color: &'a str,
+}
+
+/// A record of the JSON Lines output, see `LanguageList::display_jsonl`.
+#[derive(serde::Serialize)]
+struct Record<'a, T> {
+    /// What the record is, such as `file` or `language`.
+    #[serde(rename = "type")]
+    kind: &'a str,
+    /// The fields of the record.
+    #[serde(flatten)]
+    record: T,
+}
+
+/// A `language` record of the JSON Lines output. This has the same fields as a `LanguageReport`,
+/// except that the files are only counted, since they have records of their own.
+#[derive(serde::Serialize)]
+struct LanguageRecord<'a> {
+    /// The directory of the language, for `DirectoryList::display_jsonl`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    directory: Option<&'a str>,
+    name: &'static str,
+    category: crate::Category,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    color: Option<&'static str>,
+    files: usize,
+    lines: u64,
+    code: u64,
+    comments: u64,
+    blanks: u64,
+    bytes: u64,
+    byte_percent: f64,
+    line_percent: f64,
+    file_percent: f64,
+    #[serde(skip_serializing_if = "std::collections::BTreeMap::is_empty")]
+    extensions: &'a std::collections::BTreeMap<String, Totals>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tests: Option<&'a Totals>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    stats: Option<&'a crate::FileStats>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    todos: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    style: Option<&'a crate::StyleStats>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    encodings: Option<&'a crate::EncodingStats>,
+}
+
+/// Returns whether the lines of some files weren't counted, see `ScanOptions::skip_lines`.
+fn lines_skipped(files: &[crate::FileReport<'_>]) -> bool {
+    !files.is_empty() && files.iter().all(|file| file.lines.is_none())
+}
+
+/// The names of the fields of reports that count lines, which are left out by `WithoutLines`.
+const LINE_FIELDS: &[&str] = &["lines", "code", "comments", "blanks", "line_percent"];
+
+/// A report that is serialized without the counts of lines of its languages and totals, for the
+/// scans of `ScanOptions::skip_lines`, which didn't count them. Reports whose lines were counted
+/// are serialized as they are.
+struct WithoutLines<'a, T> {
+    /// The report to serialize.
+    report: &'a T,
+    /// Whether to leave out the counts of lines.
+    lines_skipped: bool,
+}
+
+impl<T: serde::Serialize> serde::Serialize for WithoutLines<'_, T> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if !self.lines_skipped {
+            return self.report.serialize(serializer);
+        }
+        let mut value = serde_json::to_value(self.report).map_err(serde::ser::Error::custom)?;
+        remove_line_fields(&mut value);
+        serde::Serialize::serialize(&value, serializer)
+    }
+}
+
+/// Removes the counts of lines from every object in a value, see `WithoutLines`. Only numbers are
+/// removed, so that an extension named `lines` isn't.
+fn remove_line_fields(value: &mut serde_json::Value) {
+    match value {
+        serde_json::Value::Object(object) => {
+            object.retain(|key, value| !(value.is_number() && LINE_FIELDS.contains(&key.as_str())));
+            object.values_mut().for_each(remove_line_fields);
+        }
+        serde_json::Value::Array(values) => values.iter_mut().for_each(remove_line_fields),
+        _ => {}
+    }
+}
+
+/// Writes a record of the JSON Lines output on a line of its own.
+fn write_record(writer: &mut impl std::io::Write, kind: &str, record: &impl serde::Serialize) -> std::io::Result<()> {
+    serde_json::to_writer(&mut *writer, &Record { kind, record })?;
+    writeln!(writer)
+}
+
+/// Writes a report as a TOML document.
+fn write_toml(writer: &mut impl std::io::Write, report: &impl serde::Serialize) -> std::io::Result<()> {
+    let document = toml::to_string_pretty(report).map_err(std::io::Error::other)?;
+    write!(writer, "{document}")
+}
+
+/// The number of extensions listed by `LanguageList::display_unknown`.
+const UNKNOWN_SHOWN: usize = 10;
+
+/// Writes the table of `LanguageList::display_unknown`.
+fn write_unknown(
+    writer: &mut impl std::io::Write,
+    extensions: &[crate::UnknownExtension],
+    style: DisplayStyle,
+) -> std::io::Result<()> {
+    if extensions.is_empty() {
+        return Ok(());
+    }
+    let shown = &extensions[..extensions.len().min(UNKNOWN_SHOWN)];
+    let names = shown
+        .iter()
+        .map(|extension| match extension.extension.as_str() {
+            crate::NO_EXTENSION => crate::NO_EXTENSION.to_owned(),
+            name => format!(".{name}"),
+        })
+        .collect::<Vec<_>>();
+    let columns = vec![
+        Column {
+            header: "Unknown extension",
+            right_aligned: false,
+            dimmed: false,
+            priority: 0,
+            cells: names.clone(),
+        },
+        Column {
+            header: "Files",
+            right_aligned: true,
+            dimmed: false,
+            priority: 0,
+            cells: shown.iter().map(|extension| style.count(extension.files as u64)).collect(),
+        },
+        Column {
+            header: "Bytes",
+            right_aligned: true,
+            dimmed: false,
+            priority: 0,
+            cells: shown.iter().map(|extension| style.size(extension.bytes)).collect(),
+        },
+    ];
+    writeln!(writer)?;
+    let names = names.iter().map(String::as_str).collect::<Vec<_>>();
+    let style = DisplayStyle { bars: false, ..style };
+    write_table(writer, columns, &names, &vec![0.0; names.len()], &[], style)?;
+    let hidden = extensions.len() - shown.len();
+    if hidden != 0 {
+        writeln!(
+            writer,
+            "and {hidden} more extension{}, listed in the JSON output",
+            if hidden == 1 { "" } else { "s" }
+        )?;
+    }
+    Ok(())
+}
+
+/// Writes a note that the scan was limited by depth, if it was, so that the counts aren't mistaken
+/// for those of the whole directory.
+fn write_depth_note(writer: &mut impl std::io::Write, depth_limited: bool) -> std::io::Result<()> {
+    if depth_limited {
+        writeln!(
+            writer,
+            "Note: directories deeper than the maximum depth weren't scanned"
+        )?;
+    }
+    Ok(())
+}
+
+/// Writes some differences on a single line, starting with the given label, with what was added
+/// and what was removed of each count, such as `+1,200 / -300 lines`, followed by the change of
+/// the count as a percent of the baseline.
+fn write_delta(
+    writer: &mut impl std::io::Write,
+    label: &str,
+    delta: &Delta,
+    style: DisplayStyle,
+) -> std::io::Result<()> {
+    let count = |added: u64, removed: u64| {
+        if style.human_sizes {
+            format!("+{} / -{}", format_count(added), format_count(removed))
+        } else {
+            format!("+{added} / -{removed}")
+        }
+    };
+    let bytes = if style.human_sizes {
+        format!(
+            "+{} / -{}",
+            format_size(delta.bytes_added),
+            format_size(delta.bytes_removed)
+        )
+    } else {
+        format!("+{} / -{} bytes", delta.bytes_added, delta.bytes_removed)
+    };
+    writeln!(
+        writer,
+        "{}: {}{}, {} lines{}, {} files{}",
+        label,
+        bytes,
+        format_change(delta.bytes_percent),
+        count(delta.lines_added, delta.lines_removed),
+        format_change(delta.lines_percent),
+        count(delta.files_added, delta.files_removed),
+        format_change(delta.files_percent),
+    )
+}
+
+/// Formats the change of a count as a percent in parentheses, with its sign and one decimal place,
+/// such as ` (+12.5%)`, or as nothing if there was nothing to compare against.
+fn format_change(percent: Option<f64>) -> String {
+    percent.map_or_else(String::new, |percent| format!(" ({percent:+.1}%)"))
+}
+
+/// Writes some totals on a single line, starting with the given label, formatted as described by
+/// `style`, and in bold with `DisplayStyle::color`. The counts of lines are left out if `lines`
+/// isn't set.
+fn write_totals(
+    writer: &mut impl std::io::Write,
+    label: &str,
+    totals: &Totals,
+    lines: bool,
+    style: DisplayStyle,
+) -> std::io::Result<()> {
+    let bytes = if style.human_sizes {
+        format_size(totals.bytes)
+    } else {
+        format!("{} bytes", totals.bytes)
+    };
+    if !lines {
+        let line = format!("{label}: {bytes}, {} files", style.count(totals.files as u64));
+        return writeln!(writer, "{}", paint(&line, BOLD, style.color));
+    }
+    let line = format!(
+        "{}: {}, {} lines, {} files - {} code, {} comments, {} blanks",
+        label,
+        bytes,
+        style.count(totals.lines),
+        style.count(totals.files as u64),
+        style.count(totals.code),
+        style.count(totals.comments),
+        style.count(totals.blanks),
+    );
+    writeln!(writer, "{}", paint(&line, BOLD, style.color))
+}
+
+/// Returns the name of a line ending, as it is shown in the human-readable output.
+fn line_ending_name(line_ending: crate::LineEnding) -> &'static str {
+    match line_ending {
+        crate::LineEnding::Lf => "LF",
+        crate::LineEnding::Crlf => "CRLF",
+        crate::LineEnding::Mixed => "mixed",
+    }
+}
+
+/// Writes the line endings and indentation of the files on a single line, if they were tallied,
+/// see `LanguageList::style_totals`.
+fn write_style_totals(
+    writer: &mut impl std::io::Write,
+    stats: Option<crate::StyleStats>,
+    style: DisplayStyle,
+) -> std::io::Result<()> {
+    let Some(stats) = stats else {
+        return Ok(());
+    };
+    writeln!(
+        writer,
+        "Style: {} LF lines, {} CRLF lines, {} files with mixed endings - {} files indented with tabs, {} with spaces",
+        style.count(stats.lf_lines),
+        style.count(stats.crlf_lines),
+        style.count(stats.mixed_ending_files as u64),
+        style.count(stats.tab_indented_files as u64),
+        style.count(stats.space_indented_files as u64),
+    )
+}
+
+/// Returns the name of an encoding as it is shown in the human-readable output.
+fn encoding_name(encoding: crate::Encoding) -> &'static str {
+    match encoding {
+        crate::Encoding::Utf8 => "UTF-8",
+        crate::Encoding::Utf16Le => "UTF-16LE",
+        crate::Encoding::Utf16Be => "UTF-16BE",
+        crate::Encoding::Other => "other",
+    }
+}
+
+/// Writes the encodings of the files on a single line, if they were tallied, see
+/// `LanguageList::encoding_totals`.
+fn write_encoding_totals(
+    writer: &mut impl std::io::Write,
+    encodings: Option<crate::EncodingStats>,
+    style: DisplayStyle,
+) -> std::io::Result<()> {
+    let Some(encodings) = encodings else {
+        return Ok(());
+    };
+    writeln!(
+        writer,
+        "Encodings: {} UTF-8 files, {} UTF-16 files, {} other files",
+        style.count(encodings.utf8_files as u64),
+        style.count(encodings.utf16_files as u64),
+        style.count(encodings.other_files as u64),
+    )
+}
+
+/// Returns the headers of the columns of the tests of the CSV output, with a leading comma, or
+/// nothing if test files weren't counted separately.
+fn csv_test_headers(tests: bool) -> &'static str {
+    if tests {
+        ",test_files,test_lines,test_bytes"
+    } else {
+        ""
+    }
+}
+
+/// Writes the totals of the test files, if they were counted separately, in the format of
+/// `write_totals`.
+fn write_test_totals(
+    writer: &mut impl std::io::Write,
+    tests: Option<Totals>,
+    lines: bool,
+    style: DisplayStyle,
+) -> std::io::Result<()> {
+    match tests {
+        Some(tests) => write_totals(writer, "Tests", &tests, lines, style),
+        None => Ok(()),
+    }
+}
+
+/// How the human-readable output is laid out and formatted, see `LanguageList::display`.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct DisplayStyle {
+    /// Whether to color the output with ANSI escape codes, writing the name of each language in
+    /// its color from `COLORS`, the percentages dimmed, and the totals in bold.
+    pub color: bool,
+    /// Whether to end each row with a bar proportional to the language's share of the bytes.
+    pub bars: bool,
+    /// The width of the terminal the table is written to. When the table is wider, the bars are
+    /// shortened first, then left out, and then the columns of the least important counts are
+    /// left out. If this is `None`, the table is written in full.
+    pub width: Option<usize>,
+    /// Whether to write sizes with binary prefixes, such as `46.0 MiB`, and counts with thousands
+    /// separators, such as `1,024`, instead of as plain numbers.
+    pub human_sizes: bool,
+}
+
+impl DisplayStyle {
+    /// Formats a number of bytes, with a binary prefix if `human_sizes` is set.
+    fn size(self, bytes: u64) -> String {
+        if self.human_sizes {
+            format_size(bytes)
+        } else {
+            bytes.to_string()
+        }
+    }
+
+    /// Formats a count, with thousands separators if `human_sizes` is set.
+    fn count(self, count: u64) -> String {
+        if self.human_sizes {
+            format_count(count)
+        } else {
+            count.to_string()
+        }
+    }
+}
+
+/// What the slices of the Mermaid output are sized by, see `LanguageList::display_mermaid`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+pub enum MermaidValues {
+    /// The number of bytes of each language.
+    #[default]
+    Bytes,
+    /// The percent of the total bytes of each language, rounded so that they add up to 100.
+    Percent,
+}
+
+/// A column of the human-readable table, see `write_table`.
+struct Column {
+    /// The name of the column in the header row.
+    header: &'static str,
+    /// Whether the cells are aligned to the right, as numbers are.
+    right_aligned: bool,
+    /// Whether the cells are dimmed when the table is colored.
+    dimmed: bool,
+    /// How readily the column is left out of a table that is too wide, where columns with higher
+    /// priorities are left out first, and columns with a priority of 0 are never left out.
+    priority: u8,
+    /// The text of each row.
+    cells: Vec<String>,
+}
+
+impl Column {
+    /// Returns the width of the widest cell of this column, including its header.
+    fn width(&self) -> usize {
+        self.cells
+            .iter()
+            .map(|cell| cell.chars().count())
+            .chain([self.header.len()])
+            .max()
+            .unwrap()
+    }
+}
+
+/// The number of spaces between the columns of the human-readable table.
+const COLUMN_GAP: usize = 2;
+
+/// The width of the bars of the human-readable table, when there is room for them.
+const BAR_WIDTH: usize = 20;
+
+/// The width below which bars are left out instead of being shortened.
+const MIN_BAR_WIDTH: usize = 5;
+
+/// Writes rows of languages as a table with a header row, with the columns padded to line up,
+/// fitting the table into the width of `style` as described by `DisplayStyle::width`.
+///
+/// # Parameters
+/// - `writer` - Where to write the table
+/// - `columns` - The columns of the table, the first of which is the names of the languages
+/// - `names` - The name of the language of each row, for its color
+/// - `byte_percents` - The percent of the bytes of each row, for its bar
+/// - `breaks` - The rows that are preceded by a blank line
+/// - `style` - How the table is laid out
+fn write_table(
+    writer: &mut impl std::io::Write,
+    mut columns: Vec<Column>,
+    names: &[&str],
+    byte_percents: &[f64],
+    breaks: &[usize],
+    style: DisplayStyle,
+) -> std::io::Result<()> {
+    let table_width = |columns: &[Column]| {
+        columns.iter().map(Column::width).sum::<usize>() + COLUMN_GAP * columns.len().saturating_sub(1)
+    };
+
+    // Shorten the bars, and then leave out columns, until the table fits
+    let mut bar_width = style.bars.then_some(BAR_WIDTH);
+    if let Some(width) = style.width {
+        bar_width = bar_width
+            .map(|bar_width| bar_width.min(width.saturating_sub(table_width(&columns) + COLUMN_GAP)))
+            .filter(|bar_width| *bar_width >= MIN_BAR_WIDTH);
+        while table_width(&columns) > width {
+            let Some(dropped) = (0..columns.len())
+                .filter(|index| columns[*index].priority != 0)
+                .max_by_key(|index| (columns[*index].priority, *index))
+            else {
+                break;
+            };
+            columns.remove(dropped);
+        }
+    }
+    let widths = columns.iter().map(Column::width).collect::<Vec<_>>();
+
+    // Write the header, and then each row
+    let header = columns
+        .iter()
+        .zip(&widths)
+        .map(|(column, width)| pad(column.header, column.header, *width, column.right_aligned))
+        .collect::<Vec<_>>()
+        .join(&" ".repeat(COLUMN_GAP));
+    writeln!(writer, "{}", header.trim_end())?;
+    for (index, name) in names.iter().enumerate() {
+        if breaks.contains(&index) {
+            writeln!(writer)?;
+        }
+        let mut cells = columns
+            .iter()
+            .zip(&widths)
+            .enumerate()
+            .map(|(column_index, (column, width))| {
+                let text = &column.cells[index];
+                let painted = if column_index == 0 {
+                    paint_language(name, style.color)
+                } else if column.dimmed {
+                    paint(text, DIM, style.color)
+                } else {
+                    text.clone()
+                };
+                pad(text, &painted, *width, column.right_aligned)
+            })
+            .collect::<Vec<_>>();
+        if let Some(bar_width) = bar_width {
+            let filled = ((byte_percents[index] / 100.0 * bar_width as f64).round() as usize).min(bar_width);
+            let bar = "█".repeat(filled);
+            cells.push(format!(
+                "{}{}",
+                paint_language_text(name, &bar, style.color),
+                "░".repeat(bar_width - filled)
+            ));
+        }
+        writeln!(writer, "{}", cells.join(&" ".repeat(COLUMN_GAP)).trim_end())?;
+    }
+    Ok(())
+}
+
+/// Pads a cell of a table to the given width, measuring the width of its plain text, so that
+/// escape codes in the painted text don't count towards it.
+fn pad(text: &str, painted: &str, width: usize, right_aligned: bool) -> String {
+    let padding = " ".repeat(width.saturating_sub(text.chars().count()));
+    if right_aligned {
+        format!("{padding}{painted}")
+    } else {
+        format!("{painted}{padding}")
+    }
+}
+
+/// The ANSI escape code of bold text.
+const BOLD: &str = "\x1b[1m";
+
+/// The ANSI escape code of dimmed text.
+const DIM: &str = "\x1b[2m";
+
+/// The ANSI escape code that resets the style of text.
+const RESET: &str = "\x1b[0m";
+
+/// Wraps some text in an ANSI escape code and a reset, or returns it as it is without `color`.
+fn paint(text: &str, code: &str, color: bool) -> String {
+    if color {
+        format!("{code}{text}{RESET}")
+    } else {
+        text.to_owned()
+    }
+}
+
+/// Returns the name of a language in its color from `COLORS` with `color`, as a 24-bit ANSI color.
+/// Languages without a color, such as "Other", are written as they are.
+fn paint_language(name: &str, color: bool) -> String {
+    paint_language_text(name, name, color)
+}
+
+/// Returns some text in the color of a language, see `paint_language`.
+fn paint_language_text(name: &str, text: &str, color: bool) -> String {
+    let rgb = crate::language_color(name).and_then(|hex| {
+        let hex = hex.strip_prefix('#')?;
+        let channel = |index: usize| u8::from_str_radix(hex.get(index..index + 2)?, 16).ok();
+        Some((channel(0)?, channel(2)?, channel(4)?))
+    });
+    match rgb {
+        Some((red, green, blue)) => paint(text, &format!("\x1b[38;2;{red};{green};{blue}m"), color),
+        None => text.to_owned(),
+    }
+}
+
+/// Calculates what percent each value is of the sum of all values, rounded for display. The
+/// percentages are rounded to whole numbers with the largest remainder method, so that they add
+/// up to exactly 100. Values that round down to 0% keep their exact percentage, so that they can
+/// be displayed with decimal places instead of as 0%.
+///
+/// # Parameters
+/// - `values` - The values to calculate the percentages of
+///
+/// # Returns
+/// - The percentage of each value, in the same order as `values`.
+fn round_percentages(values: &[f64]) -> Vec<f64> {
+    let total = values.iter().sum::<f64>();
+    let exact = values.iter().map(|value| percent(*value, total)).collect::<Vec<_>>();
+    let mut rounded = exact.iter().map(|percent| percent.floor()).collect::<Vec<_>>();
+
+    // Give the remaining points to the percentages with the largest remainders
+    if total != 0.0 {
+        let remaining = 100 - rounded.iter().sum::<f64>() as usize;
+        let mut by_remainder = (0..values.len()).collect::<Vec<_>>();
+        by_remainder
+            .sort_by(|index, other| (exact[*other] - rounded[*other]).total_cmp(&(exact[*index] - rounded[*index])));
+        for index in by_remainder.into_iter().take(remaining) {
+            rounded[index] += 1.0;
+        }
+    }
+
+    rounded
+        .into_iter()
+        .zip(exact)
+        .map(|(rounded, exact)| if rounded == 0.0 { exact } else { rounded })
+        .collect()
+}
+
+/// Quotes a CSV field as described in RFC 4180 if it contains a comma, quote, or newline.
+fn csv_field(field: &str) -> std::borrow::Cow<'_, str> {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\"")).into()
+    } else {
+        field.into()
+    }
+}
+
+/// Escapes the characters of some text that would break a Markdown table cell or be interpreted as
+/// formatting, such as the `#` in `C#` or a `|`.
+fn markdown_escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for character in text.chars() {
+        if matches!(character, '\\' | '|' | '#' | '*' | '_' | '`' | '<' | '>' | '[' | ']') {
+            escaped.push('\\');
+        }
+        escaped.push(character);
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    /// Returns the languages of a Rust file and a Python file, sorted by bytes.
+    fn languages() -> crate::LanguageList {
+        let options = crate::ScanOptions {
+            extension_breakdown: true,
+            todo_markers: vec!["TODO".to_owned()],
+            ..crate::ScanOptions::default()
+        };
+        let mut languages = crate::LanguageList::default();
+        let files = [
+            ("src/a.rs", "Rust", "fn main() {}\n// TODO\n"),
+            ("b.py", "Python", "x = 1\n\n"),
+        ];
+        for (name, language, contents) in files {
+            languages
+                .add_contents_as(name.to_owned(), false, language, contents.as_bytes(), &options)
+                .unwrap();
+        }
+        languages.compute_stats();
+        languages.sort(crate::SortKey::Bytes, false);
+        languages
+    }
+
+    /// Returns the output written by `display`, as text.
+    fn written(display: impl FnOnce(&mut Vec<u8>) -> std::io::Result<()>) -> String {
+        let mut output = Vec::new();
+        display(&mut output).unwrap();
+        String::from_utf8(output).unwrap()
+    }
+
+    /// Removes the `null` fields of a JSON value, which TOML can't represent.
+    fn without_nulls(value: &mut serde_json::Value) {
+        match value {
+            serde_json::Value::Object(fields) => {
+                fields.retain(|_, field| !field.is_null());
+                fields.values_mut().for_each(without_nulls);
+            }
+            serde_json::Value::Array(items) => items.iter_mut().for_each(without_nulls),
+            _ => {}
+        }
+    }
+
+    #[test]
+    fn toml_report_matches_json_report() {
+        let languages = languages();
+        let toml = written(|writer| languages.display_toml(writer, None));
+        let json = written(|writer| languages.display_json(writer, None));
+        let toml = toml::from_str::<serde_json::Value>(&toml).unwrap();
+        let mut json = serde_json::from_str::<serde_json::Value>(&json).unwrap();
+        without_nulls(&mut json);
+        assert_eq!(toml, json);
+        assert_eq!(toml["languages"][0]["files"][0], "src/a.rs");
+    }
+
+    #[test]
+    fn toml_directory_report_matches_json_report() {
+        let mut directories = crate::DirectoryList::default();
+        directories.directories.insert("src".to_owned(), languages());
+        directories.directories.insert(".".to_owned(), languages());
+        let toml = written(|writer| directories.display_toml(writer, None));
+        let json = written(|writer| directories.display_json(writer, None));
+        let toml = toml::from_str::<serde_json::Value>(&toml).unwrap();
+        let mut json = serde_json::from_str::<serde_json::Value>(&json).unwrap();
+        without_nulls(&mut json);
+        assert_eq!(toml, json);
+    }
+
+    #[test]
+    fn xml_report_layout() {
+        let mut languages = crate::LanguageList::default();
+        let options = crate::ScanOptions::default();
+        languages
+            .add_contents_as("a.rs".to_owned(), false, "Rust", b"fn main() {}\n", &options)
+            .unwrap();
+        let xml = written(|writer| languages.display_xml(writer, None));
+        let expected = r##"<?xml version="1.0" encoding="UTF-8"?>
+<report>
+  <schema_version>2</schema_version>
+  <languages>
+    <language name="Rust">
+      <category>programming</category>
+      <file_count>1</file_count>
+      <files>
+        <file>a.rs</file>
+      </files>
+      <lines>1</lines>
+      <code>1</code>
+      <comments>0</comments>
+      <blanks>0</blanks>
+      <bytes>13</bytes>
+      <color>#dea584</color>
+      <byte_percent>100.0</byte_percent>
+      <line_percent>100.0</line_percent>
+      <file_percent>100.0</file_percent>
+    </language>
+  </languages>
+  <totals>
+    <files>1</files>
+    <lines>1</lines>
+    <code>1</code>
+    <comments>0</comments>
+    <blanks>0</blanks>
+    <bytes>13</bytes>
+  </totals>
+</report>
+"##;
+        assert_eq!(xml, expected);
+    }
+}
@@ -0,0 +1,61 @@
+//! Counting the progress of a scan while it runs, such as to show that a slow scan isn't stuck.
+
+use std::sync::atomic::Ordering;
+
+/// The progress of a scan, updated as it runs, see `ScanOptions::progress`. Counters are updated
+/// from the threads of the scan with relaxed atomics, so they can be read at any time from another
+/// thread, but a reading may be a moment behind.
+#[derive(Debug, Default)]
+pub struct ScanProgress {
+    /// The number of directories that have been read.
+    directories: std::sync::atomic::AtomicU64,
+    /// The number of files that have been counted.
+    files: std::sync::atomic::AtomicU64,
+    /// The number of bytes of the files that have been counted.
+    bytes: std::sync::atomic::AtomicU64,
+}
+
+impl ScanProgress {
+    /// Returns the number of directories that have been read.
+    pub fn directories(&self) -> u64 {
+        self.directories.load(Ordering::Relaxed)
+    }
+
+    /// Returns the number of files that have been counted.
+    pub fn files(&self) -> u64 {
+        self.files.load(Ordering::Relaxed)
+    }
+
+    /// Returns the number of bytes of the files that have been counted.
+    pub fn bytes(&self) -> u64 {
+        self.bytes.load(Ordering::Relaxed)
+    }
+
+    /// Counts a directory that has been read.
+    pub(crate) fn add_directory(&self) {
+        self.directories.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Counts a file of the given size that has been counted.
+    pub(crate) fn add_file(&self, bytes: u64) {
+        self.files.fetch_add(1, Ordering::Relaxed);
+        self.bytes.fetch_add(bytes, Ordering::Relaxed);
+    }
+}
+
+impl std::fmt::Display for ScanProgress {
+    /// Formats the progress like the human-readable output, such as
+    /// `1,024 directories, 48,203 files, 46.0 MiB`.
+    fn fmt(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let (directories, files) = (self.directories(), self.files());
+        write!(
+            formatter,
+            "{} director{}, {} file{}, {}",
+            crate::format::format_count(directories),
+            if directories == 1 { "y" } else { "ies" },
+            crate::format::format_count(files),
+            if files == 1 { "" } else { "s" },
+            crate::format::format_size(self.bytes())
+        )
+    }
+}
@@ -0,0 +1,262 @@
+//! Detection of project root directories.
+
+/// Returns the root directory of the project that the given directory is located in, if one could
+/// be detected. This checks the given directory and each of its parents for the project root
+/// indicators in `ROOT_INDICATORS`, and picks the nearest directory with an indicator of the
+/// highest `RootTier` that was found, so that a `README.md` in a `docs` directory doesn't hide the
+/// `.git` of the repository above it. If the system root is reached and no directory was
+/// identified as a recognized project root, `None` is returned.
+///
+/// # Parameters
+///
+/// - `directory_path` - The path of the directory to start at. This should be a directory *inside*
+///   the project.
+///
+/// # Returns
+/// - The project root directory path, or `None` if none could be identified.
+pub fn get_root_dir(directory_path: &std::path::Path) -> Option<std::path::PathBuf> {
+    find_root(directory_path).map(|root| root.path)
+}
+
+/// Finds the root directory of the project that the given directory is located in, like
+/// `get_root_dir`, along with the indicator that identified it.
+///
+/// # Parameters
+/// - `directory_path` - The path of the directory to start at
+///
+/// # Returns
+/// - The project root, or `None` if none could be identified.
+pub fn find_root(directory_path: &std::path::Path) -> Option<ProjectRoot> {
+    RootIndicators::default().find_root(directory_path)
+}
+
+/// Finds the nearest directory that contains any of the `ROOT_INDICATORS`, regardless of their
+/// tiers, as `--nearest-root` does.
+///
+/// # Parameters
+/// - `directory_path` - The path of the directory to start at
+///
+/// # Returns
+/// - The project root, or `None` if none could be identified.
+pub fn find_nearest_root(directory_path: &std::path::Path) -> Option<ProjectRoot> {
+    RootIndicators::default().find_nearest_root(directory_path)
+}
+
+/// The root indicators that `find_root` searches for: custom indicators, such as a file that only
+/// some company's projects have, followed by the `ROOT_INDICATORS` unless they are left out.
+#[derive(Clone)]
+pub struct RootIndicators {
+    /// The custom indicators, along with the globs matching them.
+    custom: Vec<(String, globset::GlobMatcher)>,
+    /// Whether the `ROOT_INDICATORS` are searched for.
+    defaults: bool,
+}
+
+impl Default for RootIndicators {
+    fn default() -> Self {
+        Self {
+            custom: Vec::new(),
+            defaults: true,
+        }
+    }
+}
+
+impl RootIndicators {
+    /// Creates root indicators with the given custom indicators, which are in the
+    /// `RootTier::Custom` tier.
+    ///
+    /// # Parameters
+    /// - `custom` - The names of the custom indicators, which may be globs such as `*.workspace`
+    /// - `defaults` - Whether to search for the `ROOT_INDICATORS` too
+    ///
+    /// # Errors
+    /// Returns an error if a custom indicator is an invalid glob.
+    pub fn new(custom: &[String], defaults: bool) -> Result<Self, globset::Error> {
+        let custom = custom
+            .iter()
+            .map(|indicator| Ok((indicator.clone(), globset::Glob::new(indicator)?.compile_matcher())))
+            .collect::<Result<_, globset::Error>>()?;
+        Ok(Self { custom, defaults })
+    }
+
+    /// Finds the root directory of the project that the given directory is located in, see
+    /// `find_root`.
+    pub fn find_root(&self, directory_path: &std::path::Path) -> Option<ProjectRoot> {
+        let mut best: Option<ProjectRoot> = None;
+        for directory in directory_path.ancestors() {
+            let Some(found) = self.directory_root(directory) else {
+                continue;
+            };
+
+            // Nearer directories win ties, and nothing is stronger than custom indicators
+            if best.as_ref().is_none_or(|best| found.tier > best.tier) {
+                best = Some(found);
+            }
+            if best.as_ref().is_some_and(|best| best.tier == self.strongest_tier()) {
+                break;
+            }
+        }
+        best
+    }
+
+    /// Finds the root directory of the project that the given directory is located in, stopping
+    /// at the nearest build manifest instead of looking for a workspace or repository containing
+    /// it, such as a member crate of a Cargo workspace. If there is no build manifest, this is
+    /// the same as `find_root`.
+    pub fn find_project_root(&self, directory_path: &std::path::Path) -> Option<ProjectRoot> {
+        directory_path
+            .ancestors()
+            .filter_map(|directory| self.directory_root(directory))
+            .find(|root| root.tier >= RootTier::Manifest)
+            .or_else(|| self.find_root(directory_path))
+    }
+
+    /// Finds the nearest directory that contains any of these indicators, see
+    /// `find_nearest_root`.
+    pub fn find_nearest_root(&self, directory_path: &std::path::Path) -> Option<ProjectRoot> {
+        directory_path.ancestors().find_map(|directory| self.directory_root(directory))
+    }
+
+    /// Returns the tier of the strongest indicator that could be found, after which there is no
+    /// need to keep searching.
+    fn strongest_tier(&self) -> RootTier {
+        if self.custom.is_empty() {
+            RootTier::VersionControl
+        } else {
+            RootTier::Custom
+        }
+    }
+
+    /// Returns the strongest root indicator in a directory, as a project root at that directory.
+    fn directory_root(&self, directory: &std::path::Path) -> Option<ProjectRoot> {
+        // The last ancestor of a relative path is empty, which is the current directory
+        let directory = if directory.as_os_str().is_empty() {
+            std::path::Path::new(".")
+        } else {
+            directory
+        };
+        let root = |indicator: String, tier| ProjectRoot {
+            path: directory.to_owned(),
+            indicator,
+            tier,
+        };
+
+        // Custom indicators, where only globs need the directory to be read
+        for (indicator, matcher) in &self.custom {
+            if !indicator.contains(['*', '?', '[', '{']) {
+                if directory.join(indicator).exists() {
+                    return Some(root(indicator.clone(), RootTier::Custom));
+                }
+                continue;
+            }
+            let matched = std::fs::read_dir(directory)
+                .into_iter()
+                .flatten()
+                .flatten()
+                .map(|entry| entry.file_name().to_string_lossy().into_owned())
+                .find(|name| matcher.is_match(name));
+            if let Some(name) = matched {
+                return Some(root(name, RootTier::Custom));
+            }
+        }
+
+        if !self.defaults {
+            return None;
+        }
+        let found = ROOT_INDICATORS
+            .iter()
+            .filter(|(indicator, _)| directory.join(indicator).exists())
+            .max_by_key(|(_, tier)| *tier)
+            .map(|(indicator, tier)| root((*indicator).to_owned(), *tier));
+
+        // Build manifests that define a workspace are stronger than those of its members
+        if found.as_ref().is_some_and(|found| found.tier == RootTier::Manifest) {
+            let workspace = WORKSPACE_MANIFESTS.iter().find(|(manifest, marker)| {
+                std::fs::read_to_string(directory.join(manifest)).is_ok_and(|contents| contents.contains(marker))
+            });
+            if let Some((manifest, _)) = workspace {
+                return Some(root((*manifest).to_owned(), RootTier::Workspace));
+            }
+        }
+        found
+    }
+}
+
+/// Returns whether a directory is the root of a project of its own, because it has a build
+/// manifest or a version control marker, which `NestedProjects` uses to find nested projects.
+pub(crate) fn is_project_root(directory: &std::path::Path) -> bool {
+    RootIndicators::default()
+        .directory_root(directory)
+        .is_some_and(|root| root.tier >= RootTier::Manifest)
+}
+
+/// A project root directory found by `find_root`.
+#[derive(Clone, Debug)]
+pub struct ProjectRoot {
+    /// The path of the root directory.
+    pub path: std::path::PathBuf,
+    /// The name of the file or directory that identified the directory as the root, such as an
+    /// indicator in `ROOT_INDICATORS`.
+    pub indicator: String,
+    /// The tier of the indicator.
+    pub tier: RootTier,
+}
+
+/// How strongly a root indicator suggests that the directory containing it is a project root.
+/// Tiers are ordered from weakest to strongest.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum RootTier {
+    /// Files that many directories of a project may have, such as `README.md`.
+    Weak,
+    /// Build manifests, such as `Cargo.toml` or `package.json`.
+    Manifest,
+    /// Build manifests that define a workspace, such as a `Cargo.toml` with a `[workspace]`, or a
+    /// `pnpm-workspace.yaml`.
+    Workspace,
+    /// Version control markers, such as `.git`.
+    VersionControl,
+    /// Indicators given to `RootIndicators::new`, such as by `--root-indicator`.
+    Custom,
+}
+
+impl RootTier {
+    /// Returns how this tier is described by `--find-root --verbose`.
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Weak => "weak marker",
+            Self::Manifest => "build manifest",
+            Self::Workspace => "workspace manifest",
+            Self::VersionControl => "version control marker",
+            Self::Custom => "custom root indicator",
+        }
+    }
+}
+
+/// Files and directories that indicate that the directory containing them is a project root,
+/// along with how strongly they indicate it.
+pub const ROOT_INDICATORS: &[(&str, RootTier)] = &[
+    (".git", RootTier::VersionControl),
+    (".hg", RootTier::VersionControl),
+    (".svn", RootTier::VersionControl),
+    ("pnpm-workspace.yaml", RootTier::Workspace),
+    ("Cargo.toml", RootTier::Manifest),
+    ("package.json", RootTier::Manifest),
+    ("pyproject.toml", RootTier::Manifest),
+    ("go.mod", RootTier::Manifest),
+    ("build.zig", RootTier::Manifest),
+    ("tsconfig.json", RootTier::Manifest),
+    (".gitignore", RootTier::Weak),
+    ("node_modules", RootTier::Weak),
+    (".luarc.json", RootTier::Weak),
+    (".prettierrc", RootTier::Weak),
+    (".prettierrc.json", RootTier::Weak),
+    (".prettierrc.toml", RootTier::Weak),
+    ("README.md", RootTier::Weak),
+    ("README", RootTier::Weak),
+    ("LICENSE", RootTier::Weak),
+    ("index.html", RootTier::Weak),
+];
+
+/// Build manifests that define a workspace when they contain the given text, which makes them
+/// `RootTier::Workspace` indicators instead of `RootTier::Manifest` ones.
+const WORKSPACE_MANIFESTS: &[(&str, &str)] = &[("Cargo.toml", "[workspace]"), ("package.json", "\"workspaces\"")];
@@ -0,0 +1,1277 @@
+//! Scanning directories for source files.
+
+use rayon::iter::{IntoParallelIterator as _, IntoParallelRefIterator as _, ParallelIterator as _};
+
+use crate::timing::{Phase, PhaseTimer};
+
+/// Options controlling which files a scan counts, and how their languages are detected.
+#[derive(Default, Clone)]
+pub struct ScanOptions {
+    /// Include files and folders that begin with a dot (.), which are ignored by default.
+    pub include_dotfiles: bool,
+    /// Names of languages to exclude, which are matched with `language_matches`.
+    pub exclude: Vec<String>,
+    /// Names of the only languages to count, which are matched with `language_matches`. If this is
+    /// empty, every language that isn't excluded is counted.
+    pub only: Vec<String>,
+    /// Files and directories to include, which are excluded by default. Entries without a path
+    /// separator match any file or directory with that name, and entries with one match the path
+    /// relative to the scan root.
+    pub include: Vec<String>,
+    /// Paths to exclude, as globs relative to the scan root.
+    pub exclude_paths: globset::GlobSet,
+    /// Custom extension to language mappings, which take priority over `LANGUAGES`. Extensions
+    /// mapped to `None` aren't detected as any language, so their files aren't counted.
+    pub extension_map: std::collections::HashMap<String, Option<&'static str>>,
+    /// Split Vue and Svelte files into the languages of their `<script>` and `<style>` blocks,
+    /// instead of counting each of them as a whole. Each file is listed in the `files` of each of
+    /// its languages with the counts of its blocks in that language, but is only counted in the
+    /// `file_count` of the component's language.
+    pub split_components: bool,
+    /// Categories of languages to count. If this is empty, languages of every category are
+    /// counted.
+    pub categories: Vec<crate::Category>,
+    /// Read the GitHub linguist attributes of `.gitattributes` files, skipping files marked as
+    /// `linguist-vendored` or `linguist-generated` unless they are included, and counting files
+    /// with a `linguist-language` as that language.
+    pub gitattributes: bool,
+    /// How the paths of counted files are listed in `LanguageInfo::files`.
+    pub paths: PathStyle,
+    /// Follow symbolic links to files and directories, which are skipped by default. Each file and
+    /// directory is still only counted once, even if several links point to it.
+    pub follow_symlinks: bool,
+    /// The maximum depth of directories below the scan root to scan, where 0 only scans the files
+    /// directly in the root. Deeper directories aren't read at all. If this is `None`, there is no
+    /// limit.
+    pub max_depth: Option<usize>,
+    /// The maximum size of files to count, in bytes. Larger files are skipped without being read,
+    /// and counted in `LanguageList::skipped_large_files`. If this is `None`, there is no limit.
+    pub max_file_size: Option<u64>,
+    /// The size in bytes above which files are memory-mapped to count their lines, rather than
+    /// read through a buffer, which is faster for files of hundreds of megabytes. Files that can't
+    /// be mapped are read through a buffer instead, and the counts are the same either way. If
+    /// this is `None`, or splik is built without the `mmap` feature, files are never mapped.
+    pub mmap_threshold: Option<u64>,
+    /// Count files that look like binary data, such as a `.js` file that is actually a compiled
+    /// blob. By default, they are skipped and counted in `LanguageList::binary_skipped`.
+    pub count_binary: bool,
+    /// Count files that look generated or minified, such as `*.pb.go` files, files marked with
+    /// `@generated` or `DO NOT EDIT`, and minified bundles. By default, they are left out and
+    /// counted in `LanguageList::generated`.
+    pub include_generated: bool,
+    /// Tally the files whose language isn't detected by their extension in
+    /// `LanguageList::unknown`, such as to find the extensions that need a mapping. By default,
+    /// these files are skipped without being counted.
+    pub collect_unknown: bool,
+    /// Tally the files of each language by their extension in `LanguageInfo::extensions`, such as
+    /// to see how much of C++ is in `.cpp` files and how much is in `.cc` files.
+    pub extension_breakdown: bool,
+    /// What to do with test files, such as those in `tests` directories and `*_test.go` files,
+    /// which are counted like any other file by default.
+    pub tests: crate::TestFiles,
+    /// Paths of test files, as globs relative to the scan root, in addition to those found by the
+    /// conventions of common test frameworks. These are only checked when `tests` isn't
+    /// `TestFiles::Include`.
+    pub test_paths: globset::GlobSet,
+    /// Markers of tech debt to count in the lines of each file, such as `TODO` and `FIXME`, see
+    /// `TODO_MARKERS`. Markers are matched case-sensitively as whole words, including inside
+    /// strings, and are counted in `LanguageInfo::todos`. If this is empty, markers aren't counted.
+    pub todo_markers: Vec<String>,
+    /// Tally the line endings and indentation of each file in `LanguageInfo::style`, such as to
+    /// find the files with CRLF line endings or tab indentation.
+    pub style_stats: bool,
+    /// Tally the encoding of each file in `LanguageInfo::encodings`, such as to find the files
+    /// that are UTF-16 or Latin-1 rather than UTF-8. UTF-16 files are decoded whether or not this
+    /// is set.
+    pub encoding_stats: bool,
+    /// Count only the files and bytes of each language, by the metadata of each file, without
+    /// opening it to count its lines. This is much faster, but the counts of lines are left at 0,
+    /// see `LanguageList::lines_skipped`, and files aren't checked for being binary or generated by
+    /// their contents.
+    pub skip_lines: bool,
+    /// Don't keep the `FileInfo` of each file in `LanguageInfo::files`, only counting the files
+    /// in `LanguageInfo::file_count`, which saves the memory of a path per file on large trees.
+    /// Files are still passed to `ScanOptions::on_file`, but `LanguageList::find`,
+    /// `LanguageList::file_report`, `LanguageList::largest_files`, and
+    /// `LanguageList::compute_stats` find no files, and reports leave out the paths of files.
+    pub skip_paths: bool,
+    /// Names of directories to skip unless they are included. If this is `None`, the directories
+    /// in `IGNORED_DIRECTORIES` are skipped, along with the directories in `OUTPUT_DIRECTORIES`
+    /// that look like build output. If it is empty, no directories are skipped by name.
+    pub ignored_directories: Option<Vec<String>>,
+    /// What to do with the directories below the scan root that are projects of their own, such
+    /// as independent git repositories in a directory of repositories. This only applies to
+    /// `scan` and `scan_by_directory`.
+    pub nested_projects: NestedProjects,
+    /// The counts of files from previous scans, which are re-used for files that haven't changed
+    /// since, instead of reading them again. If this is `None`, every file is read.
+    pub cache: Option<std::sync::Arc<crate::FileCache>>,
+    /// Called with each file as soon as it is counted, such as to stream the files of a large scan
+    /// while it runs, instead of listing them once it is done. Files are counted in parallel, so
+    /// this may be called from several threads at once, and in any order.
+    pub on_file: Option<FileCallback>,
+    /// Counts the directories, files, and bytes of the scan as it runs, such as to show the
+    /// progress of a slow scan from another thread. If this is `None`, nothing is counted.
+    pub progress: Option<std::sync::Arc<crate::ScanProgress>>,
+    /// Measures the time that the threads of the scan spend reading directories, and reading and
+    /// counting files, such as to tell whether a scan is bound by the filesystem. If this is
+    /// `None`, nothing is measured.
+    pub timing: Option<std::sync::Arc<crate::ScanTiming>>,
+}
+
+/// A function called with each counted file, see `ScanOptions::on_file`.
+pub type FileCallback = std::sync::Arc<dyn Fn(&crate::FileReport<'_>) + Send + Sync>;
+
+/// What a scan does with nested projects, see `ScanOptions::nested_projects`. A directory is a
+/// nested project if it has a build manifest such as `Cargo.toml`, or a version control marker
+/// such as `.git`, see `ROOT_INDICATORS`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum NestedProjects {
+    /// Count nested projects like any other directory.
+    #[default]
+    Include,
+    /// Skip nested projects, listing them in `LanguageList::skipped_projects`.
+    Skip,
+    /// Count each nested project separately, under its path in the `DirectoryList` of
+    /// `scan_by_directory`, instead of under the directory it is in.
+    Separate,
+}
+
+/// How the paths of counted files are listed, see `ScanOptions::paths`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+pub enum PathStyle {
+    /// Relative to the scan root, such as `src/main.rs`.
+    #[default]
+    Relative,
+    /// Absolute, with the scan root canonicalized.
+    Absolute,
+}
+
+impl ScanOptions {
+    /// Returns whether files of the given language are counted, which is the case unless the
+    /// language is excluded, isn't one of the `only` languages, or isn't in one of the counted
+    /// categories. Languages are matched with `language_matches`.
+    pub fn counts_language(&self, language: &str) -> bool {
+        !self.exclude.iter().any(|excluded| crate::language_matches(language, excluded))
+            && (self.only.is_empty() || self.only.iter().any(|only| crate::language_matches(language, only)))
+            && (self.categories.is_empty() || self.categories.contains(&crate::language_category(language)))
+    }
+
+    /// Returns whether directories with the given name are always skipped unless they are
+    /// included, see `ignored_directories`. Directories in `OUTPUT_DIRECTORIES` aren't, since
+    /// whether they are skipped depends on what is next to them.
+    pub fn ignores_directory(&self, name: &str) -> bool {
+        match &self.ignored_directories {
+            Some(ignored_directories) => ignored_directories.iter().any(|ignored| ignored == name),
+            None => IGNORED_DIRECTORIES.contains(&name),
+        }
+    }
+}
+
+/// Directory names that are ignored by default, which are build outputs, dependencies, and
+/// vendored code.
+pub const IGNORED_DIRECTORIES: &[&str] = &[
+    "node_modules",
+    "bower_components",
+    "target",
+    "vendor",
+    "third_party",
+    "thirdparty",
+    "extern",
+    "deps",
+    "Pods",
+    ".yarn",
+    "__pycache__",
+    "venv",
+];
+
+/// Directory names that are commonly build output, but are also used for sources, such as the
+/// `public` assets of a Next.js project or the scripts in `bin`. These are only ignored by default
+/// when they look like build output: when a `package.json` or `Cargo.toml` next to them refers to
+/// them, when they are the `bin` or `obj` of a .NET project next to them (see
+/// `DOTNET_OUTPUT_DIRECTORIES`), or when they are ignored by git.
+pub const OUTPUT_DIRECTORIES: &[&str] = &["bin", "build", "dist", "obj", "out", "public"];
+
+/// The manifests that are searched for references to the directories next to them that are in
+/// `OUTPUT_DIRECTORIES`.
+const OUTPUT_MANIFESTS: &[&str] = &["package.json", "Cargo.toml"];
+
+/// The directories of `OUTPUT_DIRECTORIES` that .NET builds into, which are build output when
+/// they are next to a project or solution file with one of `DOTNET_PROJECT_EXTENSIONS`, rather
+/// than when a manifest refers to them, since the `bin` of a `package.json` is a source directory.
+const DOTNET_OUTPUT_DIRECTORIES: &[&str] = &["bin", "obj"];
+
+/// The extensions of .NET project and solution files, see `DOTNET_OUTPUT_DIRECTORIES`.
+const DOTNET_PROJECT_EXTENSIONS: &[&str] = &["csproj", "fsproj", "vbproj", "sln"];
+
+/// Scans a directory recursively and counts every recognized file in it. Entries of each directory
+/// are processed in parallel on the current rayon thread pool. Files and subdirectories that can't
+/// be read are skipped, and listed in the returned list's `skipped` paths.
+///
+/// # Parameters
+/// - `root` - The path of the directory to scan
+/// - `options` - The options controlling which files are counted
+///
+/// # Returns
+/// - The languages that were found. These are unsorted until `LanguageList::sort` is called.
+///
+/// # Errors
+/// Returns an error if `root` itself couldn't be read.
+pub fn scan(root: &std::path::Path, options: &ScanOptions) -> std::io::Result<crate::LanguageList> {
+    let directories = scan_by_directory(root, options, 0)?;
+    Ok(directories.into_languages())
+}
+
+/// Scans a directory recursively like `scan`, but counts the files under each directory at the
+/// given depth below `root` separately. Files that aren't that deep are counted under the deepest
+/// directory containing them, with the files directly in `root` counted under `.`.
+///
+/// # Parameters
+/// - `root` - The path of the directory to scan
+/// - `options` - The options controlling which files are counted
+/// - `depth` - The depth of the directories to count separately, where 1 is the directories
+///   directly in `root`. If this is 0, every file is counted under `.`.
+///
+/// # Returns
+/// - The languages that were found in each directory, keyed by their paths relative to `root`.
+///
+/// # Errors
+/// Returns an error if `root` itself couldn't be read.
+pub fn scan_by_directory(
+    root: &std::path::Path,
+    options: &ScanOptions,
+    depth: usize,
+) -> std::io::Result<crate::DirectoryList> {
+    // Canonicalize the root once, so that the paths of files can be made absolute by joining
+    let root = &root.canonicalize()?;
+    let entries = std::fs::read_dir(root)?;
+    let walk = Walk {
+        root,
+        options,
+        depth,
+        visited: Visited::new(options),
+    };
+    walk.visited.visit(root, &std::fs::metadata(root)?);
+
+    let mut attributes = crate::attributes::GitAttributes::default();
+    if options.gitattributes {
+        attributes = attributes.with_directory(root).unwrap_or(attributes);
+    }
+    let mut directories = walk_directories(&walk, entries, attributes);
+    directories.remove_empty();
+    Ok(directories)
+}
+
+/// Counts the given files, instead of scanning a directory for them, such as the files tracked by
+/// git from `git_files`. The default ignore rules of `scan`, such as those for dotfiles, aren't
+/// applied, but paths excluded by `options` and files marked as vendored or generated by
+/// `.gitattributes` are still skipped. Paths that aren't files, such as deleted files or git
+/// submodules, are ignored, as are symbolic links unless `options` follows them.
+///
+/// # Parameters
+/// - `root` - The path of the directory the files are in, which excluded paths are relative to
+/// - `paths` - The paths of the files to count
+/// - `options` - The options controlling which files are counted
+///
+/// # Returns
+/// - The languages that were found. These are unsorted until `LanguageList::sort` is called.
+pub fn scan_paths(root: &std::path::Path, paths: &[std::path::PathBuf], options: &ScanOptions) -> crate::LanguageList {
+    let directories = scan_paths_by_directory(root, paths, options, 0);
+    directories.into_languages()
+}
+
+/// Counts the given files like `scan_paths`, but counts the files under each directory at the
+/// given depth below `root` separately, see `scan_by_directory`.
+///
+/// # Parameters
+/// - `root` - The path of the directory the files are in
+/// - `paths` - The paths of the files to count
+/// - `options` - The options controlling which files are counted
+/// - `depth` - The depth of the directories to count separately
+///
+/// # Returns
+/// - The languages that were found in each directory, keyed by their paths relative to `root`.
+pub fn scan_paths_by_directory(
+    root: &std::path::Path,
+    paths: &[std::path::PathBuf],
+    options: &ScanOptions,
+    depth: usize,
+) -> crate::DirectoryList {
+    // Canonicalize the root once, and join the paths onto it, so that they can be listed as
+    // absolute paths without canonicalizing each of them
+    let canonical_root = root.canonicalize().unwrap_or_else(|_| root.to_owned());
+    let paths = paths
+        .iter()
+        .map(|path| canonical_root.join(path.strip_prefix(root).unwrap_or(path)))
+        .collect::<Vec<_>>();
+    let root = canonical_root.as_path();
+
+    // Get the `.gitattributes` rules of each directory, from the root down
+    let mut attributes = std::collections::HashMap::new();
+    let mut root_attributes = crate::attributes::GitAttributes::default();
+    if options.gitattributes {
+        root_attributes = root_attributes.with_directory(root).unwrap_or(root_attributes);
+    }
+    attributes.insert(root.to_owned(), std::sync::Arc::new(root_attributes));
+    if options.gitattributes {
+        let directories = paths
+            .iter()
+            .filter_map(|path| path.parent())
+            .flat_map(|directory| directory.ancestors().take_while(|ancestor| *ancestor != root))
+            .collect::<std::collections::BTreeSet<_>>();
+        for directory in directories {
+            let parent = &attributes[directory.parent().unwrap_or(root)];
+            let directory_attributes = parent.with_directory(directory).map(std::sync::Arc::new);
+            let directory_attributes = directory_attributes.unwrap_or_else(|| parent.clone());
+            attributes.insert(directory.to_owned(), directory_attributes);
+        }
+    }
+
+    // Count the files at their canonical paths first, and then those found through symbolic links
+    // in the order of their paths, so that a file listed both as itself and through a link, or
+    // through a linked directory, is counted under its own path
+    let (mut links, paths) = paths.iter().partition::<Vec<_>, _>(|path| {
+        options.follow_symlinks && path.canonicalize().is_ok_and(|canonical| canonical != **path)
+    });
+    links.sort();
+    let visited = Visited::new(options);
+    let scan = |mut directories: crate::DirectoryList, path: &&std::path::PathBuf| {
+        let traversal = PhaseTimer::start(options, Phase::Traversal);
+        let Some(directory) = path.parent() else { return directories };
+        let relative_path = path.strip_prefix(root).unwrap_or(path);
+        if options.exclude_paths.is_match(relative_path) {
+            log::debug!("skipped {}: excluded path", relative_path.display());
+            return directories;
+        }
+        if is_too_deep(relative_path, options) {
+            log::debug!("skipped {}: deeper than the maximum depth", relative_path.display());
+            directories.depth_limited = true;
+            return directories;
+        }
+        let metadata = followed_metadata(path, std::fs::symlink_metadata(path), options);
+        if !metadata.is_some_and(|metadata| metadata.is_file() && visited.visit(path, &metadata)) {
+            return directories;
+        }
+        let directory_attributes = attributes.get(directory).unwrap_or(&attributes[root]);
+        let languages = directories.get_or_insert(&directory_name(directory, root, depth));
+        drop(traversal);
+        let _counting = PhaseTimer::start(options, Phase::Counting);
+        scan_file(path, root, options, directory_attributes, languages);
+        directories
+    };
+    let directories = paths.par_iter().fold(crate::DirectoryList::default, scan).reduce(
+        crate::DirectoryList::default,
+        |mut directories, other| {
+            directories.merge(other);
+            directories
+        },
+    );
+    let mut directories = links.iter().fold(directories, scan);
+    directories.remove_empty();
+    directories
+}
+
+/// Counts the files of a git revision, such as a tag, instead of the files in the working tree.
+/// Files are read from git's object database, so the revision doesn't need to be checked out, and
+/// are listed as `<revision>:<path>` with paths relative to `root`. The default ignore rules of
+/// `scan` and the paths excluded by `options` are applied to the paths of the files, but
+/// `.gitattributes` aren't read. Directories in `OUTPUT_DIRECTORIES` aren't skipped, since files
+/// that are checked in aren't ignored by git.
+///
+/// # Parameters
+/// - `root` - The path of a directory in the git repository. Only the files under it are counted.
+/// - `revision` - The revision to count the files of
+/// - `options` - The options controlling which files are counted
+///
+/// # Returns
+/// - The languages that were found. These are unsorted until `LanguageList::sort` is called.
+///
+/// # Errors
+/// Returns an error if git couldn't be run, or if the revision couldn't be read.
+pub fn scan_revision(
+    root: &std::path::Path,
+    revision: &str,
+    options: &ScanOptions,
+) -> std::io::Result<crate::LanguageList> {
+    let directories = scan_revision_by_directory(root, revision, options, 0)?;
+    Ok(directories.into_languages())
+}
+
+/// Counts the files of a git revision like `scan_revision`, but counts the files under each
+/// directory at the given depth below `root` separately, see `scan_by_directory`.
+///
+/// # Parameters
+/// - `root` - The path of a directory in the git repository
+/// - `revision` - The revision to count the files of
+/// - `options` - The options controlling which files are counted
+/// - `depth` - The depth of the directories to count separately
+///
+/// # Returns
+/// - The languages that were found in each directory, keyed by their paths relative to `root`.
+///
+/// # Errors
+/// Returns an error if git couldn't be run, or if the revision couldn't be read.
+pub fn scan_revision_by_directory(
+    root: &std::path::Path,
+    revision: &str,
+    options: &ScanOptions,
+    depth: usize,
+) -> std::io::Result<crate::DirectoryList> {
+    let mut directories = crate::DirectoryList::default();
+    let traversal = PhaseTimer::start(options, Phase::Traversal);
+    let blobs = crate::git_revision_blobs(root, revision)?
+        .into_iter()
+        .filter(|blob| {
+            let is_ignored = is_ignored_path(std::path::Path::new(&blob.path), root, options);
+            if is_ignored {
+                log::debug!("skipped {revision}:{}: ignored path", blob.path);
+            }
+            !is_ignored
+        })
+        .filter(|blob| {
+            let is_too_deep = is_too_deep(std::path::Path::new(&blob.path), options);
+            if is_too_deep {
+                log::debug!("skipped {revision}:{}: deeper than the maximum depth", blob.path);
+            }
+            directories.depth_limited |= is_too_deep;
+            !is_too_deep
+        })
+        .collect::<Vec<_>>();
+    drop(traversal);
+
+    // Blobs are read from git as they are counted, so reading them is timed as counting them
+    let _counting = PhaseTimer::start(options, Phase::Counting);
+    crate::read_git_blobs(root, &blobs, |blob, contents| {
+        let relative_path = std::path::Path::new(&blob.path);
+        let directory = root.join(relative_path.parent().unwrap_or(std::path::Path::new("")));
+        let languages = directories.get_or_insert(&directory_name(&directory, root, depth));
+        let Some(language) = crate::detect_language_from_contents(relative_path, &contents, &options.extension_map)
+        else {
+            log::debug!("skipped {revision}:{}: unknown language", blob.path);
+            if options.collect_unknown {
+                languages.add_unknown(relative_path, contents.len() as u64);
+            }
+            return;
+        };
+        let name = format!("{revision}:{}", blob.path);
+        let test = is_test_file(relative_path, options);
+        if let Err(error) = languages.add_contents_as(name.clone(), test, language, &contents, options) {
+            languages.skipped.push(crate::SkippedPath::new(std::path::Path::new(&name), &error));
+        }
+    })?;
+    directories.remove_empty();
+    Ok(directories)
+}
+
+/// Returns whether a file is test code that `options` leaves out or counts separately, see
+/// `ScanOptions::tests`.
+///
+/// # Parameters
+/// - `relative_path` - The path of the file, relative to the scan root
+/// - `options` - The options of the scan
+pub(crate) fn is_test_file(relative_path: &std::path::Path, options: &ScanOptions) -> bool {
+    options.tests != crate::TestFiles::Include && crate::test_files::is_test_file(relative_path, options)
+}
+
+/// Returns whether the default ignore rules of `scan`, or the paths excluded by `options`, skip a
+/// file, based only on its path. This is used to apply them to files that aren't found by walking
+/// the working tree, so directories in `OUTPUT_DIRECTORIES` aren't skipped.
+///
+/// # Parameters
+/// - `relative_path` - The path of the file, relative to `root`
+/// - `root` - The path of the directory the scan started at
+/// - `options` - The options of the scan
+fn is_ignored_path(relative_path: &std::path::Path, root: &std::path::Path, options: &ScanOptions) -> bool {
+    let mut ancestors = relative_path
+        .ancestors()
+        .filter(|ancestor| !ancestor.as_os_str().is_empty())
+        .collect::<Vec<_>>();
+    ancestors.reverse();
+    let file_index = ancestors.len().saturating_sub(1);
+    ancestors.iter().enumerate().any(|(index, ancestor)| {
+        let filename = ancestor.file_name().unwrap_or_default().to_string_lossy();
+        let is_included = is_included(&root.join(ancestor), root, options);
+        options.exclude_paths.is_match(ancestor)
+            || (!is_included && !options.include_dotfiles && filename.starts_with('.'))
+            || (!is_included && index != file_index && options.ignores_directory(&filename))
+    })
+}
+
+/// Returns whether a file is in a directory deeper than `ScanOptions::max_depth` allows.
+///
+/// # Parameters
+/// - `relative_path` - The path of the file, relative to the scan root
+/// - `options` - The options of the scan
+pub(crate) fn is_too_deep(relative_path: &std::path::Path, options: &ScanOptions) -> bool {
+    options
+        .max_depth
+        .is_some_and(|max_depth| relative_path.components().count().saturating_sub(1) > max_depth)
+}
+
+/// Returns the name of the directory that the files directly in a directory are counted under by
+/// `scan_by_directory`, which is its path relative to the root, cut off at the given depth.
+fn directory_name(directory: &std::path::Path, root: &std::path::Path, depth: usize) -> String {
+    let relative_path = directory.strip_prefix(root).unwrap_or(directory);
+    let components = relative_path
+        .components()
+        .take(depth)
+        .map(|component| component.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>();
+    if components.is_empty() {
+        ".".to_owned()
+    } else {
+        components.join("/")
+    }
+}
+
+/// Returns whether a directory found by walking the working tree is skipped by the default ignore
+/// rules, by its name, or for being an `OUTPUT_DIRECTORIES` directory that looks like build
+/// output.
+///
+/// # Parameters
+/// - `path` - The path of the directory
+/// - `name` - The name of the directory
+/// - `options` - The options of the scan
+pub(crate) fn is_ignored_directory(path: &std::path::Path, name: &str, options: &ScanOptions) -> bool {
+    if options.ignores_directory(name) {
+        return true;
+    }
+    if options.ignored_directories.is_some() || !OUTPUT_DIRECTORIES.contains(&name) {
+        return false;
+    }
+
+    // A .NET project next to the directory, or manifests next to the directory that refer to it,
+    // such as a `package.json` with `"main": "dist/index.js"`
+    let parent = path.parent().unwrap_or(path);
+    let is_output = if DOTNET_OUTPUT_DIRECTORIES.contains(&name) {
+        is_dotnet_project(parent)
+    } else {
+        OUTPUT_MANIFESTS.iter().any(|manifest| {
+            std::fs::read_to_string(parent.join(manifest)).is_ok_and(|contents| refers_to_directory(&contents, name))
+        })
+    };
+    is_output || crate::git::is_git_ignored(path)
+}
+
+/// Returns whether a directory has a .NET project or solution file in it, such as `App.csproj`,
+/// see `DOTNET_PROJECT_EXTENSIONS`.
+fn is_dotnet_project(directory: &std::path::Path) -> bool {
+    let Ok(entries) = std::fs::read_dir(directory) else {
+        return false;
+    };
+    entries.flatten().any(|entry| {
+        let path = entry.path();
+        let extension = path.extension().unwrap_or_default();
+        DOTNET_PROJECT_EXTENSIONS.iter().any(|project| extension == *project) && path.is_file()
+    })
+}
+
+/// Returns whether a manifest refers to a directory next to it as a path, such as `"dist"`,
+/// `"./dist/index.js"`, or `--outDir dist`. Keys with the same name, such as the `"build"` script
+/// of a `package.json`, don't count.
+///
+/// # Parameters
+/// - `contents` - The contents of the manifest
+/// - `name` - The name of the directory
+fn refers_to_directory(contents: &str, name: &str) -> bool {
+    contents.match_indices(name).any(|(index, _)| {
+        let before = contents[..index].strip_suffix("./").unwrap_or(&contents[..index]);
+        let after = &contents[index + name.len()..];
+        let starts_path = before.ends_with(['"', '\'', ' ', '=']);
+        let ends_path = after.starts_with(['/', '\'', ' '])
+            || (after.starts_with('"') && !after[1..].trim_start().starts_with(':'));
+        starts_path && ends_path
+    })
+}
+
+/// Returns whether a path was explicitly included with `--include`. Entries without a path
+/// separator match any file or directory with that name, such as `.github` or `node_modules`,
+/// and entries with one match the path relative to the scan root, such as `packages/app/dist`.
+///
+/// # Parameters
+/// - `path` - The path of the file or directory
+/// - `root` - The path of the directory the scan started at
+/// - `options` - The options of the scan
+pub(crate) fn is_included(path: &std::path::Path, root: &std::path::Path, options: &ScanOptions) -> bool {
+    let relative_path = path.strip_prefix(root).unwrap_or(path);
+    options.include.iter().any(|include| {
+        let include = std::path::Path::new(include);
+        let include = include.strip_prefix(".").unwrap_or(include);
+        if include.components().count() == 1 {
+            path.file_name() == Some(include.as_os_str())
+        } else {
+            relative_path == include
+        }
+    })
+}
+
+/// The state shared by every directory of a scan, see `walk_directories`.
+struct Walk<'a> {
+    /// The canonical path of the directory the scan started at, which `--include` paths are
+    /// relative to.
+    root: &'a std::path::Path,
+    /// The options of the scan.
+    options: &'a ScanOptions,
+    /// The depth of the directories to count separately, see `scan_by_directory`.
+    depth: usize,
+    /// The files and directories that have been visited, when following symbolic links.
+    visited: Visited,
+}
+
+/// A directory that has been found by a scan, but not read yet.
+struct PendingDirectory {
+    /// The path of the directory.
+    path: std::path::PathBuf,
+    /// The `.gitattributes` rules that apply to the directory's parent, which are shared by every
+    /// directory without a `.gitattributes` file of its own.
+    attributes: std::sync::Arc<crate::attributes::GitAttributes>,
+    /// The depth of the directory below the root, where the root is 0.
+    depth: usize,
+    /// The path of the nested project the directory is in relative to the root, when nested
+    /// projects are counted separately, see `NestedProjects::Separate`.
+    project: Option<String>,
+}
+
+/// A symbolic link found by a scan that follows them. Links are only followed once every path
+/// that isn't a link has been scanned, and then in the order of their paths, so that a file or
+/// directory that can be found through both its own path and links is always counted under its
+/// own path, rather than under whichever was listed first.
+enum PendingLink {
+    /// A link to a directory, along with the metadata of the directory.
+    Directory(PendingDirectory, std::fs::Metadata),
+    /// A link to a file.
+    File {
+        /// The path of the link.
+        path: std::path::PathBuf,
+        /// The metadata of the file.
+        metadata: std::fs::Metadata,
+        /// The name of the directory the link is counted under, see `directory_name`.
+        directory_name: String,
+        /// The `.gitattributes` rules that apply to the directory the link is in.
+        attributes: std::sync::Arc<crate::attributes::GitAttributes>,
+    },
+}
+
+impl PendingLink {
+    /// Returns the path of the link.
+    fn path(&self) -> &std::path::Path {
+        match self {
+            Self::Directory(directory, _) => &directory.path,
+            Self::File { path, .. } => path,
+        }
+    }
+}
+
+/// The directories and links found by scanning a directory, which are scanned and followed next,
+/// see `walk_directories`.
+#[derive(Default)]
+struct Found {
+    /// The subdirectories that weren't ignored.
+    directories: Vec<PendingDirectory>,
+    /// The links that weren't ignored, see `PendingLink`.
+    links: Vec<PendingLink>,
+}
+
+impl Found {
+    /// Adds the directories and links found elsewhere to these.
+    fn extend(&mut self, other: Found) {
+        self.directories.extend(other.directories);
+        self.links.extend(other.links);
+    }
+}
+
+/// Scans the directories of a scan, starting from the entries of its root, and adds every
+/// recognized file to the language list of its directory. This walks the tree iteratively with a
+/// queue of pending directories rather than recursively, so that deeply nested trees can't
+/// overflow the stack. The directories found at each depth are scanned together in parallel on the
+/// current rayon thread pool, with each thread accumulating into its own `DirectoryList` that is
+/// merged in at the end. Symbolic links are followed once there are no directories left, see
+/// `PendingLink`, and the directories they lead to are scanned in turn.
+///
+/// # Parameters
+/// - `walk` - The state of the scan
+/// - `entries` - The entries of the root directory
+/// - `attributes` - The `.gitattributes` rules that apply to the root directory
+///
+/// # Returns
+/// - The languages that were found in each directory, see `scan_by_directory`.
+fn walk_directories(
+    walk: &Walk,
+    entries: std::fs::ReadDir,
+    attributes: crate::attributes::GitAttributes,
+) -> crate::DirectoryList {
+    let root = PendingDirectory {
+        path: walk.root.to_owned(),
+        attributes: std::sync::Arc::new(attributes),
+        depth: 0,
+        project: None,
+    };
+    if let Some(progress) = &walk.options.progress {
+        progress.add_directory();
+    }
+    let (mut directories, found) = scan_directory(walk, &root, &root.attributes, entries);
+    let mut pending = std::collections::VecDeque::from(found.directories);
+    let mut links = found.links;
+
+    loop {
+        if pending.is_empty() {
+            if links.is_empty() {
+                break;
+            }
+            follow_links(walk, std::mem::take(&mut links), &mut directories, &mut pending);
+            continue;
+        }
+
+        let batch = pending.drain(..).collect::<Vec<_>>();
+        let (scanned, found) = batch
+            .into_par_iter()
+            .map(|directory| {
+                let read = PhaseTimer::start(walk.options, Phase::Traversal);
+                let entries = std::fs::read_dir(&directory.path);
+                drop(read);
+                let entries = match entries {
+                    Ok(entries) => entries,
+                    Err(error) => {
+                        // Directories that can't be read are listed under their parent's directory
+                        let parent = directory.path.parent().unwrap_or(walk.root);
+                        let parent_name = directory.project.clone();
+                        let parent_name = parent_name.unwrap_or_else(|| directory_name(parent, walk.root, walk.depth));
+                        let mut directories = crate::DirectoryList::default();
+                        let languages = directories.get_or_insert(&parent_name);
+                        languages.skipped.push(crate::SkippedPath::new(&directory.path, &error));
+                        return (directories, Found::default());
+                    }
+                };
+                if let Some(progress) = &walk.options.progress {
+                    progress.add_directory();
+                }
+                let nested_attributes = walk
+                    .options
+                    .gitattributes
+                    .then(|| directory.attributes.with_directory(&directory.path))
+                    .flatten()
+                    .map(std::sync::Arc::new);
+                let attributes = nested_attributes.unwrap_or_else(|| directory.attributes.clone());
+                scan_directory(walk, &directory, &attributes, entries)
+            })
+            .reduce(
+                || (crate::DirectoryList::default(), Found::default()),
+                |(mut directories, mut found), (other, other_found)| {
+                    directories.merge(other);
+                    found.extend(other_found);
+                    (directories, found)
+                },
+            );
+        directories.merge(scanned);
+        pending.extend(found.directories);
+        links.extend(found.links);
+    }
+    directories
+}
+
+/// Follows the symbolic links found by a scan, in the order of their paths, counting the files
+/// they lead to and adding the directories they lead to to `pending`. The files and directories
+/// that were already visited through their own paths, or through an earlier link, are skipped.
+///
+/// # Parameters
+/// - `walk` - The state of the scan
+/// - `links` - The links to follow
+/// - `directories` - The languages of the scan so far, which the files are added to
+/// - `pending` - The directories that are scanned next
+fn follow_links(
+    walk: &Walk,
+    mut links: Vec<PendingLink>,
+    directories: &mut crate::DirectoryList,
+    pending: &mut std::collections::VecDeque<PendingDirectory>,
+) {
+    links.sort_by(|link, other| link.path().cmp(other.path()));
+    for link in links {
+        match link {
+            PendingLink::Directory(directory, metadata) => {
+                if walk.visited.visit(&directory.path, &metadata) {
+                    pending.push_back(directory);
+                }
+            }
+            PendingLink::File {
+                path,
+                metadata,
+                directory_name,
+                attributes,
+            } => {
+                if walk.visited.visit(&path, &metadata) {
+                    let languages = directories.get_or_insert(&directory_name);
+                    let _counting = PhaseTimer::start(walk.options, Phase::Counting);
+                    scan_file(&path, walk.root, walk.options, &attributes, languages);
+                }
+            }
+        }
+    }
+}
+
+/// Scans the entries of a single directory in parallel, adding every recognized file to the
+/// language list of the directory, and returning the subdirectories to scan next.
+///
+/// # Parameters
+/// - `walk` - The state of the scan
+/// - `directory` - The directory to scan
+/// - `attributes` - The `.gitattributes` rules that apply to the directory
+/// - `entries` - The entries of the directory
+///
+/// # Returns
+/// - The languages of the directory's files, and the subdirectories and links that weren't
+///   ignored.
+fn scan_directory(
+    walk: &Walk,
+    directory: &PendingDirectory,
+    attributes: &std::sync::Arc<crate::attributes::GitAttributes>,
+    entries: std::fs::ReadDir,
+) -> (crate::DirectoryList, Found) {
+    let (root, options) = (walk.root, walk.options);
+    let directory_name =
+        (directory.project.clone()).unwrap_or_else(|| directory_name(&directory.path, root, walk.depth));
+    let read = PhaseTimer::start(options, Phase::Traversal);
+    let entries = entries.filter_map(|entry| entry.ok()).collect::<Vec<_>>();
+    drop(read);
+    entries
+        .into_par_iter()
+        .fold(
+            || (crate::DirectoryList::default(), Found::default()),
+            |(mut directories, mut found), entry| {
+                let traversal = PhaseTimer::start(options, Phase::Traversal);
+
+                // Get the path and pathname
+                let path = entry.path();
+                let filename = entry.file_name();
+                let filename = filename.to_string_lossy();
+                let relative_path = path.strip_prefix(root).unwrap_or(&path);
+
+                // Explicitly included paths skip the default ignore rules
+                let is_included = is_included(&path, root, options);
+
+                // Excluded paths
+                if options.exclude_paths.is_match(relative_path) {
+                    log::debug!("skipped {}: excluded path", relative_path.display());
+                    return (directories, found);
+                }
+
+                // Dotifiles
+                if !is_included && !options.include_dotfiles && filename.starts_with('.') {
+                    log::debug!("skipped {}: dotfile", relative_path.display());
+                    return (directories, found);
+                }
+
+                // The cache of `--cache`, which is never counted
+                if filename == crate::CACHE_DIRECTORY {
+                    return (directories, found);
+                }
+
+                // Symbolic links, which are skipped unless they're followed. Otherwise, the type of
+                // the entry comes from the directory listing, so that files aren't stat'ed before
+                // they're opened, and only followed links need their metadata to be visited once.
+                let mut is_link = false;
+                let (file_type, metadata) = if options.follow_symlinks {
+                    is_link = entry.file_type().is_ok_and(|file_type| file_type.is_symlink());
+                    let Some(metadata) = followed_metadata(&path, entry.metadata(), options) else {
+                        log::debug!("skipped {}: broken symbolic link", relative_path.display());
+                        return (directories, found);
+                    };
+                    (metadata.file_type(), Some(metadata))
+                } else {
+                    match entry.file_type() {
+                        Ok(file_type) if !file_type.is_symlink() => (file_type, None),
+                        Ok(_) => {
+                            log::debug!("skipped {}: symbolic link", relative_path.display());
+                            return (directories, found);
+                        }
+                        Err(_) => return (directories, found),
+                    }
+                };
+                let is_first_visit = || metadata.as_ref().is_none_or(|metadata| walk.visited.visit(&path, metadata));
+
+                // Directories, which are scanned after this one. Directories linked to more than
+                // once, or linked to from inside themselves, are only scanned once, through their
+                // own path if it is scanned, see `PendingLink`.
+                if file_type.is_dir() {
+                    if !is_included && is_ignored_directory(&path, &filename, options) {
+                        log::debug!("skipped {}: ignored directory", relative_path.display());
+                        directories.ignored_directories.push(relative_path.to_string_lossy().into_owned());
+                        return (directories, found);
+                    }
+                    if options.max_depth.is_some_and(|max_depth| directory.depth >= max_depth) {
+                        log::debug!("skipped {}: deeper than the maximum depth", relative_path.display());
+                        directories.depth_limited = true;
+                        return (directories, found);
+                    }
+                    let mut project = directory.project.clone();
+                    if options.nested_projects != NestedProjects::Include && crate::root::is_project_root(&path) {
+                        let relative_path = relative_path.to_string_lossy().into_owned();
+                        if options.nested_projects == NestedProjects::Skip {
+                            log::debug!("skipped {relative_path}: nested project");
+                            directories.skipped_projects.push(relative_path);
+                            return (directories, found);
+                        }
+                        project = Some(relative_path);
+                    }
+                    let pending = PendingDirectory {
+                        path: path.clone(),
+                        attributes: attributes.clone(),
+                        depth: directory.depth + 1,
+                        project,
+                    };
+                    match metadata {
+                        Some(metadata) if is_link => found.links.push(PendingLink::Directory(pending, metadata)),
+                        _ if is_first_visit() => found.directories.push(pending),
+                        _ => {}
+                    }
+                    return (directories, found);
+                }
+
+                // Links to files, which are counted once the paths that aren't links have been
+                // scanned, see `PendingLink`
+                if file_type.is_file() && is_link {
+                    if let Some(metadata) = metadata {
+                        found.links.push(PendingLink::File {
+                            path,
+                            metadata,
+                            directory_name: directory_name.clone(),
+                            attributes: attributes.clone(),
+                        });
+                    }
+                    return (directories, found);
+                }
+
+                // Files
+                if file_type.is_file() && is_first_visit() {
+                    let languages = directories.get_or_insert(&directory_name);
+                    drop(traversal);
+                    let _counting = PhaseTimer::start(options, Phase::Counting);
+                    scan_file(&path, root, options, attributes, languages);
+                }
+
+                (directories, found)
+            },
+        )
+        .reduce(
+            || (crate::DirectoryList::default(), Found::default()),
+            |(mut directories, mut found), (other, other_found)| {
+                directories.merge(other);
+                found.extend(other_found);
+                (directories, found)
+            },
+        )
+}
+
+/// Returns the metadata of a path found by a scan, following it if it is a symbolic link that
+/// should be followed.
+///
+/// # Parameters
+/// - `path` - The path
+/// - `metadata` - The metadata of the path itself, without following symbolic links
+/// - `options` - The options of the scan
+///
+/// # Returns
+/// - The metadata, or `None` if it couldn't be read, or if the path is a symbolic link that isn't
+///   followed or is broken.
+fn followed_metadata(
+    path: &std::path::Path,
+    metadata: std::io::Result<std::fs::Metadata>,
+    options: &ScanOptions,
+) -> Option<std::fs::Metadata> {
+    let metadata = metadata.ok()?;
+    if !metadata.is_symlink() {
+        return Some(metadata);
+    }
+    if !options.follow_symlinks {
+        return None;
+    }
+    std::fs::metadata(path).ok()
+}
+
+/// The files and directories visited by a scan that follows symbolic links, so that each of them is
+/// only counted once, and links to a directory containing them don't loop forever.
+struct Visited(Option<std::sync::Mutex<std::collections::HashSet<FileIdentity>>>);
+
+/// The identity of a file or directory, which is the same for every path it can be found through.
+#[derive(PartialEq, Eq, Hash)]
+enum FileIdentity {
+    /// The device and inode numbers of the file.
+    #[cfg(unix)]
+    Inode(u64, u64),
+    /// The canonical path of the file, where inode numbers aren't available.
+    #[cfg(not(unix))]
+    Path(std::path::PathBuf),
+}
+
+impl FileIdentity {
+    /// Returns the identity of a file or directory, or `None` if it couldn't be determined.
+    #[cfg(unix)]
+    fn new(_path: &std::path::Path, metadata: &std::fs::Metadata) -> Option<Self> {
+        Some(Self::Inode(
+            std::os::unix::fs::MetadataExt::dev(metadata),
+            std::os::unix::fs::MetadataExt::ino(metadata),
+        ))
+    }
+
+    /// Returns the identity of a file or directory, or `None` if it couldn't be determined.
+    #[cfg(not(unix))]
+    fn new(path: &std::path::Path, _metadata: &std::fs::Metadata) -> Option<Self> {
+        path.canonicalize().ok().map(Self::Path)
+    }
+}
+
+impl Visited {
+    /// Creates an empty set of visited files, which only tracks them if `options` follows symbolic
+    /// links. Otherwise, every file can only be found through one path anyway.
+    fn new(options: &ScanOptions) -> Self {
+        Self(options.follow_symlinks.then(Default::default))
+    }
+
+    /// Marks a file or directory as visited.
+    ///
+    /// # Returns
+    /// - Whether the file or directory hadn't been visited before, which is always the case when
+    ///   symbolic links aren't followed.
+    fn visit(&self, path: &std::path::Path, metadata: &std::fs::Metadata) -> bool {
+        let Some(visited) = &self.0 else { return true };
+        let Some(identity) = FileIdentity::new(path, metadata) else { return true };
+        visited.lock().unwrap().insert(identity)
+    }
+}
+
+/// Counts a single file found by a scan, unless its `.gitattributes` mark it as vendored or
+/// generated. Files that can't be read are added to the language list's `skipped` paths.
+///
+/// # Parameters
+/// - `path` - The path of the file
+/// - `root` - The canonical path of the directory the scan started at
+/// - `options` - The options of the scan
+/// - `attributes` - The `.gitattributes` rules that apply to the file's directory
+/// - `languages` - The language list to add the file to
+fn scan_file(
+    path: &std::path::Path,
+    root: &std::path::Path,
+    options: &ScanOptions,
+    attributes: &crate::attributes::GitAttributes,
+    languages: &mut crate::LanguageList,
+) {
+    let file_attributes = attributes.file(path);
+    let relative_path = path.strip_prefix(root).unwrap_or(path);
+
+    // Vendored and generated files are counted if they or a directory containing them are included
+    if file_attributes.ignored
+        && !path
+            .ancestors()
+            .take_while(|ancestor| *ancestor != root)
+            .any(|ancestor| is_included(ancestor, root, options))
+    {
+        log::debug!(
+            "skipped {}: vendored or generated by .gitattributes",
+            relative_path.display()
+        );
+        return;
+    }
+
+    let Some(language) = file_attributes.language.or_else(|| crate::detect_language(path, &options.extension_map))
+    else {
+        log::debug!("skipped {}: unknown language", relative_path.display());
+        if options.collect_unknown {
+            let bytes = std::fs::metadata(path).map_or(0, |metadata| metadata.len());
+            languages.add_unknown(path, bytes);
+        }
+        return;
+    };
+
+    // The root is already canonical, so joined paths are absolute without canonicalizing them
+    let name = match options.paths {
+        PathStyle::Relative => relative_path,
+        PathStyle::Absolute => path,
+    };
+    let name = name.to_string_lossy().into_owned();
+    let test = is_test_file(relative_path, options);
+    if let Err(error) = languages.add_file_as(path, name, test, language, options) {
+        languages.skipped.push(crate::SkippedPath::new(path, &error));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    /// Returns scan options that include the given files and directories.
+    fn including(include: &[&str]) -> super::ScanOptions {
+        super::ScanOptions {
+            include: include.iter().map(|include| (*include).to_owned()).collect(),
+            ..super::ScanOptions::default()
+        }
+    }
+
+    #[test]
+    fn includes_names_anywhere() {
+        let root = std::path::Path::new("/project");
+        let options = including(&[".github", "node_modules"]);
+        assert!(super::is_included(&root.join(".github"), root, &options));
+        assert!(super::is_included(&root.join("node_modules"), root, &options));
+        assert!(super::is_included(
+            &root.join("packages/app/node_modules"),
+            root,
+            &options
+        ));
+        assert!(!super::is_included(&root.join(".github/workflows"), root, &options));
+        assert!(!super::is_included(&root.join("node_modules2"), root, &options));
+    }
+
+    #[test]
+    fn includes_paths_from_the_root() {
+        let root = std::path::Path::new("/project");
+        let options = including(&["packages/app/dist", "./vendor/lib"]);
+        assert!(super::is_included(&root.join("packages/app/dist"), root, &options));
+        assert!(super::is_included(&root.join("vendor/lib"), root, &options));
+        assert!(!super::is_included(&root.join("dist"), root, &options));
+        assert!(!super::is_included(&root.join("packages/other/dist"), root, &options));
+        assert!(!super::is_included(
+            &root.join("nested/packages/app/dist"),
+            root,
+            &options
+        ));
+    }
+
+    #[test]
+    fn bin_and_obj_are_ignored_next_to_dotnet_projects() {
+        let root = std::env::temp_dir().join(format!("splik-test-{}-dotnet", std::process::id()));
+        let tool = root.join("tool/bin");
+        let app = root.join("App");
+        std::fs::create_dir_all(&tool).unwrap();
+        std::fs::create_dir_all(app.join("bin")).unwrap();
+        std::fs::create_dir_all(app.join("obj")).unwrap();
+        std::fs::write(app.join("App.csproj"), "<Project />").unwrap();
+
+        let options = super::ScanOptions::default();
+        assert!(!super::is_ignored_directory(&tool, "bin", &options));
+        assert!(super::is_ignored_directory(&app.join("bin"), "bin", &options));
+        assert!(super::is_ignored_directory(&app.join("obj"), "obj", &options));
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn files_found_through_links_are_counted_under_their_own_paths() {
+        let root = std::env::temp_dir().join(format!("splik-test-{}-links", std::process::id()));
+        std::fs::create_dir_all(root.join("z/sub")).unwrap();
+        std::fs::write(root.join("z/sub/a.rs"), "fn a() {}\n").unwrap();
+        std::os::unix::fs::symlink("z", root.join("a")).unwrap();
+        std::os::unix::fs::symlink("z/sub/a.rs", root.join("b.rs")).unwrap();
+        let options = super::ScanOptions {
+            follow_symlinks: true,
+            ..super::ScanOptions::default()
+        };
+        let paths_of = |languages: &crate::LanguageList| {
+            languages
+                .languages
+                .iter()
+                .flat_map(|language| &language.files)
+                .map(|file| file.path.clone())
+                .collect::<Vec<_>>()
+        };
+
+        let languages = super::scan(&root, &options).unwrap();
+        assert_eq!(paths_of(&languages), ["z/sub/a.rs"]);
+        let directories = super::scan_by_directory(&root, &options, 1).unwrap();
+        assert_eq!(directories.directories.keys().collect::<Vec<_>>(), ["z"]);
+        let paths = ["a/sub/a.rs", "b.rs", "z/sub/a.rs"].map(|path| root.join(path));
+        assert_eq!(paths_of(&super::scan_paths(&root, &paths, &options)), ["z/sub/a.rs"]);
+
+        // Without its own path, a file is counted under the first of its links
+        std::fs::remove_file(root.join("b.rs")).unwrap();
+        std::os::unix::fs::symlink("a/sub/a.rs", root.join("b.rs")).unwrap();
+        let languages = super::scan_paths(&root, &paths[..2], &options);
+        assert_eq!(paths_of(&languages), ["a/sub/a.rs"]);
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    /// Creates a tree with dotfiles, dependencies, and build output next to its sources, and
+    /// returns its root.
+    fn project(name: &str) -> std::path::PathBuf {
+        let root = std::env::temp_dir().join(format!("splik-test-{}-{name}", std::process::id()));
+        let files = [
+            ("src/main.rs", "fn main() {}\n"),
+            (".github/workflows/ci.yml", "on: push\n"),
+            (".github/scripts/check.py", "print(1)\n"),
+            ("node_modules/left-pad/index.js", "module.exports = 1;\n"),
+            ("packages/app/package.json", "{\"main\": \"dist/bundle.js\"}\n"),
+            ("packages/app/dist/bundle.js", "exports.a = 1;\n"),
+        ];
+        for (path, contents) in files {
+            let path = root.join(path);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, contents).unwrap();
+        }
+        root
+    }
+
+    /// Scans a tree, returning the sorted paths of the files it counted.
+    fn scanned_paths(root: &std::path::Path, options: &super::ScanOptions) -> Vec<String> {
+        let languages = super::scan(root, options).unwrap();
+        let mut paths = languages
+            .languages
+            .iter()
+            .flat_map(|language| &language.files)
+            .map(|file| file.path.clone())
+            .collect::<Vec<_>>();
+        paths.sort();
+        paths
+    }
+
+    #[test]
+    fn scans_skip_dotfiles_and_ignored_directories() {
+        let root = project("scan-defaults");
+        let paths = scanned_paths(&root, &super::ScanOptions::default());
+        assert_eq!(paths, ["packages/app/package.json", "src/main.rs"]);
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn scans_count_included_directories() {
+        let root = project("scan-include");
+        let paths = scanned_paths(&root, &including(&[".github", "node_modules", "packages/app/dist"]));
+        assert_eq!(
+            paths,
+            [
+                ".github/scripts/check.py",
+                ".github/workflows/ci.yml",
+                "node_modules/left-pad/index.js",
+                "packages/app/dist/bundle.js",
+                "packages/app/package.json",
+                "src/main.rs",
+            ]
+        );
+
+        // A directory included by its path isn't included by its name elsewhere
+        let paths = scanned_paths(&root, &including(&["packages/app/dist"]));
+        assert_eq!(
+            paths,
+            [
+                "packages/app/dist/bundle.js",
+                "packages/app/package.json",
+                "src/main.rs"
+            ]
+        );
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn scans_exclude_languages_of_dotfiles() {
+        let root = project("scan-exclude");
+        let options = super::ScanOptions {
+            include_dotfiles: true,
+            exclude: vec!["yaml".to_owned(), "JSON".to_owned()],
+            ..super::ScanOptions::default()
+        };
+        let languages = super::scan(&root, &options).unwrap();
+        let names = languages.languages.iter().map(|language| language.name).collect::<Vec<_>>();
+        assert!(!names.contains(&"YAML") && !names.contains(&"JSON"), "{names:?}");
+        assert_eq!(
+            scanned_paths(&root, &options),
+            [".github/scripts/check.py", "src/main.rs"]
+        );
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+}
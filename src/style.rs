@@ -0,0 +1,146 @@
+//! Tallying the line endings and indentation of files, to find the files that drift from a style
+//! of LF line endings and space indentation, see `ScanOptions::style_stats`.
+
+/// The line endings and indented lines of a file, or of a region of one, as they are counted.
+#[derive(Default, Clone, Copy, serde::Serialize, serde::Deserialize)]
+pub(crate) struct StyleCounts {
+    /// The number of lines ending in `\n` alone.
+    pub(crate) lf_lines: u64,
+    /// The number of lines ending in `\r\n`.
+    pub(crate) crlf_lines: u64,
+    /// The number of lines that aren't blank and start with a tab.
+    pub(crate) tab_lines: u64,
+    /// The number of lines that aren't blank and start with a space.
+    pub(crate) space_lines: u64,
+}
+
+impl StyleCounts {
+    /// Tallies the indentation of a line from its start, which must include the first character
+    /// of the line that isn't whitespace if there is one.
+    pub(crate) fn add_line_start(&mut self, start: &[u8]) {
+        if start.trim_ascii().is_empty() {
+            return;
+        }
+        match start[0] {
+            b'\t' => self.tab_lines += 1,
+            b' ' => self.space_lines += 1,
+            _ => {}
+        }
+    }
+
+    /// Tallies the ending of a line from its end, which must include the newline if there is one.
+    pub(crate) fn add_line_end(&mut self, end: &[u8]) {
+        if end.ends_with(b"\r\n") {
+            self.crlf_lines += 1;
+        } else if end.ends_with(b"\n") {
+            self.lf_lines += 1;
+        }
+    }
+
+    /// Adds the counts of another region of the same file to these counts.
+    pub(crate) fn add(&mut self, other: &StyleCounts) {
+        self.lf_lines += other.lf_lines;
+        self.crlf_lines += other.crlf_lines;
+        self.tab_lines += other.tab_lines;
+        self.space_lines += other.space_lines;
+    }
+}
+
+/// The line endings of a file.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, serde::Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LineEnding {
+    /// Every line ends in `\n`.
+    Lf,
+    /// Every line ends in `\r\n`.
+    Crlf,
+    /// Some lines end in `\n` and others in `\r\n`.
+    Mixed,
+}
+
+/// How the lines of a file are indented.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, serde::Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Indentation {
+    /// Most indented lines start with a tab.
+    Tabs,
+    /// Most indented lines start with a space.
+    Spaces,
+}
+
+/// The line endings and indentation of a file, see `FileInfo::style`.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, serde::Serialize)]
+pub struct FileStyle {
+    /// The number of lines ending in `\n` alone.
+    pub lf_lines: u64,
+    /// The number of lines ending in `\r\n`.
+    pub crlf_lines: u64,
+    /// The line endings of the file, or `None` if it has no line endings, such as a file of a
+    /// single line without a trailing newline.
+    pub line_ending: Option<LineEnding>,
+    /// How most indented lines of the file are indented, or `None` if no lines are indented or as
+    /// many start with a tab as with a space.
+    pub indentation: Option<Indentation>,
+}
+
+impl FileStyle {
+    /// Returns the style of a file with the given counts.
+    pub(crate) fn new(counts: &StyleCounts) -> Self {
+        Self {
+            lf_lines: counts.lf_lines,
+            crlf_lines: counts.crlf_lines,
+            line_ending: match (counts.lf_lines, counts.crlf_lines) {
+                (0, 0) => None,
+                (_, 0) => Some(LineEnding::Lf),
+                (0, _) => Some(LineEnding::Crlf),
+                _ => Some(LineEnding::Mixed),
+            },
+            indentation: match counts.tab_lines.cmp(&counts.space_lines) {
+                std::cmp::Ordering::Greater => Some(Indentation::Tabs),
+                std::cmp::Ordering::Less => Some(Indentation::Spaces),
+                std::cmp::Ordering::Equal => None,
+            },
+        }
+    }
+
+    /// Returns whether the file follows a style of LF line endings and space indentation. Files
+    /// without line endings or indentation conform.
+    pub fn is_conforming(&self) -> bool {
+        matches!(self.line_ending, None | Some(LineEnding::Lf)) && self.indentation != Some(Indentation::Tabs)
+    }
+}
+
+/// The line endings and indentation of the files of a language, see `LanguageInfo::style`.
+#[derive(Default, Clone, Debug, PartialEq, Eq, serde::Serialize)]
+pub struct StyleStats {
+    /// The number of lines ending in `\n` alone.
+    pub lf_lines: u64,
+    /// The number of lines ending in `\r\n`.
+    pub crlf_lines: u64,
+    /// The number of files with both `\n` and `\r\n` line endings.
+    pub mixed_ending_files: usize,
+    /// The number of files whose indented lines mostly start with a tab.
+    pub tab_indented_files: usize,
+    /// The number of files whose indented lines mostly start with a space.
+    pub space_indented_files: usize,
+}
+
+impl StyleStats {
+    /// Adds the style of a file to these statistics.
+    pub fn add_file(&mut self, style: &FileStyle) {
+        self.lf_lines += style.lf_lines;
+        self.crlf_lines += style.crlf_lines;
+        self.mixed_ending_files += usize::from(style.line_ending == Some(LineEnding::Mixed));
+        self.tab_indented_files += usize::from(style.indentation == Some(Indentation::Tabs));
+        self.space_indented_files += usize::from(style.indentation == Some(Indentation::Spaces));
+    }
+
+    /// Adds other statistics to these statistics.
+    pub fn add_stats(&mut self, other: &StyleStats) {
+        self.lf_lines += other.lf_lines;
+        self.crlf_lines += other.crlf_lines;
+        self.mixed_ending_files += other.mixed_ending_files;
+        self.tab_indented_files += other.tab_indented_files;
+        self.space_indented_files += other.space_indented_files;
+    }
+}
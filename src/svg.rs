@@ -0,0 +1,230 @@
+//! Drawing the byte shares of languages as SVG charts, for the SVG output format and the chart of
+//! the HTML output.
+
+/// The color of the slices of languages without a color in `COLORS`, and of "Other".
+pub(crate) const DEFAULT_COLOR: &str = "#cccccc";
+
+/// The space around the chart and legend of the SVG output.
+const PADDING: f64 = 10.0;
+
+/// The distance between the rows of the legend.
+const LEGEND_ROW_HEIGHT: f64 = 20.0;
+
+/// The natural width of the SVG output, which `SvgOptions::width` and `SvgOptions::height` scale.
+const WIDTH: f64 = 600.0;
+
+/// The height of the bar of `SvgStyle::Bar`.
+const BAR_HEIGHT: f64 = 8.0;
+
+/// The number of columns of the legend of `SvgStyle::Bar`.
+const BAR_LEGEND_COLUMNS: usize = 3;
+
+/// The radius of the pie of `SvgStyle::Pie`.
+const PIE_RADIUS: f64 = 80.0;
+
+/// The kinds of chart the SVG output can draw, see `SvgOptions::style`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+pub enum SvgStyle {
+    /// A horizontal bar split into a segment for each language, like the language bar of a GitHub
+    /// repository, with the legend below it.
+    #[default]
+    Bar,
+    /// A pie chart, with the legend to its right.
+    Pie,
+}
+
+/// How the SVG output is drawn, see `LanguageList::display_svg`.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SvgOptions {
+    /// The kind of chart to draw.
+    pub style: SvgStyle,
+    /// The width of the image in pixels. When only one of the width and height is given, the other
+    /// keeps the proportions of the chart, and when neither is, the chart is drawn at its natural
+    /// size.
+    pub width: Option<u32>,
+    /// The height of the image in pixels, see `width`.
+    pub height: Option<u32>,
+}
+
+/// A slice of a chart, for a language or for the languages grouped into "Other".
+pub(crate) struct Slice<'a> {
+    /// The name the slice is labeled with.
+    pub(crate) name: &'a str,
+    /// The hex code of the color of the slice.
+    pub(crate) color: &'a str,
+    /// The share of the chart the slice takes up, from 0 to 1.
+    pub(crate) share: f64,
+    /// The percentage the slice is labeled with, such as `42%`.
+    pub(crate) label: String,
+}
+
+/// Writes an SVG image of a chart of the given slices, with a legend listing the name and
+/// percentage of each slice.
+///
+/// # Parameters
+/// - `writer` - Where to write the image
+/// - `slices` - The slices of the chart, in the order they are drawn and listed
+/// - `options` - The kind and size of the chart
+///
+/// # Errors
+/// Returns an error if the image couldn't be written.
+pub(crate) fn write_svg(
+    writer: &mut impl std::io::Write,
+    slices: &[Slice<'_>],
+    options: SvgOptions,
+) -> std::io::Result<()> {
+    let legend_rows = match options.style {
+        SvgStyle::Bar => slices.len().div_ceil(BAR_LEGEND_COLUMNS),
+        SvgStyle::Pie => slices.len(),
+    } as f64;
+    let natural_height = match options.style {
+        SvgStyle::Bar => PADDING + BAR_HEIGHT + PADDING + legend_rows * LEGEND_ROW_HEIGHT + PADDING,
+        SvgStyle::Pie => (PADDING + legend_rows * LEGEND_ROW_HEIGHT + PADDING).max(2.0 * (PADDING + PIE_RADIUS)),
+    };
+    let (width, height) = match (options.width, options.height) {
+        (Some(width), Some(height)) => (f64::from(width), f64::from(height)),
+        (Some(width), None) => (f64::from(width), f64::from(width) * natural_height / WIDTH),
+        (None, Some(height)) => (f64::from(height) * WIDTH / natural_height, f64::from(height)),
+        (None, None) => (WIDTH, natural_height),
+    };
+
+    writeln!(
+        writer,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{}" height="{}" viewBox="0 0 {WIDTH} {natural_height}" role="img" aria-label="Languages by bytes">"#,
+        width.round(),
+        height.round()
+    )?;
+    writeln!(
+        writer,
+        r##"<g font-family="sans-serif" font-size="12" fill="#24292f">"##
+    )?;
+    match options.style {
+        SvgStyle::Bar => {
+            let bar_width = WIDTH - 2.0 * PADDING;
+            writeln!(
+                writer,
+                r#"<clipPath id="bar"><rect x="{PADDING}" y="{PADDING}" width="{bar_width}" height="{BAR_HEIGHT}" rx="{}"/></clipPath>"#,
+                BAR_HEIGHT / 2.0
+            )?;
+            writeln!(writer, r#"<g clip-path="url(#bar)">"#)?;
+            writeln!(
+                writer,
+                r#"<rect x="{PADDING}" y="{PADDING}" width="{bar_width}" height="{BAR_HEIGHT}" fill="{DEFAULT_COLOR}"/>"#
+            )?;
+            let mut start = 0.0;
+            for slice in slices {
+                writeln!(
+                    writer,
+                    r#"<rect x="{:.2}" y="{PADDING}" width="{:.2}" height="{BAR_HEIGHT}" fill="{}">{}</rect>"#,
+                    PADDING + start * bar_width,
+                    slice.share * bar_width,
+                    slice.color,
+                    title(slice)
+                )?;
+                start += slice.share;
+            }
+            writeln!(writer, "</g>")?;
+
+            let column_width = bar_width / BAR_LEGEND_COLUMNS as f64;
+            for (index, slice) in slices.iter().enumerate() {
+                let x = PADDING + (index % BAR_LEGEND_COLUMNS) as f64 * column_width;
+                let y = PADDING + BAR_HEIGHT + PADDING + (index / BAR_LEGEND_COLUMNS) as f64 * LEGEND_ROW_HEIGHT;
+                write_legend_entry(writer, slice, x, y)?;
+            }
+        }
+        SvgStyle::Pie => {
+            let center = PADDING + PIE_RADIUS;
+            if slices.is_empty() {
+                writeln!(
+                    writer,
+                    r#"<circle cx="{center}" cy="{center}" r="{PIE_RADIUS}" fill="{DEFAULT_COLOR}"/>"#
+                )?;
+            }
+            write_pie(writer, slices, (center, center), PIE_RADIUS)?;
+
+            let x = 2.0 * (PADDING + PIE_RADIUS) + PADDING;
+            for (index, slice) in slices.iter().enumerate() {
+                write_legend_entry(writer, slice, x, PADDING + index as f64 * LEGEND_ROW_HEIGHT)?;
+            }
+        }
+    }
+    writeln!(writer, "</g>")?;
+    writeln!(writer, "</svg>")
+}
+
+/// Writes the slices of a pie chart, starting at the top and going clockwise. Hovering over a
+/// slice shows its name and percentage.
+///
+/// # Parameters
+/// - `writer` - Where to write the slices
+/// - `slices` - The slices to write
+/// - `center` - The position of the center of the pie
+/// - `radius` - The radius of the pie
+pub(crate) fn write_pie(
+    writer: &mut impl std::io::Write,
+    slices: &[Slice<'_>],
+    center: (f64, f64),
+    radius: f64,
+) -> std::io::Result<()> {
+    let (center_x, center_y) = center;
+    let mut start = 0.0;
+    for slice in slices.iter().filter(|slice| slice.share > 0.0) {
+        // A slice of the whole pie is a circle, which an arc can't draw
+        if slice.share >= 1.0 {
+            return writeln!(
+                writer,
+                r#"<circle cx="{center_x}" cy="{center_y}" r="{radius}" fill="{}">{}</circle>"#,
+                slice.color,
+                title(slice)
+            );
+        }
+        let end = start + slice.share;
+        let point = |share: f64| {
+            let angle = share * std::f64::consts::TAU;
+            (center_x + radius * angle.sin(), center_y - radius * angle.cos())
+        };
+        let (start_x, start_y) = point(start);
+        let (end_x, end_y) = point(end);
+        let large_arc = u8::from(slice.share > 0.5);
+        writeln!(
+            writer,
+            r#"<path d="M {center_x} {center_y} L {start_x:.4} {start_y:.4} A {radius} {radius} 0 {large_arc} 1 {end_x:.4} {end_y:.4} Z" fill="{}">{}</path>"#,
+            slice.color,
+            title(slice)
+        )?;
+        start = end;
+    }
+    Ok(())
+}
+
+/// Writes an entry of the legend: a dot in the color of the slice, followed by its name and
+/// percentage.
+///
+/// # Parameters
+/// - `writer` - Where to write the entry
+/// - `slice` - The slice to write the entry of
+/// - `x` - The left edge of the entry
+/// - `y` - The top edge of the entry
+fn write_legend_entry(writer: &mut impl std::io::Write, slice: &Slice<'_>, x: f64, y: f64) -> std::io::Result<()> {
+    writeln!(
+        writer,
+        r#"<circle cx="{:.2}" cy="{:.2}" r="5" fill="{}"/>"#,
+        x + 5.0,
+        y + LEGEND_ROW_HEIGHT / 2.0,
+        slice.color
+    )?;
+    writeln!(
+        writer,
+        r##"<text x="{:.2}" y="{:.2}" dominant-baseline="central">{} <tspan fill="#57606a">{}</tspan></text>"##,
+        x + 16.0,
+        y + LEGEND_ROW_HEIGHT / 2.0,
+        crate::xml::escape(slice.name),
+        slice.label
+    )
+}
+
+/// Returns the tooltip of a slice, with its name and percentage.
+fn title(slice: &Slice<'_>) -> String {
+    format!("<title>{}: {}</title>", crate::xml::escape(slice.name), slice.label)
+}
@@ -0,0 +1,65 @@
+//! Detecting test code by the conventions of common test frameworks, for counting it separately
+//! from the rest of a project, see `ScanOptions::tests`.
+
+/// The names of directories whose files are tests, such as Rust's `tests` and Jest's
+/// `__tests__`.
+const TEST_DIRECTORIES: &[&str] = &["tests", "test", "__tests__", "spec"];
+
+/// The endings of the names of test files, before their extension, such as `_test` for Go's
+/// `*_test.go` and `.spec` for `*.spec.ts`.
+const TEST_STEM_SUFFIXES: &[&str] = &["_test", "_spec", ".test", ".spec"];
+
+/// The starts of the names of test files, such as pytest's `test_*.py`.
+const TEST_NAME_PREFIXES: &[&str] = &["test_"];
+
+/// The endings of the names of test classes, which are also the names of their files, such as
+/// JUnit's `*Test.java`. These are matched case-sensitively, so that `Contest.java` isn't a test.
+const TEST_CLASS_SUFFIXES: &[&str] = &["Test", "Tests"];
+
+/// What a scan does with test files, see `ScanOptions::tests`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TestFiles {
+    /// Count test files like any other file.
+    #[default]
+    Include,
+    /// Count test files, and also add their counts to the `tests` of their language, so that
+    /// reports can show how much of each language is tests.
+    Separate,
+    /// Leave test files out of the counts.
+    Exclude,
+}
+
+/// Returns whether a file is test code, either by the conventions of common test frameworks, or
+/// by matching one of `ScanOptions::test_paths`.
+///
+/// # Parameters
+/// - `relative_path` - The path of the file relative to the scan root, so that the directories
+///   above the root aren't checked
+/// - `options` - The options of the scan
+pub(crate) fn is_test_file(relative_path: &std::path::Path, options: &crate::ScanOptions) -> bool {
+    if options.test_paths.is_match(relative_path) {
+        return true;
+    }
+
+    // Check the directories of the file
+    let in_test_directory = relative_path.parent().into_iter().flat_map(std::path::Path::components).any(|component| {
+        component
+            .as_os_str()
+            .to_str()
+            .is_some_and(|directory| TEST_DIRECTORIES.contains(&directory))
+    });
+    if in_test_directory {
+        return true;
+    }
+
+    // Check the file name
+    let Some(file_name) = relative_path.file_name().and_then(std::ffi::OsStr::to_str) else {
+        return false;
+    };
+    let stem = file_name.rsplit_once('.').map_or(file_name, |(stem, _)| stem);
+    TEST_STEM_SUFFIXES.iter().any(|suffix| stem.ends_with(suffix))
+        || TEST_NAME_PREFIXES.iter().any(|prefix| file_name.starts_with(prefix))
+        || TEST_CLASS_SUFFIXES
+            .iter()
+            .any(|suffix| stem.len() > suffix.len() && stem.ends_with(suffix))
+}
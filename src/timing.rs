@@ -0,0 +1,129 @@
+//! Timing the phases of a scan, such as to tell whether it is bound by the filesystem or by
+//! counting lines.
+
+use std::sync::atomic::Ordering;
+
+/// The time that the threads of a scan spent in each of its phases, see `ScanOptions::timing`.
+/// The times are summed across threads, so with several threads they usually add up to more
+/// than the wall time of the scan.
+#[derive(Debug, Default)]
+pub struct ScanTiming {
+    /// The nanoseconds spent reading directories, and finding the type of each of their entries.
+    traversal: std::sync::atomic::AtomicU64,
+    /// The nanoseconds spent detecting the languages of files, reading them, and counting their
+    /// lines.
+    counting: std::sync::atomic::AtomicU64,
+}
+
+/// A phase of a scan that is timed, see `ScanTiming`.
+#[derive(Clone, Copy)]
+pub(crate) enum Phase {
+    /// Reading directories, see `ScanTiming::traversal`.
+    Traversal,
+    /// Reading and counting files, see `ScanTiming::counting`.
+    Counting,
+}
+
+impl ScanTiming {
+    /// Returns the time spent reading directories and finding the type of each of their entries.
+    pub fn traversal(&self) -> std::time::Duration {
+        std::time::Duration::from_nanos(self.traversal.load(Ordering::Relaxed))
+    }
+
+    /// Returns the time spent detecting the languages of files, reading them, and counting their
+    /// lines.
+    pub fn counting(&self) -> std::time::Duration {
+        std::time::Duration::from_nanos(self.counting.load(Ordering::Relaxed))
+    }
+
+    /// Returns the report of the timing of a scan that counted the given totals, such as for the
+    /// `timing` of the JSON output.
+    ///
+    /// # Parameters
+    /// - `elapsed` - The wall time of the scan
+    /// - `totals` - The totals that the scan counted
+    /// - `threads` - The number of threads that the scan ran on
+    pub fn report(&self, elapsed: std::time::Duration, totals: &crate::Totals, threads: usize) -> Timing {
+        let seconds = elapsed.as_secs_f64();
+        let per_second = |count: u64| if seconds == 0.0 { 0.0 } else { count as f64 / seconds };
+        Timing {
+            scan_seconds: seconds,
+            traversal_seconds: self.traversal().as_secs_f64(),
+            counting_seconds: self.counting().as_secs_f64(),
+            threads,
+            files_per_second: per_second(totals.files as u64),
+            bytes_per_second: per_second(totals.bytes),
+        }
+    }
+
+    /// Adds time spent in a phase of the scan.
+    fn add(&self, phase: Phase, elapsed: std::time::Duration) {
+        let nanoseconds = u64::try_from(elapsed.as_nanos()).unwrap_or(u64::MAX);
+        match phase {
+            Phase::Traversal => self.traversal.fetch_add(nanoseconds, Ordering::Relaxed),
+            Phase::Counting => self.counting.fetch_add(nanoseconds, Ordering::Relaxed),
+        };
+    }
+}
+
+/// Measures the time spent in a phase of a scan until it is dropped, adding it to the
+/// `ScanOptions::timing` of the scan, if it is timed. Otherwise, this does nothing, so that scans
+/// that aren't timed don't read the clock.
+pub(crate) struct PhaseTimer<'a>(Option<(&'a ScanTiming, Phase, std::time::Instant)>);
+
+impl<'a> PhaseTimer<'a> {
+    /// Starts measuring the time spent in a phase of a scan.
+    ///
+    /// # Parameters
+    /// - `options` - The options of the scan
+    /// - `phase` - The phase that is measured
+    pub(crate) fn start(options: &'a crate::ScanOptions, phase: Phase) -> Self {
+        Self(options.timing.as_deref().map(|timing| (timing, phase, std::time::Instant::now())))
+    }
+}
+
+impl Drop for PhaseTimer<'_> {
+    fn drop(&mut self) {
+        if let Some((timing, phase, started)) = self.0 {
+            timing.add(phase, started.elapsed());
+        }
+    }
+}
+
+/// How long a scan took, and how fast it counted files, see `ScanTiming::report`.
+#[derive(Clone, Copy, Debug, PartialEq, serde::Serialize)]
+pub struct Timing {
+    /// The wall time of the scan, in seconds.
+    pub scan_seconds: f64,
+    /// The time spent reading directories, summed across threads, see `ScanTiming::traversal`.
+    pub traversal_seconds: f64,
+    /// The time spent reading and counting files, summed across threads, see
+    /// `ScanTiming::counting`.
+    pub counting_seconds: f64,
+    /// The number of threads that the scan ran on.
+    pub threads: usize,
+    /// The number of files counted per second of wall time.
+    pub files_per_second: f64,
+    /// The number of bytes counted per second of wall time.
+    pub bytes_per_second: f64,
+}
+
+impl std::fmt::Display for Timing {
+    /// Formats the timing like the human-readable output, such as `scanned in 490.12ms (204,082
+    /// files/s, 42.0 MiB/s), with 1.20s reading directories and 2.40s reading and counting files
+    /// across 8 threads`.
+    fn fmt(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            formatter,
+            "scanned in {:.2?} ({} files/s, {}/s), with {:.2?} reading directories and {:.2?} reading and \
+             counting files across {} thread{}",
+            std::time::Duration::from_secs_f64(self.scan_seconds),
+            crate::format::format_count(self.files_per_second as u64),
+            crate::format::format_size(self.bytes_per_second as u64),
+            std::time::Duration::from_secs_f64(self.traversal_seconds),
+            std::time::Duration::from_secs_f64(self.counting_seconds),
+            self.threads,
+            if self.threads == 1 { "" } else { "s" }
+        )
+    }
+}
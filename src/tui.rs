@@ -0,0 +1,859 @@
+//! The terminal interface of `--tui`, for exploring the languages and files of a scan once it is
+//! done. The terminal is put into raw mode with `stty` and drawn with ANSI escape sequences, on the
+//! terminal's alternate screen so that the scrollback is left as it was. `stty` is only run when
+//! the interface starts, stops, and opens an editor; the size of the terminal is followed by asking
+//! the terminal for it while no key is pressed.
+
+/// A column of the table of languages, which it can be sorted by.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Column {
+    Name,
+    Files,
+    Lines,
+    Code,
+    Comments,
+    Blanks,
+    Bytes,
+}
+
+impl Column {
+    /// The columns in the order they are shown, which is also the order of the keys `1` to `7` that
+    /// sort by them.
+    const ALL: [Self; 7] = [
+        Self::Name,
+        Self::Files,
+        Self::Lines,
+        Self::Code,
+        Self::Comments,
+        Self::Blanks,
+        Self::Bytes,
+    ];
+
+    /// Returns the heading of the column.
+    fn heading(self) -> &'static str {
+        match self {
+            Self::Name => "Language",
+            Self::Files => "Files",
+            Self::Lines => "Lines",
+            Self::Code => "Code",
+            Self::Comments => "Comments",
+            Self::Blanks => "Blanks",
+            Self::Bytes => "Bytes",
+        }
+    }
+
+    /// Returns the column of the table that matches the sort order of the scan, so that the table
+    /// starts out in the order of the normal output.
+    fn of(key: splik::SortKey) -> Self {
+        match key {
+            splik::SortKey::Bytes => Self::Bytes,
+            splik::SortKey::Lines => Self::Lines,
+            splik::SortKey::Files => Self::Files,
+            splik::SortKey::Name => Self::Name,
+        }
+    }
+
+    /// Returns the value of the column for a language, as shown in the table.
+    fn format(self, language: &splik::LanguageInfo) -> String {
+        match self {
+            Self::Name => language.name.to_owned(),
+            Self::Files => splik::format_count(language.file_count as u64),
+            Self::Lines => splik::format_count(language.lines),
+            Self::Code => splik::format_count(language.code),
+            Self::Comments => splik::format_count(language.comments),
+            Self::Blanks => splik::format_count(language.blanks),
+            Self::Bytes => splik::format_size(language.bytes),
+        }
+    }
+
+    /// Compares two languages by the column, with names in alphabetical order and the other
+    /// columns from most to least, like `--sort`.
+    fn compare(self, language: &splik::LanguageInfo, other: &splik::LanguageInfo) -> std::cmp::Ordering {
+        match self {
+            Self::Name => language.name.to_lowercase().cmp(&other.name.to_lowercase()),
+            Self::Files => other.file_count.cmp(&language.file_count),
+            Self::Lines => other.lines.cmp(&language.lines),
+            Self::Code => other.code.cmp(&language.code),
+            Self::Comments => other.comments.cmp(&language.comments),
+            Self::Blanks => other.blanks.cmp(&language.blanks),
+            Self::Bytes => other.bytes.cmp(&language.bytes),
+        }
+    }
+}
+
+/// A key pressed in the terminal.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Key {
+    Char(char),
+    Up,
+    Down,
+    Left,
+    Right,
+    PageUp,
+    PageDown,
+    Home,
+    End,
+    Enter,
+    Backspace,
+    Escape,
+    Interrupt,
+    /// The number of lines and columns of the terminal, as reported by the terminal in answer to
+    /// `Terminal::probe_size`.
+    Size(usize, usize),
+}
+
+/// What the explorer asks of the terminal after a key is pressed.
+enum Action {
+    None,
+    Quit,
+    Open(std::path::PathBuf),
+    Copy(String),
+}
+
+/// The state of the interface: the view that is shown, how it is sorted and filtered, and the
+/// selected row of each view.
+struct Explorer<'a> {
+    /// The languages of the scan.
+    languages: &'a [splik::LanguageInfo],
+    /// The roots that were scanned, which relative paths are joined to when opening files.
+    roots: &'a [std::path::PathBuf],
+    /// The total bytes of all languages, which their percentages are of.
+    total_bytes: u64,
+    /// The column that the languages are sorted by.
+    column: Column,
+    /// Whether the order of `column` is reversed.
+    reverse: bool,
+    /// The text that the languages and files are filtered by, case-insensitively.
+    filter: String,
+    /// Whether keys are typed into the filter.
+    filtering: bool,
+    /// The language whose files are shown, or `None` when the languages are shown.
+    language: Option<&'a splik::LanguageInfo>,
+    /// The index of the selected language, and of the first one shown.
+    language_row: (usize, usize),
+    /// The index of the selected file, and of the first one shown.
+    file_row: (usize, usize),
+    /// The number of rows of the table that fit in the terminal.
+    page: usize,
+    /// A message shown below the table until the next key is pressed, such as when a path is
+    /// copied.
+    message: Option<String>,
+}
+
+impl<'a> Explorer<'a> {
+    /// Returns the languages that are shown, in their order.
+    fn visible_languages(&self) -> Vec<&'a splik::LanguageInfo> {
+        let filter = self.filter.to_lowercase();
+        let mut languages: Vec<_> = self
+            .languages
+            .iter()
+            .filter(|language| {
+                filter.is_empty()
+                    || language.name.to_lowercase().contains(&filter)
+                    || language.files.iter().any(|file| file.path.to_lowercase().contains(&filter))
+            })
+            .collect();
+        languages.sort_by(|language, other| {
+            let ordering = self.column.compare(language, other);
+            let ordering = if self.reverse { ordering.reverse() } else { ordering };
+            ordering.then_with(|| language.name.cmp(other.name))
+        });
+        languages
+    }
+
+    /// Returns the files of the language that are shown, from the most to the least bytes. All of
+    /// its files are shown when the filter matches the name of the language.
+    fn visible_files(&self, language: &'a splik::LanguageInfo) -> Vec<&'a splik::FileInfo> {
+        let filter = self.filter.to_lowercase();
+        let name_matches = language.name.to_lowercase().contains(&filter);
+        let mut files: Vec<_> = language
+            .files
+            .iter()
+            .filter(|file| name_matches || file.path.to_lowercase().contains(&filter))
+            .collect();
+        files.sort_by(|file, other| other.bytes.cmp(&file.bytes).then_with(|| file.path.cmp(&other.path)));
+        files
+    }
+
+    /// Returns the number of rows of the current view, and its selected row.
+    fn rows(&mut self) -> (usize, &mut (usize, usize)) {
+        match self.language {
+            Some(language) => (self.visible_files(language).len(), &mut self.file_row),
+            None => (self.visible_languages().len(), &mut self.language_row),
+        }
+    }
+
+    /// Moves the selection of the current view by the given number of rows, keeping it within the
+    /// view and the selected row on screen.
+    fn select(&mut self, rows: isize) {
+        let page = self.page.max(1);
+        let (count, (selected, offset)) = self.rows();
+        *selected = selected.saturating_add_signed(rows).min(count.saturating_sub(1));
+        *offset = (*offset).min(*selected).max((*selected + 1).saturating_sub(page));
+    }
+
+    /// Sorts the languages by a column, or reverses the order if they are already sorted by it,
+    /// keeping the same language selected.
+    fn sort_by(&mut self, column: Column) {
+        let selected = self.visible_languages().get(self.language_row.0).map(|language| language.name);
+        if self.column == column {
+            self.reverse = !self.reverse;
+        } else {
+            self.column = column;
+            self.reverse = false;
+        }
+        let languages = self.visible_languages();
+        self.language_row.0 = languages.iter().position(|language| Some(language.name) == selected).unwrap_or(0);
+        self.select(0);
+    }
+
+    /// Returns the path to open the selected file at, relative to the current directory.
+    fn file_path(&self, file: &splik::FileInfo) -> std::path::PathBuf {
+        // With several roots, the paths of files already start with their root
+        match self.roots {
+            [root] => root.join(&file.path),
+            _ => std::path::PathBuf::from(&file.path),
+        }
+    }
+
+    /// Handles a key pressed while typing into the filter.
+    fn type_filter(&mut self, key: Key) {
+        match key {
+            Key::Char(character) => self.filter.push(character),
+            Key::Backspace => {
+                self.filter.pop();
+            }
+            Key::Enter => {
+                self.filtering = false;
+                return;
+            }
+            Key::Escape => {
+                self.filter.clear();
+                self.filtering = false;
+            }
+            _ => return,
+        }
+
+        // The rows shown change with the filter, so the selection starts over at the top
+        self.language_row = (0, 0);
+        self.file_row = (0, 0);
+    }
+
+    /// Handles a pressed key, returning what the terminal should do.
+    fn handle(&mut self, key: Key) -> Action {
+        self.message = None;
+        let page = self.page.max(1) as isize;
+        match key {
+            Key::Interrupt => return Action::Quit,
+            Key::Up => self.select(-1),
+            Key::Down => self.select(1),
+            Key::PageUp => self.select(-page),
+            Key::PageDown => self.select(page),
+            Key::Home => self.select(isize::MIN),
+            Key::End => self.select(isize::MAX),
+            _ if self.filtering => {
+                self.type_filter(key);
+                self.select(0);
+            }
+            Key::Char('q') => return Action::Quit,
+            Key::Char('k') => self.select(-1),
+            Key::Char('j') => self.select(1),
+            Key::Char('g') => self.select(isize::MIN),
+            Key::Char('G') => self.select(isize::MAX),
+            Key::Char('/') => self.filtering = true,
+            Key::Enter | Key::Right | Key::Char('l') if self.language.is_none() => {
+                self.language = self.visible_languages().get(self.language_row.0).copied();
+                self.file_row = (0, 0);
+            }
+            Key::Escape | Key::Backspace | Key::Left | Key::Char('h') if self.language.is_some() => {
+                self.language = None;
+            }
+            Key::Escape if !self.filter.is_empty() => {
+                self.filter.clear();
+                self.language_row = (0, 0);
+            }
+            Key::Char('s') if self.language.is_none() => {
+                let index = Column::ALL.iter().position(|column| *column == self.column).unwrap_or(0);
+                self.sort_by(Column::ALL[(index + 1) % Column::ALL.len()]);
+            }
+            Key::Char('r') if self.language.is_none() => self.sort_by(self.column),
+            Key::Char(digit @ '1'..='7') if self.language.is_none() => {
+                self.sort_by(Column::ALL[digit as usize - '1' as usize]);
+            }
+            Key::Char(action @ ('e' | 'y')) => {
+                let file = self
+                    .language
+                    .and_then(|language| self.visible_files(language).get(self.file_row.0).copied());
+                if let Some(file) = file {
+                    return match action {
+                        'e' => Action::Open(self.file_path(file)),
+                        _ => Action::Copy(file.path.clone()),
+                    };
+                }
+            }
+            _ => {}
+        }
+        Action::None
+    }
+
+    /// Returns the lines of the screen, each at most `width` characters wide before their escape
+    /// sequences.
+    ///
+    /// # Parameters
+    /// - `height` - The number of lines of the terminal
+    /// - `width` - The number of columns of the terminal
+    fn render(&mut self, height: usize, width: usize) -> Vec<String> {
+        // The title and headings above the table, and the status and keys below it
+        self.page = height.saturating_sub(4).max(1);
+        self.select(0);
+
+        let (title, header, rows, (selected, offset), status, keys) = match self.language {
+            None => {
+                let languages = self.visible_languages();
+                let title = format!(
+                    "splik: {} language{}, {} files, {}",
+                    self.languages.len(),
+                    if self.languages.len() == 1 { "" } else { "s" },
+                    splik::format_count(self.languages.iter().map(|language| language.file_count as u64).sum()),
+                    splik::format_size(self.total_bytes)
+                );
+
+                // The columns are as wide as their widest value among all languages, so that they
+                // don't move as the languages are filtered
+                let widths: Vec<usize> = Column::ALL
+                    .iter()
+                    .map(|column| {
+                        let widest = self.languages.iter().map(|language| column.format(language).chars().count());
+                        widest.max().unwrap_or(0).max(column.heading().len() + 1)
+                    })
+                    .collect();
+                let cells = |values: Vec<String>| {
+                    let mut row = format!("{:<width$}", values[0], width = widths[0]);
+                    for (value, width) in values[1..].iter().zip(&widths[1..]) {
+                        row.push_str(&format!("  {value:>width$}"));
+                    }
+                    row
+                };
+                let descending = (self.column != Column::Name) != self.reverse;
+                let headings = Column::ALL
+                    .iter()
+                    .map(|column| match *column == self.column {
+                        true => format!("{}{}", column.heading(), if descending { '▾' } else { '▴' }),
+                        false => column.heading().to_owned(),
+                    })
+                    .collect();
+                let header = format!("{}  {:>6}", cells(headings), "%");
+                let rows: Vec<String> = languages
+                    .iter()
+                    .map(|language| {
+                        let share = match self.total_bytes {
+                            0 => 0.0,
+                            total => language.bytes as f64 * 100.0 / total as f64,
+                        };
+                        let values = Column::ALL.iter().map(|column| column.format(language)).collect();
+                        format!("{}  {:>5.1}%", cells(values), share)
+                    })
+                    .collect();
+                let keys = "↑↓ move  enter files  1-7 sort  s next column  r reverse  / filter  q quit";
+                (title, header, rows, self.language_row, None, keys)
+            }
+            Some(language) => {
+                let files = self.visible_files(language);
+                let title = format!(
+                    "{}: {} of {} files, {}",
+                    language.name,
+                    splik::format_count(files.len() as u64),
+                    splik::format_count(language.file_count as u64),
+                    splik::format_size(language.bytes)
+                );
+                let counts = |file: &splik::FileInfo| {
+                    [
+                        splik::format_size(file.bytes),
+                        splik::format_count(file.lines),
+                        splik::format_count(file.code),
+                        splik::format_count(file.comments),
+                        splik::format_count(file.blanks),
+                    ]
+                };
+                let headings = ["Bytes▾", "Lines", "Code", "Comments", "Blanks"];
+                let mut widths = headings.map(|heading| heading.chars().count());
+                for file in &language.files {
+                    for (width, count) in widths.iter_mut().zip(counts(file)) {
+                        *width = (*width).max(count.chars().count());
+                    }
+                }
+                let cells = |values: [String; 5], path: &str| {
+                    let mut row = String::new();
+                    for (value, width) in values.iter().zip(widths) {
+                        row.push_str(&format!("{value:>width$}  "));
+                    }
+                    let room = width.saturating_sub(row.chars().count());
+                    row + &shorten_path(path, room)
+                };
+                let header = cells(headings.map(str::to_owned), "Path");
+                let rows = files.iter().map(|file| cells(counts(file), &file.path)).collect();
+                let status = files.get(self.file_row.0).map(|file| file.path.clone());
+                let keys = "↑↓ move  e open in $EDITOR  y copy path  esc back  / filter  q quit";
+                (title, header, rows, self.file_row, status, keys)
+            }
+        };
+
+        let mut lines = vec![
+            format!("\x1b[1m{}\x1b[0m", fit(&title, width)),
+            format!("\x1b[4m{}\x1b[0m", fit(&header, width)),
+        ];
+        for index in offset..offset + self.page {
+            lines.push(match rows.get(index) {
+                Some(row) if index == selected => format!("\x1b[7m{}\x1b[0m", fit(row, width)),
+                Some(row) => fit(row, width),
+                None => String::new(),
+            });
+        }
+        let status = match (&self.message, self.filtering, self.filter.is_empty()) {
+            (Some(message), _, _) => message.clone(),
+            (None, true, _) => format!("/{}▏", self.filter),
+            (None, false, false) => format!("filter: {}  (/ to edit, esc to clear)", self.filter),
+            (None, false, true) => status.unwrap_or_default(),
+        };
+        lines.push(fit(&status, width));
+        lines.push(format!("\x1b[2m{}\x1b[0m", fit(keys, width)));
+        lines
+    }
+}
+
+/// Returns the text cut to `width` characters, padded with spaces to that width so that the
+/// highlight of the selected row spans the terminal.
+fn fit(text: &str, width: usize) -> String {
+    let mut fitted: String = text.chars().take(width).collect();
+    let length = fitted.chars().count();
+    fitted.extend(std::iter::repeat_n(' ', width - length));
+    fitted
+}
+
+/// Returns the path shortened to `width` characters by leaving out the start of it, since the end
+/// of a path, with the name of the file, tells the most about it.
+fn shorten_path(path: &str, width: usize) -> String {
+    let length = path.chars().count();
+    if length <= width {
+        return path.to_owned();
+    }
+    let kept: String = path.chars().skip(length - width.saturating_sub(1)).collect();
+    format!("…{kept}")
+}
+
+/// Encodes bytes as base64, for copying text to the clipboard with the OSC 52 escape sequence.
+fn base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut encoded = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let group = chunk
+            .iter()
+            .enumerate()
+            .fold(0u32, |group, (index, byte)| group | (*byte as u32) << (16 - 8 * index));
+        for index in 0..4 {
+            encoded.push(match index <= chunk.len() {
+                true => ALPHABET[(group >> (18 - 6 * index) & 0x3f) as usize] as char,
+                false => '=',
+            });
+        }
+    }
+    encoded
+}
+
+/// The number of reads of the terminal that time out, each after a tenth of a second without
+/// input, between the times the size of the terminal is asked for.
+const PROBE_INTERVAL: u32 = 5;
+
+/// The settings of the terminal that the panic hook restores, as given by `stty -g`, while the
+/// interface is shown. A panic aborts without running `Terminal::drop`, so this is what leaves the
+/// terminal usable after one.
+static RESTORE_SETTINGS: std::sync::Mutex<Option<String>> = std::sync::Mutex::new(None);
+
+/// Installs a panic hook, once, that restores the terminal from `RESTORE_SETTINGS` before the
+/// panic message is printed.
+fn install_panic_hook() {
+    static INSTALL: std::sync::Once = std::sync::Once::new();
+    INSTALL.call_once(|| {
+        let previous = std::panic::take_hook();
+        std::panic::set_hook(Box::new(move |info| {
+            let settings = RESTORE_SETTINGS.lock().ok().and_then(|mut settings| settings.take());
+            if let Some(settings) = settings {
+                if let Ok(mut tty) = std::fs::OpenOptions::new().write(true).open("/dev/tty") {
+                    let _ = std::io::Write::write_all(&mut tty, b"\x1b[?25h\x1b[?1049l");
+                    let _ = stty(&tty, &[&settings]);
+                }
+            }
+            previous(info);
+        }));
+    });
+}
+
+/// Runs `stty` on the terminal, returning its output.
+fn stty(terminal: &std::fs::File, arguments: &[&str]) -> std::io::Result<String> {
+    let output = std::process::Command::new("stty")
+        .args(arguments)
+        .stdin(terminal.try_clone()?)
+        .stderr(std::process::Stdio::null())
+        .output()?;
+    if !output.status.success() {
+        return Err(std::io::Error::other(format!("stty {} failed", arguments.join(" "))));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_owned())
+}
+
+/// The terminal that the interface is drawn on, which is restored to how it was when this is
+/// dropped.
+struct Terminal {
+    /// The terminal, opened from `/dev/tty` so that the interface works while stdout is piped.
+    tty: std::fs::File,
+    /// The settings of the terminal before it was put into raw mode, as given by `stty -g`.
+    settings: String,
+    /// Whether the terminal is in raw mode on the alternate screen.
+    active: bool,
+    /// The number of lines and columns of the terminal.
+    size: (usize, usize),
+    /// The bytes read from the terminal that aren't a whole key yet, such as the start of an
+    /// escape sequence.
+    pending: Vec<u8>,
+}
+
+impl Terminal {
+    /// Opens the terminal, and switches it to raw mode on the alternate screen.
+    ///
+    /// # Errors
+    /// If there is no terminal, or `stty` can't change its settings.
+    fn open() -> std::io::Result<Self> {
+        let tty = std::fs::OpenOptions::new()
+            .read(true)
+            .write(true)
+            .open("/dev/tty")
+            .map_err(|error| std::io::Error::new(error.kind(), format!("there is no terminal ({error})")))?;
+        let settings = stty(&tty, &["-g"])?;
+        let mut terminal = Self {
+            tty,
+            settings,
+            active: false,
+            size: (24, 80),
+            pending: Vec::new(),
+        };
+        install_panic_hook();
+        terminal.resume()?;
+        Ok(terminal)
+    }
+
+    /// Switches the terminal to raw mode on the alternate screen, with the cursor hidden, and
+    /// reads its size. Reads of the terminal time out after a tenth of a second without input.
+    fn resume(&mut self) -> std::io::Result<()> {
+        // The size is read before the terminal is changed, so that a failure leaves it as it was
+        let size = stty(&self.tty, &["size"]).unwrap_or_default();
+        let mut numbers = size.split_whitespace().map(|number| number.parse().unwrap_or(0));
+        if let (Some(lines @ 1..), Some(columns @ 1..)) = (numbers.next(), numbers.next()) {
+            self.size = (lines, columns);
+        }
+
+        stty(&self.tty, &["raw", "-echo", "min", "0", "time", "1"])?;
+        self.active = true;
+        if let Ok(mut settings) = RESTORE_SETTINGS.lock() {
+            *settings = Some(self.settings.clone());
+        }
+        std::io::Write::write_all(&mut self.tty, b"\x1b[?1049h\x1b[?25l")
+    }
+
+    /// Restores the terminal to how it was before `resume`.
+    fn suspend(&mut self) -> std::io::Result<()> {
+        if !std::mem::replace(&mut self.active, false) {
+            return Ok(());
+        }
+        if let Ok(mut settings) = RESTORE_SETTINGS.lock() {
+            *settings = None;
+        }
+        std::io::Write::write_all(&mut self.tty, b"\x1b[?25h\x1b[?1049l")?;
+        stty(&self.tty, &[&self.settings]).map(drop)
+    }
+
+    /// Asks the terminal for its size, by moving the cursor as far as it goes and requesting its
+    /// position, which the terminal answers with a key that `read_keys` takes as the new size.
+    /// Terminals that don't answer keep the size read by `resume`.
+    fn probe_size(&mut self) -> std::io::Result<()> {
+        std::io::Write::write_all(&mut self.tty, b"\x1b7\x1b[999;999H\x1b[6n\x1b8")?;
+        std::io::Write::flush(&mut self.tty)
+    }
+
+    /// Draws the lines of a frame over the previous one.
+    fn draw(&mut self, lines: &[String]) -> std::io::Result<()> {
+        let frame = format!("\x1b[H{}", lines.join("\x1b[K\r\n"));
+        std::io::Write::write_all(&mut self.tty, frame.as_bytes())?;
+        std::io::Write::flush(&mut self.tty)
+    }
+
+    /// Waits for keys to be pressed, returning those read at once, such as the characters of
+    /// pasted text, or no keys if the terminal was resized and should be drawn again.
+    fn read_keys(&mut self) -> std::io::Result<Vec<Key>> {
+        let mut buffer = [0; 64];
+        let mut timeouts = 0;
+        loop {
+            let read = std::io::Read::read(&mut self.tty, &mut buffer)?;
+            self.pending.extend_from_slice(&buffer[..read]);
+
+            // A read that times out ends an escape on its own, which is then the escape key
+            let mut resized = false;
+            let mut keys = take_keys(&mut self.pending, read == 0);
+            keys.retain(|key| match *key {
+                Key::Size(lines, columns) => {
+                    resized |= self.size != (lines, columns);
+                    self.size = (lines, columns);
+                    false
+                }
+                _ => true,
+            });
+            if !keys.is_empty() || resized {
+                return Ok(keys);
+            }
+            if read == 0 {
+                timeouts += 1;
+                if timeouts % PROBE_INTERVAL == 0 {
+                    self.probe_size()?;
+                }
+            }
+        }
+    }
+
+    /// Opens a file in the editor given by `VISUAL` or `EDITOR`, or `vi`, returning a message
+    /// saying if it couldn't be opened.
+    fn open_in_editor(&mut self, path: &std::path::Path) -> std::io::Result<Option<String>> {
+        let editor = std::env::var("VISUAL")
+            .ok()
+            .filter(|editor| !editor.trim().is_empty())
+            .or_else(|| std::env::var("EDITOR").ok().filter(|editor| !editor.trim().is_empty()))
+            .unwrap_or_else(|| "vi".to_owned());
+
+        // The editor can be given with arguments, such as `code --wait`
+        let mut words = editor.split_whitespace();
+        let program = words.next().unwrap_or("vi");
+        self.suspend()?;
+        let status = std::process::Command::new(program)
+            .args(words)
+            .arg(path)
+            .stdin(self.tty.try_clone()?)
+            .stdout(self.tty.try_clone()?)
+            .stderr(self.tty.try_clone()?)
+            .status();
+        self.resume()?;
+        Ok(match status {
+            Ok(status) if status.success() => None,
+            Ok(status) => Some(format!("{program} exited with {status}")),
+            Err(error) => Some(format!("couldn't run {program}: {error}")),
+        })
+    }
+
+    /// Copies text to the clipboard with the OSC 52 escape sequence, which terminals that don't
+    /// support it ignore.
+    fn copy(&mut self, text: &str) -> std::io::Result<()> {
+        let sequence = format!("\x1b]52;c;{}\x07", base64(text.as_bytes()));
+        std::io::Write::write_all(&mut self.tty, sequence.as_bytes())
+    }
+}
+
+/// Takes the keys from the start of the bytes read from the terminal, leaving the bytes of a key
+/// that hasn't been read in full, such as an escape sequence split between two reads.
+///
+/// # Parameters
+/// - `pending` - The bytes read from the terminal that haven't been taken yet
+/// - `timed_out` - Whether the terminal has had no input since the last of the bytes, in which case
+///   the bytes of an unfinished key are taken too: an escape on its own is the escape key, and
+///   anything else is dropped
+fn take_keys(pending: &mut Vec<u8>, timed_out: bool) -> Vec<Key> {
+    let mut keys = Vec::new();
+    let mut start = 0;
+    while start < pending.len() {
+        let (key, length) = match parse_key(&pending[start..]) {
+            Some(parsed) => parsed,
+            None if !timed_out => break,
+            None if pending[start..] == [0x1b] => (Some(Key::Escape), 1),
+            None => (None, pending.len() - start),
+        };
+        keys.extend(key);
+        start += length;
+    }
+    pending.drain(..start);
+    keys
+}
+
+/// Parses the key at the start of the bytes read from the terminal.
+///
+/// # Returns
+/// - The key, or `None` for a sequence that isn't a key, such as an unknown escape sequence or a
+///   control character, and the number of bytes it takes up, or `None` if the key isn't read in
+///   full yet.
+fn parse_key(bytes: &[u8]) -> Option<(Option<Key>, usize)> {
+    match bytes {
+        [] | [0x1b] | [0x1b, b'O'] => None,
+        [0x1b, 0x1b, ..] => Some((Some(Key::Escape), 1)),
+        [0x1b, b'[', sequence @ ..] => {
+            // A control sequence ends with a byte from `@` to `~`, after its parameters
+            let end = sequence.iter().position(|byte| (0x40..=0x7e).contains(byte))?;
+            let key = match (&sequence[..end], sequence[end]) {
+                (b"", b'A') => Some(Key::Up),
+                (b"", b'B') => Some(Key::Down),
+                (b"", b'C') => Some(Key::Right),
+                (b"", b'D') => Some(Key::Left),
+                (b"5", b'~') => Some(Key::PageUp),
+                (b"6", b'~') => Some(Key::PageDown),
+                (b"", b'H') | (b"1", b'~') => Some(Key::Home),
+                (b"", b'F') | (b"4", b'~') => Some(Key::End),
+                // The position of the cursor, answering `probe_size`. A single line is taken as a
+                // modified F3 key instead, which some terminals send the same way.
+                (parameters, b'R') => std::str::from_utf8(parameters)
+                    .ok()
+                    .and_then(|parameters| parameters.split_once(';'))
+                    .and_then(|(lines, columns)| Some((lines.parse().ok()?, columns.parse().ok()?)))
+                    .filter(|&(lines, columns)| lines > 1 && columns > 0)
+                    .map(|(lines, columns)| Key::Size(lines, columns)),
+                _ => None,
+            };
+            Some((key, end + 3))
+        }
+        [0x1b, b'O', last, ..] => {
+            let key = match last {
+                b'A' => Some(Key::Up),
+                b'B' => Some(Key::Down),
+                b'C' => Some(Key::Right),
+                b'D' => Some(Key::Left),
+                b'H' => Some(Key::Home),
+                b'F' => Some(Key::End),
+                _ => None,
+            };
+            Some((key, 3))
+        }
+        // Alt and another key
+        [0x1b, _, ..] => Some((None, 2)),
+        [first, ..] => {
+            let length = match first {
+                0x00..=0x7f => 1,
+                0xc0..=0xdf => 2,
+                0xe0..=0xef => 3,
+                0xf0..=0xf7 => 4,
+                _ => return Some((None, 1)),
+            };
+            let bytes = bytes.get(..length)?;
+            let Some(character) = std::str::from_utf8(bytes).ok().and_then(|text| text.chars().next()) else {
+                return Some((None, 1));
+            };
+            let key = match character {
+                '\r' | '\n' => Some(Key::Enter),
+                '\x7f' | '\x08' => Some(Key::Backspace),
+                '\x03' | '\x04' => Some(Key::Interrupt),
+                character if character.is_control() => None,
+                character => Some(Key::Char(character)),
+            };
+            Some((key, length))
+        }
+    }
+}
+
+impl Drop for Terminal {
+    fn drop(&mut self) {
+        let _ = self.suspend();
+    }
+}
+
+/// Shows the languages of a scan in the terminal until `q` is pressed: a table of the languages
+/// that can be sorted by each column, and the files of each language from the largest to the
+/// smallest, which can be opened in an editor or copied. Typing after `/` filters both the
+/// languages and their files.
+///
+/// # Parameters
+/// - `languages` - The languages of the scan, with their files
+/// - `roots` - The roots that were scanned, which the paths of the files are relative to
+/// - `sort` - The order that the languages are shown in at first
+/// - `reverse` - Whether that order is reversed
+///
+/// # Errors
+/// If there is no terminal, such as when splik isn't run from one, or it can't be drawn on.
+pub fn explore(
+    languages: &splik::LanguageList,
+    roots: &[std::path::PathBuf],
+    sort: splik::SortKey,
+    reverse: bool,
+) -> std::io::Result<()> {
+    let mut terminal = Terminal::open()?;
+    let mut explorer = Explorer {
+        languages: &languages.languages,
+        roots,
+        total_bytes: languages.languages.iter().map(|language| language.bytes).sum(),
+        column: Column::of(sort),
+        reverse,
+        filter: String::new(),
+        filtering: false,
+        language: None,
+        language_row: (0, 0),
+        file_row: (0, 0),
+        page: 1,
+        message: None,
+    };
+    loop {
+        let (height, width) = terminal.size;
+        terminal.draw(&explorer.render(height, width))?;
+        for key in terminal.read_keys()? {
+            match explorer.handle(key) {
+                Action::None => {}
+                Action::Quit => return Ok(()),
+                Action::Open(path) => explorer.message = terminal.open_in_editor(&path)?,
+                Action::Copy(path) => {
+                    terminal.copy(&path)?;
+                    explorer.message = Some(format!("copied {path}"));
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{take_keys, Key};
+
+    /// Takes the keys of bytes read from the terminal in the given chunks, with the terminal
+    /// timing out after the last one.
+    fn keys(chunks: &[&[u8]]) -> Vec<Key> {
+        let mut pending = Vec::new();
+        let mut keys = Vec::new();
+        for chunk in chunks {
+            pending.extend_from_slice(chunk);
+            keys.extend(take_keys(&mut pending, false));
+        }
+        keys.extend(take_keys(&mut pending, true));
+        assert!(pending.is_empty());
+        keys
+    }
+
+    #[test]
+    fn escape_sequences_split_between_reads() {
+        assert_eq!(keys(&[b"\x1b", b"[A"]), [Key::Up]);
+        assert_eq!(keys(&[b"\x1b[", b"5", b"~"]), [Key::PageUp]);
+        assert_eq!(keys(&[b"j\x1bO", b"Bk"]), [Key::Char('j'), Key::Down, Key::Char('k')]);
+    }
+
+    #[test]
+    fn lone_escapes_wait_for_a_timeout() {
+        let mut pending = b"\x1b".to_vec();
+        assert!(take_keys(&mut pending, false).is_empty());
+        assert_eq!(take_keys(&mut pending, true), [Key::Escape]);
+        assert_eq!(keys(&[b"\x1b\x1b[B"]), [Key::Escape, Key::Down]);
+        assert!(keys(&[b"\x1b[1"]).is_empty());
+    }
+
+    #[test]
+    fn characters_split_between_reads() {
+        assert_eq!(
+            keys(&["é".as_bytes()[..1].as_ref(), "é".as_bytes()[1..].as_ref()]),
+            [Key::Char('é')]
+        );
+        assert_eq!(keys(&[b"\r\x7f\x03\x01"]), [Key::Enter, Key::Backspace, Key::Interrupt]);
+    }
+
+    #[test]
+    fn size_reports() {
+        assert_eq!(keys(&[b"\x1b[50;1", b"20R"]), [Key::Size(50, 120)]);
+        assert!(keys(&[b"\x1b[1;2R"]).is_empty());
+    }
+}
@@ -0,0 +1,148 @@
+//! Writing reports as XML, for the XML output format.
+
+/// Maps whose keys are data rather than names, such as the paths of the `directories` of a
+/// `DirectoryReport`, along with the names of their items and the attribute holding each key.
+const KEYED_MAPS: &[(&str, &str, &str)] = &[("directories", "directory", "path")];
+
+/// Writes a report as an XML document whose root element has the given name. The element layout
+/// follows the structure of the report's JSON output:
+///
+/// - Objects become elements with a child element for each field, such as `<lines>`.
+/// - Arrays become elements with a child element for each item, named after the singular of the
+///   array's name, such as `<languages><language>` or `<files><file>`.
+/// - Maps in `KEYED_MAPS` become elements with a child element for each item, such as
+///   `<directories><directory>`.
+/// - The `name` of an item of an array, and the key of an item of a map in `KEYED_MAPS`, are
+///   written as an attribute of the item, such as `<language name="Rust">`.
+///
+/// # Parameters
+/// - `writer` - Where to write the document
+/// - `root` - The name of the root element
+/// - `report` - The report to write
+///
+/// # Errors
+/// Returns an error if the report couldn't be serialized or written.
+pub(crate) fn write_xml(
+    writer: &mut impl std::io::Write,
+    root: &str,
+    report: &impl serde::Serialize,
+) -> std::io::Result<()> {
+    let value = serde_json::to_value(report)?;
+    writeln!(writer, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+    write_element(writer, root, None, &value, 0)
+}
+
+/// Writes a value as an element, see `write_xml`.
+///
+/// # Parameters
+/// - `writer` - Where to write the element
+/// - `name` - The name of the element
+/// - `attribute` - The name and value of an attribute of the element, if it has one
+/// - `value` - The value to write as the contents of the element
+/// - `depth` - The depth of the element in the document, which it is indented by
+fn write_element(
+    writer: &mut impl std::io::Write,
+    name: &str,
+    attribute: Option<(&str, &str)>,
+    value: &serde_json::Value,
+    depth: usize,
+) -> std::io::Result<()> {
+    let indent = "  ".repeat(depth);
+    let attribute = attribute
+        .map(|(attribute, value)| format!(r#" {attribute}="{}""#, escape(value)))
+        .unwrap_or_default();
+    match value {
+        serde_json::Value::Null => writeln!(writer, "{indent}<{name}{attribute}/>"),
+        serde_json::Value::Bool(boolean) => writeln!(writer, "{indent}<{name}{attribute}>{boolean}</{name}>"),
+        serde_json::Value::Number(number) => writeln!(writer, "{indent}<{name}{attribute}>{number}</{name}>"),
+        serde_json::Value::String(string) => {
+            writeln!(writer, "{indent}<{name}{attribute}>{}</{name}>", escape(string))
+        }
+        serde_json::Value::Array(items) => {
+            writeln!(writer, "{indent}<{name}{attribute}>")?;
+            let item_name = name.strip_suffix('s').unwrap_or("item");
+            for item in items {
+                // The name of an item is written as its attribute, rather than as a child
+                match item.get("name").and_then(serde_json::Value::as_str) {
+                    Some(item_attribute) => {
+                        let mut item = item.clone();
+                        item.as_object_mut().unwrap().shift_remove("name");
+                        write_element(writer, item_name, Some(("name", item_attribute)), &item, depth + 1)?;
+                    }
+                    None => write_element(writer, item_name, None, item, depth + 1)?,
+                }
+            }
+            writeln!(writer, "{indent}</{name}>")
+        }
+        serde_json::Value::Object(fields) => {
+            writeln!(writer, "{indent}<{name}{attribute}>")?;
+            let keyed_map = KEYED_MAPS.iter().find(|(map, _, _)| *map == name);
+            for (key, field) in fields {
+                match keyed_map {
+                    Some((_, item_name, key_attribute)) => {
+                        write_element(writer, item_name, Some((key_attribute, key)), field, depth + 1)?
+                    }
+                    None => write_element(writer, key, None, field, depth + 1)?,
+                }
+            }
+            writeln!(writer, "{indent}</{name}>")
+        }
+    }
+}
+
+/// Escapes the characters of some text that can't appear as they are in XML text or attributes.
+pub(crate) fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for character in text.chars() {
+        match character {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            _ => escaped.push(character),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn element_layout() {
+        let report = serde_json::json!({
+            "languages": [{ "name": "C#", "files": ["a<b>.cs"], "color": null }],
+            "directories": { "src/app": { "bytes": 3 } },
+            "lines_skipped": false,
+        });
+        let mut xml = Vec::new();
+        super::write_xml(&mut xml, "report", &report).unwrap();
+        let expected = r#"<?xml version="1.0" encoding="UTF-8"?>
+<report>
+  <languages>
+    <language name="C#">
+      <files>
+        <file>a&lt;b&gt;.cs</file>
+      </files>
+      <color/>
+    </language>
+  </languages>
+  <directories>
+    <directory path="src/app">
+      <bytes>3</bytes>
+    </directory>
+  </directories>
+  <lines_skipped>false</lines_skipped>
+</report>
+"#;
+        assert_eq!(String::from_utf8(xml).unwrap(), expected);
+    }
+
+    #[test]
+    fn escapes_attributes() {
+        assert_eq!(
+            super::escape(r#"Tom & "Jerry's""#),
+            "Tom &amp; &quot;Jerry&apos;s&quot;"
+        );
+    }
+}
@@ -0,0 +1,105 @@
+//! Tests of the `splik` command line, which run the built binary.
+
+/// Creates a temporary directory with the given files, named after the test.
+fn project(name: &str, files: &[(&str, &str)]) -> std::path::PathBuf {
+    let root = std::env::temp_dir().join(format!("splik-cli-test-{}-{name}", std::process::id()));
+    for (path, contents) in files {
+        let path = root.join(path);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, contents).unwrap();
+    }
+    root
+}
+
+/// Runs splik with the given arguments.
+fn splik(arguments: &[&std::ffi::OsStr]) -> std::process::Output {
+    std::process::Command::new(env!("CARGO_BIN_EXE_splik"))
+        .args(arguments)
+        .env_remove("SPLIK_OUTPUT")
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn jsonl_output_file_holds_the_files() {
+    let root = project("jsonl-output-file", &[("src/main.rs", "fn main() {}\n")]);
+    let output = root.join("report.jsonl");
+    let result = splik(&[
+        "--here".as_ref(),
+        "--no-config".as_ref(),
+        "-o".as_ref(),
+        "jsonl".as_ref(),
+        "--output-file".as_ref(),
+        output.as_os_str(),
+        root.as_os_str(),
+    ]);
+    assert!(result.status.success(), "{}", String::from_utf8_lossy(&result.stderr));
+
+    // Nothing is written to stdout but the line saying where the output went
+    let stdout = String::from_utf8(result.stdout).unwrap();
+    assert_eq!(stdout, format!("wrote {}\n", output.display()));
+
+    let types = std::fs::read_to_string(&output)
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str::<serde_json::Value>(line).unwrap()["type"].clone())
+        .collect::<Vec<_>>();
+    assert_eq!(types, ["file", "language", "totals"]);
+    std::fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+fn strict_empty_states_nothing_was_found_once() {
+    let root = project("strict-empty", &[("notes.unknown-extension", "text\n")]);
+    let result = splik(&[
+        "--here".as_ref(),
+        "--no-config".as_ref(),
+        "--strict-empty".as_ref(),
+        root.as_os_str(),
+    ]);
+    assert_eq!(result.status.code(), Some(2));
+    assert_eq!(String::from_utf8(result.stdout).unwrap(), "");
+    let stderr = String::from_utf8(result.stderr).unwrap();
+    assert_eq!(
+        stderr.matches("no recognized source files found under").count(),
+        1,
+        "{stderr}"
+    );
+    std::fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+fn baseline_of_files_tells_added_and_removed_lines_apart() {
+    let root = project(
+        "baseline-files",
+        &[("main.rs", "fn main() {}\n"), ("lib.rs", "a\nb\nc\n")],
+    );
+    let baseline = root.with_extension("json");
+    let result = splik(&[
+        "--here".as_ref(),
+        "--no-config".as_ref(),
+        "--files".as_ref(),
+        "-o".as_ref(),
+        "json".as_ref(),
+        "--output-file".as_ref(),
+        baseline.as_os_str(),
+        root.as_os_str(),
+    ]);
+    assert!(result.status.success(), "{}", String::from_utf8_lossy(&result.stderr));
+
+    // One line is added to one file, and two are removed from the other
+    std::fs::write(root.join("main.rs"), "fn main() {}\n// Done\n").unwrap();
+    std::fs::write(root.join("lib.rs"), "a\n").unwrap();
+    let result = splik(&[
+        "--here".as_ref(),
+        "--no-config".as_ref(),
+        "--baseline".as_ref(),
+        baseline.as_os_str(),
+        root.as_os_str(),
+    ]);
+    assert!(result.status.success(), "{}", String::from_utf8_lossy(&result.stderr));
+    let stdout = String::from_utf8(result.stdout).unwrap();
+    assert!(stdout.contains("+1 / -2 lines (-25.0%)"), "{stdout}");
+    std::fs::remove_dir_all(&root).unwrap();
+    std::fs::remove_file(&baseline).unwrap();
+}